@@ -38,6 +38,7 @@ fn run() -> Result<()> {
     let client = args.rpc()?;
     let (start, end) = Selector::parse(&args.selector, &client)?;
     let network = client.get_network()?;
+    brk_types::init_network(network);
 
     let mode = Mode::pick(args.pretty, args.compact, args.paths.len())?;
     let reader = Reader::new(args.blocks_dir(), &client);
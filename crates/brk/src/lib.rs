@@ -75,3 +75,7 @@ pub use brk_traversable as traversable;
 #[cfg(feature = "types")]
 #[doc(inline)]
 pub use brk_types as types;
+
+#[cfg(feature = "zmq")]
+#[doc(inline)]
+pub use brk_zmq as zmq;
@@ -6,9 +6,10 @@ use std::{
 use brk_error::{Error, Result};
 use brk_rpc::{Auth, Client};
 use brk_server::{CdnCacheMode, DEFAULT_MAX_UTXOS, DEFAULT_MAX_WEIGHT, Website};
-use brk_types::Port;
+use brk_types::{AddrLabelList, CustomSeries, Network, PoolOverrides, Port, UTXOCohortList};
+use brk_zmq::{Notifications, Topic};
 use owo_colors::OwoColorize;
-use serde::{Deserialize, Serialize};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
 
 use crate::{default_brk_path, dot_brk_path, fix_user_path};
 
@@ -21,6 +22,9 @@ pub struct Config {
     #[serde(default)]
     brkport: Option<Port>,
 
+    #[serde(default)]
+    electrumport: Option<u16>,
+
     #[serde(default)]
     website: Option<Website>,
 
@@ -33,6 +37,12 @@ pub struct Config {
     #[serde(default)]
     maxutxos: Option<usize>,
 
+    #[serde(default)]
+    entities: Option<bool>,
+
+    #[serde(default)]
+    chain: Option<String>,
+
     #[serde(default)]
     bitcoindir: Option<String>,
 
@@ -53,6 +63,23 @@ pub struct Config {
 
     #[serde(default)]
     rpcpassword: Option<String>,
+
+    #[serde(default)]
+    zmqpubhashblock: Option<String>,
+
+    #[serde(default)]
+    zmqpubrawtx: Option<String>,
+
+    #[serde(default)]
+    zmqpubsequence: Option<String>,
+}
+
+/// `~/.brk/series.toml`, one `[[series]]` table per custom series.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SeriesFile {
+    #[serde(default)]
+    series: Vec<CustomSeries>,
 }
 
 impl Config {
@@ -65,7 +92,7 @@ impl Config {
 
         let path = path.join("config.toml");
 
-        let mut config = Self::read(&path);
+        let mut config: Self = read(&path);
 
         if let Some(v) = config_args.brkdir {
             config.brkdir = Some(v);
@@ -73,6 +100,9 @@ impl Config {
         if let Some(v) = config_args.brkport {
             config.brkport = Some(v);
         }
+        if let Some(v) = config_args.electrumport {
+            config.electrumport = Some(v);
+        }
         if let Some(v) = config_args.website {
             config.website = Some(v);
         }
@@ -85,6 +115,12 @@ impl Config {
         if let Some(v) = config_args.maxutxos {
             config.maxutxos = Some(v);
         }
+        if let Some(v) = config_args.entities {
+            config.entities = Some(v);
+        }
+        if let Some(v) = config_args.chain {
+            config.chain = Some(v);
+        }
         if let Some(v) = config_args.bitcoindir {
             config.bitcoindir = Some(v);
         }
@@ -106,6 +142,15 @@ impl Config {
         if let Some(v) = config_args.rpcpassword {
             config.rpcpassword = Some(v);
         }
+        if let Some(v) = config_args.zmqpubhashblock {
+            config.zmqpubhashblock = Some(v);
+        }
+        if let Some(v) = config_args.zmqpubrawtx {
+            config.zmqpubrawtx = Some(v);
+        }
+        if let Some(v) = config_args.zmqpubsequence {
+            config.zmqpubsequence = Some(v);
+        }
 
         config.check();
 
@@ -130,6 +175,9 @@ impl Config {
                 }
                 Long("brkdir") => config.brkdir = Some(parser.value().unwrap().parse().unwrap()),
                 Long("brkport") => config.brkport = Some(parser.value().unwrap().parse().unwrap()),
+                Long("electrumport") => {
+                    config.electrumport = Some(parser.value().unwrap().parse().unwrap())
+                }
                 Long("website") => config.website = Some(parser.value().unwrap().parse().unwrap()),
                 Long("cdn") => config.cdn = Some(parser.value().unwrap().parse().unwrap()),
                 Long("maxweight") => {
@@ -138,6 +186,10 @@ impl Config {
                 Long("maxutxos") => {
                     config.maxutxos = Some(parser.value().unwrap().parse().unwrap())
                 }
+                Long("entities") => {
+                    config.entities = Some(parser.value().unwrap().parse().unwrap())
+                }
+                Long("chain") => config.chain = Some(parser.value().unwrap().parse().unwrap()),
                 Long("bitcoindir") => {
                     config.bitcoindir = Some(parser.value().unwrap().parse().unwrap())
                 }
@@ -155,6 +207,15 @@ impl Config {
                 Long("rpcpassword") => {
                     config.rpcpassword = Some(parser.value().unwrap().parse().unwrap())
                 }
+                Long("zmqpubhashblock") => {
+                    config.zmqpubhashblock = Some(parser.value().unwrap().parse().unwrap())
+                }
+                Long("zmqpubrawtx") => {
+                    config.zmqpubrawtx = Some(parser.value().unwrap().parse().unwrap())
+                }
+                Long("zmqpubsequence") => {
+                    config.zmqpubsequence = Some(parser.value().unwrap().parse().unwrap())
+                }
                 _ => {
                     eprintln!("{}", arg.unexpected());
                     std::process::exit(1);
@@ -183,7 +244,7 @@ impl Config {
         println!("    -V, --version             Print version");
         println!();
         println!(
-            "    --brkdir {}           Output directory {}",
+            "    --brkdir {}           Output directory, non-mainnet chains use a subdirectory {}",
             "<PATH>".bright_black(),
             "[~/.brk]".bright_black()
         );
@@ -192,6 +253,11 @@ impl Config {
             "<PORT>".bright_black(),
             "[3110]".bright_black()
         );
+        println!(
+            "    --electrumport {}     Electrum server port, usually 50001 {}",
+            "<PORT>".bright_black(),
+            "[off]".bright_black()
+        );
         println!(
             "    --website {}     Website {}",
             "<BOOL|PATH>".bright_black(),
@@ -212,7 +278,17 @@ impl Config {
             "<COUNT>".bright_black(),
             format!("[{}]", DEFAULT_MAX_UTXOS).bright_black()
         );
+        println!(
+            "    --entities {}         Cluster addresses into entities and compute entity cohorts {}",
+            "<BOOL>".bright_black(),
+            "[false]".bright_black()
+        );
         println!();
+        println!(
+            "    --chain {}            Chain: main, test, testnet4, signet or regtest {}",
+            "<CHAIN>".bright_black(),
+            "[main]".bright_black()
+        );
         println!(
             "    --bitcoindir {}       Bitcoin directory {}",
             "<PATH>".bright_black(),
@@ -221,7 +297,7 @@ impl Config {
         println!(
             "    --blocksdir {}        Blocks directory {}",
             "<PATH>".bright_black(),
-            "[<bitcoindir>/blocks]".bright_black()
+            "[<bitcoindir>/<chain>/blocks]".bright_black()
         );
         println!();
         println!(
@@ -232,12 +308,12 @@ impl Config {
         println!(
             "    --rpcport {}          RPC port {}",
             "<PORT>".bright_black(),
-            "[8332]".bright_black()
+            "[8332, per chain]".bright_black()
         );
         println!(
             "    --rpccookiefile {}    RPC cookie file {}",
             "<PATH>".bright_black(),
-            "[<bitcoindir>/.cookie]".bright_black()
+            "[<bitcoindir>/<chain>/.cookie]".bright_black()
         );
         println!(
             "    --rpcuser {}      RPC username",
@@ -248,6 +324,22 @@ impl Config {
             "<PASSWORD>".bright_black()
         );
         println!();
+        println!(
+            "    --zmqpubhashblock {}  ZMQ endpoint of bitcoind's hashblock notifications {}",
+            "<ADDR>".bright_black(),
+            "[off, polls]".bright_black()
+        );
+        println!(
+            "    --zmqpubrawtx {}      ZMQ endpoint of bitcoind's rawtx notifications {}",
+            "<ADDR>".bright_black(),
+            "[off, polls]".bright_black()
+        );
+        println!(
+            "    --zmqpubsequence {}   ZMQ endpoint of bitcoind's sequence notifications {}",
+            "<ADDR>".bright_black(),
+            "[off, polls]".bright_black()
+        );
+        println!();
         println!("{}", "ENVIRONMENT:".bold());
         println!(
             "    LOG={}               Log level {}",
@@ -269,9 +361,28 @@ impl Config {
             "    {}",
             "bitcoindir = \"/path/to/.bitcoin\"".bright_black()
         );
+        println!(
+            "    Declare custom series in {}, see the README",
+            "~/.brk/series.toml".bright_black()
+        );
+        println!(
+            "    Add or fix mining pools in {}, see the README",
+            "~/.brk/pools.toml".bright_black()
+        );
     }
 
     fn check(&self) {
+        if let Some(chain) = self.chain.as_deref()
+            && Network::from_core_arg(chain).is_err()
+        {
+            println!("{chain:?} isn't a valid chain");
+            println!(
+                "Please use the --chain parameter with main, test, testnet4, signet or regtest."
+            );
+            println!("Run the program with '-h' for help.");
+            std::process::exit(1);
+        }
+
         if !self.bitcoindir().is_dir() {
             println!("{:?} isn't a valid directory", self.bitcoindir());
             println!("Please use the --bitcoindir parameter to set a valid path.");
@@ -286,8 +397,8 @@ impl Config {
             std::process::exit(1);
         }
 
-        if !self.brkdir().is_dir() {
-            println!("{:?} isn't a valid directory", self.brkdir());
+        if !self.base_brkdir().is_dir() {
+            println!("{:?} isn't a valid directory", self.base_brkdir());
             println!("Please use the --brkdir parameter to set a valid path.");
             println!("Run the program with '-h' for help.");
             std::process::exit(1);
@@ -303,19 +414,25 @@ Finally, you can run the program with '-h' for help."
         }
     }
 
-    fn read(path: &Path) -> Self {
-        let contents = match fs::read_to_string(path) {
-            Ok(contents) => contents,
-            Err(e) if e.kind() == io::ErrorKind::NotFound => return Config::default(),
-            Err(e) => {
-                eprintln!("Cannot read {}: {e}", path.display());
-                std::process::exit(1);
-            }
-        };
-        toml::from_str(&contents).unwrap_or_else(|e| {
-            eprintln!("Invalid {}:\n{e}", path.display());
-            std::process::exit(1);
-        })
+    /// Custom series declared in `~/.brk/series.toml`, none if it's missing.
+    pub fn custom_series(&self) -> Vec<CustomSeries> {
+        read::<SeriesFile>(&dot_brk_path().join("series.toml")).series
+    }
+
+    /// Mining pool overrides declared in `~/.brk/pools.toml`, none if it's
+    /// missing.
+    pub fn pool_overrides(&self) -> PoolOverrides {
+        read(&dot_brk_path().join("pools.toml"))
+    }
+
+    /// Address labels declared in `~/.brk/labels.toml`, none if it's missing.
+    pub fn addr_labels(&self) -> AddrLabelList {
+        read(&dot_brk_path().join("labels.toml"))
+    }
+
+    /// UTXO cohorts declared in `~/.brk/cohorts.toml`, none if it's missing.
+    pub fn utxo_cohorts(&self) -> UTXOCohortList {
+        read(&dot_brk_path().join("cohorts.toml"))
     }
 
     pub fn rpc(&self) -> Result<Client> {
@@ -323,7 +440,8 @@ Finally, you can run the program with '-h' for help."
             &format!(
                 "http://{}:{}",
                 self.rpcconnect().unwrap_or(&"localhost".to_string()),
-                self.rpcport().unwrap_or(8332)
+                self.rpcport()
+                    .unwrap_or_else(|| Client::default_port(self.network()))
             ),
             self.rpc_auth()?,
         )
@@ -344,6 +462,31 @@ Finally, you can run the program with '-h' for help."
         }
     }
 
+    /// ZMQ subscriptions, one per endpoint since bitcoind can publish
+    /// several topics on the same one. Disabled when none is set.
+    pub fn notifications(&self) -> Result<Notifications> {
+        let mut endpoints: Vec<(&str, Vec<Topic>)> = vec![];
+        for (endpoint, topic) in [
+            (&self.zmqpubhashblock, Topic::HashBlock),
+            (&self.zmqpubrawtx, Topic::RawTx),
+            (&self.zmqpubsequence, Topic::Sequence),
+        ] {
+            let Some(endpoint) = endpoint.as_deref() else {
+                continue;
+            };
+            match endpoints.iter_mut().find(|(e, _)| *e == endpoint) {
+                Some((_, topics)) => topics.push(topic),
+                None => endpoints.push((endpoint, vec![topic])),
+            }
+        }
+
+        let notifications = Notifications::default();
+        for (endpoint, topics) in endpoints {
+            notifications.subscribe(endpoint, &topics)?;
+        }
+        Ok(notifications)
+    }
+
     fn rpcconnect(&self) -> Option<&String> {
         self.rpcconnect.as_ref()
     }
@@ -352,28 +495,56 @@ Finally, you can run the program with '-h' for help."
         self.rpcport
     }
 
+    pub fn network(&self) -> Network {
+        self.chain
+            .as_deref()
+            .and_then(|chain| Network::from_core_arg(chain).ok())
+            .unwrap_or(Network::Bitcoin)
+    }
+
     pub fn bitcoindir(&self) -> PathBuf {
         self.bitcoindir
             .as_ref()
             .map_or_else(Client::default_bitcoin_path, |s| fix_user_path(s.as_ref()))
     }
 
+    /// Where bitcoind keeps the selected chain's data, mirroring its
+    /// `<datadir>/<chain>` layout.
+    fn chaindir(&self) -> PathBuf {
+        let bitcoindir = self.bitcoindir();
+        match Client::network_dir_name(self.network()) {
+            Some(name) => bitcoindir.join(name),
+            None => bitcoindir,
+        }
+    }
+
     pub fn blocksdir(&self) -> PathBuf {
         self.blocksdir.as_ref().map_or_else(
-            || self.bitcoindir().join("blocks"),
+            || self.chaindir().join("blocks"),
             |blocksdir| fix_user_path(blocksdir.as_str()),
         )
     }
 
-    pub fn brkdir(&self) -> PathBuf {
+    fn base_brkdir(&self) -> PathBuf {
         self.brkdir
             .as_ref()
             .map_or_else(default_brk_path, |s| fix_user_path(s.as_ref()))
     }
 
+    /// Data directory of the selected chain. Mainnet keeps the root so
+    /// existing installs are untouched, other chains get their own
+    /// subdirectory so their data never mixes.
+    pub fn brkdir(&self) -> PathBuf {
+        let base = self.base_brkdir();
+        match Client::network_dir_name(self.network()) {
+            Some(name) => base.join(name),
+            None => base,
+        }
+    }
+
     fn path_cookiefile(&self) -> PathBuf {
         self.rpccookiefile.as_ref().map_or_else(
-            || self.bitcoindir().join(".cookie"),
+            || self.chaindir().join(".cookie"),
             |p| fix_user_path(p.as_str()),
         )
     }
@@ -398,7 +569,30 @@ Finally, you can run the program with '-h' for help."
         self.maxutxos.unwrap_or(DEFAULT_MAX_UTXOS)
     }
 
+    pub fn entities(&self) -> bool {
+        self.entities.unwrap_or(false)
+    }
+
     pub fn brkport(&self) -> Option<Port> {
         self.brkport
     }
+
+    pub fn electrumport(&self) -> Option<u16> {
+        self.electrumport
+    }
+}
+
+fn read<T: Default + DeserializeOwned>(path: &Path) -> T {
+    let contents = match fs::read_to_string(path) {
+        Ok(contents) => contents,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return T::default(),
+        Err(e) => {
+            eprintln!("Cannot read {}: {e}", path.display());
+            std::process::exit(1);
+        }
+    };
+    toml::from_str(&contents).unwrap_or_else(|e| {
+        eprintln!("Invalid {}:\n{e}", path.display());
+        std::process::exit(1);
+    })
 }
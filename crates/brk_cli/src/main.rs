@@ -1,20 +1,17 @@
 #![doc = include_str!("../README.md")]
 
-use std::{
-    fs,
-    thread::{self, sleep},
-    time::{Duration, Instant},
-};
+use std::{fs, thread, time::Instant};
 
 use brk_alloc::Mimalloc;
 use brk_computer::Computer;
+use brk_electrum::ElectrumServer;
 use brk_error::Result;
 use brk_indexer::Indexer;
 use brk_mempool::Mempool;
 use brk_query::AsyncQuery;
 use brk_reader::Reader;
 use brk_server::{Server, ServerConfig};
-use tracing::info;
+use tracing::{error, info};
 use vecdb::Exit;
 
 mod config;
@@ -29,8 +26,27 @@ pub fn main() -> anyhow::Result<()> {
 
     let config = Config::import()?;
 
+    let network = config.network();
+    brk_types::init_network(network);
+    brk_types::init_pools(&config.pool_overrides())?;
+    brk_types::init_addr_labels(&config.addr_labels())?;
+    brk_cohort::init_intersections(&config.utxo_cohorts())?;
+    fs::create_dir_all(config.brkdir())?;
+
     let client = config.rpc()?;
 
+    let node_network = client.get_network()?;
+    if node_network != network {
+        anyhow::bail!(
+            "bitcoind is running on {} but brk is configured for {}, set --chain {}",
+            node_network.to_core_arg(),
+            network.to_core_arg(),
+            node_network.to_core_arg()
+        );
+    }
+
+    let notifications = config.notifications()?;
+
     let exit = Exit::new();
     exit.set_ctrlc_handler();
 
@@ -48,7 +64,7 @@ pub fn main() -> anyhow::Result<()> {
             info!("---");
             info!("Indexing {blocks_behind} blocks before starting server...");
             info!("---");
-            sleep(Duration::from_secs(10));
+            thread::sleep(std::time::Duration::from_secs(10));
             indexer.index(&reader, &client, &exit)?;
             drop(indexer);
             Mimalloc::collect();
@@ -56,11 +72,21 @@ pub fn main() -> anyhow::Result<()> {
         }
     }
 
-    let mut computer = Computer::forced_import(&config.brkdir(), &indexer)?;
+    let mut computer =
+        Computer::forced_import_with_custom(&config.brkdir(), &indexer, &config.custom_series())?;
+    if config.entities() {
+        computer.enable_entities();
+    }
 
-    let mempool = Mempool::new(&client);
+    let mempool = Mempool::new_persisted(
+        &client,
+        &notifications,
+        &config.brkdir().join("mempool.dat"),
+    );
 
     let query = AsyncQuery::build(&reader, &indexer, &computer, Some(mempool.clone()));
+    // Feeds the fee estimator with each new block from the loop below.
+    let confirmer = query.clone();
 
     let mempool_clone = mempool.clone();
     let resolver = query.sync(|q| q.indexer_prevout_resolver());
@@ -77,8 +103,17 @@ pub fn main() -> anyhow::Result<()> {
     };
 
     let port = config.brkport();
+    let electrum_port = config.electrumport();
+    let max_utxos = config.max_utxos();
 
     let future = async move {
+        if let Some(electrum_port) = electrum_port {
+            let electrum = ElectrumServer::new(&query, max_utxos);
+            tokio::spawn(async move {
+                electrum.serve(electrum_port).await.unwrap();
+            });
+        }
+
         let server = Server::new(&query, server_config);
 
         tokio::spawn(async move {
@@ -94,6 +129,8 @@ pub fn main() -> anyhow::Result<()> {
 
     let _handle = runtime.spawn(future);
 
+    let mut block_seen = notifications.block().generation();
+
     loop {
         client.wait_for_synced_node()?;
 
@@ -102,6 +139,7 @@ pub fn main() -> anyhow::Result<()> {
         info!("{} blocks found.", u32::from(last_height) + 1);
 
         let total_start = Instant::now();
+        let confirmed_from = indexer.vecs.next_height();
 
         if cfg!(debug_assertions) {
             indexer.checked_index(&reader, &client, &exit)?;
@@ -111,15 +149,20 @@ pub fn main() -> anyhow::Result<()> {
 
         Mimalloc::collect();
 
+        computer.record_mempool(mempool.take_history(), &exit)?;
         computer.compute(&indexer, &exit)?;
 
         indexer.advance_safe_lengths()?;
 
+        if let Err(e) = confirmer.sync(|q| q.confirm_mempool_txs(confirmed_from)) {
+            error!("Failed to feed confirmed blocks to the fee estimator: {e}");
+        }
+
         info!("Total time: {:?}", total_start.elapsed());
         info!("Waiting for new blocks...");
 
         while last_height == client.get_last_height()? {
-            sleep(Duration::from_secs(1))
+            notifications.wait_for_block(&mut block_seen);
         }
     }
 }
@@ -7,6 +7,8 @@ pub enum CohortContext {
     Utxo,
     /// Address-based cohorts: uses "addrs_" prefix for Amount filters
     Addr,
+    /// Entity-based cohorts: uses "entities_" prefix for Amount filters
+    Entity,
 }
 
 impl CohortContext {
@@ -14,6 +16,7 @@ impl CohortContext {
         match self {
             CohortContext::Utxo => "utxos",
             CohortContext::Addr => "addrs",
+            CohortContext::Entity => "entities",
         }
     }
 
@@ -21,11 +24,13 @@ impl CohortContext {
         format!("{}_{}", self.prefix(), name)
     }
 
-    /// Build full name for a filter, adding prefix only for Time/Amount filters.
+    /// Build full name for a filter, adding prefix only for Time/Amount/Label/Intersection filters.
     ///
     /// Prefix rules:
     /// - No prefix: `All`, `Term`, `Epoch`, `Class`, `Entry`, `Type`
     /// - Context prefix: `Time`, `Amount`
+    /// - `label_` prefix, whatever the context: `Label`
+    /// - `cohort_` prefix, whatever the context: `Intersection`
     pub fn full_name(&self, filter: &Filter, name: &str) -> String {
         match filter {
             Filter::All
@@ -35,6 +40,8 @@ impl CohortContext {
             | Filter::Entry(_)
             | Filter::Type(_) => name.to_string(),
             Filter::Time(_) | Filter::Amount(_) => self.prefixed(name),
+            Filter::Label(_) => format!("label_{name}"),
+            Filter::Intersection(_) => format!("cohort_{name}"),
         }
     }
 }
@@ -1,6 +1,6 @@
 use brk_types::{Halving, OutputType, Sats, Year};
 
-use super::{AmountFilter, CohortContext, EntryPrice, Term, TimeFilter};
+use super::{AmountFilter, CohortContext, EntryPrice, Intersection, Term, TimeFilter};
 
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub enum Filter {
@@ -12,6 +12,10 @@ pub enum Filter {
     Class(Year),
     Entry(EntryPrice),
     Type(OutputType),
+    /// Addresses carrying a user-supplied label
+    Label(String),
+    /// UTXOs matching a user-declared combination of filters
+    Intersection(Intersection),
 }
 
 impl Filter {
@@ -26,6 +30,7 @@ impl Filter {
             Filter::Term(Term::Sth) => true,
             Filter::Term(Term::Lth) => false,
             Filter::Time(t) => t.includes_first_day(),
+            Filter::Intersection(i) => i.contains_age(0),
             _ => false,
         }
     }
@@ -37,6 +42,7 @@ impl Filter {
             Filter::Term(Term::Sth) => hours < Term::THRESHOLD_HOURS,
             Filter::Term(Term::Lth) => hours >= Term::THRESHOLD_HOURS,
             Filter::Time(t) => t.contains(hours),
+            Filter::Intersection(i) => i.contains_age(hours),
             _ => false,
         }
     }
@@ -64,6 +70,8 @@ impl Filter {
             }
             (Filter::Time(t1), Filter::Time(t2)) => t1.includes(t2),
             (Filter::Amount(a1), Filter::Amount(a2)) => a1.includes(a2),
+            (Filter::Label(l1), Filter::Label(l2)) => l1 == l2,
+            (Filter::Intersection(i1), Filter::Intersection(i2)) => i1 == i2,
             _ => false,
         }
     }
@@ -71,10 +79,10 @@ impl Filter {
     /// Whether to compute extended metrics (realized cap ratios, profit/loss ratios, percentiles)
     /// For UTXO context: true for age range cohorts (Range), aggregate cohorts (All, Term),
     /// and immutable entry valuation cohorts.
-    /// For address context: always false
+    /// For address and entity contexts: always false
     pub fn is_extended(&self, context: CohortContext) -> bool {
         match context {
-            CohortContext::Addr => false,
+            CohortContext::Addr | CohortContext::Entity => false,
             CohortContext::Utxo => {
                 matches!(
                     self,
@@ -95,12 +103,12 @@ impl Filter {
 
     /// Whether to compute adjusted metrics (adjusted SOPR, adjusted value created/destroyed)
     /// For UTXO context: true for All, STH, and under_age (LowerThan)
-    /// For address context: always false
+    /// For address and entity contexts: always false
     /// Note: LTH doesn't need adjusted (everything >= 5 months is already > 1 hour)
     /// Note: age ranges don't need adjusted (0-1h data lives in its own cohort)
     pub fn compute_adjusted(&self, context: CohortContext) -> bool {
         match context {
-            CohortContext::Addr => false,
+            CohortContext::Addr | CohortContext::Entity => false,
             CohortContext::Utxo => matches!(
                 self,
                 Filter::All | Filter::Term(Term::Sth) | Filter::Time(TimeFilter::LowerThan(_))
@@ -0,0 +1,256 @@
+use std::{ops::Range, sync::OnceLock};
+
+use brk_error::{Error, Result};
+use brk_types::{OutputType, Sats, Term, UTXOCohortDefinition, UTXOCohortList};
+
+use super::{SPENDABLE_TYPE_NAMES, SPENDABLE_TYPE_VALUES};
+
+static INTERSECTIONS: OnceLock<Vec<Intersection>> = OnceLock::new();
+
+/// Metrics computed for a cohort, from the amount cohorts' to the entry
+/// cohorts' set.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum CohortTier {
+    #[default]
+    Minimal,
+    Basic,
+    Core,
+    Extended,
+}
+
+/// A UTXO cohort combining age, amount and output type filters.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Intersection {
+    /// Position in [`intersections`]
+    pub index: usize,
+    pub name: String,
+    /// Age, in hours
+    pub age: Range<usize>,
+    pub amount: Range<Sats>,
+    /// Spendable output types, all of them if empty
+    pub types: Vec<OutputType>,
+    pub tier: CohortTier,
+}
+
+impl Intersection {
+    /// Whether an output of this type and value is in the cohort at some
+    /// point of its life.
+    pub fn contains_output(&self, output_type: OutputType, value: Sats) -> bool {
+        value >= self.amount.start
+            && value < self.amount.end
+            && (self.types.is_empty() || self.types.contains(&output_type))
+    }
+
+    pub fn contains_age(&self, hours: usize) -> bool {
+        self.age.contains(&hours)
+    }
+
+    /// Age boundaries, in hours, where the outputs enter the cohort and
+    /// where they leave it, if they ever do.
+    pub fn age_boundaries(&self) -> (Option<usize>, Option<usize>) {
+        (
+            (self.age.start > 0).then_some(self.age.start),
+            (self.age.end < usize::MAX).then_some(self.age.end),
+        )
+    }
+
+    fn build(index: usize, def: &UTXOCohortDefinition) -> Result<Self> {
+        let name = &def.name;
+        let invalid = |reason: &str| Error::Parse(format!("invalid cohort `{name}`: {reason}"));
+
+        let valid_name = !name.is_empty()
+            && name
+                .bytes()
+                .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+        if !valid_name {
+            return Err(invalid("use lowercase letters, digits and underscores"));
+        }
+
+        let mut age = def.min_age_hours.unwrap_or(0)..def.max_age_hours.unwrap_or(usize::MAX);
+        match def.term.as_deref() {
+            None => {}
+            Some("sth") => age.end = age.end.min(Term::THRESHOLD_HOURS),
+            Some("lth") => age.start = age.start.max(Term::THRESHOLD_HOURS),
+            Some(term) => return Err(invalid(&format!("unknown term `{term}`"))),
+        }
+        if age.is_empty() {
+            return Err(invalid("no age is in range"));
+        }
+
+        let amount = def.min_sats.unwrap_or(Sats::ZERO)..def.max_sats.unwrap_or(Sats::MAX);
+        if amount.is_empty() {
+            return Err(invalid("no amount is in range"));
+        }
+
+        let types = def
+            .types
+            .iter()
+            .map(|t| {
+                SPENDABLE_TYPE_NAMES
+                    .iter()
+                    .zip(SPENDABLE_TYPE_VALUES.iter())
+                    .find(|(n, _)| n.id == t.as_str())
+                    .map(|(_, output_type)| *output_type)
+                    .ok_or_else(|| invalid(&format!("unknown output type `{t}`")))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let tier = match def.metrics.as_deref() {
+            None | Some("minimal") => CohortTier::Minimal,
+            Some("basic") => CohortTier::Basic,
+            Some("core") => CohortTier::Core,
+            Some("extended") => CohortTier::Extended,
+            Some(tier) => return Err(invalid(&format!("unknown metrics tier `{tier}`"))),
+        };
+
+        Ok(Self {
+            index,
+            name: name.clone(),
+            age,
+            amount,
+            types,
+            tier,
+        })
+    }
+}
+
+/// Loads the intersection cohorts. Call once at startup, before
+/// [`intersections`] is first used: later calls are ignored (first-wins),
+/// so library users that never call it get none.
+pub fn init_intersections(list: &UTXOCohortList) -> Result<()> {
+    let intersections = list
+        .cohorts
+        .iter()
+        .enumerate()
+        .map(|(index, def)| Intersection::build(index, def))
+        .collect::<Result<Vec<_>>>()?;
+
+    for (i, a) in intersections.iter().enumerate() {
+        if intersections[..i].iter().any(|b| b.name == a.name) {
+            return Err(Error::Parse(format!("cohort `{}` declared twice", a.name)));
+        }
+    }
+
+    let _ = INTERSECTIONS.set(intersections);
+    Ok(())
+}
+
+pub fn intersections() -> &'static [Intersection] {
+    INTERSECTIONS.get_or_init(Vec::new)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn def(name: &str) -> UTXOCohortDefinition {
+        UTXOCohortDefinition {
+            name: name.to_string(),
+            term: None,
+            min_age_hours: None,
+            max_age_hours: None,
+            min_sats: None,
+            max_sats: None,
+            types: Vec::new(),
+            metrics: None,
+        }
+    }
+
+    #[test]
+    fn defaults_cover_every_age_amount_and_type() {
+        let cohort = Intersection::build(3, &def("all_utxos")).unwrap();
+        assert_eq!(cohort.index, 3);
+        assert_eq!(cohort.age, 0..usize::MAX);
+        assert_eq!(cohort.amount, Sats::ZERO..Sats::MAX);
+        assert!(cohort.types.is_empty());
+        assert_eq!(cohort.tier, CohortTier::Minimal);
+        assert_eq!(cohort.age_boundaries(), (None, None));
+    }
+
+    #[test]
+    fn filters_combine() {
+        let cohort = Intersection::build(
+            0,
+            &UTXOCohortDefinition {
+                term: Some("lth".into()),
+                max_age_hours: Some(24 * 365),
+                min_sats: Some(Sats::new(1_000)),
+                max_sats: Some(Sats::new(100_000)),
+                types: vec!["p2tr".into(), "p2wpkh".into()],
+                metrics: Some("core".into()),
+                ..def("lth_small_segwit")
+            },
+        )
+        .unwrap();
+
+        assert_eq!(cohort.age, Term::THRESHOLD_HOURS..24 * 365);
+        assert_eq!(
+            cohort.age_boundaries(),
+            (Some(Term::THRESHOLD_HOURS), Some(24 * 365))
+        );
+        assert_eq!(cohort.types, vec![OutputType::P2TR, OutputType::P2WPKH]);
+        assert_eq!(cohort.tier, CohortTier::Core);
+
+        assert!(cohort.contains_output(OutputType::P2TR, Sats::new(1_000)));
+        assert!(!cohort.contains_output(OutputType::P2TR, Sats::new(100_000)));
+        assert!(!cohort.contains_output(OutputType::P2PKH, Sats::new(5_000)));
+        assert!(cohort.contains_age(Term::THRESHOLD_HOURS));
+        assert!(!cohort.contains_age(Term::THRESHOLD_HOURS - 1));
+    }
+
+    #[test]
+    fn sth_caps_the_max_age() {
+        let cohort = Intersection::build(
+            0,
+            &UTXOCohortDefinition {
+                term: Some("sth".into()),
+                max_age_hours: Some(usize::MAX),
+                ..def("sth")
+            },
+        )
+        .unwrap();
+        assert_eq!(cohort.age, 0..Term::THRESHOLD_HOURS);
+    }
+
+    #[test]
+    fn invalid_definitions_are_rejected() {
+        let invalid = [
+            def("Upper"),
+            def(""),
+            def("with-dash"),
+            UTXOCohortDefinition {
+                term: Some("mid".into()),
+                ..def("a")
+            },
+            UTXOCohortDefinition {
+                term: Some("sth".into()),
+                min_age_hours: Some(Term::THRESHOLD_HOURS),
+                ..def("a")
+            },
+            UTXOCohortDefinition {
+                min_sats: Some(Sats::new(10)),
+                max_sats: Some(Sats::new(10)),
+                ..def("a")
+            },
+            UTXOCohortDefinition {
+                types: vec!["op_return".into()],
+                ..def("a")
+            },
+            UTXOCohortDefinition {
+                metrics: Some("full".into()),
+                ..def("a")
+            },
+        ];
+        for def in &invalid {
+            assert!(Intersection::build(0, def).is_err(), "{def:?}");
+        }
+    }
+
+    #[test]
+    fn duplicate_names_are_rejected() {
+        let list = UTXOCohortList {
+            cohorts: vec![def("twice"), def("twice")],
+        };
+        assert!(init_intersections(&list).is_err());
+    }
+}
@@ -15,6 +15,7 @@ mod cohort_context;
 mod cohort_name;
 mod filter;
 mod filtered;
+mod intersection;
 mod loss;
 mod over_age;
 mod over_amount;
@@ -45,6 +46,7 @@ pub use cohort_context::*;
 pub use cohort_name::*;
 pub use filter::*;
 pub use filtered::*;
+pub use intersection::*;
 pub use loss::*;
 pub use over_age::*;
 pub use over_amount::*;
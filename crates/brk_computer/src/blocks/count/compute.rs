@@ -1,6 +1,7 @@
 use brk_error::Result;
 use brk_indexer::Indexer;
-use brk_types::StoredU32;
+use brk_types::{Height, StoredU32};
+use rustc_hash::FxHashMap;
 use vecdb::Exit;
 
 use super::Vecs;
@@ -16,6 +17,21 @@ impl Vecs {
         )?;
         self.total.compute_rest(starting_height, exit)?;
 
+        // A reorg lowers the indexer below the heights it orphaned, so
+        // the pass that follows always reaches its entry.
+        let mut reorgs: FxHashMap<Height, u32> = FxHashMap::default();
+        for reorg in indexer.reorgs.all() {
+            *reorgs.entry(reorg.fork_height.incremented()).or_default() += 1;
+        }
+        let count_at = |h: Height| StoredU32::from(reorgs.get(&h).copied().unwrap_or_default());
+        self.reorgs.block.compute_range(
+            starting_height,
+            &indexer.vecs.blocks.weight,
+            |h| (h, count_at(h)),
+            exit,
+        )?;
+        self.reorgs.compute_rest(starting_height, exit)?;
+
         Ok(())
     }
 }
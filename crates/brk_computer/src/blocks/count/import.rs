@@ -48,6 +48,13 @@ impl Vecs {
                 indexes,
                 cached_starts,
             )?,
+            reorgs: PerBlockCumulativeRolling::forced_import(
+                db,
+                "reorg_count",
+                version,
+                indexes,
+                cached_starts,
+            )?,
         })
     }
 }
@@ -8,4 +8,8 @@ use crate::internal::{ConstantVecs, PerBlockCumulativeRolling, Windows};
 pub struct Vecs<M: StorageMode = Rw> {
     pub target: Windows<ConstantVecs<StoredU64>>,
     pub total: PerBlockCumulativeRolling<StoredU32, StoredU64, M>,
+    /// Reorgs from the indexer's journal, counted at the first height
+    /// they orphaned. Reorgs that happened while brk wasn't running
+    /// aren't seen.
+    pub reorgs: PerBlockCumulativeRolling<StoredU32, StoredU64, M>,
 }
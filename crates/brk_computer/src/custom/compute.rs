@@ -0,0 +1,262 @@
+use brk_error::{Error, Result};
+use brk_indexer::{Indexer, Lengths};
+use brk_types::{Index, SeriesExpr, SeriesName, StoredF64};
+use vecdb::{
+    AnyExportableVec, AnyStoredVec, AnyVec, EagerVec, Exit, PcoVec, ReadableVec, VecIndex,
+    WritableVec,
+};
+
+use super::Vecs;
+use crate::{indexes, internal::SeriesExprEval};
+
+impl Vecs {
+    /// Rejects names taken by a built-in series and references to series
+    /// missing at the custom series' index.
+    pub(crate) fn check(&self, sources: &[&dyn AnyExportableVec]) -> Result<()> {
+        for (name, vec) in &self.series {
+            if sources.iter().any(|source| source.name() == name.as_str()) {
+                return Err(Error::Parse(format!(
+                    "Custom series '{name}': name taken by a built-in series"
+                )));
+            }
+            inputs(name, &self.exprs[name], vec.index(), sources)?;
+        }
+        Ok(())
+    }
+
+    /// `sources` are every indexed and computed vec a custom series may
+    /// reference.
+    pub(crate) fn compute(
+        &mut self,
+        indexer: &Indexer,
+        indexes: &indexes::Vecs,
+        sources: &[&dyn AnyExportableVec],
+        exit: &Exit,
+    ) -> Result<()> {
+        self.db.sync_bg_tasks()?;
+
+        let starting_lengths = indexer.safe_lengths();
+
+        for (name, vec) in self.series.iter_mut() {
+            let expr = &self.exprs[name];
+            let index = vec.index();
+            let inputs = inputs(name, expr, index, sources)?;
+
+            vec.compute(
+                starting_index(index, indexes, &starting_lengths),
+                expr,
+                &inputs,
+                exit,
+            )?;
+        }
+
+        let exit = exit.clone();
+        self.db.run_bg(move |db| {
+            let _lock = exit.lock();
+            db.compact_deferred_default()
+        });
+        Ok(())
+    }
+}
+
+/// Looks up each series `expr` references at `index`. Custom series
+/// aren't in `sources`, so they can't reference each other.
+fn inputs<'a>(
+    name: &str,
+    expr: &'a SeriesExpr,
+    index: Index,
+    sources: &[&'a dyn AnyExportableVec],
+) -> Result<Vec<(&'a SeriesName, &'a dyn AnyExportableVec)>> {
+    expr.series()
+        .into_iter()
+        .map(|series| {
+            let key = series.normalize();
+            sources
+                .iter()
+                .find(|source| {
+                    source.name() == key
+                        && Index::try_from(source.index_type_to_string()).is_ok_and(|i| i == index)
+                })
+                .map(|source| (series, *source))
+                .ok_or_else(|| {
+                    Error::NotFound(format!(
+                        "Custom series '{name}': no series '{series}' at index '{}'",
+                        index.name()
+                    ))
+                })
+        })
+        .collect()
+}
+
+/// First entry the blocks since the last run can change.
+fn starting_index(index: Index, indexes: &indexes::Vecs, starting_lengths: &Lengths) -> usize {
+    let prev_height = starting_lengths.height.decremented().unwrap_or_default();
+
+    macro_rules! period {
+        ($field:ident) => {
+            indexes
+                .height
+                .$field
+                .collect_one(prev_height)
+                .unwrap_or_default()
+                .to_usize()
+        };
+    }
+
+    match index {
+        Index::Minute10 => period!(minute10),
+        Index::Minute30 => period!(minute30),
+        Index::Hour1 => period!(hour1),
+        Index::Hour4 => period!(hour4),
+        Index::Hour12 => period!(hour12),
+        Index::Day1 => period!(day1),
+        Index::Day3 => period!(day3),
+        Index::Week1 => period!(week1),
+        Index::Month1 => period!(month1),
+        Index::Month3 => period!(month3),
+        Index::Month6 => period!(month6),
+        Index::Year1 => period!(year1),
+        Index::Year10 => period!(year10),
+        Index::Halving => period!(halving),
+        Index::Epoch => period!(epoch),
+        _ => starting_lengths.height.to_usize(),
+    }
+}
+
+/// Recomputes from `starting_index`, reading back as many points as the
+/// expression's windows and lags need. `ema` depends on the full history
+/// and is re-read from the start.
+pub(super) fn compute_vec<I: VecIndex>(
+    vec: &mut EagerVec<PcoVec<I, StoredF64>>,
+    starting_index: usize,
+    expr: &SeriesExpr,
+    sources: &[(&SeriesName, &dyn AnyExportableVec)],
+    exit: &Exit,
+) -> Result<()> {
+    vec.validate_computed_version_or_reset(sources.iter().map(|(_, s)| s.version()).sum())?;
+
+    let end = sources
+        .iter()
+        .map(|(_, s)| s.len())
+        .min()
+        .unwrap_or_default();
+    vec.truncate_if_needed_at(starting_index.min(end))?;
+
+    let start = vec.len();
+    if start >= end {
+        return Ok(());
+    }
+
+    let read_start = start.saturating_sub(expr.lookback());
+    let mut buf = Vec::new();
+    let leaves = sources
+        .iter()
+        .map(|(name, source)| {
+            buf.clear();
+            source.write_json(Some(read_start), Some(end), &mut buf)?;
+            let values: Vec<Option<f64>> = serde_json::from_slice(&buf)
+                .map_err(|_| Error::Parse(format!("Series '{name}' is not numeric")))?;
+            Ok((*name, values))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    for value in expr
+        .eval(&leaves, end - read_start)
+        .into_iter()
+        .skip(start - read_start)
+    {
+        vec.push(StoredF64::from(value.unwrap_or(f64::NAN)));
+    }
+
+    let _lock = exit.lock();
+    vec.write()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use brk_types::{Height, Version};
+    use vecdb::{Database, ImportableVec};
+
+    use super::*;
+
+    type F64Vec = EagerVec<PcoVec<Height, StoredF64>>;
+
+    fn open(name: &str) -> (Database, F64Vec) {
+        let path = std::env::temp_dir().join(format!("brk_custom_compute_{name}"));
+        let _ = std::fs::remove_dir_all(&path);
+        let db = Database::open(&path).unwrap();
+        let source = F64Vec::forced_import(&db, "a", Version::ZERO).unwrap();
+        (db, source)
+    }
+
+    fn extend(vec: &mut F64Vec, values: impl IntoIterator<Item = f64>) {
+        for value in values {
+            vec.push(StoredF64::from(value));
+        }
+        vec.write().unwrap();
+    }
+
+    fn values(vec: &F64Vec) -> Vec<Option<f64>> {
+        vec.collect()
+            .into_iter()
+            .map(|v| v.is_finite().then_some(*v))
+            .collect()
+    }
+
+    #[test]
+    fn resumes_with_the_points_windows_need() {
+        let (db, mut a) = open("resume");
+        let name = SeriesName::from("a");
+        let expr: SeriesExpr = "sma(a, 2) - lag(a, 2)".parse().unwrap();
+        let exit = Exit::new();
+
+        let mut resumed = F64Vec::forced_import(&db, "resumed", Version::ZERO).unwrap();
+        extend(&mut a, (0..5).map(|i| (i * i) as f64));
+        compute_vec(&mut resumed, 0, &expr, &[(&name, &a)], &exit).unwrap();
+        assert_eq!(resumed.len(), 5);
+
+        extend(&mut a, (5..12).map(|i| (i * i) as f64));
+        compute_vec(&mut resumed, 5, &expr, &[(&name, &a)], &exit).unwrap();
+
+        let mut full = F64Vec::forced_import(&db, "full", Version::ZERO).unwrap();
+        compute_vec(&mut full, 0, &expr, &[(&name, &a)], &exit).unwrap();
+
+        assert_eq!(values(&resumed), values(&full));
+        assert_eq!(values(&full)[..3], [None, None, Some(2.5)]);
+    }
+
+    #[test]
+    fn recomputes_from_the_starting_index() {
+        let (db, mut a) = open("rewrite");
+        let name = SeriesName::from("a");
+        let expr: SeriesExpr = "a * 2".parse().unwrap();
+        let exit = Exit::new();
+
+        let mut doubled = F64Vec::forced_import(&db, "doubled", Version::ZERO).unwrap();
+        extend(&mut a, [1.0, 2.0, 3.0]);
+        compute_vec(&mut doubled, 0, &expr, &[(&name, &a)], &exit).unwrap();
+
+        // The last block is replaced, as after a reorg.
+        a.truncate_if_needed_at(2).unwrap();
+        extend(&mut a, [5.0]);
+        compute_vec(&mut doubled, 2, &expr, &[(&name, &a)], &exit).unwrap();
+
+        assert_eq!(values(&doubled), [Some(2.0), Some(4.0), Some(10.0)]);
+    }
+
+    #[test]
+    fn inputs_must_exist_at_the_index() {
+        let (_db, a) = open("inputs");
+        let sources: [&dyn AnyExportableVec; 1] = [&a];
+
+        let expr: SeriesExpr = "ema(a, 2) / a".parse().unwrap();
+        let found = inputs("x", &expr, Index::Height, &sources).unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].1.name(), "a");
+
+        assert!(inputs("x", &expr, Index::Day1, &sources).is_err());
+        let expr: SeriesExpr = "a + b".parse().unwrap();
+        assert!(inputs("x", &expr, Index::Height, &sources).is_err());
+    }
+}
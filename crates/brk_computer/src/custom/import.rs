@@ -0,0 +1,131 @@
+use std::{collections::BTreeMap, fs, path::Path};
+
+use brk_error::{Error, Result};
+use brk_types::{CustomSeries, SeriesExpr, Version};
+
+use super::{CustomVec, Vecs};
+use crate::internal::db_utils::{finalize_db, open_db};
+
+const VERSION: Version = Version::new(3);
+
+impl Vecs {
+    /// Parses each declaration and imports its vec. Whether the series it
+    /// references exist is checked by `Computer` once every module is
+    /// imported.
+    pub(crate) fn forced_import(
+        parent_path: &Path,
+        parent_version: Version,
+        declared: &[CustomSeries],
+    ) -> Result<Self> {
+        let db = open_db(parent_path, super::DB_NAME, 1_000)?;
+
+        let mut exprs = BTreeMap::new();
+        let mut series = BTreeMap::new();
+
+        for CustomSeries { name, index, expr } in declared {
+            let invalid =
+                |reason: String| Error::Parse(format!("Custom series '{name}': {reason}"));
+
+            if name.is_empty()
+                || !name
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_')
+            {
+                return Err(invalid(
+                    "names may only contain lowercase letters, digits and '_'".to_string(),
+                ));
+            }
+            if series.contains_key(name) {
+                return Err(invalid("declared twice".to_string()));
+            }
+
+            let expr = expr
+                .parse::<SeriesExpr>()
+                .map_err(|e| invalid(e.to_string()))?;
+
+            let mut vec = CustomVec::forced_import(&db, name, parent_version + VERSION, *index)?
+                .ok_or_else(|| {
+                    invalid(format!(
+                        "unsupported index '{}', use height or a date-based index",
+                        index.name()
+                    ))
+                })?;
+
+            // A changed expression resets the series. Its hash is kept next
+            // to the vec as a `Version` only holds 32 bits.
+            let hash_path = db.path().join(format!("{name}.expr"));
+            let hash = expr_hash(&expr.to_string()).to_le_bytes();
+            if fs::read(&hash_path).ok().as_deref() != Some(&hash[..]) {
+                vec.reset()?;
+                fs::write(&hash_path, hash)?;
+            }
+
+            exprs.insert(name.clone(), expr);
+            series.insert(name.clone(), vec);
+        }
+
+        let this = Self { db, exprs, series };
+
+        finalize_db(&this.db, &this)?;
+        Ok(this)
+    }
+}
+
+/// 64-bit FNV-1a of the normalized expression, stable across builds.
+fn expr_hash(expr: &str) -> u64 {
+    expr.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, b| {
+        (hash ^ b as u64).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use brk_types::{Index, StoredF64};
+    use vecdb::{AnyStoredVec, AnyVec, WritableVec};
+
+    use super::*;
+
+    fn import(parent: &Path, expr: &str) -> Vecs {
+        let declared = [CustomSeries {
+            name: "x".to_string(),
+            index: Index::Height,
+            expr: expr.to_string(),
+        }];
+        Vecs::forced_import(parent, Version::ZERO, &declared).unwrap()
+    }
+
+    fn len(vecs: &Vecs) -> usize {
+        let CustomVec::Height(vec) = &vecs.series["x"] else {
+            unreachable!()
+        };
+        vec.len()
+    }
+
+    #[test]
+    fn changed_expression_resets_the_series() {
+        let parent = std::env::temp_dir().join("brk_custom_import");
+        let _ = fs::remove_dir_all(&parent);
+
+        let mut vecs = import(&parent, "a * 2");
+        let CustomVec::Height(vec) = vecs.series.get_mut("x").unwrap() else {
+            unreachable!()
+        };
+        vec.push(StoredF64::from(1.0));
+        vec.write().unwrap();
+        drop(vecs);
+
+        // Same expression once normalized.
+        let vecs = import(&parent, "a*2");
+        assert_eq!(len(&vecs), 1);
+        drop(vecs);
+
+        let vecs = import(&parent, "a * 3");
+        assert_eq!(len(&vecs), 0);
+    }
+
+    #[test]
+    fn expr_hash_uses_all_64_bits() {
+        assert_eq!(expr_hash(""), 0xcbf2_9ce4_8422_2325);
+        assert_eq!(expr_hash("a"), 0xaf63_dc4c_8601_ec8c);
+    }
+}
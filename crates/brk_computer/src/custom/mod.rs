@@ -0,0 +1,7 @@
+mod compute;
+mod import;
+mod vecs;
+
+pub use vecs::{CustomVec, Vecs};
+
+pub const DB_NAME: &str = "custom";
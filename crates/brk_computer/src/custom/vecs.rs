@@ -0,0 +1,123 @@
+use std::{collections::BTreeMap, mem};
+
+use brk_error::Result;
+use brk_traversable::{Traversable, TreeNode};
+use brk_types::{
+    Day1, Day3, Epoch, Halving, Height, Hour1, Hour4, Hour12, Index, Minute10, Minute30, Month1,
+    Month3, Month6, SeriesExpr, SeriesName, StoredF64, Version, Week1, Year1, Year10,
+};
+use vecdb::{
+    AnyExportableVec, Database, EagerVec, Exit, ImportableVec, PcoVec, ReadOnlyClone, Ro, Rw,
+    StorageMode, WritableVec,
+};
+
+use super::compute::compute_vec;
+
+/// Series declared in `series.toml`, keyed by name.
+#[derive(Traversable)]
+pub struct Vecs<M: StorageMode = Rw> {
+    #[traversable(skip)]
+    pub(crate) db: Database,
+    #[traversable(skip)]
+    pub(crate) exprs: BTreeMap<String, SeriesExpr>,
+    #[traversable(flatten)]
+    pub series: BTreeMap<String, CustomVec<M>>,
+}
+
+impl Vecs {
+    /// Moves the series out, leaving none on the same database, so they
+    /// can be computed while the rest of the computer is borrowed.
+    pub(crate) fn take(&mut self) -> Self {
+        Self {
+            db: self.db.clone(),
+            exprs: mem::take(&mut self.exprs),
+            series: mem::take(&mut self.series),
+        }
+    }
+}
+
+macro_rules! custom_vec {
+    ($($index:ident),+ $(,)?) => {
+        /// A custom series, stored at the index it was declared with.
+        pub enum CustomVec<M: StorageMode = Rw> {
+            $($index(M::Stored<EagerVec<PcoVec<$index, StoredF64>>>)),+
+        }
+
+        impl<M: StorageMode> CustomVec<M> {
+            pub fn index(&self) -> Index {
+                match self {
+                    $(Self::$index(_) => Index::$index),+
+                }
+            }
+        }
+
+        impl CustomVec {
+            /// `None` if `index` isn't one custom series can be stored at.
+            pub(crate) fn forced_import(
+                db: &Database,
+                name: &str,
+                version: Version,
+                index: Index,
+            ) -> Result<Option<Self>> {
+                Ok(match index {
+                    $(Index::$index => Some(Self::$index(
+                        EagerVec::forced_import(db, name, version)?,
+                    )),)+
+                    _ => None,
+                })
+            }
+
+            pub(crate) fn reset(&mut self) -> Result<()> {
+                match self {
+                    $(Self::$index(v) => Ok(v.reset()?)),+
+                }
+            }
+
+            pub(crate) fn compute(
+                &mut self,
+                starting_index: usize,
+                expr: &SeriesExpr,
+                sources: &[(&SeriesName, &dyn AnyExportableVec)],
+                exit: &Exit,
+            ) -> Result<()> {
+                match self {
+                    $(Self::$index(v) => compute_vec(v, starting_index, expr, sources, exit)),+
+                }
+            }
+        }
+
+        impl ReadOnlyClone for CustomVec {
+            type ReadOnly = CustomVec<Ro>;
+
+            fn read_only_clone(&self) -> Self::ReadOnly {
+                match self {
+                    $(Self::$index(v) => CustomVec::$index(v.read_only_clone())),+
+                }
+            }
+        }
+
+        custom_vec!(@traversable Rw, $($index),+);
+        custom_vec!(@traversable Ro, $($index),+);
+    };
+    (@traversable $mode:ty, $($index:ident),+) => {
+        impl Traversable for CustomVec<$mode> {
+            fn to_tree_node(&self) -> TreeNode {
+                match self {
+                    $(Self::$index(v) => v.to_tree_node()),+
+                }
+            }
+
+            fn iter_any_exportable(&self) -> impl Iterator<Item = &dyn AnyExportableVec> {
+                let iter: Box<dyn Iterator<Item = &dyn AnyExportableVec>> = match self {
+                    $(Self::$index(v) => Box::new(v.iter_any_exportable())),+
+                };
+                iter
+            }
+        }
+    };
+}
+
+custom_vec!(
+    Height, Minute10, Minute30, Hour1, Hour4, Hour12, Day1, Day3, Week1, Month1, Month3, Month6,
+    Year1, Year10, Halving, Epoch,
+);
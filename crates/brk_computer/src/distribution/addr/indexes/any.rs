@@ -66,6 +66,14 @@ macro_rules! define_any_addr_indexes_vecs {
                 }
             }
 
+            /// Number of addresses of a given type with an index.
+            pub(crate) fn type_len(&self, addr_type: OutputType) -> usize {
+                match addr_type {
+                    $(OutputType::$variant => self.$field.len(),)*
+                    _ => unreachable!("Invalid addr type: {:?}", addr_type),
+                }
+            }
+
             /// Returns a parallel iterator over all vecs for parallel writing.
             pub(crate) fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = &mut dyn AnyStoredVec> {
                 vec![$(&mut self.$field as &mut dyn AnyStoredVec),*].into_par_iter()
@@ -6,6 +6,7 @@ mod exposed;
 mod indexes;
 mod new_addr_count;
 mod reused;
+mod rich_list;
 mod state;
 mod supply;
 mod total_addr_count;
@@ -19,6 +20,7 @@ pub use exposed::{ExposedAddrState, ExposedAddrVecs};
 pub use indexes::AnyAddrIndexesVecs;
 pub use new_addr_count::NewAddrCountVecs;
 pub use reused::{ReusedAddrState, ReusedAddrVecs};
+pub use rich_list::{RichListState, RichListVecs};
 pub use state::{AddrMetricsState, AddrReceivePreState, AddrSendPreState};
 pub use supply::AddrTypeToSupply;
 pub use total_addr_count::TotalAddrCountVecs;
@@ -0,0 +1,88 @@
+//! Rich list: the largest funded addresses by balance.
+//!
+//! [`RichListState`] ranks them incrementally as `process_received` and
+//! `process_sent` change balances, and writes a daily snapshot of the
+//! [`state::RICH_LIST_DEPTH`] largest of each type to `states/rich_list/snapshots`.
+//! The supply held by the 10, 100 and 1000 largest is pushed per block, of
+//! every address type and of each.
+
+use brk_error::Result;
+use brk_traversable::Traversable;
+use brk_types::{Height, Version};
+use rayon::prelude::*;
+use vecdb::{AnyStoredVec, Database, Exit, Rw, StorageMode};
+
+use super::supply::AddrSupplyVecs;
+use crate::{indexes, price};
+
+mod state;
+
+pub use state::RichListState;
+
+/// Supply held by the largest funded addresses.
+#[derive(Traversable)]
+pub struct RichListVecs<M: StorageMode = Rw> {
+    pub top_10: AddrSupplyVecs<M>,
+    pub top_100: AddrSupplyVecs<M>,
+    pub top_1000: AddrSupplyVecs<M>,
+}
+
+impl RichListVecs {
+    pub(crate) fn forced_import(
+        db: &Database,
+        version: Version,
+        indexes: &indexes::Vecs,
+    ) -> Result<Self> {
+        Ok(Self {
+            top_10: AddrSupplyVecs::forced_import(db, "top_10", version, indexes)?,
+            top_100: AddrSupplyVecs::forced_import(db, "top_100", version, indexes)?,
+            top_1000: AddrSupplyVecs::forced_import(db, "top_1000", version, indexes)?,
+        })
+    }
+
+    pub(crate) fn min_stateful_len(&self) -> usize {
+        self.top_10
+            .min_stateful_len()
+            .min(self.top_100.min_stateful_len())
+            .min(self.top_1000.min_stateful_len())
+    }
+
+    pub(crate) fn par_iter_height_mut(
+        &mut self,
+    ) -> impl ParallelIterator<Item = &mut dyn AnyStoredVec> {
+        self.top_10
+            .par_iter_height_mut()
+            .chain(self.top_100.par_iter_height_mut())
+            .chain(self.top_1000.par_iter_height_mut())
+    }
+
+    pub(crate) fn reset_height(&mut self) -> Result<()> {
+        self.top_10.reset_height()?;
+        self.top_100.reset_height()?;
+        self.top_1000.reset_height()?;
+        Ok(())
+    }
+
+    pub(crate) fn push_height(&mut self, state: &RichListState) {
+        for (n, vecs) in [
+            (10, &mut self.top_10),
+            (100, &mut self.top_100),
+            (1000, &mut self.top_1000),
+        ] {
+            let (all, per_type) = state.top_supply(n);
+            vecs.push_height(all, per_type.values().copied());
+        }
+    }
+
+    pub(crate) fn compute_rest(
+        &mut self,
+        max_from: Height,
+        prices: &price::Vecs,
+        exit: &Exit,
+    ) -> Result<()> {
+        self.top_10.compute_rest(max_from, prices, exit)?;
+        self.top_100.compute_rest(max_from, prices, exit)?;
+        self.top_1000.compute_rest(max_from, prices, exit)?;
+        Ok(())
+    }
+}
@@ -0,0 +1,391 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use brk_cohort::ByAddrType;
+use brk_error::Result;
+use brk_types::{
+    AnyAddrDataIndexEnum, Date, Height, OutputType, RichListRaw, RichListRawType, Sats, TypeIndex,
+};
+use rustc_hash::FxHashMap;
+use tracing::info;
+
+use crate::distribution::{
+    addr::{AddrsDataVecs, AnyAddrIndexesVecs},
+    block::AddrCache,
+    compute::VecsReaders,
+};
+
+/// Deepest list kept per address type, and in daily snapshots.
+pub const RICH_LIST_DEPTH: usize = 1_000;
+
+/// Ranked addresses past which the floor is raised
+const PRUNE_LEN: usize = 8 * RICH_LIST_DEPTH;
+
+/// Ranked addresses left once the floor is raised
+const PRUNED_LEN: usize = 2 * RICH_LIST_DEPTH;
+
+const DIR: &str = "rich_list";
+const STATES: &str = "states";
+const SNAPSHOTS: &str = "snapshots";
+
+/// Per-height rankings kept, so a rollback to one of the last writes
+/// resumes without rescanning
+const STATES_TO_KEEP: usize = 10;
+
+/// Largest funded addresses of each type, updated on every balance change.
+///
+/// Each type ranks every address holding at least its floor, so the list is
+/// exact down to the floor. The floor is raised once far more addresses than
+/// [`RICH_LIST_DEPTH`] are ranked, and the type is rescanned from storage in
+/// the rare case too many of them drop below it.
+///
+/// Persisted to `states/rich_list/states/{height}` on every write, the last
+/// [`STATES_TO_KEEP`] kept like the cohort states, rebuilt from address
+/// storage when none matches the starting height.
+#[derive(Debug, Default)]
+pub struct RichListState {
+    rankings: ByAddrType<Ranking>,
+}
+
+#[derive(Debug, Default)]
+struct Ranking {
+    floor: Sats,
+    ranked: BTreeSet<(Sats, TypeIndex)>,
+    balances: FxHashMap<TypeIndex, Sats>,
+}
+
+impl Ranking {
+    #[inline]
+    fn update(&mut self, type_index: TypeIndex, balance: Sats) {
+        if let Some(prev) = self.balances.remove(&type_index) {
+            self.ranked.remove(&(prev, type_index));
+        }
+        if balance == Sats::ZERO || balance < self.floor {
+            return;
+        }
+        self.balances.insert(type_index, balance);
+        self.ranked.insert((balance, type_index));
+        if self.ranked.len() > PRUNE_LEN {
+            self.prune();
+        }
+    }
+
+    /// Raises the floor to the [`PRUNED_LEN`]th largest balance, keeping ties.
+    fn prune(&mut self) {
+        let Some(&(floor, _)) = self.ranked.iter().rev().nth(PRUNED_LEN - 1) else {
+            return;
+        };
+        let kept = self.ranked.split_off(&(floor, TypeIndex::default()));
+        for (_, type_index) in std::mem::replace(&mut self.ranked, kept) {
+            self.balances.remove(&type_index);
+        }
+        self.floor = floor;
+    }
+
+    fn needs_refill(&self) -> bool {
+        self.floor > Sats::ZERO && self.ranked.len() < RICH_LIST_DEPTH
+    }
+
+    fn top(&self) -> impl Iterator<Item = (Sats, TypeIndex)> + '_ {
+        self.ranked.iter().rev().copied()
+    }
+
+    /// Ranks every funded address of `output_type`, the cached data taking
+    /// precedence over storage.
+    fn scan(
+        output_type: OutputType,
+        cache: &AddrCache,
+        vr: &VecsReaders,
+        any_addr_indexes: &AnyAddrIndexesVecs,
+        addrs_data: &AddrsDataVecs,
+    ) -> Result<Self> {
+        let mut ranking = Self::default();
+
+        let reader = vr.addr_reader(output_type);
+        let funded_reader = &vr.any_addr_index_to_any_addr_data.funded;
+        for type_index in (0..any_addr_indexes.type_len(output_type)).map(TypeIndex::from) {
+            if cache.contains(output_type, type_index) {
+                continue;
+            }
+            let AnyAddrDataIndexEnum::Funded(funded_index) = any_addr_indexes
+                .get(output_type, type_index, reader)?
+                .to_enum()
+            else {
+                continue;
+            };
+            let data = addrs_data
+                .funded
+                .get_any_or_read_at(funded_index.into(), funded_reader)?
+                .unwrap();
+            ranking.update(type_index, data.balance());
+        }
+
+        for (type_index, data) in cache.funded_of(output_type) {
+            ranking.update(type_index, data.balance());
+        }
+
+        Ok(ranking)
+    }
+}
+
+impl RichListState {
+    /// Reads the rankings persisted at the end of the block before
+    /// `starting_height`, or ranks every funded address from storage.
+    pub(crate) fn import(
+        states_path: &Path,
+        starting_height: Height,
+        vr: &VecsReaders,
+        any_addr_indexes: &AnyAddrIndexesVecs,
+        addrs_data: &AddrsDataVecs,
+    ) -> Result<Self> {
+        let raw = starting_height
+            .decremented()
+            .and_then(|height| read_state(states_path, height))
+            .filter(|raw| raw.types.len() == OutputType::ADDR_TYPES.len());
+        if let Some(raw) = raw {
+            return Ok(Self::from(raw));
+        }
+
+        info!("Ranking funded addresses for the rich list...");
+        let cache = AddrCache::new();
+        let mut this = Self::default();
+        for (output_type, ranking) in this.rankings.iter_mut() {
+            *ranking = Ranking::scan(output_type, &cache, vr, any_addr_indexes, addrs_data)?;
+        }
+        Ok(this)
+    }
+
+    /// Apply a new balance of an address, zero if it was emptied.
+    #[inline]
+    pub(crate) fn update(&mut self, output_type: OutputType, type_index: TypeIndex, balance: Sats) {
+        self.rankings
+            .get_mut_unwrap(output_type)
+            .update(type_index, balance);
+    }
+
+    /// Rescans the types left with fewer than [`RICH_LIST_DEPTH`] ranked
+    /// addresses above their floor.
+    pub(crate) fn refill_if_needed(
+        &mut self,
+        cache: &AddrCache,
+        vr: &VecsReaders,
+        any_addr_indexes: &AnyAddrIndexesVecs,
+        addrs_data: &AddrsDataVecs,
+    ) -> Result<()> {
+        for (output_type, ranking) in self.rankings.iter_mut() {
+            if ranking.needs_refill() {
+                info!("Rescanning {output_type} addresses for the rich list...");
+                *ranking = Ranking::scan(output_type, cache, vr, any_addr_indexes, addrs_data)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Sum of the `n` largest balances, of every type and of each.
+    pub(crate) fn top_supply(&self, n: usize) -> (Sats, ByAddrType<Sats>) {
+        let mut tops: Vec<_> = self
+            .rankings
+            .values()
+            .map(|r| r.top().map(|(balance, _)| balance).peekable())
+            .collect();
+        let all = std::iter::from_fn(|| {
+            let (i, _) = tops
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(i, top)| top.peek().map(|balance| (i, *balance)))
+                .max_by_key(|(_, balance)| *balance)?;
+            tops[i].next()
+        })
+        .take(n)
+        .sum();
+
+        let mut per_type = ByAddrType::<Sats>::default();
+        for (sum, ranking) in per_type.values_mut().zip(self.rankings.values()) {
+            *sum = ranking.top().take(n).map(|(balance, _)| balance).sum();
+        }
+
+        (all, per_type)
+    }
+
+    /// Persists the rankings at the end of block `height`, dropping the ones
+    /// of later heights, left by a rollback, and the oldest.
+    pub(crate) fn write(&self, states_path: &Path, height: Height) -> Result<()> {
+        let dir = states_path.join(DIR).join(STATES);
+        fs::create_dir_all(&dir)?;
+        let files = read_states_dir(&dir)?;
+        let (kept, stale): (Vec<_>, Vec<_>) = files.into_iter().partition(|(h, _)| *h < height);
+        let outdated = kept.len().saturating_sub(STATES_TO_KEEP - 1);
+        for (_, path) in stale.into_iter().chain(kept.into_iter().take(outdated)) {
+            fs::remove_file(path)?;
+        }
+
+        let tmp = dir.join(format!("{height}.tmp"));
+        fs::write(&tmp, self.to_raw(height, usize::MAX).serialize()?)?;
+        fs::rename(tmp, dir.join(height.to_string()))?;
+        Ok(())
+    }
+
+    /// Writes the [`RICH_LIST_DEPTH`] largest addresses of each type at the
+    /// end of `date`, `height` being its last block.
+    pub(crate) fn write_snapshot(
+        &self,
+        states_path: &Path,
+        height: Height,
+        date: Date,
+    ) -> Result<()> {
+        let dir = states_path.join(DIR).join(SNAPSHOTS);
+        fs::create_dir_all(&dir)?;
+        fs::write(
+            dir.join(date.to_string()),
+            self.to_raw(height, RICH_LIST_DEPTH).serialize()?,
+        )?;
+        Ok(())
+    }
+
+    fn to_raw(&self, height: Height, depth: usize) -> RichListRaw {
+        RichListRaw {
+            height,
+            types: self
+                .rankings
+                .values()
+                .map(|ranking| RichListRawType {
+                    floor: ranking.floor,
+                    entries: ranking
+                        .top()
+                        .take(depth)
+                        .map(|(balance, type_index)| (type_index, balance))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Rankings persisted at the end of block `height`, if kept.
+fn read_state(states_path: &Path, height: Height) -> Option<RichListRaw> {
+    let path = states_path.join(DIR).join(STATES).join(height.to_string());
+    let raw = RichListRaw::deserialize(&fs::read(path).ok()?).ok()?;
+    (raw.height == height).then_some(raw)
+}
+
+fn read_states_dir(dir: &Path) -> Result<BTreeMap<Height, PathBuf>> {
+    Ok(fs::read_dir(dir)?
+        .filter_map(|entry| {
+            let path = entry.ok()?.path();
+            let height = path.file_name()?.to_str()?.parse::<u32>().ok()?;
+            Some((Height::from(height), path))
+        })
+        .collect())
+}
+
+impl From<RichListRaw> for RichListState {
+    fn from(raw: RichListRaw) -> Self {
+        let mut this = Self::default();
+        for (ranking, raw) in this.rankings.values_mut().zip(raw.types) {
+            ranking.floor = raw.floor;
+            for (type_index, balance) in raw.entries {
+                ranking.balances.insert(type_index, balance);
+                ranking.ranked.insert((balance, type_index));
+            }
+        }
+        this
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranking(balances: impl IntoIterator<Item = u64>) -> Ranking {
+        let mut ranking = Ranking::default();
+        for (i, balance) in balances.into_iter().enumerate() {
+            ranking.update(TypeIndex::from(i), Sats::new(balance));
+        }
+        ranking
+    }
+
+    #[test]
+    fn prune_raises_the_floor_keeping_ties() {
+        // One past PRUNE_LEN, with a tie at the PRUNED_LENth balance
+        let mut balances: Vec<u64> = (1..=PRUNE_LEN as u64).collect();
+        balances.push((PRUNE_LEN - PRUNED_LEN + 1) as u64);
+        let ranking = ranking(balances);
+
+        assert_eq!(
+            ranking.floor,
+            Sats::new((PRUNE_LEN - PRUNED_LEN + 1) as u64)
+        );
+        assert_eq!(ranking.ranked.len(), PRUNED_LEN + 1);
+        assert_eq!(ranking.balances.len(), ranking.ranked.len());
+        assert_eq!(
+            ranking.top().next().map(|(balance, _)| balance),
+            Some(Sats::new(PRUNE_LEN as u64))
+        );
+    }
+
+    #[test]
+    fn balances_below_the_floor_are_dropped() {
+        let mut ranking = ranking(1..=PRUNE_LEN as u64 + 1);
+        let floor = ranking.floor;
+        let (top, type_index) = ranking.top().next().unwrap();
+        assert!(top > floor);
+
+        ranking.update(type_index, Sats::new(u64::from(floor) - 1));
+        assert!(!ranking.balances.contains_key(&type_index));
+        ranking.update(TypeIndex::from(usize::MAX >> 40), floor);
+        assert_eq!(ranking.balances.len(), ranking.ranked.len());
+    }
+
+    #[test]
+    fn needs_refill_once_too_few_are_left_above_the_floor() {
+        let mut ranking = ranking(1..=PRUNE_LEN as u64 + 1);
+        assert!(!ranking.needs_refill());
+
+        let emptied: Vec<_> = ranking
+            .top()
+            .take(PRUNED_LEN - RICH_LIST_DEPTH + 1)
+            .map(|(_, type_index)| type_index)
+            .collect();
+        for type_index in emptied {
+            ranking.update(type_index, Sats::ZERO);
+        }
+        assert!(ranking.needs_refill());
+
+        // Never pruned: everything is ranked, nothing to refill from
+        assert!(!self::ranking([5, 3]).needs_refill());
+    }
+
+    #[test]
+    fn write_keeps_the_last_states_and_drops_rolled_back_ones() {
+        let path = std::env::temp_dir().join("brk_rich_list_states");
+        let _ = fs::remove_dir_all(&path);
+
+        let mut state = RichListState::default();
+        state.update(OutputType::P2WPKH, TypeIndex::from(7usize), Sats::new(42));
+        for height in 0..(STATES_TO_KEEP + 5) {
+            state.write(&path, Height::from(height)).unwrap();
+        }
+        let dir = path.join(DIR).join(STATES);
+        let heights = |dir: &Path| read_states_dir(dir).unwrap().into_keys().collect::<Vec<_>>();
+        assert_eq!(
+            heights(&dir),
+            (5..STATES_TO_KEEP + 5).map(Height::from).collect::<Vec<_>>()
+        );
+
+        // Rollback to after block 10, then a new block 11
+        let raw = read_state(&path, Height::from(10usize)).unwrap();
+        let restored = RichListState::from(raw);
+        assert_eq!(restored.top_supply(1).0, Sats::new(42));
+        restored.write(&path, Height::from(11usize)).unwrap();
+        assert_eq!(
+            heights(&dir),
+            (5..=11usize).map(Height::from).collect::<Vec<_>>()
+        );
+        assert!(read_state(&path, Height::from(12usize)).is_none());
+
+        let _ = fs::remove_dir_all(&path);
+    }
+}
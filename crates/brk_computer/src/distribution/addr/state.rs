@@ -2,7 +2,9 @@ use brk_types::{FundedAddrData, Height, OutputType, Sats};
 
 use crate::distribution::{block::TrackingStatus, vecs::AddrMetricsVecs};
 
-use super::{AddrTypeToActivityCounts, AddrTypeToAddrCount, ExposedAddrState, ReusedAddrState};
+use super::{
+    AddrTypeToActivityCounts, AddrTypeToAddrCount, ExposedAddrState, ReusedAddrState, RichListState,
+};
 
 /// Bundle of per-block runtime state for the full address-metrics pipeline.
 /// Feeds `process_received` / `process_sent` and is pushed to [`AddrMetricsVecs`]
@@ -11,7 +13,8 @@ use super::{AddrTypeToActivityCounts, AddrTypeToAddrCount, ExposedAddrState, Reu
 /// Recovery: [`From<(&AddrMetricsVecs, Height)>`] reads the prior block from
 /// disk to seed all persistent running totals. Per-block counters (activity,
 /// and event counts inside each [`ReusedAddrState`]) default to zero and are
-/// cleared at the top of each block via [`Self::reset_per_block`].
+/// cleared at the top of each block via [`Self::reset_per_block`]. The
+/// [`RichListState`] is read from its own file, see [`RichListState::import`].
 #[derive(Debug, Default)]
 pub struct AddrMetricsState {
     pub funded: AddrTypeToAddrCount,
@@ -20,6 +23,7 @@ pub struct AddrMetricsState {
     pub reused: ReusedAddrState,
     pub respent: ReusedAddrState,
     pub exposed: ExposedAddrState,
+    pub rich_list: RichListState,
 }
 
 /// Snapshot of [`FundedAddrData`] taken BEFORE a receive mutates it.
@@ -175,6 +179,7 @@ impl From<(&AddrMetricsVecs, Height)> for AddrMetricsState {
             reused: ReusedAddrState::from((&vecs.reused, starting_height)),
             respent: ReusedAddrState::from((&vecs.respent, starting_height)),
             exposed: ExposedAddrState::from((&vecs.exposed, starting_height)),
+            rich_list: RichListState::default(),
         }
     }
 }
@@ -47,6 +47,17 @@ impl AddrCache {
                 .is_some_and(|m| m.contains_key(&type_index))
     }
 
+    /// Funded addresses of a type in cache.
+    pub(crate) fn funded_of(
+        &self,
+        addr_type: OutputType,
+    ) -> impl Iterator<Item = (TypeIndex, &FundedAddrData)> {
+        self.funded
+            .get(addr_type)
+            .into_iter()
+            .flat_map(|m| m.iter().map(|(type_index, data)| (*type_index, &**data)))
+    }
+
     /// Merge address data into funded cache.
     #[inline]
     pub(crate) fn merge_funded(
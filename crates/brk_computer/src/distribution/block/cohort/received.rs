@@ -46,6 +46,9 @@ pub(crate) fn process_received(
         for (type_index, recv) in aggregated.drain() {
             let (addr_data, status) = lookup.get_or_create_for_receive(output_type, type_index);
             let pre = AddrReceivePreState::capture(addr_data, output_type);
+            let prev = cohorts
+                .is_labeled(output_type, type_index)
+                .then(|| addr_data.cost_basis_snapshot());
 
             if matches!(status, TrackingStatus::New | TrackingStatus::WasEmpty) {
                 addr_data.receive_outputs(recv.total_value, price, recv.output_count);
@@ -105,7 +108,21 @@ pub(crate) fn process_received(
                 }
             }
 
+            if let Some(prev) = &prev {
+                cohorts.receive_labeled(
+                    output_type,
+                    type_index,
+                    addr_data,
+                    prev,
+                    recv.total_value,
+                    price,
+                    recv.output_count,
+                );
+            }
             state.on_receive_applied(output_type, status, addr_data, &pre, recv.output_count);
+            state
+                .rich_list
+                .update(output_type, type_index, addr_data.balance());
         }
     }
 }
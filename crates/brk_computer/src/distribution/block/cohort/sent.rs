@@ -49,6 +49,9 @@ pub(crate) fn process_sent(
             for (type_index, value) in vec {
                 let addr_data = lookup.get_for_send(output_type, type_index);
                 let pre = AddrSendPreState::capture(addr_data, output_type);
+                let prev = cohorts
+                    .is_labeled(output_type, type_index)
+                    .then(|| addr_data.cost_basis_snapshot());
 
                 let prev_balance = addr_data.balance();
                 let new_balance = prev_balance.checked_sub(value).unwrap();
@@ -77,10 +80,26 @@ pub(crate) fn process_sent(
                     also_received,
                     will_be_empty,
                 );
+                state
+                    .rich_list
+                    .update(output_type, type_index, addr_data.balance());
 
                 if will_be_empty || crossing_boundary {
                     cohort_state.subtract(addr_data);
                 }
+                if let Some(prev) = &prev {
+                    cohorts.send_labeled(
+                        output_type,
+                        type_index,
+                        addr_data,
+                        prev,
+                        value,
+                        current_price,
+                        prev_price,
+                        peak_price,
+                        age,
+                    );
+                }
                 if will_be_empty {
                     lookup.move_to_empty(output_type, type_index);
                 } else if crossing_boundary {
@@ -1,16 +1,24 @@
-use std::path::Path;
+use std::{collections::BTreeMap, path::Path};
 
 use brk_cohort::{AddrGroups, AmountRange, Filter, Filtered, OverAmount, UnderAmount};
 use brk_error::Result;
-use brk_indexer::Lengths;
+use brk_indexer::{Indexer, Lengths};
 use brk_traversable::Traversable;
-use brk_types::{Height, Sats, StoredU64, Version};
+use brk_types::{
+    AddrHash, Age, Cents, CostBasisSnapshot, FundedAddrData, Height, OutputType, Sats, StoredU64,
+    TypeIndex, Version, addr_labels,
+};
 use derive_more::{Deref, DerefMut};
 use rayon::prelude::*;
+use rustc_hash::FxHashMap;
+use smallvec::SmallVec;
 use vecdb::{AnyStoredVec, Database, Exit, ReadableVec, Rw, StorageMode};
 
 use crate::{
-    distribution::DynCohortVecs,
+    distribution::{
+        DynCohortVecs,
+        state::{AddrCohortState, MinimalRealizedState},
+    },
     indexes,
     internal::{WindowStartVec, Windows},
     price,
@@ -22,7 +30,19 @@ const VERSION: Version = Version::new(0);
 
 /// All Addr cohorts organized by filter type.
 #[derive(Deref, DerefMut, Traversable)]
-pub struct AddrCohorts<M: StorageMode = Rw>(AddrGroups<AddrCohortVecs<M>>);
+pub struct AddrCohorts<M: StorageMode = Rw> {
+    #[deref]
+    #[deref_mut]
+    #[traversable(flatten)]
+    groups: AddrGroups<AddrCohortVecs<M>>,
+    /// One stateful cohort per address label (see
+    /// [`brk_types::init_addr_labels`]), by name.
+    pub label: BTreeMap<String, AddrCohortVecs<M>>,
+    /// Labels of each labeled address seen on chain, as positions in
+    /// `label`, which lists them by name like [`brk_types::AddrLabels`].
+    #[traversable(skip)]
+    labeled: FxHashMap<(OutputType, TypeIndex), SmallVec<[usize; 1]>>,
+}
 
 impl AddrCohorts {
     /// Import all Addr cohorts from database.
@@ -45,11 +65,142 @@ impl AddrCohorts {
         let full = |f: Filter, name: &'static str| create(f, name, true);
         let none = |f: Filter, name: &'static str| create(f, name, false);
 
-        Ok(Self(AddrGroups {
-            amount_range: AmountRange::try_new(&full)?,
-            under_amount: UnderAmount::try_new(&none)?,
-            over_amount: OverAmount::try_new(&none)?,
-        }))
+        // A label's addresses changing invalidates its series, and with
+        // them the whole distribution, which restarts from genesis.
+        let mut label = BTreeMap::new();
+        for addr_label in addr_labels().iter() {
+            let fingerprint = addr_label.fingerprint();
+            let version = v + Version::new(((fingerprint >> 48) ^ (fingerprint & 0xffff)) as u32);
+            label.insert(
+                addr_label.name.clone(),
+                AddrCohortVecs::forced_import_label(
+                    db,
+                    &addr_label.name,
+                    version,
+                    indexes,
+                    states_path,
+                    cached_starts,
+                )?,
+            );
+        }
+
+        Ok(Self {
+            groups: AddrGroups {
+                amount_range: AmountRange::try_new(&full)?,
+                under_amount: UnderAmount::try_new(&none)?,
+                over_amount: OverAmount::try_new(&none)?,
+            },
+            label,
+            labeled: FxHashMap::default(),
+        })
+    }
+
+    /// Looks the labels' addresses up in the indexer. The ones not seen
+    /// on chain yet have nothing to track until they are.
+    pub(crate) fn resolve_labels(&mut self, indexer: &Indexer) -> Result<()> {
+        self.labeled.clear();
+        for (i, label) in addr_labels().iter().enumerate() {
+            for bytes in &label.addrs {
+                let output_type = OutputType::from(bytes);
+                let type_index = indexer
+                    .stores
+                    .addr_type_to_addr_hash_to_addr_index
+                    .get_unwrap(output_type)
+                    .get(&AddrHash::from(bytes))?
+                    .map(|cow| cow.into_owned());
+                if let Some(type_index) = type_index {
+                    self.labeled
+                        .entry((output_type, type_index))
+                        .or_default()
+                        .push(i);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the address carries a label.
+    pub(crate) fn is_labeled(&self, output_type: OutputType, type_index: TypeIndex) -> bool {
+        !self.labeled.is_empty() && self.labeled.contains_key(&(output_type, type_index))
+    }
+
+    /// Applies a receive, already applied to `addr_data` by its amount
+    /// cohort, to the cohorts of the address's labels.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn receive_labeled(
+        &mut self,
+        output_type: OutputType,
+        type_index: TypeIndex,
+        addr_data: &FundedAddrData,
+        prev: &CostBasisSnapshot,
+        value: Sats,
+        price: Cents,
+        output_count: u32,
+    ) {
+        self.for_each_label_state(output_type, type_index, |state| {
+            state.receive_applied(addr_data, prev, value, price, output_count)
+        });
+    }
+
+    /// Applies a spend, already applied to `addr_data` by its amount
+    /// cohort, to the cohorts of the address's labels.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn send_labeled(
+        &mut self,
+        output_type: OutputType,
+        type_index: TypeIndex,
+        addr_data: &FundedAddrData,
+        prev: &CostBasisSnapshot,
+        value: Sats,
+        current_price: Cents,
+        prev_price: Cents,
+        ath: Cents,
+        age: Age,
+    ) {
+        self.for_each_label_state(output_type, type_index, |state| {
+            state.send_applied(addr_data, prev, value, current_price, prev_price, ath, age)
+        });
+    }
+
+    fn for_each_label_state(
+        &mut self,
+        output_type: OutputType,
+        type_index: TypeIndex,
+        mut f: impl FnMut(&mut AddrCohortState<MinimalRealizedState>),
+    ) {
+        let Some(labels) = self.labeled.get(&(output_type, type_index)) else {
+            return;
+        };
+        for (i, vecs) in self.label.values_mut().enumerate() {
+            if labels.contains(&i) {
+                f(vecs.state.as_mut().unwrap());
+            }
+        }
+    }
+
+    pub(crate) fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = &mut AddrCohortVecs> {
+        self.groups
+            .par_iter_mut()
+            .chain(self.label.par_iter_mut().map(|(_, vecs)| vecs))
+    }
+
+    /// The stateful cohorts: the amount ranges and the labels.
+    pub(crate) fn iter_separate(&self) -> impl Iterator<Item = &AddrCohortVecs> {
+        self.groups.iter_separate().chain(self.label.values())
+    }
+
+    pub(crate) fn iter_separate_mut(&mut self) -> impl Iterator<Item = &mut AddrCohortVecs> {
+        self.groups
+            .iter_separate_mut()
+            .chain(self.label.values_mut())
+    }
+
+    pub(crate) fn par_iter_separate_mut(
+        &mut self,
+    ) -> impl ParallelIterator<Item = &mut AddrCohortVecs> {
+        self.groups
+            .par_iter_separate_mut()
+            .chain(self.label.par_iter_mut().map(|(_, vecs)| vecs))
     }
 
     /// Apply a function to each aggregate cohort with its source cohorts (in parallel).
@@ -57,13 +208,13 @@ impl AddrCohorts {
     where
         F: Fn(&mut AddrCohortVecs, Vec<&AddrCohortVecs>) -> Result<()> + Sync,
     {
-        let by_amount_range = &self.0.amount_range;
+        let by_amount_range = &self.groups.amount_range;
 
         let pairs: Vec<_> = self
-            .0
+            .groups
             .over_amount
             .iter_mut()
-            .chain(self.0.under_amount.iter_mut())
+            .chain(self.groups.under_amount.iter_mut())
             .map(|vecs| {
                 let filter = vecs.filter().clone();
                 (
@@ -114,7 +265,7 @@ impl AddrCohorts {
         all_utxo_count: &impl ReadableVec<Height, StoredU64>,
         exit: &Exit,
     ) -> Result<()> {
-        self.0.par_iter_mut().try_for_each(|v| {
+        self.par_iter_mut().try_for_each(|v| {
             v.compute_rest_part2(
                 prices,
                 starting_lengths,
@@ -130,8 +281,9 @@ impl AddrCohorts {
         &mut self,
     ) -> impl ParallelIterator<Item = &mut dyn AnyStoredVec> {
         // Collect all vecs from all cohorts
-        self.0
+        self.groups
             .iter_mut()
+            .chain(self.label.values_mut())
             .flat_map(|v| v.par_iter_vecs_mut().collect::<Vec<_>>())
             .collect::<Vec<_>>()
             .into_par_iter()
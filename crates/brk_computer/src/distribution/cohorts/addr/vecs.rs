@@ -11,7 +11,7 @@ use vecdb::{AnyStoredVec, AnyVec, Database, Exit, ReadableVec, Rw, StorageMode,
 use crate::{
     distribution::state::{AddrCohortState, MinimalRealizedState},
     indexes,
-    internal::{PerBlockWithDeltas, WindowStartVec, Windows},
+    internal::{PerBlockWithDeltas, ValuePerBlockCumulativeRolling, WindowStartVec, Windows},
     price,
 };
 
@@ -29,6 +29,9 @@ pub struct AddrCohortVecs<M: StorageMode = Rw> {
     pub metrics: MinimalCohortMetrics<M>,
 
     pub addr_count: PerBlockWithDeltas<StoredU64, StoredI64, BasisPointsSigned32, M>,
+    /// Received by the cohort's addresses, moves between them included.
+    /// Label cohorts only, their outflow being `transfer_volume`.
+    pub inflow: Option<ValuePerBlockCumulativeRolling<M>>,
 }
 
 impl AddrCohortVecs {
@@ -66,9 +69,41 @@ impl AddrCohortVecs {
             state: states_path.map(|path| Box::new(AddrCohortState::new(path, &full_name))),
             metrics: MinimalCohortMetrics::forced_import(&cfg)?,
             addr_count,
+            inflow: None,
         })
     }
 
+    /// A stateful cohort of the addresses carrying the label `name`.
+    pub(crate) fn forced_import_label(
+        db: &Database,
+        name: &str,
+        version: Version,
+        indexes: &indexes::Vecs,
+        states_path: &Path,
+        cached_starts: &Windows<&WindowStartVec>,
+    ) -> Result<Self> {
+        let filter = Filter::Label(name.to_string());
+        let full_name = CohortContext::Addr.full_name(&filter, name);
+        let inflow = ValuePerBlockCumulativeRolling::forced_import(
+            db,
+            &format!("{full_name}_inflow"),
+            version,
+            indexes,
+            cached_starts,
+        )?;
+        let mut this = Self::forced_import(
+            db,
+            filter,
+            name,
+            version,
+            indexes,
+            Some(states_path),
+            cached_starts,
+        )?;
+        this.inflow = Some(inflow);
+        Ok(this)
+    }
+
     pub(crate) fn reset_starting_height(&mut self) {
         self.starting_height = Some(Height::ZERO);
     }
@@ -78,6 +113,9 @@ impl AddrCohortVecs {
     ) -> impl ParallelIterator<Item = &mut dyn AnyStoredVec> {
         let mut vecs: Vec<&mut dyn AnyStoredVec> = Vec::new();
         vecs.push(&mut self.addr_count.height as &mut dyn AnyStoredVec);
+        if let Some(inflow) = self.inflow.as_mut() {
+            vecs.push(&mut inflow.block.sats);
+        }
         vecs.extend(self.metrics.collect_all_vecs_mut());
         vecs.into_par_iter()
     }
@@ -102,6 +140,11 @@ impl DynCohortVecs for AddrCohortVecs {
             .height
             .len()
             .min(self.metrics.min_stateful_len())
+            .min(
+                self.inflow
+                    .as_ref()
+                    .map_or(usize::MAX, |inflow| inflow.block.sats.len()),
+            )
     }
 
     fn reset_state_starting_height(&mut self) {
@@ -167,6 +210,9 @@ impl DynCohortVecs for AddrCohortVecs {
             self.metrics.outputs.push_state(&state.inner);
             self.metrics.activity.push_state(&state.inner);
             self.metrics.realized.push_state(&state.inner);
+            if let Some(inflow) = self.inflow.as_mut() {
+                inflow.block.sats.push(state.inner.received);
+            }
         }
     }
 
@@ -178,6 +224,9 @@ impl DynCohortVecs for AddrCohortVecs {
         starting_lengths: &Lengths,
         exit: &Exit,
     ) -> Result<()> {
+        if let Some(inflow) = self.inflow.as_mut() {
+            inflow.compute_rest(starting_lengths.height, prices, exit)?;
+        }
         self.metrics
             .compute_rest_part1(prices, starting_lengths, exit)
     }
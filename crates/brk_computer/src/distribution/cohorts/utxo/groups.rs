@@ -30,7 +30,7 @@ use crate::{
     price,
 };
 
-use super::{fenwick::CostBasisFenwick, vecs::UTXOCohortVecs};
+use super::{fenwick::CostBasisFenwick, intersections::UTXOIntersections, vecs::UTXOCohortVecs};
 
 const VERSION: Version = Version::new(0);
 
@@ -51,6 +51,7 @@ pub struct UTXOCohorts<M: StorageMode = Rw> {
     pub under_amount: UnderAmount<UTXOCohortVecs<MinimalCohortMetrics<M>>>,
     #[traversable(rename = "type")]
     pub type_: SpendableType<UTXOCohortVecs<TypeCohortMetrics<M>>>,
+    pub intersection: UTXOIntersections<M>,
     pub profitability: ProfitabilityMetrics<M>,
     pub matured: AgeRange<ValuePerBlockCumulativeRolling<M>>,
     #[traversable(skip)]
@@ -69,7 +70,8 @@ pub(crate) struct UTXOCohortsTransientState {
 
 impl UTXOCohorts<Rw> {
     /// Separate cohorts currently total 72:
-    /// 21 age + 5 epoch + 18 class + 2 entry + 15 amount + 11 spendable type.
+    /// 21 age + 5 epoch + 18 class + 2 entry + 15 amount + 11 spendable type,
+    /// plus the configured intersections.
     /// Keep small headroom because this is only Vec allocation capacity.
     const SEPARATE_COHORT_CAPACITY: usize = 82;
 
@@ -200,6 +202,9 @@ impl UTXOCohorts<Rw> {
 
         let type_ = SpendableType::try_new(&type_separate)?;
 
+        let intersection =
+            UTXOIntersections::forced_import(db, v, indexes, states_path, cached_starts)?;
+
         // Phase 3: Import "all" cohort with pre-imported supply.
         let all = UTXOCohortVecs::new(
             None,
@@ -306,6 +311,7 @@ impl UTXOCohorts<Rw> {
             class,
             entry,
             type_,
+            intersection,
             under_age,
             over_age,
             age_range,
@@ -409,6 +415,7 @@ impl UTXOCohorts<Rw> {
             entry,
             amount_range,
             type_,
+            intersection,
             ..
         } = self;
         age_range
@@ -423,6 +430,7 @@ impl UTXOCohorts<Rw> {
                     .map(|x| x as &mut dyn DynCohortVecs),
             )
             .chain(type_.par_iter_mut().map(|x| x as &mut dyn DynCohortVecs))
+            .chain(intersection.par_iter_separate_mut())
     }
 
     /// Sequential mutable iterator over all separate (stateful) cohorts.
@@ -435,6 +443,7 @@ impl UTXOCohorts<Rw> {
             entry,
             amount_range,
             type_,
+            intersection,
             ..
         } = self;
         age_range
@@ -445,6 +454,7 @@ impl UTXOCohorts<Rw> {
             .chain(entry.iter_mut().map(|x| x as &mut dyn DynCohortVecs))
             .chain(amount_range.iter_mut().map(|x| x as &mut dyn DynCohortVecs))
             .chain(type_.iter_mut().map(|x| x as &mut dyn DynCohortVecs))
+            .chain(intersection.iter_separate_mut())
     }
 
     /// Immutable iterator over all separate (stateful) cohorts.
@@ -457,6 +467,7 @@ impl UTXOCohorts<Rw> {
             .chain(self.entry.iter().map(|x| x as &dyn DynCohortVecs))
             .chain(self.amount_range.iter().map(|x| x as &dyn DynCohortVecs))
             .chain(self.type_.iter().map(|x| x as &dyn DynCohortVecs))
+            .chain(self.intersection.iter_separate())
     }
 
     pub(crate) fn compute_overlapping_vecs(
@@ -574,6 +585,7 @@ impl UTXOCohorts<Rw> {
                     .map(|x| x as &mut dyn DynCohortVecs),
             );
             all.extend(self.type_.iter_mut().map(|x| x as &mut dyn DynCohortVecs));
+            all.extend(self.intersection.iter_separate_mut());
             all.into_par_iter()
                 .try_for_each(|v| v.compute_rest_part1(prices, starting_lengths, exit))?;
         }
@@ -653,6 +665,7 @@ impl UTXOCohorts<Rw> {
             class,
             entry,
             type_,
+            intersection,
             ..
         } = self;
 
@@ -755,6 +768,17 @@ impl UTXOCohorts<Rw> {
                         .compute_rest_part2(prices, starting_lengths, ss, au, exit)
                 })
             }),
+            Box::new(|| {
+                intersection.compute_rest_part2(
+                    blocks,
+                    prices,
+                    starting_lengths,
+                    height_to_market_cap,
+                    ss,
+                    au,
+                    exit,
+                )
+            }),
         ];
 
         tasks
@@ -803,6 +827,7 @@ impl UTXOCohorts<Rw> {
         for v in self.type_.iter_mut() {
             vecs.extend(v.metrics.collect_all_vecs_mut());
         }
+        vecs.extend(self.intersection.collect_all_vecs_mut());
         vecs.extend(self.profitability.collect_all_vecs_mut());
         for v in self.matured.iter_mut() {
             let inner = &mut v.inner;
@@ -834,6 +859,7 @@ impl UTXOCohorts<Rw> {
             .min(Height::from(self.all.min_stateful_len()))
             .min(Height::from(self.sth.min_stateful_len()))
             .min(Height::from(self.lth.min_stateful_len()))
+            .min(self.intersection.min_supply_state_len())
     }
 
     /// Import state for all separate cohorts at or before given height.
@@ -0,0 +1,692 @@
+use std::{collections::BTreeMap, ops::Range, path::Path};
+
+use brk_cohort::{CohortContext, CohortTier, Filter, Intersection, intersections};
+use brk_error::Result;
+use brk_indexer::Lengths;
+use brk_traversable::Traversable;
+use brk_types::{
+    Age, Cents, CostBasisSnapshot, Dollars, Height, ONE_HOUR_IN_SEC, Sats, StoredU64, SupplyState,
+    Timestamp, Version,
+};
+use rayon::prelude::*;
+use vecdb::{
+    AnyStoredVec, AnyVec, BytesVec, Database, Exit, ImportOptions, ImportableVec, ReadableVec, Rw,
+    Stamp, StorageMode, VecIndex, WritableVec,
+};
+
+use crate::{
+    blocks,
+    distribution::{
+        DynCohortVecs,
+        metrics::{
+            BasicCohortMetrics, CohortMetricsBase, CoreCohortMetrics, ExtendedCohortMetrics,
+            ImportConfig, MinimalCohortMetrics,
+        },
+        state::{BlockState, CostBasisOps, RealizedOps, Transacted, UTXOCohortState},
+    },
+    indexes,
+    internal::{WindowStartVec, Windows},
+    price,
+};
+
+use super::vecs::UTXOCohortVecs;
+
+const SAVED_STAMPED_CHANGES: u16 = 10;
+
+/// User-declared cohorts (see [`brk_cohort::init_intersections`]), one map
+/// per metrics tier.
+///
+/// A cohort's age boundaries can't be tracked through the age range
+/// cohorts, which mix every type and amount, so each one keeps what the
+/// outputs of its types and amounts left per creation height, moved in
+/// and out of the cohort as it crosses them, like `chain_state`.
+#[derive(Traversable)]
+pub struct UTXOIntersections<M: StorageMode = Rw> {
+    #[traversable(flatten)]
+    pub minimal: BTreeMap<String, UTXOCohortVecs<MinimalCohortMetrics<M>>>,
+    #[traversable(flatten)]
+    pub basic: BTreeMap<String, UTXOCohortVecs<BasicCohortMetrics<M>>>,
+    #[traversable(flatten)]
+    pub core: BTreeMap<String, UTXOCohortVecs<CoreCohortMetrics<M>>>,
+    #[traversable(flatten)]
+    pub extended: BTreeMap<String, UTXOCohortVecs<ExtendedCohortMetrics<M>>>,
+    #[traversable(hidden)]
+    pub supply_state: BTreeMap<String, M::Stored<BytesVec<Height, SupplyState>>>,
+    /// Per cohort (by [`Intersection::index`]) and creation height, the
+    /// unspent outputs of its types and amounts, whatever their age.
+    /// Persisted via `supply_state`.
+    #[traversable(skip)]
+    chains: Vec<Vec<SupplyState>>,
+}
+
+impl UTXOIntersections<Rw> {
+    pub(crate) fn forced_import(
+        db: &Database,
+        version: Version,
+        indexes: &indexes::Vecs,
+        states_path: &Path,
+        cached_starts: &Windows<&WindowStartVec>,
+    ) -> Result<Self> {
+        let mut this = Self {
+            minimal: BTreeMap::new(),
+            basic: BTreeMap::new(),
+            core: BTreeMap::new(),
+            extended: BTreeMap::new(),
+            supply_state: BTreeMap::new(),
+            chains: vec![Vec::new(); intersections().len()],
+        };
+
+        for intersection in intersections() {
+            let name = &intersection.name;
+            let filter = Filter::Intersection(intersection.clone());
+            let full_name = CohortContext::Utxo.full_name(&filter, name);
+            // A changed definition invalidates the cohort's series
+            let version = version + Version::new(definition_hash(intersection));
+            let cfg = ImportConfig {
+                db,
+                filter: &filter,
+                full_name: &full_name,
+                version,
+                indexes,
+                cached_starts,
+            };
+
+            match intersection.tier {
+                CohortTier::Minimal => {
+                    let state = Some(Box::new(UTXOCohortState::new(states_path, &full_name)));
+                    let metrics = MinimalCohortMetrics::forced_import(&cfg)?;
+                    this.minimal
+                        .insert(name.clone(), UTXOCohortVecs::new(state, metrics));
+                }
+                CohortTier::Basic => {
+                    let state = Some(Box::new(UTXOCohortState::new(states_path, &full_name)));
+                    let metrics = BasicCohortMetrics::forced_import(&cfg)?;
+                    this.basic
+                        .insert(name.clone(), UTXOCohortVecs::new(state, metrics));
+                }
+                CohortTier::Core => {
+                    let state = Some(Box::new(UTXOCohortState::new(states_path, &full_name)));
+                    let metrics = CoreCohortMetrics::forced_import(&cfg)?;
+                    this.core
+                        .insert(name.clone(), UTXOCohortVecs::new(state, metrics));
+                }
+                CohortTier::Extended => {
+                    let state = Some(Box::new(UTXOCohortState::new(states_path, &full_name)));
+                    let metrics = ExtendedCohortMetrics::forced_import(&cfg)?;
+                    this.extended
+                        .insert(name.clone(), UTXOCohortVecs::new(state, metrics));
+                }
+            }
+
+            this.supply_state.insert(
+                name.clone(),
+                BytesVec::forced_import_with(
+                    ImportOptions::new(db, &format!("{full_name}_supply_state"), version)
+                        .with_saved_stamped_changes(SAVED_STAMPED_CHANGES),
+                )?,
+            );
+        }
+
+        Ok(this)
+    }
+
+    pub(crate) fn par_iter_separate_mut(
+        &mut self,
+    ) -> impl ParallelIterator<Item = &mut dyn DynCohortVecs> {
+        self.minimal
+            .par_iter_mut()
+            .map(|(_, x)| x as &mut dyn DynCohortVecs)
+            .chain(
+                self.basic
+                    .par_iter_mut()
+                    .map(|(_, x)| x as &mut dyn DynCohortVecs),
+            )
+            .chain(
+                self.core
+                    .par_iter_mut()
+                    .map(|(_, x)| x as &mut dyn DynCohortVecs),
+            )
+            .chain(
+                self.extended
+                    .par_iter_mut()
+                    .map(|(_, x)| x as &mut dyn DynCohortVecs),
+            )
+    }
+
+    pub(crate) fn iter_separate_mut(&mut self) -> impl Iterator<Item = &mut dyn DynCohortVecs> {
+        self.minimal
+            .values_mut()
+            .map(|x| x as &mut dyn DynCohortVecs)
+            .chain(self.basic.values_mut().map(|x| x as &mut dyn DynCohortVecs))
+            .chain(self.core.values_mut().map(|x| x as &mut dyn DynCohortVecs))
+            .chain(
+                self.extended
+                    .values_mut()
+                    .map(|x| x as &mut dyn DynCohortVecs),
+            )
+    }
+
+    pub(crate) fn iter_separate(&self) -> impl Iterator<Item = &dyn DynCohortVecs> {
+        self.minimal
+            .values()
+            .map(|x| x as &dyn DynCohortVecs)
+            .chain(self.basic.values().map(|x| x as &dyn DynCohortVecs))
+            .chain(self.core.values().map(|x| x as &dyn DynCohortVecs))
+            .chain(self.extended.values().map(|x| x as &dyn DynCohortVecs))
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn compute_rest_part2(
+        &mut self,
+        blocks: &blocks::Vecs,
+        prices: &price::Vecs,
+        starting_lengths: &Lengths,
+        height_to_market_cap: &impl ReadableVec<Height, Dollars>,
+        all_supply_sats: &impl ReadableVec<Height, Sats>,
+        all_utxo_count: &impl ReadableVec<Height, StoredU64>,
+        exit: &Exit,
+    ) -> Result<()> {
+        let (sl, ss, au) = (starting_lengths, all_supply_sats, all_utxo_count);
+        self.minimal
+            .par_iter_mut()
+            .try_for_each(|(_, v)| v.metrics.compute_rest_part2(prices, sl, ss, au, exit))?;
+        self.basic
+            .par_iter_mut()
+            .try_for_each(|(_, v)| v.metrics.compute_rest_part2(prices, sl, ss, au, exit))?;
+        self.core
+            .par_iter_mut()
+            .try_for_each(|(_, v)| v.metrics.compute_rest_part2(prices, sl, ss, au, exit))?;
+        self.extended.par_iter_mut().try_for_each(|(_, v)| {
+            v.metrics
+                .compute_rest_part2(blocks, prices, sl, height_to_market_cap, ss, au, exit)
+        })
+    }
+
+    pub(crate) fn collect_all_vecs_mut(&mut self) -> Vec<&mut dyn AnyStoredVec> {
+        let mut vecs: Vec<&mut dyn AnyStoredVec> = Vec::new();
+        for v in self.minimal.values_mut() {
+            vecs.extend(v.metrics.collect_all_vecs_mut());
+        }
+        for v in self.basic.values_mut() {
+            vecs.extend(v.metrics.collect_all_vecs_mut());
+        }
+        for v in self.core.values_mut() {
+            vecs.extend(v.metrics.collect_all_vecs_mut());
+        }
+        for v in self.extended.values_mut() {
+            vecs.extend(v.metrics.collect_all_vecs_mut());
+        }
+        vecs.extend(
+            self.supply_state
+                .values_mut()
+                .map(|v| v as &mut dyn AnyStoredVec),
+        );
+        vecs
+    }
+
+    pub(crate) fn min_supply_state_len(&self) -> Height {
+        self.supply_state
+            .values()
+            .map(|v| Height::from(v.len()))
+            .min()
+            .unwrap_or(Height::MAX)
+    }
+
+    /// Outputs created this block, of age zero.
+    pub(crate) fn receive(&mut self, received: &Transacted, price: Cents) {
+        let Self {
+            minimal,
+            basic,
+            core,
+            extended,
+            chains,
+            ..
+        } = self;
+        receive(
+            states_mut(minimal, basic, core, extended),
+            chains,
+            received,
+            price,
+        );
+    }
+
+    /// Outputs created at `receive_height` and spent this block.
+    pub(crate) fn send(
+        &mut self,
+        receive_height: Height,
+        sent: &Transacted,
+        current_price: Cents,
+        prev_price: Cents,
+        peak_price: Cents,
+        age: Age,
+    ) {
+        let Self {
+            minimal,
+            basic,
+            core,
+            extended,
+            chains,
+            ..
+        } = self;
+        send(
+            states_mut(minimal, basic, core, extended),
+            chains,
+            receive_height,
+            sent,
+            current_price,
+            prev_price,
+            peak_price,
+            age,
+        );
+    }
+
+    /// Moves the blocks whose age crossed a cohort's boundaries between
+    /// `prev_timestamp` and `timestamp` in or out of it.
+    pub(crate) fn tick_tock(
+        &mut self,
+        chain_state: &[BlockState],
+        prev_timestamp: Timestamp,
+        timestamp: Timestamp,
+    ) {
+        let Self {
+            minimal,
+            basic,
+            core,
+            extended,
+            chains,
+            ..
+        } = self;
+        tick_tock(
+            states_mut(minimal, basic, core, extended),
+            chains,
+            chain_state,
+            prev_timestamp,
+            timestamp,
+        );
+    }
+
+    /// Rewrites the stored supply from the earliest height a send modified.
+    pub(crate) fn push_supply_states(&mut self, min_modified: Option<Height>) -> Result<()> {
+        for intersection in intersections() {
+            let vec = self.supply_state.get_mut(&intersection.name).unwrap();
+            let len = vec.len();
+            let truncate_to = min_modified.map_or(len, |h| h.to_usize().min(len));
+            vec.truncate_if_needed(Height::from(truncate_to))?;
+            for supply in &self.chains[intersection.index][truncate_to..] {
+                vec.push(*supply);
+            }
+        }
+        Ok(())
+    }
+
+    /// Reloads the per-height supply of the cohorts whose in-memory copy
+    /// doesn't stop at `height`.
+    pub(crate) fn rebuild_chains(&mut self, height: Height) {
+        for intersection in intersections() {
+            let chain = &mut self.chains[intersection.index];
+            if chain.len() != height.to_usize() {
+                *chain =
+                    self.supply_state[&intersection.name].collect_range_at(0, height.to_usize());
+            }
+        }
+    }
+
+    pub(crate) fn rollback_before(&mut self, stamp: Stamp) -> Result<Vec<Stamp>> {
+        self.supply_state
+            .values_mut()
+            .map(|v| Ok(v.rollback_before(stamp)?))
+            .collect()
+    }
+
+    pub(crate) fn reset(&mut self) -> Result<()> {
+        for v in self.supply_state.values_mut() {
+            v.reset()?;
+        }
+        self.chains.iter_mut().for_each(Vec::clear);
+        Ok(())
+    }
+}
+
+fn receive<'a>(
+    states: impl Iterator<Item = (&'a Intersection, &'a mut dyn IntersectionState)>,
+    chains: &mut [Vec<SupplyState>],
+    received: &Transacted,
+    price: Cents,
+) {
+    for (intersection, state) in states {
+        let supply = received.intersection(intersection.index);
+        chains[intersection.index].push(supply);
+        if supply.utxo_count > 0 && intersection.contains_age(0) {
+            state.receive(&supply, price);
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn send<'a>(
+    states: impl Iterator<Item = (&'a Intersection, &'a mut dyn IntersectionState)>,
+    chains: &mut [Vec<SupplyState>],
+    receive_height: Height,
+    sent: &Transacted,
+    current_price: Cents,
+    prev_price: Cents,
+    peak_price: Cents,
+    age: Age,
+) {
+    for (intersection, state) in states {
+        let supply = sent.intersection(intersection.index);
+        if supply.utxo_count == 0 {
+            continue;
+        }
+        chains[intersection.index][receive_height.to_usize()] -= &supply;
+        if intersection.contains_age(age.hours()) {
+            state.send(&supply, current_price, prev_price, peak_price, age);
+        }
+    }
+}
+
+fn tick_tock<'a>(
+    states: impl Iterator<Item = (&'a Intersection, &'a mut dyn IntersectionState)>,
+    chains: &[Vec<SupplyState>],
+    chain_state: &[BlockState],
+    prev_timestamp: Timestamp,
+    timestamp: Timestamp,
+) {
+    for (intersection, state) in states {
+        let chain = &chains[intersection.index];
+        let (enter, leave) = intersection.age_boundaries();
+        for (hours, entering) in [(enter, true), (leave, false)] {
+            let Some(hours) = hours else {
+                continue;
+            };
+            for h in crossing(chain_state, prev_timestamp, timestamp, hours) {
+                let supply = &chain[h];
+                if supply.utxo_count == 0 {
+                    continue;
+                }
+                let snapshot = CostBasisSnapshot::from_utxo(chain_state[h].price, supply);
+                if entering {
+                    state.increment(&snapshot);
+                } else {
+                    state.decrement(&snapshot);
+                }
+            }
+        }
+    }
+}
+
+/// State updates of a cohort, whatever its tier.
+trait IntersectionState {
+    fn receive(&mut self, supply: &SupplyState, price: Cents);
+    fn send(
+        &mut self,
+        supply: &SupplyState,
+        current_price: Cents,
+        prev_price: Cents,
+        peak_price: Cents,
+        age: Age,
+    );
+    fn increment(&mut self, snapshot: &CostBasisSnapshot);
+    fn decrement(&mut self, snapshot: &CostBasisSnapshot);
+}
+
+impl<R: RealizedOps, C: CostBasisOps> IntersectionState for UTXOCohortState<R, C> {
+    fn receive(&mut self, supply: &SupplyState, price: Cents) {
+        self.receive_utxo(supply, price);
+    }
+
+    fn send(
+        &mut self,
+        supply: &SupplyState,
+        current_price: Cents,
+        prev_price: Cents,
+        peak_price: Cents,
+        age: Age,
+    ) {
+        self.send_utxo(supply, current_price, prev_price, peak_price, age);
+    }
+
+    fn increment(&mut self, snapshot: &CostBasisSnapshot) {
+        self.increment_snapshot(snapshot);
+    }
+
+    fn decrement(&mut self, snapshot: &CostBasisSnapshot) {
+        self.decrement_snapshot(snapshot);
+    }
+}
+
+fn states_mut<'a>(
+    minimal: &'a mut BTreeMap<String, UTXOCohortVecs<MinimalCohortMetrics>>,
+    basic: &'a mut BTreeMap<String, UTXOCohortVecs<BasicCohortMetrics>>,
+    core: &'a mut BTreeMap<String, UTXOCohortVecs<CoreCohortMetrics>>,
+    extended: &'a mut BTreeMap<String, UTXOCohortVecs<ExtendedCohortMetrics>>,
+) -> impl Iterator<Item = (&'a Intersection, &'a mut dyn IntersectionState)> {
+    minimal
+        .values_mut()
+        .map(|v| {
+            (
+                intersection_of(&v.metrics.filter),
+                v.state.as_deref_mut().unwrap() as &mut dyn IntersectionState,
+            )
+        })
+        .chain(basic.values_mut().map(|v| {
+            (
+                intersection_of(&v.metrics.filter),
+                v.state.as_deref_mut().unwrap() as &mut dyn IntersectionState,
+            )
+        }))
+        .chain(core.values_mut().map(|v| {
+            (
+                intersection_of(&v.metrics.filter),
+                v.state.as_deref_mut().unwrap() as &mut dyn IntersectionState,
+            )
+        }))
+        .chain(extended.values_mut().map(|v| {
+            (
+                intersection_of(&v.metrics.filter),
+                v.state.as_deref_mut().unwrap() as &mut dyn IntersectionState,
+            )
+        }))
+}
+
+fn intersection_of(filter: &Filter) -> &Intersection {
+    match filter {
+        Filter::Intersection(intersection) => intersection,
+        _ => unreachable!(),
+    }
+}
+
+/// Positions in `chain_state` of the blocks that turned `hours` old
+/// between `prev_timestamp` and `timestamp`, same bounds as the age range
+/// cohorts' tick-tock.
+fn crossing(
+    chain_state: &[BlockState],
+    prev_timestamp: Timestamp,
+    timestamp: Timestamp,
+    hours: usize,
+) -> Range<usize> {
+    let seconds = hours as u64 * ONE_HOUR_IN_SEC as u64;
+    let upper = (*timestamp as u64).saturating_sub(seconds);
+    let lower = (*prev_timestamp as u64).saturating_sub(seconds);
+    if upper <= lower {
+        return 0..0;
+    }
+    let start = chain_state.partition_point(|b| *b.timestamp as u64 <= lower);
+    let end = chain_state.partition_point(|b| *b.timestamp as u64 <= upper);
+    start..end
+}
+
+fn definition_hash(intersection: &Intersection) -> u32 {
+    let definition = format!(
+        "{:?}{:?}{:?}",
+        intersection.age, intersection.amount, intersection.types
+    );
+    let hash = definition.bytes().fold(0x811c_9dc5_u32, |hash, b| {
+        (hash ^ b as u32).wrapping_mul(0x0100_0193)
+    });
+    (hash >> 16) ^ (hash & 0xffff)
+}
+
+#[cfg(test)]
+mod tests {
+    use brk_cohort::{CohortTier, EntryPrice};
+
+    use super::*;
+
+    const HOUR: u32 = ONE_HOUR_IN_SEC;
+    const T0: u32 = 1_700_000_000;
+
+    /// Sums the values each hook saw.
+    #[derive(Default)]
+    struct Recorder {
+        received: u64,
+        sent: u64,
+        incremented: u64,
+        decremented: u64,
+    }
+
+    impl IntersectionState for Recorder {
+        fn receive(&mut self, supply: &SupplyState, _: Cents) {
+            self.received += u64::from(supply.value);
+        }
+
+        fn send(&mut self, supply: &SupplyState, _: Cents, _: Cents, _: Cents, _: Age) {
+            self.sent += u64::from(supply.value);
+        }
+
+        fn increment(&mut self, snapshot: &CostBasisSnapshot) {
+            self.incremented += u64::from(snapshot.supply_state.value);
+        }
+
+        fn decrement(&mut self, snapshot: &CostBasisSnapshot) {
+            self.decremented += u64::from(snapshot.supply_state.value);
+        }
+    }
+
+    fn intersection(index: usize, age: Range<usize>) -> Intersection {
+        Intersection {
+            index,
+            name: format!("cohort_{index}"),
+            age,
+            amount: Sats::ZERO..Sats::MAX,
+            types: Vec::new(),
+            tier: CohortTier::Minimal,
+        }
+    }
+
+    fn supply(value: u64) -> SupplyState {
+        SupplyState {
+            utxo_count: 1,
+            value: Sats::new(value),
+        }
+    }
+
+    fn transacted(by_intersection: Vec<SupplyState>) -> Transacted {
+        Transacted {
+            by_intersection,
+            ..Default::default()
+        }
+    }
+
+    fn block(timestamp: u32) -> BlockState {
+        BlockState {
+            supply: SupplyState::default(),
+            entry: EntryPrice::Discount,
+            price: Cents::from(100u64),
+            timestamp: Timestamp::new(T0 + timestamp),
+        }
+    }
+
+    fn states<'a>(
+        cohorts: &'a [Intersection],
+        recorders: &'a mut [Recorder],
+    ) -> impl Iterator<Item = (&'a Intersection, &'a mut dyn IntersectionState)> {
+        cohorts
+            .iter()
+            .zip(recorders.iter_mut().map(|r| r as &mut dyn IntersectionState))
+    }
+
+    #[test]
+    fn receive_extends_chains_and_feeds_only_cohorts_of_age_zero() {
+        let cohorts = [intersection(0, 0..24), intersection(1, 24..usize::MAX)];
+        let mut recorders = [Recorder::default(), Recorder::default()];
+        let mut chains = vec![Vec::new(), Vec::new()];
+
+        let received = transacted(vec![supply(5), supply(7)]);
+        receive(
+            states(&cohorts, &mut recorders),
+            &mut chains,
+            &received,
+            Cents::from(1u64),
+        );
+        // A block with no matching output still extends every chain
+        receive(
+            states(&cohorts, &mut recorders),
+            &mut chains,
+            &Transacted::default(),
+            Cents::from(1u64),
+        );
+
+        assert_eq!(chains[0].len(), 2);
+        assert_eq!(u64::from(chains[1][0].value), 7);
+        assert_eq!(recorders[0].received, 5);
+        assert_eq!(recorders[1].received, 0);
+    }
+
+    #[test]
+    fn send_debits_the_creation_height_and_cohorts_of_that_age() {
+        let cohorts = [intersection(0, 0..24), intersection(1, 24..usize::MAX)];
+        let mut recorders = [Recorder::default(), Recorder::default()];
+        let mut chains = vec![vec![supply(10), supply(20)], vec![supply(30), supply(40)]];
+
+        let age = Age::new(Timestamp::new(48 * HOUR), Timestamp::new(0));
+        let sent = transacted(vec![supply(4), supply(6)]);
+        send(
+            states(&cohorts, &mut recorders),
+            &mut chains,
+            Height::from(1usize),
+            &sent,
+            Cents::from(1u64),
+            Cents::from(1u64),
+            Cents::from(1u64),
+            age,
+        );
+
+        assert_eq!(u64::from(chains[0][1].value), 16);
+        assert_eq!(chains[0][1].utxo_count, 0);
+        assert_eq!(u64::from(chains[1][1].value), 34);
+        assert_eq!(u64::from(chains[1][0].value), 30);
+        // Two days old: only in the second cohort
+        assert_eq!(recorders[0].sent, 0);
+        assert_eq!(recorders[1].sent, 6);
+    }
+
+    #[test]
+    fn tick_tock_moves_blocks_across_age_boundaries() {
+        let cohorts = [intersection(0, 1..3)];
+        let mut recorders = [Recorder::default()];
+        let chain_state = [block(0), block(HOUR), block(2 * HOUR)];
+        let chains = vec![vec![supply(1), supply(10), supply(100)]];
+
+        // From 2.5h to 3.5h: block 0 turns 3h (leaves), block 2 turns 1h
+        // (enters)
+        tick_tock(
+            states(&cohorts, &mut recorders),
+            &chains,
+            &chain_state,
+            Timestamp::new(T0 + 2 * HOUR + HOUR / 2),
+            Timestamp::new(T0 + 3 * HOUR + HOUR / 2),
+        );
+
+        assert_eq!(recorders[0].incremented, 100);
+        assert_eq!(recorders[0].decremented, 1);
+    }
+
+    #[test]
+    fn crossing_is_empty_when_time_does_not_move() {
+        let chain_state = [block(0), block(HOUR)];
+        let now = Timestamp::new(T0 + 10 * HOUR);
+        assert_eq!(crossing(&chain_state, now, now, 1), 0..0);
+        assert_eq!(crossing(&chain_state, Timestamp::new(T0), now, 1), 0..2);
+    }
+}
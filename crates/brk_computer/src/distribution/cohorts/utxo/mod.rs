@@ -1,5 +1,6 @@
 mod fenwick;
 mod groups;
+mod intersections;
 mod percentiles;
 mod receive;
 mod send;
@@ -16,6 +16,7 @@ impl UTXOCohorts<Rw> {
     /// - The immutable entry valuation cohort based on creation price versus anchor
     /// - The appropriate output type cohort (P2PKH, P2SH, etc.)
     /// - The appropriate amount range cohort based on value
+    /// - The intersection cohorts matching their type and value, if young enough
     pub(crate) fn receive(
         &mut self,
         received: Transacted,
@@ -24,6 +25,8 @@ impl UTXOCohorts<Rw> {
         price: Cents,
         entry: EntryPrice,
     ) {
+        self.intersection.receive(&received, price);
+
         let supply_state = received.spendable_supply;
 
         // Pre-compute snapshot once for cohorts sharing the block-level supply_state
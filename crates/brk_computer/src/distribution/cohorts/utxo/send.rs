@@ -125,6 +125,15 @@ impl UTXOCohorts<Rw> {
                         .unwrap()
                         .send_utxo(supply_state, current_price, prev_price, peak_price, age);
                 });
+
+            self.intersection.send(
+                receive_height,
+                &sent,
+                current_price,
+                prev_price,
+                peak_price,
+                age,
+            );
         }
 
         min_receive_height
@@ -35,6 +35,9 @@ impl UTXOCohorts<Rw> {
             return AgeRange::default();
         }
 
+        self.intersection
+            .tick_tock(chain_state, prev_timestamp, timestamp);
+
         let mut matured = [Sats::ZERO; 21];
 
         // Get age_range cohort states (indexed 0..21)
@@ -11,7 +11,7 @@ use vecdb::{AnyStoredVec, AnyVec, Exit, ReadableVec, VecIndex, WritableVec, unli
 
 use crate::{
     distribution::{
-        addr::AddrMetricsState,
+        addr::{AddrMetricsState, RichListState},
         block::{
             AddrCache, InputsResult, process_inputs, process_outputs, process_received,
             process_sent,
@@ -196,6 +196,13 @@ pub(crate) fn process_blocks(
         starting_height
     );
     let mut state = AddrMetricsState::from((&vecs.addrs, starting_height));
+    state.rich_list = RichListState::import(
+        &vecs.states_path,
+        starting_height,
+        &vr,
+        &vecs.any_addr_indexes,
+        &vecs.addrs_data,
+    )?;
     debug!("addr metrics state recovered");
 
     debug!("creating AddrCache");
@@ -455,6 +462,11 @@ pub(crate) fn process_blocks(
         );
         addr_result?;
 
+        // Rescan the address types whose rich list fell below its depth
+        state
+            .rich_list
+            .refill_if_needed(&cache, &vr, &vecs.any_addr_indexes, &vecs.addrs_data)?;
+
         // Update Fenwick tree from pending deltas (must happen before push_cohort_states drains pending)
         vecs.utxo_cohorts.update_fenwick_from_pending();
 
@@ -476,6 +488,12 @@ pub(crate) fn process_blocks(
         vecs.utxo_cohorts
             .push_aggregate_percentiles(block_price, date_opt, &vecs.states_path)?;
 
+        if let Some(date) = date_opt {
+            state
+                .rich_list
+                .write_snapshot(&vecs.states_path, height, date)?;
+        }
+
         // Periodic checkpoint flush
         if height != last_height
             && height != Height::ZERO
@@ -497,7 +515,14 @@ pub(crate) fn process_blocks(
             let _lock = exit.lock();
 
             // Write to disk (pure I/O) - no changes saved for periodic flushes
-            write(vecs, height, chain_state, min_supply_modified, false)?;
+            write(
+                vecs,
+                height,
+                chain_state,
+                &state.rich_list,
+                min_supply_modified,
+                false,
+            )?;
             min_supply_modified = None;
             vecs.flush()?;
 
@@ -522,7 +547,14 @@ pub(crate) fn process_blocks(
     )?;
 
     // Write to disk (pure I/O) - save changes for rollback
-    write(vecs, last_height, chain_state, min_supply_modified, true)?;
+    write(
+        vecs,
+        last_height,
+        chain_state,
+        &state.rich_list,
+        min_supply_modified,
+        true,
+    )?;
 
     Ok(())
 }
@@ -40,12 +40,14 @@ pub(crate) fn recover_state(
             // Rollback address state vectors
             let addr_indexes_rollback = any_addr_indexes.rollback_before(stamp);
             let addr_data_rollback = addrs_data.rollback_before(stamp);
+            let intersection_rollback = utxo_cohorts.intersection.rollback_before(stamp);
 
             // Verify rollback consistency - all must agree on the same height
             let consistent_height = rollback_states(
                 chain_state_rollback,
                 addr_indexes_rollback,
                 addr_data_rollback,
+                intersection_rollback,
             );
 
             // If rollbacks are inconsistent, start fresh
@@ -178,6 +180,7 @@ fn rollback_states(
     chain_state_rollback: vecdb::Result<Stamp>,
     addr_indexes_rollbacks: Result<Vec<Stamp>>,
     addr_data_rollbacks: Result<[Stamp; 2]>,
+    intersection_rollbacks: Result<Vec<Stamp>>,
 ) -> Height {
     let mut heights: BTreeSet<Height> = BTreeSet::new();
 
@@ -219,6 +222,22 @@ fn rollback_states(
         heights.insert(h);
     }
 
+    let Ok(stamps) = intersection_rollbacks else {
+        warn!(
+            "intersection supply_state rollback failed: {:?}",
+            intersection_rollbacks
+        );
+        return Height::ZERO;
+    };
+    for (i, s) in stamps.iter().enumerate() {
+        let h = Height::from(*s).incremented();
+        debug!(
+            "intersection supply_state[{}] rolled back to stamp {:?}, height {}",
+            i, s, h
+        );
+        heights.insert(h);
+    }
+
     // All must agree on the same height
     if heights.len() == 1 {
         heights.pop_first().unwrap()
@@ -227,3 +246,42 @@ fn rollback_states(
         Height::ZERO
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn stamps(heights: &[u64]) -> Vec<Stamp> {
+        heights.iter().map(|&h| Stamp::from(h)).collect()
+    }
+
+    #[test]
+    fn intersections_rolled_back_like_the_rest_resume_after_the_stamp() {
+        let height = rollback_states(
+            Ok(Stamp::from(9)),
+            Ok(stamps(&[9, 9])),
+            Ok([Stamp::from(9), Stamp::from(9)]),
+            Ok(stamps(&[9, 9, 9])),
+        );
+        assert_eq!(height, Height::from(10usize));
+    }
+
+    #[test]
+    fn intersection_out_of_step_or_failing_restarts_from_scratch() {
+        let behind = rollback_states(
+            Ok(Stamp::from(9)),
+            Ok(stamps(&[9])),
+            Ok([Stamp::from(9), Stamp::from(9)]),
+            Ok(stamps(&[9, 7])),
+        );
+        assert_eq!(behind, Height::ZERO);
+
+        let failed = rollback_states(
+            Ok(Stamp::from(9)),
+            Ok(stamps(&[9])),
+            Ok([Stamp::from(9), Stamp::from(9)]),
+            Err(brk_error::Error::Internal("rollback")),
+        );
+        assert_eq!(failed, Height::ZERO);
+    }
+}
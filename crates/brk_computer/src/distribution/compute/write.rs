@@ -12,7 +12,9 @@ use crate::distribution::{
     state::BlockState,
 };
 
-use super::super::addr::{AddrTypeToTypeIndexMap, AddrsDataVecs, AnyAddrIndexesVecs};
+use super::super::addr::{
+    AddrTypeToTypeIndexMap, AddrsDataVecs, AnyAddrIndexesVecs, RichListState,
+};
 
 /// Process address updates from caches.
 ///
@@ -48,12 +50,14 @@ pub(crate) fn process_addr_updates(
 /// - Address indexes and data
 /// - Transaction output index mappings
 /// - Chain state
+/// - Rich list rankings
 ///
 /// Set `with_changes=true` near chain tip to enable rollback support.
 pub(crate) fn write(
     vecs: &mut Vecs,
     height: Height,
     chain_state: &[BlockState],
+    rich_list: &RichListState,
     min_supply_modified: Option<Height>,
     with_changes: bool,
 ) -> Result<()> {
@@ -72,6 +76,9 @@ pub(crate) fn write(
     for block_state in &chain_state[truncate_to..] {
         vecs.supply_state.push(block_state.supply);
     }
+    vecs.utxo_cohorts
+        .intersection
+        .push_supply_states(min_supply_modified)?;
 
     vecs.any_addr_indexes
         .par_iter_mut()
@@ -92,6 +99,7 @@ pub(crate) fn write(
     let cleanup = with_changes;
     vecs.utxo_cohorts.commit_all_states(height, cleanup)?;
     vecs.addr_cohorts.commit_all_states(height, cleanup)?;
+    rich_list.write(&vecs.states_path, height)?;
 
     info!("Wrote in {:?}", i.elapsed());
 
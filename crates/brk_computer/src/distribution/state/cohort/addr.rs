@@ -1,7 +1,7 @@
 use std::path::Path;
 
 use brk_error::Result;
-use brk_types::{Age, Cents, FundedAddrData, Sats, SupplyState};
+use brk_types::{Age, Cents, CostBasisSnapshot, FundedAddrData, Sats, SupplyState};
 use vecdb::unlikely;
 
 use super::super::cost_basis::{CostBasisRaw, RealizedOps};
@@ -28,6 +28,7 @@ impl<R: RealizedOps> AddrCohortState<R> {
         self.addr_count = 0;
         self.inner.supply = SupplyState::default();
         self.inner.sent = Sats::ZERO;
+        self.inner.received = Sats::ZERO;
         self.inner.spent_utxo_count = 0;
         self.inner.satdays_destroyed = Sats::ZERO;
         self.inner.realized = R::default();
@@ -84,6 +85,64 @@ impl<R: RealizedOps> AddrCohortState<R> {
         );
     }
 
+    /// Like [`Self::receive_outputs`], for a receive another cohort
+    /// already applied to `addr_data`, whose snapshot before it was `prev`.
+    /// An address funded by it joins the cohort.
+    pub(crate) fn receive_applied(
+        &mut self,
+        addr_data: &FundedAddrData,
+        prev: &CostBasisSnapshot,
+        value: Sats,
+        price: Cents,
+        output_count: u32,
+    ) {
+        if prev.supply_state.utxo_count == 0 {
+            self.add(addr_data);
+            self.inner.received += value;
+            return;
+        }
+        self.inner.receive_addr(
+            &SupplyState {
+                utxo_count: output_count as u64,
+                value,
+            },
+            price,
+            &addr_data.cost_basis_snapshot(),
+            prev,
+        );
+    }
+
+    /// Like [`Self::send`], for a spend another cohort already applied to
+    /// `addr_data`, whose snapshot before it was `prev`. An address
+    /// emptied by it leaves the cohort.
+    #[allow(clippy::too_many_arguments)]
+    pub(crate) fn send_applied(
+        &mut self,
+        addr_data: &FundedAddrData,
+        prev: &CostBasisSnapshot,
+        value: Sats,
+        current_price: Cents,
+        prev_price: Cents,
+        ath: Cents,
+        age: Age,
+    ) {
+        self.inner.send_addr(
+            &SupplyState {
+                utxo_count: 1,
+                value,
+            },
+            current_price,
+            prev_price,
+            ath,
+            age,
+            &addr_data.cost_basis_snapshot(),
+            prev,
+        );
+        if addr_data.utxo_count() == 0 {
+            self.subtract(addr_data);
+        }
+    }
+
     pub(crate) fn add(&mut self, addr_data: &FundedAddrData) {
         self.addr_count += 1;
         self.inner
@@ -137,3 +196,104 @@ impl<R: RealizedOps> AddrCohortState<R> {
         self.inner.decrement_snapshot(&snapshot);
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::{env, fs};
+
+    use brk_types::Timestamp;
+
+    use super::*;
+    use crate::distribution::state::{MinimalRealizedState, RealizedOps};
+
+    fn state(dir: &Path, name: &str) -> AddrCohortState<MinimalRealizedState> {
+        let mut state = AddrCohortState::new(dir, name);
+        state.inner.reset_cost_basis_data_if_needed().unwrap();
+        state
+    }
+
+    fn assert_same(
+        a: &AddrCohortState<MinimalRealizedState>,
+        b: &AddrCohortState<MinimalRealizedState>,
+    ) {
+        assert_eq!(a.addr_count, b.addr_count);
+        assert_eq!(a.inner.supply.utxo_count, b.inner.supply.utxo_count);
+        assert_eq!(a.inner.supply.value, b.inner.supply.value);
+        assert_eq!(a.inner.sent, b.inner.sent);
+        assert_eq!(a.inner.realized.cap(), b.inner.realized.cap());
+    }
+
+    /// A label cohort follows an address through the changes its amount
+    /// cohort applies, from funding to emptying.
+    #[test]
+    fn applied_changes_match_the_amount_cohort() {
+        let dir = env::temp_dir().join(format!("brk_addr_cohort_state_{}", std::process::id()));
+        let mut amount = state(&dir, "amount");
+        let mut label = state(&dir, "label");
+        let mut addr = FundedAddrData::default();
+        let (sats, price) = (Sats::new(1_000), Cents::new(100));
+
+        let prev = addr.cost_basis_snapshot();
+        addr.receive_outputs(sats, price, 1);
+        amount.add(&addr);
+        label.receive_applied(&addr, &prev, sats, price, 1);
+        assert_same(&amount, &label);
+        assert_eq!(label.inner.received, sats);
+
+        let prev = addr.cost_basis_snapshot();
+        amount.receive_outputs(&mut addr, Sats::new(500), Cents::new(200), 1);
+        label.receive_applied(&addr, &prev, Sats::new(500), Cents::new(200), 1);
+        assert_same(&amount, &label);
+        assert_eq!(label.inner.received, Sats::new(1_500));
+
+        let age = Age::new(Timestamp::new(86_400), Timestamp::new(0));
+        let prev = addr.cost_basis_snapshot();
+        amount
+            .send(
+                &mut addr,
+                sats,
+                Cents::new(300),
+                price,
+                Cents::new(300),
+                age,
+            )
+            .unwrap();
+        label.send_applied(
+            &addr,
+            &prev,
+            sats,
+            Cents::new(300),
+            price,
+            Cents::new(300),
+            age,
+        );
+        assert_same(&amount, &label);
+
+        let prev = addr.cost_basis_snapshot();
+        amount
+            .send(
+                &mut addr,
+                Sats::new(500),
+                Cents::new(300),
+                Cents::new(200),
+                Cents::new(300),
+                age,
+            )
+            .unwrap();
+        amount.subtract(&addr);
+        label.send_applied(
+            &addr,
+            &prev,
+            Sats::new(500),
+            Cents::new(300),
+            Cents::new(200),
+            Cents::new(300),
+            age,
+        );
+        assert_same(&amount, &label);
+        assert_eq!(label.addr_count, 0);
+        assert_eq!(label.inner.supply.value, Sats::ZERO);
+
+        let _ = fs::remove_dir_all(dir);
+    }
+}
@@ -59,6 +59,8 @@ pub struct CohortState<R: RealizedOps, C: CostBasisOps> {
     pub supply: SupplyState,
     pub realized: R,
     pub sent: Sats,
+    /// Received by the cohort's addresses (address cohorts only)
+    pub received: Sats,
     pub spent_utxo_count: u64,
     pub satdays_destroyed: Sats,
     cost_basis: C,
@@ -70,6 +72,7 @@ impl<R: RealizedOps, C: CostBasisOps> CohortState<R, C> {
             supply: SupplyState::default(),
             realized: R::default(),
             sent: Sats::ZERO,
+            received: Sats::ZERO,
             spent_utxo_count: 0,
             satdays_destroyed: Sats::ZERO,
             cost_basis: C::create(path, name),
@@ -105,6 +108,7 @@ impl<R: RealizedOps, C: CostBasisOps> CohortState<R, C> {
 
     pub(crate) fn reset_single_iteration_values(&mut self) {
         self.sent = Sats::ZERO;
+        self.received = Sats::ZERO;
         self.spent_utxo_count = 0;
         if R::TRACK_ACTIVITY {
             self.satdays_destroyed = Sats::ZERO;
@@ -177,6 +181,7 @@ impl<R: RealizedOps, C: CostBasisOps> CohortState<R, C> {
         self.supply += supply;
 
         if supply.value > Sats::ZERO {
+            self.received += supply.value;
             self.realized.receive(price, supply.value);
 
             if current.supply_state.value.is_not_zero() {
@@ -1,6 +1,6 @@
 use std::ops::{Add, AddAssign};
 
-use brk_cohort::{AmountRange, ByType};
+use brk_cohort::{AmountRange, ByType, intersections};
 use brk_types::{OutputType, Sats, SupplyState};
 use vecdb::unlikely;
 
@@ -9,6 +9,9 @@ pub struct Transacted {
     pub spendable_supply: SupplyState,
     pub by_type: ByType<SupplyState>,
     pub by_size_group: AmountRange<SupplyState>,
+    /// Per intersection cohort, the outputs of its types and amounts,
+    /// empty if none matched
+    pub by_intersection: Vec<SupplyState>,
 }
 
 impl Transacted {
@@ -28,6 +31,21 @@ impl Transacted {
         self.spendable_supply += &supply;
 
         *self.by_size_group.get_mut(value) += &supply;
+
+        for intersection in intersections() {
+            if intersection.contains_output(_type, value) {
+                if self.by_intersection.is_empty() {
+                    self.by_intersection
+                        .resize(intersections().len(), SupplyState::default());
+                }
+                self.by_intersection[intersection.index] += &supply;
+            }
+        }
+    }
+
+    /// What the outputs left in intersection cohort `index`.
+    pub(crate) fn intersection(&self, index: usize) -> SupplyState {
+        self.by_intersection.get(index).copied().unwrap_or_default()
     }
 }
 
@@ -38,6 +56,7 @@ impl Add for Transacted {
             spendable_supply: self.spendable_supply + rhs.spendable_supply,
             by_type: self.by_type + rhs.by_type,
             by_size_group: self.by_size_group + rhs.by_size_group,
+            by_intersection: add_intersections(self.by_intersection, rhs.by_intersection),
         }
     }
 }
@@ -47,5 +66,17 @@ impl AddAssign for Transacted {
         self.by_size_group += rhs.by_size_group;
         self.spendable_supply += &rhs.spendable_supply;
         self.by_type += rhs.by_type;
+        self.by_intersection = add_intersections(
+            std::mem::take(&mut self.by_intersection),
+            rhs.by_intersection,
+        );
+    }
+}
+
+fn add_intersections(mut lhs: Vec<SupplyState>, rhs: Vec<SupplyState>) -> Vec<SupplyState> {
+    if lhs.is_empty() {
+        return rhs;
     }
+    lhs.iter_mut().zip(&rhs).for_each(|(l, r)| *l += r);
+    lhs
 }
@@ -36,7 +36,7 @@ use super::{
     AddrCohorts, AddrsDataVecs, AnyAddrIndexesVecs, RangeMap, UTXOCohorts,
     addr::{
         AddrActivityVecs, AddrCountsVecs, AddrMetricsState, DeltaVecs, ExposedAddrVecs,
-        NewAddrCountVecs, ReusedAddrVecs, TotalAddrCountVecs,
+        NewAddrCountVecs, ReusedAddrVecs, RichListVecs, TotalAddrCountVecs,
     },
     metrics::AvgAmountMetrics,
 };
@@ -53,6 +53,7 @@ pub struct AddrMetricsVecs<M: StorageMode = Rw> {
     pub reused: ReusedAddrVecs<M>,
     pub respent: ReusedAddrVecs<M>,
     pub exposed: ExposedAddrVecs<M>,
+    pub rich_list: RichListVecs<M>,
     pub delta: DeltaVecs,
     pub avg_amount: WithAddrTypes<AvgAmountMetrics<M>>,
     #[traversable(wrap = "indexes", rename = "funded")]
@@ -70,6 +71,7 @@ impl AddrMetricsVecs {
         self.reused.reset_height()?;
         self.respent.reset_height()?;
         self.exposed.reset_height()?;
+        self.rich_list.reset_height()?;
         self.avg_amount.reset_height()?;
         Ok(())
     }
@@ -82,6 +84,7 @@ impl AddrMetricsVecs {
             .min(self.reused.min_stateful_len())
             .min(self.respent.min_stateful_len())
             .min(self.exposed.min_stateful_len())
+            .min(self.rich_list.min_stateful_len())
     }
 
     /// Stateful vecs pushed per block. Mirrors [`Self::push_height`] and
@@ -96,6 +99,7 @@ impl AddrMetricsVecs {
             .chain(self.reused.par_iter_height_mut())
             .chain(self.respent.par_iter_height_mut())
             .chain(self.exposed.par_iter_height_mut())
+            .chain(self.rich_list.par_iter_height_mut())
     }
 
     /// All height-indexed vecs including derived (`avg_amount`). Used for
@@ -110,6 +114,7 @@ impl AddrMetricsVecs {
             .chain(self.reused.par_iter_height_mut())
             .chain(self.respent.par_iter_height_mut())
             .chain(self.exposed.par_iter_height_mut())
+            .chain(self.rich_list.par_iter_height_mut())
             .chain(self.avg_amount.par_iter_height_mut())
     }
 
@@ -124,6 +129,7 @@ impl AddrMetricsVecs {
         self.exposed.push_height(&state.exposed);
         self.reused.push_height(&state.reused, active_addr_count);
         self.respent.push_height(&state.respent, active_addr_count);
+        self.rich_list.push_height(&state.rich_list);
     }
 }
 
@@ -240,6 +246,9 @@ impl Vecs {
         // Exposed address tracking (counts + supply) - quantum / pubkey-exposure sense
         let exposed_addr_vecs = ExposedAddrVecs::forced_import(&db, version, indexes)?;
 
+        // Supply held by the largest funded addresses (global + per-type)
+        let rich_list = RichListVecs::forced_import(&db, version, indexes)?;
+
         // Growth rate: delta change + rate (global + per-type)
         let delta = DeltaVecs::new(version, &addr_count, cached_starts, indexes);
 
@@ -261,6 +270,7 @@ impl Vecs {
                 reused: reused_addr_count,
                 respent: respent_addr_count,
                 exposed: exposed_addr_vecs,
+                rich_list,
                 delta,
                 avg_amount,
                 funded_index: funded_addr_index,
@@ -377,6 +387,7 @@ impl Vecs {
 
         if needs_fresh_start {
             self.supply_state.reset()?;
+            self.utxo_cohorts.intersection.reset()?;
             self.addrs.reset_height()?;
             reset_state(
                 &mut self.any_addr_indexes,
@@ -387,6 +398,9 @@ impl Vecs {
             info!("State recovery: fresh start");
         }
 
+        // Label cohorts follow the labeled addresses the indexer knows of.
+        self.addr_cohorts.resolve_labels(indexer)?;
+
         // Populate price/timestamp caches from the prices module.
         // Must happen AFTER rollback/reset (which clears caches) but BEFORE
         // chain_state rebuild (which reads from them).
@@ -474,6 +488,11 @@ impl Vecs {
             recovered_height
         };
 
+        // Intersection cohorts keep their own per-height supply, persisted like chain_state
+        self.utxo_cohorts
+            .intersection
+            .rebuild_chains(starting_height);
+
         // 2c. Validate computed versions
         debug!("validating computed versions");
         let base_version = VERSION;
@@ -611,6 +630,9 @@ impl Vecs {
             &type_supply_sats,
             exit,
         )?;
+        self.addrs
+            .rich_list
+            .compute_rest(starting_lengths.height, prices, exit)?;
 
         // Average amount (supply / utxo_count, supply / funded_addr_count) for `all` and per addr type.
         let all_m = &self.utxo_cohorts.all.metrics;
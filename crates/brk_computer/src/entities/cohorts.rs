@@ -0,0 +1,279 @@
+use brk_cohort::{
+    AddrGroups, AmountBucket, AmountRange, CohortContext, Filter, Filtered, OverAmount, UnderAmount,
+};
+use brk_error::Result;
+use brk_traversable::Traversable;
+use brk_types::{EntityNode, Height, Sats, StoredU64, Version};
+use derive_more::{Deref, DerefMut};
+use rayon::prelude::*;
+use vecdb::{AnyStoredVec, AnyVec, Database, Exit, ReadableVec, Rw, StorageMode, WritableVec};
+
+use crate::{
+    indexes,
+    internal::{PerBlock, ValuePerBlock},
+    price,
+};
+
+/// Funded entities of a cohort and what they hold.
+#[derive(Debug, Default, Clone, Copy)]
+pub(super) struct EntityCohortState {
+    pub entity_count: u64,
+    pub addr_count: u64,
+    pub supply: Sats,
+}
+
+#[derive(Traversable)]
+pub struct EntityCohortVecs<M: StorageMode = Rw> {
+    #[traversable(skip)]
+    filter: Filter,
+    #[traversable(skip)]
+    state: Option<EntityCohortState>,
+
+    pub entity_count: PerBlock<StoredU64, M>,
+    /// Addresses held by the cohort's entities
+    pub addr_count: PerBlock<StoredU64, M>,
+    pub supply: ValuePerBlock<M>,
+}
+
+impl EntityCohortVecs {
+    fn forced_import(
+        db: &Database,
+        filter: Filter,
+        name: &str,
+        version: Version,
+        indexes: &indexes::Vecs,
+        has_state: bool,
+    ) -> Result<Self> {
+        let full_name = CohortContext::Entity.full_name(&filter, name);
+        Ok(Self {
+            entity_count: PerBlock::forced_import(
+                db,
+                &format!("{full_name}_entity_count"),
+                version,
+                indexes,
+            )?,
+            addr_count: PerBlock::forced_import(
+                db,
+                &format!("{full_name}_addr_count"),
+                version,
+                indexes,
+            )?,
+            supply: ValuePerBlock::forced_import(
+                db,
+                &format!("{full_name}_supply"),
+                version,
+                indexes,
+            )?,
+            state: has_state.then(EntityCohortState::default),
+            filter,
+        })
+    }
+
+    fn min_stateful_len(&self) -> usize {
+        self.entity_count
+            .height
+            .len()
+            .min(self.addr_count.height.len())
+            .min(self.supply.sats.height.len())
+    }
+
+    fn par_iter_stateful_mut(&mut self) -> impl ParallelIterator<Item = &mut dyn AnyStoredVec> {
+        [
+            &mut self.entity_count.height as &mut dyn AnyStoredVec,
+            &mut self.addr_count.height,
+            &mut self.supply.sats.height,
+        ]
+        .into_par_iter()
+    }
+
+    /// Restores the running state from the values pushed at the height
+    /// before `height`.
+    fn import_state(&mut self, height: Height) {
+        let Some(state) = self.state.as_mut() else {
+            return;
+        };
+        *state = height
+            .decremented()
+            .map(|prev| EntityCohortState {
+                entity_count: self
+                    .entity_count
+                    .height
+                    .collect_one(prev)
+                    .map_or(0, u64::from),
+                addr_count: self
+                    .addr_count
+                    .height
+                    .collect_one(prev)
+                    .map_or(0, u64::from),
+                supply: self
+                    .supply
+                    .sats
+                    .height
+                    .collect_one(prev)
+                    .unwrap_or_default(),
+            })
+            .unwrap_or_default();
+    }
+
+    fn push_state(&mut self) {
+        let state = self.state.unwrap_or_default();
+        self.entity_count
+            .height
+            .push(StoredU64::from(state.entity_count));
+        self.addr_count
+            .height
+            .push(StoredU64::from(state.addr_count));
+        self.supply.sats.height.push(state.supply);
+    }
+
+    fn compute_from_stateful(
+        &mut self,
+        starting_height: Height,
+        others: &[&Self],
+        exit: &Exit,
+    ) -> Result<()> {
+        self.entity_count.height.compute_sum_of_others(
+            starting_height,
+            &others
+                .iter()
+                .map(|v| &v.entity_count.height)
+                .collect::<Vec<_>>(),
+            exit,
+        )?;
+        self.addr_count.height.compute_sum_of_others(
+            starting_height,
+            &others
+                .iter()
+                .map(|v| &v.addr_count.height)
+                .collect::<Vec<_>>(),
+            exit,
+        )?;
+        self.supply.sats.height.compute_sum_of_others(
+            starting_height,
+            &others
+                .iter()
+                .map(|v| &v.supply.sats.height)
+                .collect::<Vec<_>>(),
+            exit,
+        )?;
+        Ok(())
+    }
+}
+
+impl Filtered for EntityCohortVecs {
+    fn filter(&self) -> &Filter {
+        &self.filter
+    }
+}
+
+/// Entity balance cohorts, laid out like the address ones. Only funded
+/// entities are counted.
+#[derive(Deref, DerefMut, Traversable)]
+pub struct EntityCohorts<M: StorageMode = Rw>(AddrGroups<EntityCohortVecs<M>>);
+
+impl EntityCohorts {
+    pub(super) fn forced_import(
+        db: &Database,
+        version: Version,
+        indexes: &indexes::Vecs,
+    ) -> Result<Self> {
+        // Only amount_range cohorts have state
+        let create = |filter: Filter, name: &'static str, has_state: bool| {
+            EntityCohortVecs::forced_import(db, filter, name, version, indexes, has_state)
+        };
+        let full = |f: Filter, name: &'static str| create(f, name, true);
+        let none = |f: Filter, name: &'static str| create(f, name, false);
+
+        Ok(Self(AddrGroups {
+            amount_range: AmountRange::try_new(&full)?,
+            under_amount: UnderAmount::try_new(&none)?,
+            over_amount: OverAmount::try_new(&none)?,
+        }))
+    }
+
+    pub(super) fn min_stateful_len(&self) -> Height {
+        self.iter_separate()
+            .map(|v| Height::from(v.min_stateful_len()))
+            .min()
+            .unwrap_or_default()
+    }
+
+    pub(super) fn import_states(&mut self, height: Height) {
+        self.iter_separate_mut()
+            .for_each(|v| v.import_state(height));
+    }
+
+    /// Moves a funded entity in or out of its cohort.
+    pub(super) fn apply(&mut self, node: &EntityNode, add: bool) {
+        if node.balance == Sats::ZERO {
+            return;
+        }
+        let state = self
+            .amount_range
+            .get_mut_by_bucket(AmountBucket::from(node.balance))
+            .state
+            .as_mut()
+            .unwrap();
+        if add {
+            state.entity_count += 1;
+            state.addr_count += node.addr_count as u64;
+            state.supply += node.balance;
+        } else {
+            state.entity_count -= 1;
+            state.addr_count -= node.addr_count as u64;
+            state.supply -= node.balance;
+        }
+    }
+
+    pub(super) fn push_states(&mut self) {
+        self.iter_separate_mut().for_each(|v| v.push_state());
+    }
+
+    pub(super) fn truncate_stateful_at(&mut self, height: usize) -> Result<()> {
+        self.par_iter_stateful_mut()
+            .try_for_each(|v| v.any_truncate_if_needed_at(height))?;
+        Ok(())
+    }
+
+    pub(super) fn par_iter_stateful_mut(
+        &mut self,
+    ) -> impl ParallelIterator<Item = &mut dyn AnyStoredVec> {
+        self.0
+            .iter_separate_mut()
+            .flat_map(|v| v.par_iter_stateful_mut().collect::<Vec<_>>())
+            .collect::<Vec<_>>()
+            .into_par_iter()
+    }
+
+    /// Sums the amount_range cohorts into the overlapping ones, then
+    /// values every supply.
+    pub(super) fn compute_rest(
+        &mut self,
+        starting_height: Height,
+        prices: &price::Vecs,
+        exit: &Exit,
+    ) -> Result<()> {
+        let by_amount_range = &self.0.amount_range;
+        self.0
+            .over_amount
+            .iter_mut()
+            .chain(self.0.under_amount.iter_mut())
+            .map(|vecs| {
+                let filter = vecs.filter().clone();
+                let sources = by_amount_range
+                    .iter()
+                    .filter(|other| filter.includes(other.filter()))
+                    .collect::<Vec<_>>();
+                (vecs, sources)
+            })
+            .collect::<Vec<_>>()
+            .into_par_iter()
+            .try_for_each(|(vecs, sources)| {
+                vecs.compute_from_stateful(starting_height, &sources, exit)
+            })?;
+
+        self.0
+            .par_iter_mut()
+            .try_for_each(|v| v.supply.compute(prices, starting_height, exit))
+    }
+}
@@ -0,0 +1,274 @@
+use brk_cohort::ByAddrType;
+use brk_error::{Error, Result};
+use brk_types::{EntityNode, OutputType, Sats, TypeIndex};
+use rustc_hash::FxHashMap;
+use vecdb::Reader;
+
+use super::nodes::EntityNodesVecs;
+
+/// An address: its type and its index within the type.
+pub(super) type AddrKey = (OutputType, TypeIndex);
+
+/// Union-find over addresses. Nodes are read from disk on first use and
+/// kept in memory until the next flush.
+#[derive(Default)]
+pub(super) struct EntityGraph {
+    cache: ByAddrType<FxHashMap<TypeIndex, EntityNode>>,
+    /// Roots changed during the current block, with their state before
+    /// the block
+    touched: FxHashMap<AddrKey, EntityNode>,
+}
+
+impl EntityGraph {
+    /// Loads the node of `key`, creating a single-address entity if the
+    /// address was never seen. Returns whether it was created.
+    pub(super) fn ensure(
+        &mut self,
+        vecs: &EntityNodesVecs,
+        readers: &ByAddrType<Reader>,
+        key: AddrKey,
+    ) -> Result<bool> {
+        let (addr_type, type_index) = key;
+        if self.cache.get_unwrap(addr_type).contains_key(&type_index) {
+            return Ok(false);
+        }
+        let (node, created) = match vecs.get(addr_type, type_index, readers)? {
+            Some(node) => (node, false),
+            None => (EntityNode::root(addr_type, type_index), true),
+        };
+        self.cache
+            .get_mut_unwrap(addr_type)
+            .insert(type_index, node);
+        Ok(created)
+    }
+
+    fn node(
+        &mut self,
+        vecs: &EntityNodesVecs,
+        readers: &ByAddrType<Reader>,
+        key: AddrKey,
+    ) -> Result<&mut EntityNode> {
+        self.ensure(vecs, readers, key)?;
+        Ok(self.cache.get_mut_unwrap(key.0).get_mut(&key.1).unwrap())
+    }
+
+    /// Root of the entity `key` belongs to, compressing the path to it.
+    pub(super) fn find(
+        &mut self,
+        vecs: &EntityNodesVecs,
+        readers: &ByAddrType<Reader>,
+        key: AddrKey,
+    ) -> Result<AddrKey> {
+        let mut root = key;
+        loop {
+            let parent = self.node(vecs, readers, root)?.parent();
+            if parent == root {
+                break;
+            }
+            root = parent;
+        }
+
+        let mut current = key;
+        while current != root {
+            let node = self.node(vecs, readers, current)?;
+            let parent = node.parent();
+            (node.parent_type, node.parent_index) = root;
+            current = parent;
+        }
+
+        Ok(root)
+    }
+
+    /// Records the state of `root` before the block changes it.
+    fn touch(&mut self, root: AddrKey, node: EntityNode) {
+        self.touched.entry(root).or_insert(node);
+    }
+
+    /// Adds `value` to the entity of `key`.
+    pub(super) fn receive(
+        &mut self,
+        vecs: &EntityNodesVecs,
+        readers: &ByAddrType<Reader>,
+        key: AddrKey,
+        value: Sats,
+    ) -> Result<()> {
+        let root = self.find(vecs, readers, key)?;
+        let node = *self.node(vecs, readers, root)?;
+        self.touch(root, node);
+        self.node(vecs, readers, root)?.balance += value;
+        Ok(())
+    }
+
+    /// Removes `value` from the entity of `key`.
+    pub(super) fn send(
+        &mut self,
+        vecs: &EntityNodesVecs,
+        readers: &ByAddrType<Reader>,
+        key: AddrKey,
+        value: Sats,
+    ) -> Result<()> {
+        let root = self.find(vecs, readers, key)?;
+        let node = *self.node(vecs, readers, root)?;
+        self.touch(root, node);
+        let root_node = self.node(vecs, readers, root)?;
+        if root_node.balance < value {
+            return Err(Error::Internal("Entity balance underflow"));
+        }
+        root_node.balance -= value;
+        Ok(())
+    }
+
+    /// Merges the entities of `a` and `b`, the larger one keeping its
+    /// root.
+    pub(super) fn union(
+        &mut self,
+        vecs: &EntityNodesVecs,
+        readers: &ByAddrType<Reader>,
+        a: AddrKey,
+        b: AddrKey,
+    ) -> Result<()> {
+        let root_a = self.find(vecs, readers, a)?;
+        let root_b = self.find(vecs, readers, b)?;
+        if root_a == root_b {
+            return Ok(());
+        }
+
+        let node_a = *self.node(vecs, readers, root_a)?;
+        let node_b = *self.node(vecs, readers, root_b)?;
+        self.touch(root_a, node_a);
+        self.touch(root_b, node_b);
+
+        let ((root, _), (child, child_node)) = if node_a.addr_count >= node_b.addr_count {
+            ((root_a, node_a), (root_b, node_b))
+        } else {
+            ((root_b, node_b), (root_a, node_a))
+        };
+
+        let child_mut = self.node(vecs, readers, child)?;
+        (child_mut.parent_type, child_mut.parent_index) = root;
+
+        let root_mut = self.node(vecs, readers, root)?;
+        root_mut.addr_count += child_node.addr_count;
+        root_mut.balance += child_node.balance;
+        Ok(())
+    }
+
+    /// Entities changed by the block: their state before it, then their
+    /// roots and state after it. Clears the block's tracking.
+    pub(super) fn take_block_changes(
+        &mut self,
+        vecs: &EntityNodesVecs,
+        readers: &ByAddrType<Reader>,
+    ) -> Result<(Vec<EntityNode>, FxHashMap<AddrKey, EntityNode>)> {
+        let touched = std::mem::take(&mut self.touched);
+        let mut after = FxHashMap::default();
+        for key in touched.keys() {
+            let root = self.find(vecs, readers, *key)?;
+            let node = *self.node(vecs, readers, root)?;
+            after.insert(root, node);
+        }
+        Ok((touched.into_values().collect(), after))
+    }
+
+    /// Nodes loaded since the last call, changed or not, for flushing.
+    pub(super) fn take(&mut self) -> ByAddrType<FxHashMap<TypeIndex, EntityNode>> {
+        std::mem::take(&mut self.cache)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brk_types::Version;
+    use vecdb::Database;
+
+    use super::*;
+
+    fn open(name: &str) -> (Database, EntityNodesVecs) {
+        let path = std::env::temp_dir().join(format!("brk_entity_graph_{name}"));
+        let _ = std::fs::remove_dir_all(&path);
+        let db = Database::open(&path).unwrap();
+        let vecs = EntityNodesVecs::forced_import(&db, Version::ZERO).unwrap();
+        (db, vecs)
+    }
+
+    fn key(output_type: OutputType, index: usize) -> AddrKey {
+        (output_type, TypeIndex::from(index))
+    }
+
+    #[test]
+    fn find_of_unseen_address_is_itself() {
+        let (_db, vecs) = open("find");
+        let readers = vecs.create_readers();
+        let mut graph = EntityGraph::default();
+        let a = key(OutputType::P2WPKH, 3);
+        assert_eq!(graph.find(&vecs, &readers, a).unwrap(), a);
+    }
+
+    #[test]
+    fn union_merges_into_the_larger_entity() {
+        let (_db, vecs) = open("union");
+        let readers = vecs.create_readers();
+        let mut graph = EntityGraph::default();
+        let a = key(OutputType::P2WPKH, 0);
+        let b = key(OutputType::P2WPKH, 1);
+        let c = key(OutputType::P2TR, 0);
+
+        graph.receive(&vecs, &readers, a, Sats::new(10)).unwrap();
+        graph.receive(&vecs, &readers, b, Sats::new(20)).unwrap();
+        graph.receive(&vecs, &readers, c, Sats::new(5)).unwrap();
+        graph.union(&vecs, &readers, a, b).unwrap();
+        graph.union(&vecs, &readers, c, b).unwrap();
+        // Same entity twice is a no-op
+        graph.union(&vecs, &readers, a, c).unwrap();
+
+        let root = graph.find(&vecs, &readers, c).unwrap();
+        assert_eq!(root, a);
+        assert_eq!(graph.find(&vecs, &readers, b).unwrap(), root);
+        let node = *graph.node(&vecs, &readers, root).unwrap();
+        assert_eq!(node.addr_count, 3);
+        assert_eq!(node.balance, Sats::new(35));
+    }
+
+    #[test]
+    fn send_debits_the_root_and_rejects_underflow() {
+        let (_db, vecs) = open("send");
+        let readers = vecs.create_readers();
+        let mut graph = EntityGraph::default();
+        let a = key(OutputType::P2PKH, 0);
+        let b = key(OutputType::P2PKH, 1);
+
+        graph.receive(&vecs, &readers, a, Sats::new(100)).unwrap();
+        graph.union(&vecs, &readers, a, b).unwrap();
+        graph.send(&vecs, &readers, b, Sats::new(40)).unwrap();
+        let root = graph.find(&vecs, &readers, b).unwrap();
+        assert_eq!(
+            graph.node(&vecs, &readers, root).unwrap().balance,
+            Sats::new(60)
+        );
+
+        assert!(graph.send(&vecs, &readers, a, Sats::new(61)).is_err());
+    }
+
+    #[test]
+    fn block_changes_report_state_before_and_after() {
+        let (_db, vecs) = open("changes");
+        let readers = vecs.create_readers();
+        let mut graph = EntityGraph::default();
+        let a = key(OutputType::P2SH, 0);
+        let b = key(OutputType::P2SH, 1);
+
+        graph.receive(&vecs, &readers, a, Sats::new(7)).unwrap();
+        graph.take_block_changes(&vecs, &readers).unwrap();
+
+        graph.receive(&vecs, &readers, b, Sats::new(3)).unwrap();
+        graph.union(&vecs, &readers, a, b).unwrap();
+        let (before, after) = graph.take_block_changes(&vecs, &readers).unwrap();
+
+        let mut before_balances = before.iter().map(|n| n.balance).collect::<Vec<_>>();
+        before_balances.sort();
+        assert_eq!(before_balances, vec![Sats::ZERO, Sats::new(7)]);
+        assert_eq!(after.len(), 1);
+        assert_eq!(after[&a].balance, Sats::new(10));
+        assert_eq!(after[&a].addr_count, 2);
+    }
+}
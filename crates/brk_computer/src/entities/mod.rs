@@ -0,0 +1,144 @@
+//! Address clustering into entities, off unless enabled with
+//! [`crate::Computer::enable_entities`].
+//!
+//! Addresses are grouped by a union-find, one node per address, fed by
+//! the common-input-ownership heuristic (the inputs of a transaction share
+//! an owner) and a change heuristic (see [`process`]). Roots hold their
+//! entity's address count and balance, so the balance cohorts count
+//! entities where the distribution ones count addresses.
+
+use std::path::Path;
+
+use brk_error::Result;
+use brk_indexer::Indexer;
+use brk_traversable::Traversable;
+use brk_types::{Height, Version};
+use tracing::{info, warn};
+use vecdb::{AnyVec, Database, Exit, Rw, Stamp, StorageMode, VecIndex};
+
+mod cohorts;
+mod graph;
+mod nodes;
+mod process;
+
+pub use cohorts::EntityCohorts;
+pub use nodes::EntityNodesVecs;
+
+use crate::{
+    indexes, inputs,
+    internal::db_utils::{finalize_db, open_db},
+    price,
+};
+
+pub const DB_NAME: &str = "entities";
+
+const VERSION: Version = Version::ZERO;
+
+#[derive(Traversable)]
+pub struct Vecs<M: StorageMode = Rw> {
+    #[traversable(skip)]
+    db: Database,
+    #[traversable(skip)]
+    enabled: bool,
+
+    pub nodes: EntityNodesVecs<M>,
+    pub cohorts: EntityCohorts<M>,
+}
+
+impl Vecs {
+    pub(crate) fn forced_import(
+        parent_path: &Path,
+        parent_version: Version,
+        indexes: &indexes::Vecs,
+    ) -> Result<Self> {
+        let db = open_db(parent_path, DB_NAME, 1_000_000)?;
+        let version = parent_version + VERSION;
+
+        let this = Self {
+            nodes: EntityNodesVecs::forced_import(&db, version)?,
+            cohorts: EntityCohorts::forced_import(&db, version, indexes)?,
+            enabled: false,
+            db,
+        };
+
+        finalize_db(&this.db, &this)?;
+        Ok(this)
+    }
+
+    pub(crate) fn enable(&mut self) {
+        self.enabled = true;
+    }
+
+    pub(crate) fn compute(
+        &mut self,
+        indexer: &Indexer,
+        inputs: &inputs::Vecs,
+        prices: &price::Vecs,
+        exit: &Exit,
+    ) -> Result<()> {
+        if !self.enabled {
+            return Ok(());
+        }
+
+        let starting_height = self.recover(indexer.safe_lengths().height)?;
+        let last_height = Height::from(indexer.vecs.blocks.blockhash.len().saturating_sub(1));
+
+        if starting_height <= last_height {
+            process::process_blocks(self, indexer, inputs, starting_height, last_height, exit)?;
+        }
+
+        self.cohorts.compute_rest(starting_height, prices, exit)
+    }
+
+    /// Height to resume clustering from, rolling the nodes back on a
+    /// reorg. Resets everything when the nodes and the cohorts disagree.
+    fn recover(&mut self, safe_height: Height) -> Result<Height> {
+        let computed_height = self.cohorts.min_stateful_len();
+        let resume_target = computed_height.min(safe_height);
+
+        let mut starting_height = Height::ZERO;
+        if !resume_target.is_zero() && self.nodes.min_stamped_len() == computed_height {
+            if resume_target == computed_height {
+                starting_height = resume_target;
+            } else {
+                info!(
+                    "Reorg detected: rolling entities back from {} to {}",
+                    computed_height, resume_target
+                );
+                match self.nodes.rollback_before(Stamp::from(resume_target)) {
+                    Ok(stamps) => {
+                        let heights = stamps
+                            .into_iter()
+                            .map(|s| Height::from(s).incremented())
+                            .collect::<Vec<_>>();
+                        if heights
+                            .iter()
+                            .all(|h| *h == heights[0] && *h <= resume_target)
+                        {
+                            starting_height = heights[0];
+                        } else {
+                            warn!("Entity rollback inconsistent: {:?}", heights);
+                        }
+                    }
+                    Err(e) => warn!("Entity rollback failed: {e}"),
+                }
+            }
+        }
+
+        if starting_height.is_zero() {
+            info!("Entity clustering: fresh start");
+            self.nodes.reset()?;
+        }
+
+        self.cohorts
+            .truncate_stateful_at(starting_height.to_usize())?;
+        self.cohorts.import_states(starting_height);
+
+        Ok(starting_height)
+    }
+
+    pub(crate) fn flush(&self) -> Result<()> {
+        self.db.flush()?;
+        Ok(())
+    }
+}
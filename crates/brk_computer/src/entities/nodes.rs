@@ -0,0 +1,135 @@
+use brk_cohort::ByAddrType;
+use brk_error::Result;
+use brk_traversable::Traversable;
+use brk_types::{
+    EntityNode, Height, OutputType, P2AAddrIndex, P2PK33AddrIndex, P2PK65AddrIndex, P2PKHAddrIndex,
+    P2SHAddrIndex, P2TRAddrIndex, P2WPKHAddrIndex, P2WSHAddrIndex, TypeIndex, Version,
+};
+use rayon::prelude::*;
+use rustc_hash::FxHashMap;
+use vecdb::{
+    AnyStoredVec, AnyVec, BytesVec, Database, ImportOptions, ImportableVec, Reader, Rw, Stamp,
+    StorageMode, VecIndex, WritableVec,
+};
+
+const SAVED_STAMPED_CHANGES: u16 = 10;
+
+macro_rules! define_entity_nodes_vecs {
+    ($(($field:ident, $variant:ident, $index:ty)),* $(,)?) => {
+        /// Union-find nodes, one vec per address type, indexed like the
+        /// addresses themselves.
+        #[derive(Traversable)]
+        pub struct EntityNodesVecs<M: StorageMode = Rw> {
+            $(pub $field: M::Stored<BytesVec<$index, EntityNode>>,)*
+        }
+
+        impl EntityNodesVecs {
+            pub(crate) fn forced_import(db: &Database, version: Version) -> Result<Self> {
+                Ok(Self {
+                    $($field: BytesVec::forced_import_with(
+                        ImportOptions::new(db, "entity_node", version)
+                            .with_saved_stamped_changes(SAVED_STAMPED_CHANGES),
+                    )?,)*
+                })
+            }
+
+            /// Get minimum stamped height across all address types.
+            pub(crate) fn min_stamped_len(&self) -> Height {
+                [$(Height::from(self.$field.stamp()).incremented()),*]
+                    .into_iter()
+                    .min()
+                    .unwrap_or_default()
+            }
+
+            /// Rollback all address types to before the given stamp.
+            pub(crate) fn rollback_before(&mut self, stamp: Stamp) -> Result<Vec<Stamp>> {
+                Ok(vec![$(self.$field.rollback_before(stamp)?),*])
+            }
+
+            pub(crate) fn reset(&mut self) -> Result<()> {
+                $(self.$field.reset()?;)*
+                Ok(())
+            }
+
+            pub(crate) fn create_readers(&self) -> ByAddrType<Reader> {
+                ByAddrType {
+                    $($field: self.$field.create_reader(),)*
+                }
+            }
+
+            /// Stored node of an address, `None` if it was never flushed.
+            /// Checks the updated layer, needed after a rollback.
+            pub(crate) fn get(
+                &self,
+                addr_type: OutputType,
+                type_index: TypeIndex,
+                readers: &ByAddrType<Reader>,
+            ) -> Result<Option<EntityNode>> {
+                match addr_type {
+                    $(OutputType::$variant => {
+                        if usize::from(type_index) >= self.$field.len() {
+                            return Ok(None);
+                        }
+                        Ok(self.$field.get_any_or_read_at(
+                            usize::from(type_index),
+                            &readers.$field,
+                        )?)
+                    })*
+                    _ => unreachable!("Invalid addr type: {:?}", addr_type),
+                }
+            }
+
+            /// Writes the nodes changed since the last flush: updates in
+            /// place, pushes the new ones in index order.
+            pub(crate) fn apply(
+                &mut self,
+                changes: ByAddrType<FxHashMap<TypeIndex, EntityNode>>,
+            ) -> Result<()> {
+                let ByAddrType { $($field,)* } = changes;
+                $(apply_single_type(&mut self.$field, $field)?;)*
+                Ok(())
+            }
+
+            /// Returns a parallel iterator over all vecs for parallel writing.
+            pub(crate) fn par_iter_mut(&mut self) -> impl ParallelIterator<Item = &mut dyn AnyStoredVec> {
+                vec![$(&mut self.$field as &mut dyn AnyStoredVec),*].into_par_iter()
+            }
+        }
+    };
+}
+
+define_entity_nodes_vecs!(
+    (p2a, P2A, P2AAddrIndex),
+    (p2pk33, P2PK33, P2PK33AddrIndex),
+    (p2pk65, P2PK65, P2PK65AddrIndex),
+    (p2pkh, P2PKH, P2PKHAddrIndex),
+    (p2sh, P2SH, P2SHAddrIndex),
+    (p2tr, P2TR, P2TRAddrIndex),
+    (p2wpkh, P2WPKH, P2WPKHAddrIndex),
+    (p2wsh, P2WSH, P2WSHAddrIndex),
+);
+
+fn apply_single_type<I: VecIndex>(
+    vec: &mut BytesVec<I, EntityNode>,
+    changes: FxHashMap<TypeIndex, EntityNode>,
+) -> Result<()> {
+    let current_len = vec.len();
+    let mut pushes = Vec::new();
+
+    for (type_index, node) in changes {
+        if usize::from(type_index) < current_len {
+            vec.update(I::from(usize::from(type_index)), node)?;
+        } else {
+            pushes.push((type_index, node));
+        }
+    }
+
+    // Every new address gets a node the block it's first seen, so the
+    // pushes are contiguous.
+    pushes.sort_unstable_by_key(|(type_index, _)| *type_index);
+    for (_, node) in pushes {
+        vec.push(node);
+    }
+
+    Ok(())
+}
@@ -0,0 +1,349 @@
+//! Block loop of the clustering. Per transaction, the spent addresses
+//! lose their value and the paid ones receive it, then:
+//! - every input address joins one entity (common-input ownership),
+//! - with two outputs, the only one paying a never-seen address of the
+//!   inputs' script type is taken as change and joins them too.
+//!
+//! Transactions with two outputs of the same value look like CoinJoins,
+//! whose inputs belong to different people, and are left unclustered.
+
+use brk_cohort::ByAddrType;
+use brk_error::Result;
+use brk_indexer::Indexer;
+use brk_types::{Height, OutputType, Sats, TxIndex, TypeIndex};
+use rayon::prelude::*;
+use smallvec::SmallVec;
+use tracing::{debug, info};
+use vecdb::{AnyVec, Exit, ReadableVec, Reader, Stamp, VecIndex};
+
+use crate::inputs;
+
+use super::{
+    Vecs,
+    graph::{AddrKey, EntityGraph},
+    nodes::EntityNodesVecs,
+};
+
+const FLUSH_INTERVAL: usize = 10_000;
+
+/// One side of a transaction: address type, index and value.
+type Leg = (OutputType, TypeIndex, Sats);
+
+pub(super) fn process_blocks(
+    vecs: &mut Vecs,
+    indexer: &Indexer,
+    inputs: &inputs::Vecs,
+    starting_height: Height,
+    last_height: Height,
+    exit: &Exit,
+) -> Result<()> {
+    let start = starting_height.to_usize();
+    let end = last_height.to_usize() + 1;
+
+    // Block boundaries, with the end of the last block appended
+    let mut first_tx_indexes = indexer
+        .vecs
+        .transactions
+        .first_tx_index
+        .collect_range_at(start, end);
+    first_tx_indexes.push(TxIndex::from(indexer.vecs.transactions.txid.len()));
+    let mut first_txin_indexes = indexer
+        .vecs
+        .inputs
+        .first_txin_index
+        .collect_range_at(start, end)
+        .into_iter()
+        .map(|i| i.to_usize())
+        .collect::<Vec<_>>();
+    first_txin_indexes.push(indexer.vecs.inputs.outpoint.len());
+    let mut first_txout_indexes = indexer
+        .vecs
+        .outputs
+        .first_txout_index
+        .collect_range_at(start, end)
+        .into_iter()
+        .map(|i| i.to_usize())
+        .collect::<Vec<_>>();
+    first_txout_indexes.push(indexer.vecs.outputs.value.len());
+
+    let mut input_legs = LegReader::default();
+    let mut output_legs = LegReader::default();
+
+    let mut graph = EntityGraph::default();
+    let mut readers = vecs.nodes.create_readers();
+
+    for (offset, height) in (start..end).enumerate() {
+        let height = Height::from(height);
+        if height.to_usize() % FLUSH_INTERVAL == 0 {
+            info!("Clustering entities at {}...", height);
+        } else {
+            debug!("Clustering entities at {}...", height);
+        }
+
+        let (txin_start, txin_end) = (first_txin_indexes[offset], first_txin_indexes[offset + 1]);
+        let ins = input_legs.collect(
+            txin_start,
+            txin_end,
+            &indexer.vecs.inputs.tx_index,
+            &indexer.vecs.inputs.output_type,
+            &indexer.vecs.inputs.type_index,
+            &inputs.spent.value,
+        );
+        let (txout_start, txout_end) =
+            (first_txout_indexes[offset], first_txout_indexes[offset + 1]);
+        let outs = output_legs.collect(
+            txout_start,
+            txout_end,
+            &indexer.vecs.outputs.tx_index,
+            &indexer.vecs.outputs.output_type,
+            &indexer.vecs.outputs.type_index,
+            &indexer.vecs.outputs.value,
+        );
+
+        let mut i = 0;
+        let mut o = 0;
+        for tx_index in first_tx_indexes[offset].to_usize()..first_tx_indexes[offset + 1].to_usize()
+        {
+            let tx_index = TxIndex::from(tx_index);
+            let i_start = i;
+            while i < ins.len() && ins[i].0 == tx_index {
+                i += 1;
+            }
+            let o_start = o;
+            while o < outs.len() && outs[o].0 == tx_index {
+                o += 1;
+            }
+            process_tx(
+                &mut graph,
+                &vecs.nodes,
+                &readers,
+                &ins[i_start..i],
+                &outs[o_start..o],
+            )?;
+        }
+
+        let (before, after) = graph.take_block_changes(&vecs.nodes, &readers)?;
+        before
+            .iter()
+            .for_each(|node| vecs.cohorts.apply(node, false));
+        after
+            .values()
+            .for_each(|node| vecs.cohorts.apply(node, true));
+        vecs.cohorts.push_states();
+
+        if height != last_height && height.to_usize() % FLUSH_INTERVAL == 0 {
+            drop(readers);
+            let _lock = exit.lock();
+            write(vecs, &mut graph, height, false)?;
+            readers = vecs.nodes.create_readers();
+        }
+    }
+
+    drop(readers);
+    let _lock = exit.lock();
+    write(vecs, &mut graph, last_height, true)
+}
+
+fn process_tx(
+    graph: &mut EntityGraph,
+    nodes: &EntityNodesVecs,
+    readers: &ByAddrType<Reader>,
+    ins: &[(TxIndex, Leg)],
+    outs: &[(TxIndex, Leg)],
+) -> Result<()> {
+    let mut input_keys: SmallVec<[AddrKey; 4]> = SmallVec::new();
+    for &(_, (output_type, type_index, value)) in ins {
+        if output_type.is_not_addr() {
+            continue;
+        }
+        let key = (output_type, type_index);
+        graph.send(nodes, readers, key, value)?;
+        if !input_keys.contains(&key) {
+            input_keys.push(key);
+        }
+    }
+
+    let mut output_keys: SmallVec<[(AddrKey, bool); 2]> = SmallVec::new();
+    for &(_, (output_type, type_index, value)) in outs {
+        if output_type.is_not_addr() {
+            continue;
+        }
+        let key = (output_type, type_index);
+        let fresh = graph.ensure(nodes, readers, key)?;
+        graph.receive(nodes, readers, key, value)?;
+        output_keys.push((key, fresh));
+    }
+
+    let Some(&first) = input_keys.first() else {
+        return Ok(());
+    };
+    if looks_like_coinjoin(outs) {
+        return Ok(());
+    }
+
+    for &key in &input_keys[1..] {
+        graph.union(nodes, readers, first, key)?;
+    }
+
+    if outs.len() == 2
+        && let Some(change) = change_output(&input_keys, &output_keys)
+    {
+        graph.union(nodes, readers, first, change)?;
+    }
+
+    Ok(())
+}
+
+/// The fresh output, when it's the only one and both outputs pay an
+/// address: wallets send change to a new address of their own type,
+/// payments often go to known ones.
+fn change_output(input_keys: &[AddrKey], output_keys: &[(AddrKey, bool)]) -> Option<AddrKey> {
+    let [(a, a_fresh), (b, b_fresh)] = output_keys else {
+        return None;
+    };
+    let change = match (a_fresh, b_fresh) {
+        (true, false) => *a,
+        (false, true) => *b,
+        _ => return None,
+    };
+    input_keys
+        .iter()
+        .all(|(output_type, _)| *output_type == change.0)
+        .then_some(change)
+}
+
+fn looks_like_coinjoin(outs: &[(TxIndex, Leg)]) -> bool {
+    outs.iter().enumerate().any(|(i, (_, (_, _, value)))| {
+        *value > Sats::ZERO && outs[i + 1..].iter().any(|(_, (_, _, v))| v == value)
+    })
+}
+
+/// Reads the legs of a block's inputs or outputs, reusing its buffers.
+#[derive(Default)]
+struct LegReader {
+    tx_indexes: Vec<TxIndex>,
+    output_types: Vec<OutputType>,
+    type_indexes: Vec<TypeIndex>,
+    values: Vec<Sats>,
+    legs: Vec<(TxIndex, Leg)>,
+}
+
+impl LegReader {
+    fn collect<I: VecIndex>(
+        &mut self,
+        start: usize,
+        end: usize,
+        tx_index: &impl ReadableVec<I, TxIndex>,
+        output_type: &impl ReadableVec<I, OutputType>,
+        type_index: &impl ReadableVec<I, TypeIndex>,
+        value: &impl ReadableVec<I, Sats>,
+    ) -> &[(TxIndex, Leg)] {
+        tx_index.collect_range_into_at(start, end, &mut self.tx_indexes);
+        output_type.collect_range_into_at(start, end, &mut self.output_types);
+        type_index.collect_range_into_at(start, end, &mut self.type_indexes);
+        value.collect_range_into_at(start, end, &mut self.values);
+
+        self.legs.clear();
+        self.legs.extend(
+            self.tx_indexes
+                .iter()
+                .zip(&self.output_types)
+                .zip(&self.type_indexes)
+                .zip(&self.values)
+                .map(|(((&tx_index, &output_type), &type_index), &value)| {
+                    (tx_index, (output_type, type_index, value))
+                }),
+        );
+        &self.legs
+    }
+}
+
+/// Writes the nodes and the cohorts' running series. Set `with_changes`
+/// near the tip to enable rollback.
+fn write(
+    vecs: &mut Vecs,
+    graph: &mut EntityGraph,
+    height: Height,
+    with_changes: bool,
+) -> Result<()> {
+    vecs.nodes.apply(graph.take())?;
+
+    let stamp = Stamp::from(height);
+    vecs.nodes
+        .par_iter_mut()
+        .chain(vecs.cohorts.par_iter_stateful_mut())
+        .try_for_each(|v| v.any_stamped_write_maybe_with_changes(stamp, with_changes))?;
+
+    vecs.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn out(output_type: OutputType, index: usize, value: u64) -> (TxIndex, Leg) {
+        (
+            TxIndex::from(0usize),
+            (output_type, TypeIndex::from(index), Sats::new(value)),
+        )
+    }
+
+    fn key(output_type: OutputType, index: usize) -> AddrKey {
+        (output_type, TypeIndex::from(index))
+    }
+
+    #[test]
+    fn equal_outputs_look_like_a_coinjoin() {
+        let outs = [
+            out(OutputType::P2WPKH, 0, 100_000),
+            out(OutputType::P2WPKH, 1, 4_321),
+            out(OutputType::P2WPKH, 2, 100_000),
+        ];
+        assert!(looks_like_coinjoin(&outs));
+    }
+
+    #[test]
+    fn distinct_or_empty_outputs_dont_look_like_a_coinjoin() {
+        let outs = [
+            out(OutputType::P2WPKH, 0, 100_000),
+            out(OutputType::P2WPKH, 1, 4_321),
+        ];
+        assert!(!looks_like_coinjoin(&outs));
+
+        let zero = [
+            out(OutputType::OpReturn, 0, 0),
+            out(OutputType::OpReturn, 1, 0),
+        ];
+        assert!(!looks_like_coinjoin(&zero));
+    }
+
+    #[test]
+    fn single_fresh_output_of_the_input_type_is_change() {
+        let inputs = [key(OutputType::P2WPKH, 0), key(OutputType::P2WPKH, 1)];
+        let fresh = key(OutputType::P2WPKH, 9);
+        let known = key(OutputType::P2TR, 4);
+        assert_eq!(
+            change_output(&inputs, &[(known, false), (fresh, true)]),
+            Some(fresh)
+        );
+        assert_eq!(
+            change_output(&inputs, &[(fresh, true), (known, false)]),
+            Some(fresh)
+        );
+    }
+
+    #[test]
+    fn ambiguous_or_mismatched_outputs_have_no_change() {
+        let inputs = [key(OutputType::P2WPKH, 0)];
+        let a = key(OutputType::P2WPKH, 5);
+        let b = key(OutputType::P2WPKH, 6);
+        // Both fresh, both known
+        assert_eq!(change_output(&inputs, &[(a, true), (b, true)]), None);
+        assert_eq!(change_output(&inputs, &[(a, false), (b, false)]), None);
+        // Fresh output of another script type
+        let other = key(OutputType::P2TR, 1);
+        assert_eq!(change_output(&inputs, &[(other, true), (a, false)]), None);
+        // Not two address outputs
+        assert_eq!(change_output(&inputs, &[(a, true)]), None);
+    }
+}
@@ -1,10 +1,14 @@
 use brk_error::Result;
 use brk_indexer::Indexer;
-use brk_types::{Bitcoin, Dollars, StoredF32};
+use brk_types::{Bitcoin, Dollars, Sats, StoredF32};
 use vecdb::Exit;
 
 use super::{Vecs, gini};
-use crate::{distribution, internal::RatioDollarsBp32, market, mining, transactions};
+use crate::{
+    distribution,
+    internal::{RatioDollarsBp32, RatioSatsBp16},
+    market, mining, transactions,
+};
 
 impl Vecs {
     #[allow(clippy::too_many_arguments)]
@@ -92,6 +96,21 @@ impl Vecs {
         let all_activity = &all_metrics.activity;
         let supply_total_sats = &all_metrics.supply.total.sats.height;
 
+        // Top holders: supply of the N largest funded addresses / circulating supply
+        let rich_list = &distribution.addrs.rich_list;
+        for (share, top) in [
+            (&mut self.top_holders.top_10, &rich_list.top_10),
+            (&mut self.top_holders.top_100, &rich_list.top_100),
+            (&mut self.top_holders.top_1000, &rich_list.top_1000),
+        ] {
+            share.compute_binary::<Sats, Sats, RatioSatsBp16>(
+                starting_lengths.height,
+                &top.all.sats.height,
+                supply_total_sats,
+                exit,
+            )?;
+        }
+
         // Supply-Adjusted CDD = sum_24h(CDD) / circulating_supply_btc
         self.coindays_destroyed_supply_adj
             .height
@@ -26,6 +26,11 @@ impl Vecs {
         let puell_multiple = RatioPerBlock::forced_import_raw(&db, "puell_multiple", v, indexes)?;
         let nvt = RatioPerBlock::forced_import_raw(&db, "nvt", v, indexes)?;
         let gini = PercentPerBlock::forced_import(&db, "gini", v, indexes)?;
+        let top_holders = super::vecs::TopHoldersVecs {
+            top_10: PercentPerBlock::forced_import(&db, "top_10_supply_share", v, indexes)?,
+            top_100: PercentPerBlock::forced_import(&db, "top_100_supply_share", v, indexes)?,
+            top_1000: PercentPerBlock::forced_import(&db, "top_1000_supply_share", v, indexes)?,
+        };
         let rhodl_ratio = RatioPerBlock::forced_import_raw(&db, "rhodl_ratio", v, indexes)?;
         let thermo_cap_multiple =
             RatioPerBlock::forced_import_raw(&db, "thermo_cap_multiple", v, indexes)?;
@@ -47,6 +52,7 @@ impl Vecs {
             puell_multiple,
             nvt,
             gini,
+            top_holders,
             rhodl_ratio,
             thermo_cap_multiple,
             coindays_destroyed_supply_adj,
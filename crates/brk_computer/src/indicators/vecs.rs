@@ -11,6 +11,14 @@ pub struct DormancyVecs<M: StorageMode = Rw> {
     pub flow: PerBlock<StoredF32, M>,
 }
 
+/// Share of the supply held by the largest funded addresses
+#[derive(Traversable)]
+pub struct TopHoldersVecs<M: StorageMode = Rw> {
+    pub top_10: PercentPerBlock<BasisPoints16, M>,
+    pub top_100: PercentPerBlock<BasisPoints16, M>,
+    pub top_1000: PercentPerBlock<BasisPoints16, M>,
+}
+
 #[derive(Traversable)]
 pub struct Vecs<M: StorageMode = Rw> {
     #[traversable(skip)]
@@ -18,6 +26,7 @@ pub struct Vecs<M: StorageMode = Rw> {
     pub puell_multiple: RatioPerBlock<BasisPoints32, M>,
     pub nvt: RatioPerBlock<BasisPoints32, M>,
     pub gini: PercentPerBlock<BasisPoints16, M>,
+    pub top_holders: TopHoldersVecs<M>,
     pub rhodl_ratio: RatioPerBlock<BasisPoints32, M>,
     pub thermo_cap_multiple: RatioPerBlock<BasisPoints32, M>,
     pub coindays_destroyed_supply_adj: PerBlock<StoredF32, M>,
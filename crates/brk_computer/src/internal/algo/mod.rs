@@ -1,6 +1,7 @@
 mod drawdown;
 mod expanding_percentiles;
 mod fenwick;
+mod rolling_moments;
 mod sliding_distribution;
 mod sliding_median;
 mod sliding_window;
@@ -8,5 +9,6 @@ mod sliding_window;
 pub(crate) use drawdown::*;
 pub(crate) use expanding_percentiles::*;
 pub(crate) use fenwick::*;
+pub(crate) use rolling_moments::*;
 pub(crate) use sliding_distribution::*;
 pub(crate) use sliding_median::*;
@@ -0,0 +1,165 @@
+use std::collections::VecDeque;
+
+/// Neumaier-compensated sum: the low bits each addition drops are kept
+/// aside, so adding then removing a large value gives back the sum of
+/// the small ones.
+#[derive(Default, Clone, Copy)]
+struct CompensatedSum {
+    sum: f64,
+    compensation: f64,
+}
+
+impl CompensatedSum {
+    fn add(&mut self, value: f64) {
+        let t = self.sum + value;
+        self.compensation += if self.sum.abs() >= value.abs() {
+            (self.sum - t) + value
+        } else {
+            (value - t) + self.sum
+        };
+        self.sum = t;
+    }
+
+    fn get(&self) -> f64 {
+        self.sum + self.compensation
+    }
+}
+
+/// Mean and population standard deviation of the last `n` values.
+///
+/// Running sums are compensated, and rebuilt from the window every `n`
+/// values around its newest one, so neither rounding errors nor the
+/// variance's `E[X²] - E[X]²` cancellation grow with the series length.
+pub(crate) struct RollingMoments {
+    window: VecDeque<f64>,
+    n: usize,
+    /// Subtracted from every value before summing.
+    shift: f64,
+    sum: CompensatedSum,
+    sum_sq: CompensatedSum,
+    until_rebuild: usize,
+}
+
+impl RollingMoments {
+    pub fn new(n: usize) -> Self {
+        Self {
+            window: VecDeque::with_capacity(n.min(1 << 16)),
+            n,
+            shift: 0.0,
+            sum: CompensatedSum::default(),
+            sum_sq: CompensatedSum::default(),
+            until_rebuild: n,
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.window.clear();
+        self.sum = CompensatedSum::default();
+        self.sum_sq = CompensatedSum::default();
+        self.until_rebuild = self.n;
+    }
+
+    /// Adds `value`, dropping the oldest one once `n` are held.
+    pub fn push(&mut self, value: f64) {
+        if self.window.is_empty() {
+            self.shift = value;
+        }
+        if self.window.len() == self.n {
+            let old = self.window.pop_front().unwrap() - self.shift;
+            self.sum.add(-old);
+            self.sum_sq.add(-old * old);
+        }
+        self.window.push_back(value);
+        let d = value - self.shift;
+        self.sum.add(d);
+        self.sum_sq.add(d * d);
+
+        self.until_rebuild -= 1;
+        if self.until_rebuild == 0 {
+            self.rebuild();
+        }
+    }
+
+    pub fn is_full(&self) -> bool {
+        self.window.len() == self.n
+    }
+
+    pub fn mean(&self) -> f64 {
+        self.shift + self.sum.get() / self.window.len() as f64
+    }
+
+    pub fn sd(&self) -> f64 {
+        let len = self.window.len() as f64;
+        let mean = self.sum.get() / len;
+        (self.sum_sq.get() / len - mean * mean).max(0.0).sqrt()
+    }
+
+    fn rebuild(&mut self) {
+        self.shift = self.window.back().copied().unwrap_or_default();
+        self.sum = CompensatedSum::default();
+        self.sum_sq = CompensatedSum::default();
+        for value in &self.window {
+            let d = value - self.shift;
+            self.sum.add(d);
+            self.sum_sq.add(d * d);
+        }
+        self.until_rebuild = self.n;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exact(window: &[f64]) -> (f64, f64) {
+        let len = window.len() as f64;
+        let mean = window.iter().sum::<f64>() / len;
+        let var = window.iter().map(|v| (v - mean) * (v - mean)).sum::<f64>() / len;
+        (mean, var.sqrt())
+    }
+
+    #[test]
+    fn matches_two_pass_over_a_long_series() {
+        let values: Vec<f64> = (0..200_000)
+            .map(|i| 1e9 + ((i * 7_919) % 1_000) as f64 * 0.001 + i as f64 * 1e3)
+            .collect();
+        let n = 30;
+        let mut moments = RollingMoments::new(n);
+        for (i, value) in values.iter().enumerate() {
+            moments.push(*value);
+            if i + 1 >= n && i % 9_973 == 0 {
+                let (mean, sd) = exact(&values[i + 1 - n..=i]);
+                assert!((moments.mean() - mean).abs() <= mean.abs() * 1e-12);
+                assert!(
+                    (moments.sd() - sd).abs() <= 1e-6,
+                    "{} vs {sd}",
+                    moments.sd()
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn large_value_leaves_no_trace() {
+        let mut moments = RollingMoments::new(3);
+        for value in [1e17, 1.0, 2.0, 3.0] {
+            moments.push(value);
+        }
+        assert_eq!(moments.mean(), 2.0);
+        assert_eq!(moments.sd(), (2.0f64 / 3.0).sqrt());
+    }
+
+    #[test]
+    fn fills_up_again_after_clear() {
+        let mut moments = RollingMoments::new(2);
+        moments.push(1.0);
+        moments.push(2.0);
+        assert!(moments.is_full());
+        moments.clear();
+        moments.push(5.0);
+        assert!(!moments.is_full());
+        moments.push(7.0);
+        assert_eq!(moments.mean(), 6.0);
+        assert_eq!(moments.sd(), 1.0);
+    }
+}
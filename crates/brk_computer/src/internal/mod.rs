@@ -6,6 +6,7 @@ pub(crate) mod db_utils;
 mod indexes;
 mod per_block;
 mod per_tx;
+mod series_expr;
 mod traits;
 mod transform;
 mod value;
@@ -17,6 +18,7 @@ pub(crate) use containers::*;
 pub(crate) use indexes::*;
 pub(crate) use per_block::*;
 pub(crate) use per_tx::*;
+pub use series_expr::*;
 pub(crate) use traits::*;
 pub use transform::*;
 pub(crate) use value::*;
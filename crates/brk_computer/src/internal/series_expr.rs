@@ -0,0 +1,173 @@
+use brk_types::{BinaryOp, SeriesExpr, SeriesFn, SeriesName};
+
+use super::algo::RollingMoments;
+
+/// Evaluation of [`SeriesExpr`]s, shared by custom series and the API.
+/// Windows follow the computer's own: `ema` is seeded with the average
+/// of its first `n` points like `compute_ema`, `pct_change` is
+/// `compute_percentage_change`'s `(x / prev - 1) * 100`.
+pub trait SeriesExprEval {
+    /// Evaluates the expression point by point over `len` aligned values,
+    /// `leaves` holding the values of each referenced series. Missing
+    /// inputs, divisions by zero and other non-finite results become `None`.
+    fn eval(&self, leaves: &[(&SeriesName, Vec<Option<f64>>)], len: usize) -> Vec<Option<f64>>;
+}
+
+impl SeriesExprEval for SeriesExpr {
+    fn eval(&self, leaves: &[(&SeriesName, Vec<Option<f64>>)], len: usize) -> Vec<Option<f64>> {
+        match self {
+            Self::Series(name) => leaves
+                .iter()
+                .find(|(leaf, _)| leaf.normalize() == name.normalize())
+                .map(|(_, values)| values.clone())
+                .unwrap_or_else(|| vec![None; len]),
+            Self::Number(n) => vec![Some(*n); len],
+            Self::Neg(x) => x
+                .eval(leaves, len)
+                .into_iter()
+                .map(|v| v.map(|v| -v))
+                .collect(),
+            Self::Binary(op, l, r) => l
+                .eval(leaves, len)
+                .into_iter()
+                .zip(r.eval(leaves, len))
+                .map(|(l, r)| {
+                    let (l, r) = (l?, r?);
+                    finite(match op {
+                        BinaryOp::Add => l + r,
+                        BinaryOp::Sub => l - r,
+                        BinaryOp::Mul => l * r,
+                        BinaryOp::Div => l / r,
+                    })
+                })
+                .collect(),
+            Self::Call(func, x, n) => {
+                let x = x.eval(leaves, len);
+                let n = *n;
+                match func {
+                    SeriesFn::Sma => rolling(&x, n, |m, _| m.mean()),
+                    SeriesFn::StdDev => rolling(&x, n, |m, _| m.sd()),
+                    SeriesFn::ZScore => rolling(&x, n, |m, v| (v - m.mean()) / m.sd()),
+                    SeriesFn::Ema => ema(&x, n),
+                    SeriesFn::Lag => (0..x.len())
+                        .map(|i| i.checked_sub(n).and_then(|j| x[j]))
+                        .collect(),
+                    SeriesFn::PctChange => (0..x.len())
+                        .map(|i| {
+                            let prev = x[i.checked_sub(n)?]?;
+                            finite((x[i]? / prev - 1.0) * 100.0)
+                        })
+                        .collect(),
+                }
+            }
+        }
+    }
+}
+
+fn finite(v: f64) -> Option<f64> {
+    v.is_finite().then_some(v)
+}
+
+/// `f` of the last `n` values and the current one, `None` until the
+/// window is full and while it holds a missing value.
+fn rolling(
+    x: &[Option<f64>],
+    n: usize,
+    f: impl Fn(&RollingMoments, f64) -> f64,
+) -> Vec<Option<f64>> {
+    let mut moments = RollingMoments::new(n);
+    x.iter()
+        .map(|v| {
+            let Some(v) = *v else {
+                moments.clear();
+                return None;
+            };
+            moments.push(v);
+            if !moments.is_full() {
+                return None;
+            }
+            finite(f(&moments, v))
+        })
+        .collect()
+}
+
+/// Average of the values so far over the first `n`, then smoothed with
+/// `alpha = 2 / (n + 1)`. Missing values are skipped.
+fn ema(x: &[Option<f64>], n: usize) -> Vec<Option<f64>> {
+    let alpha = 2.0 / (n as f64 + 1.0);
+    let (mut state, mut seen) = (0.0, 0usize);
+    x.iter()
+        .map(|v| {
+            let v = (*v)?;
+            seen += 1;
+            state = if seen > n {
+                state + alpha * (v - state)
+            } else {
+                state + (v - state) / seen as f64
+            };
+            finite(state)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn eval(s: &str, values: Vec<Option<f64>>) -> Vec<Option<f64>> {
+        let a = SeriesName::from("a");
+        let len = values.len();
+        s.parse::<SeriesExpr>().unwrap().eval(&[(&a, values)], len)
+    }
+
+    #[test]
+    fn arithmetic_windows_and_lags() {
+        let a = vec![Some(1.0), Some(2.0), None, Some(4.0), Some(5.0)];
+        assert_eq!(
+            eval("sma(a, 2)", a.clone()),
+            [None, Some(1.5), None, None, Some(4.5)]
+        );
+        assert_eq!(
+            eval("a / lag(a, 1) - 1", a.clone()),
+            [None, Some(1.0), None, None, Some(0.25)]
+        );
+        assert_eq!(
+            eval("pct_change(a)", a.clone()),
+            [None, Some(100.0), None, None, Some(25.0)]
+        );
+        assert_eq!(eval("a / 0", a), [None; 5]);
+    }
+
+    #[test]
+    fn stddev_and_zscore() {
+        let a = vec![Some(2.0), Some(4.0), Some(4.0), Some(4.0), Some(6.0)];
+        assert_eq!(
+            eval("stddev(a, 2)", a.clone()),
+            [None, Some(1.0), Some(0.0), Some(0.0), Some(1.0)]
+        );
+        assert_eq!(
+            eval("zscore(a, 2)", a),
+            [None, Some(1.0), None, None, Some(1.0)]
+        );
+    }
+
+    #[test]
+    fn ema_is_seeded_with_the_average() {
+        let a = vec![Some(1.0), Some(3.0), None, Some(5.0), Some(8.0)];
+        let ema = eval("ema(a, 2)", a);
+        assert_eq!(ema[..4], [Some(1.0), Some(2.0), None, Some(4.0)]);
+        assert!((ema[4].unwrap() - 20.0 / 3.0).abs() < 1e-12);
+    }
+
+    /// A large value once in a long series doesn't shift the windows
+    /// long after it left them.
+    #[test]
+    fn windows_dont_drift() {
+        let mut a: Vec<Option<f64>> = (0..100_000).map(|i| Some((i % 10) as f64)).collect();
+        a[10] = Some(1e18);
+        let sma = eval("sma(a, 10)", a.clone());
+        let sd = eval("stddev(a, 10)", a);
+        assert_eq!(sma.last(), Some(&Some(4.5)));
+        assert_eq!(sd.last(), Some(&Some(8.25f64.sqrt())));
+    }
+}
@@ -5,20 +5,23 @@ use std::{fs, path::Path, thread, time::Instant};
 use brk_error::Result;
 use brk_indexer::Indexer;
 use brk_traversable::Traversable;
-use brk_types::{Height, Version};
+use brk_types::{CustomSeries, Height, MempoolHistory, Version};
 use tracing::info;
 use vecdb::{AnyExportableVec, Exit, Ro, Rw, StorageMode};
 
 mod blocks;
 mod cointime;
 mod constants;
+mod custom;
 mod distribution;
+mod entities;
 pub mod indexes;
 mod indicators;
 mod inputs;
 mod internal;
 mod investing;
 mod market;
+mod mempool;
 mod mining;
 mod outputs;
 mod pools;
@@ -26,6 +29,8 @@ pub mod price;
 mod supply;
 mod transactions;
 
+pub use internal::SeriesExprEval;
+
 #[derive(Traversable)]
 pub struct Computer<M: StorageMode = Rw> {
     pub blocks: Box<blocks::Vecs<M>>,
@@ -41,15 +46,29 @@ pub struct Computer<M: StorageMode = Rw> {
     pub price: Box<price::Vecs<M>>,
     #[traversable(flatten)]
     pub distribution: Box<distribution::Vecs<M>>,
+    pub entities: Box<entities::Vecs<M>>,
     pub supply: Box<supply::Vecs<M>>,
     pub inputs: Box<inputs::Vecs<M>>,
     pub outputs: Box<outputs::Vecs<M>>,
+    pub mempool: Box<mempool::Vecs<M>>,
+    pub custom: Box<custom::Vecs<M>>,
 }
 
 const VERSION: Version = Version::new(6);
 
 impl Computer {
     pub fn forced_import(outputs_path: &Path, indexer: &Indexer) -> Result<Self> {
+        Self::forced_import_with_custom(outputs_path, indexer, &[])
+    }
+
+    /// Same as `forced_import`, plus the custom series declared in
+    /// `series.toml`. Fails if one is invalid, shadows a built-in series or
+    /// references a series missing at its index.
+    pub fn forced_import_with_custom(
+        outputs_path: &Path,
+        indexer: &Indexer,
+        custom_series: &[CustomSeries],
+    ) -> Result<Self> {
         info!("Importing computer...");
         let import_start = Instant::now();
 
@@ -196,6 +215,14 @@ impl Computer {
                 })
             })?;
 
+        let entities = timed("Imported entities", || -> Result<_> {
+            Ok(Box::new(entities::Vecs::forced_import(
+                &computed_path,
+                VERSION,
+                &indexes,
+            )?))
+        })?;
+
         let supply = timed("Imported supply", || -> Result<_> {
             Ok(Box::new(supply::Vecs::forced_import(
                 &computed_path,
@@ -207,6 +234,21 @@ impl Computer {
             )?))
         })?;
 
+        let mempool = timed("Imported mempool", || -> Result<_> {
+            Ok(Box::new(mempool::Vecs::forced_import(
+                &computed_path,
+                VERSION,
+            )?))
+        })?;
+
+        let custom = timed("Imported custom", || -> Result<_> {
+            Ok(Box::new(custom::Vecs::forced_import(
+                &computed_path,
+                VERSION,
+                custom_series,
+            )?))
+        })?;
+
         info!("Total import time: {:?}", import_start.elapsed());
 
         let this = Self {
@@ -218,6 +260,7 @@ impl Computer {
             investing,
             market,
             distribution,
+            entities,
             supply,
             pools,
             cointime,
@@ -225,8 +268,12 @@ impl Computer {
             inputs,
             price,
             outputs,
+            mempool,
+            custom,
         };
 
+        this.custom.check(&this.custom_sources(indexer))?;
+
         Self::retain_databases(&computed_path)?;
 
         Ok(this)
@@ -246,9 +293,12 @@ impl Computer {
             pools::DB_NAME,
             price::DB_NAME,
             distribution::DB_NAME,
+            entities::DB_NAME,
             supply::DB_NAME,
             inputs::DB_NAME,
             outputs::DB_NAME,
+            mempool::DB_NAME,
+            custom::DB_NAME,
         ];
 
         if !computed_path.exists() {
@@ -362,6 +412,7 @@ impl Computer {
                         &self.blocks,
                         &self.price,
                         &self.mining,
+                        &self.mempool,
                         exit,
                     )
                 })
@@ -380,6 +431,13 @@ impl Computer {
                 })
             });
 
+            let entities = scope.spawn(|| {
+                timed("Computed entities", || {
+                    self.entities
+                        .compute(indexer, &self.inputs, &self.price, exit)
+                })
+            });
+
             timed("Computed distribution", || {
                 self.distribution.compute(
                     indexer,
@@ -395,6 +453,7 @@ impl Computer {
 
             pools.join().unwrap()?;
             investing.join().unwrap()?;
+            entities.join().unwrap()?;
             Ok(())
         })?;
 
@@ -447,9 +506,44 @@ impl Computer {
             .rarity_meter
             .compute(indexer, &self.distribution, &self.price, exit)?;
 
+        timed("Computed custom", || {
+            let mut custom = self.custom.take();
+            let result =
+                custom.compute(indexer, &self.indexes, &self.custom_sources(indexer), exit);
+            *self.custom = custom;
+            result
+        })?;
+
         info!("Total compute time: {:?}", compute_start.elapsed());
         Ok(())
     }
+
+    /// Turns on address clustering (see `entities`), off by default as it
+    /// keeps a node per address on disk. Call it before `compute`.
+    pub fn enable_entities(&mut self) {
+        self.entities.enable();
+    }
+
+    /// Writes the mempool samples recorded since the previous call (see
+    /// `brk_mempool::Mempool::take_history`). Call it before `compute`
+    /// so custom series built on mempool series see them.
+    pub fn record_mempool(&mut self, history: MempoolHistory, exit: &Exit) -> Result<()> {
+        self.mempool.record(history, exit)
+    }
+
+    /// Every indexed and computed vec a custom series may reference,
+    /// custom ones excluded.
+    fn custom_sources<'a>(&'a self, indexer: &'a Indexer) -> Vec<&'a dyn AnyExportableVec> {
+        indexer
+            .vecs
+            .iter_any_exportable()
+            .chain(
+                self.iter_named_exportable()
+                    .filter(|(db, _)| *db != custom::DB_NAME)
+                    .map(|(_, vec)| vec),
+            )
+            .collect()
+    }
 }
 
 impl Computer<Ro> {
@@ -500,9 +594,12 @@ impl_iter_named!(
     pools,
     price,
     distribution,
+    entities,
     supply,
     inputs,
-    outputs
+    outputs,
+    mempool,
+    custom
 );
 
 fn timed<T>(label: &str, f: impl FnOnce() -> T) -> T {
@@ -0,0 +1,142 @@
+use brk_error::Result;
+use brk_types::{
+    BlockAudit, Height, MempoolHistory, MempoolSample, Minute10, SatsSigned, StoredF32, StoredU32,
+    StoredU64,
+};
+use schemars::JsonSchema;
+use vecdb::{AnyStoredVec, AnyVec, EagerVec, Exit, PcoVec, VecIndex, WritableVec};
+
+use super::{Sampled, Vecs};
+use crate::internal::ComputedVecValue;
+
+/// Where a sample lands.
+#[derive(Clone, Copy)]
+enum At {
+    Height(Height),
+    Minute10(Minute10),
+}
+
+impl Vecs {
+    /// Writes the samples the mempool driver recorded since the previous
+    /// pass. A height or bucket recorded again (a reorg, or a bucket
+    /// still open at the last pass) replaces its old value and drops
+    /// everything after it.
+    pub(crate) fn record(&mut self, history: MempoolHistory, exit: &Exit) -> Result<()> {
+        if history.is_empty() {
+            return Ok(());
+        }
+
+        for (height, sample) in &history.height {
+            self.push(At::Height(*height), sample)?;
+        }
+        for (minute10, sample) in &history.minute10 {
+            self.push(At::Minute10(*minute10), sample)?;
+        }
+        for (height, audit) in &history.audit {
+            self.push_audit(*height, audit)?;
+        }
+
+        let _lock = exit.lock();
+        self.tx_count.write()?;
+        self.vsize.write()?;
+        self.total_fee.write()?;
+        for band in self.band_vsize.values_mut() {
+            band.write()?;
+        }
+        self.fastest_fee.write()?;
+        self.half_hour_fee.write()?;
+        self.hour_fee.write()?;
+        self.economy_fee.write()?;
+        self.minimum_fee.write()?;
+        self.next_block_fee.write()?;
+        self.next_block_median_fee_rate.write()?;
+        self.block_audit_match_rate.write()?;
+        self.block_audit_missing_tx_count.write()?;
+        self.block_audit_added_tx_count.write()?;
+        self.block_audit_fee_delta.write()?;
+        Ok(())
+    }
+
+    fn push(&mut self, at: At, sample: &MempoolSample) -> Result<()> {
+        self.tx_count.push(at, StoredU64::from(sample.count))?;
+        self.vsize.push(at, sample.vsize)?;
+        self.total_fee.push(at, sample.total_fee)?;
+        for (band, vsize) in self.band_vsize.values_mut().zip(sample.band_vsize) {
+            band.push(at, vsize)?;
+        }
+        let fees = &sample.fees;
+        self.fastest_fee.push(at, fees.fastest_fee)?;
+        self.half_hour_fee.push(at, fees.half_hour_fee)?;
+        self.hour_fee.push(at, fees.hour_fee)?;
+        self.economy_fee.push(at, fees.economy_fee)?;
+        self.minimum_fee.push(at, fees.minimum_fee)?;
+        self.next_block_fee.push(at, sample.next_block_fee)?;
+        self.next_block_median_fee_rate
+            .push(at, sample.next_block_median_fee_rate)?;
+        Ok(())
+    }
+
+    fn push_audit(&mut self, height: Height, audit: &BlockAudit) -> Result<()> {
+        let i = height.to_usize();
+        put(
+            &mut self.block_audit_match_rate,
+            i,
+            StoredF32::from(audit.match_rate as f32),
+            StoredF32::NAN,
+        )?;
+        put(
+            &mut self.block_audit_missing_tx_count,
+            i,
+            StoredU32::from(audit.missing.len()),
+            StoredU32::ZERO,
+        )?;
+        put(
+            &mut self.block_audit_added_tx_count,
+            i,
+            StoredU32::from(audit.added.len()),
+            StoredU32::ZERO,
+        )?;
+        put(
+            &mut self.block_audit_fee_delta,
+            i,
+            audit.fee_delta,
+            SatsSigned::ZERO,
+        )?;
+        Ok(())
+    }
+}
+
+impl<T> Sampled<T>
+where
+    T: ComputedVecValue + PartialOrd + JsonSchema + Default,
+{
+    fn push(&mut self, at: At, value: T) -> Result<()> {
+        match at {
+            At::Height(height) => put(&mut self.height, height.to_usize(), value, T::default()),
+            At::Minute10(minute10) => {
+                put(&mut self.minute10, minute10.to_usize(), value, T::default())
+            }
+        }
+    }
+
+    fn write(&mut self) -> Result<()> {
+        self.height.write()?;
+        self.minute10.write()?;
+        Ok(())
+    }
+}
+
+/// Sets `vec[index] = value`, filling the gap since the last entry with
+/// `gap`.
+fn put<I, T>(vec: &mut EagerVec<PcoVec<I, T>>, index: usize, value: T, gap: T) -> Result<()>
+where
+    I: VecIndex,
+    T: ComputedVecValue,
+{
+    vec.truncate_if_needed_at(index)?;
+    while vec.len() < index {
+        vec.push(gap);
+    }
+    vec.push(value);
+    Ok(())
+}
@@ -0,0 +1,79 @@
+use std::{collections::BTreeMap, path::Path};
+
+use brk_error::Result;
+use brk_types::{MEMPOOL_FEE_BANDS, Version};
+use schemars::JsonSchema;
+use vecdb::{Database, EagerVec, ImportableVec};
+
+use super::{Sampled, Vecs};
+use crate::internal::{
+    ComputedVecValue,
+    db_utils::{finalize_db, open_db},
+};
+
+const VERSION: Version = Version::new(1);
+
+impl Vecs {
+    pub(crate) fn forced_import(parent_path: &Path, parent_version: Version) -> Result<Self> {
+        let db = open_db(parent_path, super::DB_NAME, 10_000)?;
+        let v = parent_version + VERSION;
+
+        let band_vsize = MEMPOOL_FEE_BANDS
+            .iter()
+            .enumerate()
+            .map(|(i, &lower)| {
+                let name = match MEMPOOL_FEE_BANDS.get(i + 1) {
+                    Some(upper) => format!("mempool_vsize_fee_rate_{lower}_to_{upper}"),
+                    None => format!("mempool_vsize_fee_rate_{lower}_plus"),
+                };
+                Ok((lower, Sampled::forced_import(&db, &name, v)?))
+            })
+            .collect::<Result<BTreeMap<_, _>>>()?;
+
+        let this = Self {
+            tx_count: Sampled::forced_import(&db, "mempool_tx_count", v)?,
+            vsize: Sampled::forced_import(&db, "mempool_vsize", v)?,
+            total_fee: Sampled::forced_import(&db, "mempool_total_fee", v)?,
+            band_vsize,
+            fastest_fee: Sampled::forced_import(&db, "mempool_fastest_fee", v)?,
+            half_hour_fee: Sampled::forced_import(&db, "mempool_half_hour_fee", v)?,
+            hour_fee: Sampled::forced_import(&db, "mempool_hour_fee", v)?,
+            economy_fee: Sampled::forced_import(&db, "mempool_economy_fee", v)?,
+            minimum_fee: Sampled::forced_import(&db, "mempool_minimum_fee", v)?,
+            next_block_fee: Sampled::forced_import(&db, "mempool_next_block_fee", v)?,
+            next_block_median_fee_rate: Sampled::forced_import(
+                &db,
+                "mempool_next_block_median_fee_rate",
+                v,
+            )?,
+            block_audit_match_rate: EagerVec::forced_import(&db, "block_audit_match_rate", v)?,
+            block_audit_missing_tx_count: EagerVec::forced_import(
+                &db,
+                "block_audit_missing_tx_count",
+                v,
+            )?,
+            block_audit_added_tx_count: EagerVec::forced_import(
+                &db,
+                "block_audit_added_tx_count",
+                v,
+            )?,
+            block_audit_fee_delta: EagerVec::forced_import(&db, "block_audit_fee_delta", v)?,
+            db,
+        };
+
+        finalize_db(&this.db, &this)?;
+        Ok(this)
+    }
+}
+
+impl<T> Sampled<T>
+where
+    T: ComputedVecValue + PartialOrd + JsonSchema,
+{
+    fn forced_import(db: &Database, name: &str, version: Version) -> Result<Self> {
+        Ok(Self {
+            height: EagerVec::forced_import(db, name, version)?,
+            minute10: EagerVec::forced_import(db, name, version)?,
+        })
+    }
+}
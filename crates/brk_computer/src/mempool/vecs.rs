@@ -0,0 +1,52 @@
+use std::collections::BTreeMap;
+
+use brk_traversable::Traversable;
+use brk_types::{
+    FeeRate, Height, Minute10, Sats, SatsSigned, StoredF32, StoredU32, StoredU64, VSize,
+};
+use schemars::JsonSchema;
+use vecdb::{Database, EagerVec, PcoVec, Rw, StorageMode};
+
+use crate::internal::ComputedVecValue;
+
+/// Mempool readings recorded by the live mempool driver, not computed
+/// from the chain. `height` holds the pool right before each block was
+/// seen, `minute10` the last reading of each 10-minute bucket. Entries
+/// without a reading (brk not running) hold zeros.
+#[derive(Traversable)]
+pub struct Vecs<M: StorageMode = Rw> {
+    #[traversable(skip)]
+    pub(crate) db: Database,
+
+    pub tx_count: Sampled<StoredU64, M>,
+    pub vsize: Sampled<VSize, M>,
+    pub total_fee: Sampled<Sats, M>,
+    /// Vsize per fee-rate band, keyed by the band's lower bound in sat/vB
+    pub band_vsize: BTreeMap<u16, Sampled<VSize, M>>,
+    pub fastest_fee: Sampled<FeeRate, M>,
+    pub half_hour_fee: Sampled<FeeRate, M>,
+    pub hour_fee: Sampled<FeeRate, M>,
+    pub economy_fee: Sampled<FeeRate, M>,
+    pub minimum_fee: Sampled<FeeRate, M>,
+    pub next_block_fee: Sampled<Sats, M>,
+    pub next_block_median_fee_rate: Sampled<FeeRate, M>,
+
+    /// Audit of each block against the template projected for it (see
+    /// `brk_types::BlockAudit`). Unaudited blocks hold a NaN match rate
+    /// and zeros.
+    pub block_audit_match_rate: M::Stored<EagerVec<PcoVec<Height, StoredF32>>>,
+    pub block_audit_missing_tx_count: M::Stored<EagerVec<PcoVec<Height, StoredU32>>>,
+    pub block_audit_added_tx_count: M::Stored<EagerVec<PcoVec<Height, StoredU32>>>,
+    pub block_audit_fee_delta: M::Stored<EagerVec<PcoVec<Height, SatsSigned>>>,
+}
+
+/// One recorded series, at both sampling indexes.
+#[derive(Traversable)]
+#[traversable(merge)]
+pub struct Sampled<T, M: StorageMode = Rw>
+where
+    T: ComputedVecValue + PartialOrd + JsonSchema,
+{
+    pub height: M::Stored<EagerVec<PcoVec<Height, T>>>,
+    pub minute10: M::Stored<EagerVec<PcoVec<Minute10, T>>>,
+}
@@ -0,0 +1,144 @@
+use brk_error::Result;
+use brk_indexer::Indexer;
+use brk_traversable::Traversable;
+use brk_types::{Height, OutputType, Sats, StoredBool, StoredU32, TxOutIndex};
+use vecdb::{
+    AnyStoredVec, AnyVec, Database, EagerVec, Exit, ImportableVec, PcoVec, ReadableVec, Rw,
+    StorageMode, VecIndex, Version, WritableVec,
+};
+
+use crate::indexes;
+
+/// Coinbase structure of every block. Witness commitments and RSK tags
+/// need the `OP_RETURN` bytes, so the indexer records them, as
+/// `coinbase_witness_commitment` and `coinbase_rsk_tag`.
+#[derive(Traversable)]
+pub struct Vecs<M: StorageMode = Rw> {
+    pub output_count: M::Stored<EagerVec<PcoVec<Height, StoredU32>>>,
+    /// Outputs carrying value: the fan-out of pools paying their miners
+    /// from the coinbase
+    pub payout_count: M::Stored<EagerVec<PcoVec<Height, StoredU32>>>,
+    /// Script type of the largest output
+    pub payout_type: M::Stored<EagerVec<PcoVec<Height, OutputType>>>,
+    pub extranonce_size: M::Stored<EagerVec<PcoVec<Height, StoredU32>>>,
+    /// AuxPoW marker in the scriptSig
+    pub namecoin_tag: M::Stored<EagerVec<PcoVec<Height, StoredBool>>>,
+}
+
+impl Vecs {
+    pub(crate) fn forced_import(db: &Database, version: Version) -> Result<Self> {
+        Ok(Self {
+            output_count: EagerVec::forced_import(db, "coinbase_output_count", version)?,
+            payout_count: EagerVec::forced_import(db, "coinbase_payout_count", version)?,
+            payout_type: EagerVec::forced_import(db, "coinbase_payout_type", version)?,
+            extranonce_size: EagerVec::forced_import(
+                db,
+                "coinbase_extranonce_size",
+                version + Version::ONE,
+            )?,
+            namecoin_tag: EagerVec::forced_import(db, "coinbase_namecoin_tag", version)?,
+        })
+    }
+
+    pub(crate) fn compute(
+        &mut self,
+        indexer: &Indexer,
+        indexes: &indexes::Vecs,
+        exit: &Exit,
+    ) -> Result<()> {
+        let starting_height = indexer.safe_lengths().height;
+        let coinbase_tag = &indexer.vecs.blocks.coinbase_tag;
+
+        self.extranonce_size.compute_transform(
+            starting_height,
+            coinbase_tag,
+            |(h, tag, ..)| (h, StoredU32::from(tag.extranonce_size())),
+            exit,
+        )?;
+        self.namecoin_tag.compute_transform(
+            starting_height,
+            coinbase_tag,
+            |(h, tag, ..)| (h, StoredBool::from(tag.has_auxpow())),
+            exit,
+        )?;
+
+        self.compute_outputs(starting_height, indexer, indexes, exit)
+    }
+
+    fn compute_outputs(
+        &mut self,
+        starting_height: Height,
+        indexer: &Indexer,
+        indexes: &indexes::Vecs,
+        exit: &Exit,
+    ) -> Result<()> {
+        let dep_version = indexer.vecs.outputs.value.version()
+            + indexer.vecs.outputs.output_type.version()
+            + indexes.tx_index.output_count.version();
+        self.output_count
+            .validate_computed_version_or_reset(dep_version)?;
+        self.payout_count
+            .validate_computed_version_or_reset(dep_version)?;
+        self.payout_type
+            .validate_computed_version_or_reset(dep_version)?;
+
+        let start = starting_height
+            .to_usize()
+            .min(self.output_count.len())
+            .min(self.payout_count.len())
+            .min(self.payout_type.len());
+        let start_height = Height::from(start);
+        self.output_count.truncate_if_needed(start_height)?;
+        self.payout_count.truncate_if_needed(start_height)?;
+        self.payout_type.truncate_if_needed(start_height)?;
+
+        let first_tx_indexes = indexer
+            .vecs
+            .transactions
+            .first_tx_index
+            .collect_range_at(start, indexer.vecs.transactions.first_tx_index.len());
+        let first_txout_index = indexer.vecs.transactions.first_txout_index.reader();
+        let output_value = indexer.vecs.outputs.value.reader();
+        let output_type = indexer.vecs.outputs.output_type.reader();
+        let mut output_count_cursor = indexes.tx_index.output_count.cursor();
+
+        for (i, tx_index) in first_tx_indexes.into_iter().enumerate() {
+            let ti = tx_index.to_usize();
+            let out_start = first_txout_index.get(ti);
+            output_count_cursor.advance(ti - output_count_cursor.position());
+            let output_count = output_count_cursor.next().unwrap();
+
+            let mut payout_count = 0u32;
+            let mut largest = (Sats::ZERO, OutputType::Unknown);
+            for txout_index in (*out_start..(*out_start + *output_count)).map(TxOutIndex::from) {
+                let value = output_value.get(txout_index.to_usize());
+                if value > Sats::ZERO {
+                    payout_count += 1;
+                }
+                if value > largest.0 {
+                    largest = (value, output_type.get(txout_index.to_usize()));
+                }
+            }
+
+            self.output_count
+                .push(StoredU32::from(*output_count as u32));
+            self.payout_count.push(StoredU32::from(payout_count));
+            self.payout_type.push(largest.1);
+
+            if (start + i).is_multiple_of(10_000) {
+                let _lock = exit.lock();
+                self.write()?;
+            }
+        }
+
+        let _lock = exit.lock();
+        self.write()
+    }
+
+    fn write(&mut self) -> Result<()> {
+        self.output_count.write()?;
+        self.payout_count.write()?;
+        self.payout_type.write()?;
+        Ok(())
+    }
+}
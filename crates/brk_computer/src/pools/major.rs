@@ -11,10 +11,10 @@ use crate::{
         MaskSats, PercentRollingWindows, RatioU64Bp16, ValuePerBlockCumulativeRolling,
         WindowStartVec, Windows,
     },
-    mining, price,
+    mempool, mining, price,
 };
 
-use super::minor;
+use super::{coinbase, minor};
 
 #[derive(Deref, DerefMut, Traversable)]
 pub struct Vecs<M: StorageMode = Rw> {
@@ -58,18 +58,28 @@ impl Vecs {
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn compute(
         &mut self,
+        starting_height: Height,
         indexer: &Indexer,
         pool: &impl ReadableVec<Height, PoolSlug>,
+        coinbase: &coinbase::Vecs,
         blocks: &blocks::Vecs,
         prices: &price::Vecs,
         mining: &mining::Vecs,
+        mempool: &mempool::Vecs,
         exit: &Exit,
     ) -> Result<()> {
-        let starting_height = indexer.safe_lengths().height;
-
-        self.base.compute(indexer, pool, blocks, exit)?;
+        self.base.compute(
+            starting_height,
+            indexer,
+            pool,
+            coinbase,
+            blocks,
+            mempool,
+            exit,
+        )?;
 
         for (dom, (mined, total)) in self.dominance_rolling.as_mut_array().into_iter().zip(
             self.base
@@ -1,12 +1,20 @@
 use brk_error::Result;
 use brk_indexer::Indexer;
 use brk_traversable::Traversable;
-use brk_types::{BasisPoints16, Height, PoolSlug, StoredU32, StoredU64};
-use vecdb::{Database, Exit, ReadableVec, Rw, StorageMode, Version};
+use brk_types::{BasisPoints16, Height, PoolSlug, StoredBool, StoredF32, StoredU32, StoredU64};
+use vecdb::{
+    AnyVec, Database, EagerVec, Exit, ImportableVec, PcoVec, ReadableVec, Rw, StorageMode,
+    VecValue, Version,
+};
 
+use super::coinbase;
 use crate::{
     blocks, indexes,
-    internal::{PerBlockCumulativeRolling, PercentPerBlock, RatioU64Bp16, WindowStartVec, Windows},
+    internal::{
+        LazyRollingSumsFromHeight, PerBlock, PerBlockCumulativeRolling, PercentPerBlock,
+        PercentRollingWindows, RatioU64Bp16, RatioU64F32, RollingWindows, WindowStartVec, Windows,
+    },
+    mempool,
 };
 
 #[derive(Traversable)]
@@ -16,6 +24,27 @@ pub struct Vecs<M: StorageMode = Rw> {
 
     pub blocks_mined: PerBlockCumulativeRolling<StoredU32, StoredU64, M>,
     pub dominance: PercentPerBlock<BasisPoints16, M>,
+
+    /// The mempool's block audits, restricted to this pool's blocks:
+    /// NaN and zeros at blocks mined by others or never audited.
+    pub block_audit_match_rate: M::Stored<EagerVec<PcoVec<Height, StoredF32>>>,
+    pub block_audit_missing_tx_count: M::Stored<EagerVec<PcoVec<Height, StoredU32>>>,
+    pub block_audit_added_tx_count: M::Stored<EagerVec<PcoVec<Height, StoredU32>>>,
+
+    /// The coinbase structure over this pool's blocks: how many carry
+    /// a witness commitment, an RSK or a Namecoin tag, and the payouts and
+    /// extranonce bytes they add up to, with their rolling shares and
+    /// means per mined block.
+    pub coinbase_witness_commitment: CoinbaseSum<M>,
+    pub coinbase_rsk_tag: CoinbaseSum<M>,
+    pub coinbase_namecoin_tag: CoinbaseSum<M>,
+    pub coinbase_payout_count: CoinbaseSum<M>,
+    pub coinbase_extranonce_size: CoinbaseSum<M>,
+    pub coinbase_witness_commitment_share: PercentRollingWindows<BasisPoints16, M>,
+    pub coinbase_rsk_tag_share: PercentRollingWindows<BasisPoints16, M>,
+    pub coinbase_namecoin_tag_share: PercentRollingWindows<BasisPoints16, M>,
+    pub coinbase_payout_count_average: RollingWindows<StoredF32, M>,
+    pub coinbase_extranonce_size_average: RollingWindows<StoredF32, M>,
 }
 
 impl Vecs {
@@ -42,18 +71,100 @@ impl Vecs {
             slug,
             blocks_mined,
             dominance,
+            block_audit_match_rate: EagerVec::forced_import(
+                db,
+                &suffix("block_audit_match_rate"),
+                version,
+            )?,
+            block_audit_missing_tx_count: EagerVec::forced_import(
+                db,
+                &suffix("block_audit_missing_tx_count"),
+                version,
+            )?,
+            block_audit_added_tx_count: EagerVec::forced_import(
+                db,
+                &suffix("block_audit_added_tx_count"),
+                version,
+            )?,
+            coinbase_witness_commitment: CoinbaseSum::forced_import(
+                db,
+                &suffix("coinbase_witness_commitment_count"),
+                version,
+                indexes,
+                cached_starts,
+            )?,
+            coinbase_rsk_tag: CoinbaseSum::forced_import(
+                db,
+                &suffix("coinbase_rsk_tag_count"),
+                version,
+                indexes,
+                cached_starts,
+            )?,
+            coinbase_namecoin_tag: CoinbaseSum::forced_import(
+                db,
+                &suffix("coinbase_namecoin_tag_count"),
+                version,
+                indexes,
+                cached_starts,
+            )?,
+            coinbase_payout_count: CoinbaseSum::forced_import(
+                db,
+                &suffix("coinbase_payout_count"),
+                version,
+                indexes,
+                cached_starts,
+            )?,
+            coinbase_extranonce_size: CoinbaseSum::forced_import(
+                db,
+                &suffix("coinbase_extranonce_size"),
+                version,
+                indexes,
+                cached_starts,
+            )?,
+            coinbase_witness_commitment_share: PercentRollingWindows::forced_import(
+                db,
+                &suffix("coinbase_witness_commitment_share"),
+                version,
+                indexes,
+            )?,
+            coinbase_rsk_tag_share: PercentRollingWindows::forced_import(
+                db,
+                &suffix("coinbase_rsk_tag_share"),
+                version,
+                indexes,
+            )?,
+            coinbase_namecoin_tag_share: PercentRollingWindows::forced_import(
+                db,
+                &suffix("coinbase_namecoin_tag_share"),
+                version,
+                indexes,
+            )?,
+            coinbase_payout_count_average: RollingWindows::forced_import(
+                db,
+                &suffix("coinbase_payout_count_average"),
+                version,
+                indexes,
+            )?,
+            coinbase_extranonce_size_average: RollingWindows::forced_import(
+                db,
+                &suffix("coinbase_extranonce_size_average"),
+                version,
+                indexes,
+            )?,
         })
     }
 
+    #[allow(clippy::too_many_arguments)]
     pub(crate) fn compute(
         &mut self,
+        starting_height: Height,
         indexer: &Indexer,
         pool: &impl ReadableVec<Height, PoolSlug>,
+        coinbase: &coinbase::Vecs,
         blocks: &blocks::Vecs,
+        mempool: &mempool::Vecs,
         exit: &Exit,
     ) -> Result<()> {
-        let starting_height = indexer.safe_lengths().height;
-
         self.blocks_mined.compute(starting_height, exit, |vec| {
             vec.compute_transform(
                 starting_height,
@@ -81,6 +192,194 @@ impl Vecs {
                 exit,
             )?;
 
+        self.compute_audit(starting_height, pool, mempool, exit)?;
+        self.compute_coinbase(starting_height, indexer, pool, coinbase, exit)
+    }
+
+    /// Audits can land after their block was computed (they come with
+    /// the mempool history), so each series resumes from its own length
+    /// rather than from the indexer's.
+    fn compute_audit(
+        &mut self,
+        starting_height: Height,
+        pool: &impl ReadableVec<Height, PoolSlug>,
+        mempool: &mempool::Vecs,
+        exit: &Exit,
+    ) -> Result<()> {
+        let slug = self.slug;
+        let resume = |len: usize| starting_height.min(Height::from(len));
+
+        self.block_audit_match_rate.compute_transform2(
+            resume(self.block_audit_match_rate.len()),
+            pool,
+            &mempool.block_audit_match_rate,
+            |(h, id, rate, ..)| (h, if id == slug { rate } else { StoredF32::NAN }),
+            exit,
+        )?;
+        for (vec, source) in [
+            (
+                &mut self.block_audit_missing_tx_count,
+                &mempool.block_audit_missing_tx_count,
+            ),
+            (
+                &mut self.block_audit_added_tx_count,
+                &mempool.block_audit_added_tx_count,
+            ),
+        ] {
+            vec.compute_transform2(
+                resume(vec.len()),
+                pool,
+                source,
+                |(h, id, count, ..)| (h, if id == slug { count } else { StoredU32::ZERO }),
+                exit,
+            )?;
+        }
+        Ok(())
+    }
+
+    fn compute_coinbase(
+        &mut self,
+        starting_height: Height,
+        indexer: &Indexer,
+        pool: &impl ReadableVec<Height, PoolSlug>,
+        coinbase: &coinbase::Vecs,
+        exit: &Exit,
+    ) -> Result<()> {
+        let slug = self.slug;
+        let blocks = &indexer.vecs.blocks;
+        let flag = |flag: StoredBool| u64::from(flag.is_true());
+        self.coinbase_witness_commitment.compute(
+            starting_height,
+            pool,
+            slug,
+            &blocks.coinbase_witness_commitment,
+            flag,
+            exit,
+        )?;
+        self.coinbase_rsk_tag.compute(
+            starting_height,
+            pool,
+            slug,
+            &blocks.coinbase_rsk_tag,
+            flag,
+            exit,
+        )?;
+        self.coinbase_namecoin_tag.compute(
+            starting_height,
+            pool,
+            slug,
+            &coinbase.namecoin_tag,
+            flag,
+            exit,
+        )?;
+        let value = |value: StoredU32| u64::from(*value);
+        self.coinbase_payout_count.compute(
+            starting_height,
+            pool,
+            slug,
+            &coinbase.payout_count,
+            value,
+            exit,
+        )?;
+        self.coinbase_extranonce_size.compute(
+            starting_height,
+            pool,
+            slug,
+            &coinbase.extranonce_size,
+            value,
+            exit,
+        )?;
+
+        let mined = self.blocks_mined.sum.as_array().map(|sum| &sum.height);
+        for (share, sum) in [
+            (
+                &mut self.coinbase_witness_commitment_share,
+                &self.coinbase_witness_commitment,
+            ),
+            (&mut self.coinbase_rsk_tag_share, &self.coinbase_rsk_tag),
+            (
+                &mut self.coinbase_namecoin_tag_share,
+                &self.coinbase_namecoin_tag,
+            ),
+        ] {
+            share.compute_binary::<StoredU64, StoredU64, RatioU64Bp16, _, _>(
+                starting_height,
+                sum.sum.as_array().map(|sum| &sum.height),
+                mined,
+                exit,
+            )?;
+        }
+        for (average, sum) in [
+            (
+                &mut self.coinbase_payout_count_average,
+                &self.coinbase_payout_count,
+            ),
+            (
+                &mut self.coinbase_extranonce_size_average,
+                &self.coinbase_extranonce_size,
+            ),
+        ] {
+            for (average, (sum, mined)) in average
+                .as_mut_array()
+                .into_iter()
+                .zip(sum.sum.as_array().into_iter().zip(mined))
+            {
+                average.compute_binary::<StoredU64, StoredU64, RatioU64F32>(
+                    starting_height,
+                    &sum.height,
+                    mined,
+                    exit,
+                )?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A coinbase series summed over one pool's blocks: a running total,
+/// so that rolling sums stay lazy and other pools' blocks cost nothing.
+#[derive(Traversable)]
+pub struct CoinbaseSum<M: StorageMode = Rw> {
+    pub cumulative: PerBlock<StoredU64, M>,
+    pub sum: LazyRollingSumsFromHeight<StoredU64>,
+}
+
+impl CoinbaseSum {
+    fn forced_import(
+        db: &Database,
+        name: &str,
+        version: Version,
+        indexes: &indexes::Vecs,
+        cached_starts: &Windows<&WindowStartVec>,
+    ) -> Result<Self> {
+        let cumulative =
+            PerBlock::forced_import(db, &format!("{name}_cumulative"), version, indexes)?;
+        let sum = LazyRollingSumsFromHeight::new(
+            &format!("{name}_sum"),
+            version,
+            &cumulative.height,
+            cached_starts,
+            indexes,
+        );
+        Ok(Self { cumulative, sum })
+    }
+
+    fn compute<T: VecValue>(
+        &mut self,
+        starting_height: Height,
+        pool: &impl ReadableVec<Height, PoolSlug>,
+        slug: PoolSlug,
+        source: &impl ReadableVec<Height, T>,
+        value: impl Fn(T) -> u64,
+        exit: &Exit,
+    ) -> Result<()> {
+        self.cumulative.height.compute_cumulative_transformed_binary(
+            starting_height,
+            pool,
+            source,
+            |id, v| StoredU64::from(if id == slug { value(v) } else { 0 }),
+            exit,
+        )?;
         Ok(())
     }
 }
@@ -1,15 +1,24 @@
-use std::{collections::BTreeMap, path::Path};
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
 
 use brk_error::Result;
 use brk_indexer::Indexer;
 use brk_traversable::Traversable;
-use brk_types::{Addr, AddrBytes, Height, OutputType, PoolSlug, Pools, TxOutIndex, pools};
+use brk_types::{
+    Addr, AddrBytes, Height, OutputType, PoolSlug, Pools, TxOutIndex, is_mainnet, pools,
+};
 use rayon::prelude::*;
+use rustc_hash::FxHashSet;
+use tracing::info;
 use vecdb::{
     AnyStoredVec, AnyVec, BytesVec, Database, Exit, ImportableVec, ReadableVec, Rw, StorageMode,
     VecIndex, Version, WritableVec,
 };
 
+pub mod coinbase;
 pub mod major;
 pub mod minor;
 mod pool_heights;
@@ -22,7 +31,7 @@ use crate::{
         WindowStartVec, Windows,
         db_utils::{finalize_db, open_db},
     },
-    mining, price,
+    mempool, mining, price,
 };
 
 pub const DB_NAME: &str = "pools";
@@ -31,10 +40,13 @@ pub const DB_NAME: &str = "pools";
 pub struct Vecs<M: StorageMode = Rw> {
     db: Database,
     pools: &'static Pools,
+    #[traversable(skip)]
+    fingerprint_path: PathBuf,
 
     pub pool: M::Stored<BytesVec<Height, PoolSlug>>,
     #[traversable(skip)]
     pub pool_heights: PoolHeights,
+    pub coinbase: coinbase::Vecs<M>,
     pub major: BTreeMap<PoolSlug, major::Vecs<M>>,
     pub minor: BTreeMap<PoolSlug, minor::Vecs<M>>,
 }
@@ -49,7 +61,8 @@ impl Vecs {
         let db = open_db(parent_path, DB_NAME, 100_000)?;
         let pools = pools();
 
-        let version = parent_version + Version::new(4) + Version::new(pools.len() as u32);
+        // Pool list changes are caught by the fingerprint, see `compute_pool`.
+        let version = parent_version + Version::new(5);
 
         let mut major_map = BTreeMap::new();
         let mut minor_map = BTreeMap::new();
@@ -70,13 +83,16 @@ impl Vecs {
 
         let pool = BytesVec::forced_import(&db, "pool", version)?;
         let pool_heights = PoolHeights::build(&pool);
+        let coinbase = coinbase::Vecs::forced_import(&db, version)?;
 
         let this = Self {
             pool,
             pool_heights,
+            coinbase,
             major: major_map,
             minor: minor_map,
             pools,
+            fingerprint_path: parent_path.join(DB_NAME).join("pools_fingerprint"),
             db,
         };
 
@@ -92,19 +108,45 @@ impl Vecs {
         blocks: &blocks::Vecs,
         prices: &price::Vecs,
         mining: &mining::Vecs,
+        mempool: &mempool::Vecs,
         exit: &Exit,
     ) -> Result<()> {
         self.db.sync_bg_tasks()?;
 
-        self.compute_pool(indexer, indexes, exit)?;
+        let starting_height = indexer.safe_lengths().height;
+        let reattributed = self.compute_pool(indexer, indexes, exit)?;
+        self.coinbase.compute(indexer, indexes, exit)?;
+        // Re-attributed pools recompute from the first changed height.
+        let starting_height_of = |slug: &PoolSlug| match &reattributed {
+            Some((height, slugs)) if slugs.contains(slug) => starting_height.min(*height),
+            _ => starting_height,
+        };
 
-        self.major.par_iter_mut().try_for_each(|(_, vecs)| {
-            vecs.compute(indexer, &self.pool, blocks, prices, mining, exit)
+        self.major.par_iter_mut().try_for_each(|(slug, vecs)| {
+            vecs.compute(
+                starting_height_of(slug),
+                indexer,
+                &self.pool,
+                &self.coinbase,
+                blocks,
+                prices,
+                mining,
+                mempool,
+                exit,
+            )
         })?;
 
-        self.minor
-            .par_iter_mut()
-            .try_for_each(|(_, vecs)| vecs.compute(indexer, &self.pool, blocks, exit))?;
+        self.minor.par_iter_mut().try_for_each(|(slug, vecs)| {
+            vecs.compute(
+                starting_height_of(slug),
+                indexer,
+                &self.pool,
+                &self.coinbase,
+                blocks,
+                mempool,
+                exit,
+            )
+        })?;
 
         let exit = exit.clone();
         self.db.run_bg(move |db| {
@@ -114,12 +156,16 @@ impl Vecs {
         Ok(())
     }
 
+    /// Attributes the new blocks to their pool. After a change to the
+    /// pool list (a new release or `pools.toml`), every block is
+    /// attributed again and the first height whose pool changed is
+    /// returned, with the pools it was taken from or given to.
     fn compute_pool(
         &mut self,
         indexer: &Indexer,
         indexes: &indexes::Vecs,
         exit: &Exit,
-    ) -> Result<()> {
+    ) -> Result<Option<(Height, FxHashSet<PoolSlug>)>> {
         let starting_height = indexer.safe_lengths().height;
 
         let dep_version = indexer.vecs.blocks.coinbase_tag.version();
@@ -134,6 +180,60 @@ impl Vecs {
         }
         self.pool.validate_computed_version_or_reset(dep_version)?;
 
+        let mut min = starting_height.to_usize().min(self.pool.len());
+        let len = indexer.vecs.blocks.coinbase_tag.len();
+
+        let fingerprint = self.pools.fingerprint();
+        let mut slugs = Vec::new();
+        let mut reattributed = None;
+        if min > 0 && self.stored_fingerprint() != Some(fingerprint) {
+            let fresh = self.identify_range(indexer, indexes, 0, min)?;
+            let stored = self.pool.collect_range_at(0, min);
+            if let Some((first, affected)) = reattribution(&fresh, &stored) {
+                info!(
+                    "Pool list changed, {} pools re-attributed from height {first}",
+                    affected.len()
+                );
+                reattributed = Some((Height::from(first), affected));
+                slugs = fresh[first..].to_vec();
+                min = first;
+            }
+        }
+
+        self.pool.truncate_if_needed_at(min)?;
+        self.pool_heights.truncate(min);
+
+        slugs.extend(self.identify_range(indexer, indexes, min + slugs.len(), len)?);
+        for (i, slug) in slugs.into_iter().enumerate() {
+            self.pool.push(slug);
+            self.pool_heights.push(slug, Height::from(min + i));
+        }
+
+        let _lock = exit.lock();
+        self.pool.write()?;
+        fs::write(&self.fingerprint_path, fingerprint.to_le_bytes())?;
+        Ok(reattributed)
+    }
+
+    fn stored_fingerprint(&self) -> Option<u64> {
+        let bytes = fs::read(&self.fingerprint_path).ok()?;
+        Some(u64::from_le_bytes(bytes.try_into().ok()?))
+    }
+
+    /// Pools of the blocks in `start..end`, from their coinbase tag and
+    /// the addresses the coinbase pays.
+    fn identify_range(
+        &self,
+        indexer: &Indexer,
+        indexes: &indexes::Vecs,
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<PoolSlug>> {
+        let mut slugs = Vec::with_capacity(end.saturating_sub(start));
+        if start >= end {
+            return Ok(slugs);
+        }
+
         let first_txout_index = indexer.vecs.transactions.first_txout_index.reader();
         let output_type = indexer.vecs.outputs.output_type.reader();
         let type_index = indexer.vecs.outputs.type_index.reader();
@@ -146,26 +246,21 @@ impl Vecs {
         let p2tr = indexer.vecs.addrs.p2tr.bytes.reader();
         let p2a = indexer.vecs.addrs.p2a.bytes.reader();
 
-        let unknown = self.pools.get_unknown();
-
-        let min = starting_height.to_usize().min(self.pool.len());
+        // `pools-v2.json` only describes mainnet coinbase tags and payout
+        // addresses: other networks attribute blocks through `pools.toml`
+        // alone, and every block to `Unknown` without one.
+        let identify = is_mainnet() || self.pools.has_overrides();
 
         // Cursors avoid per-height PcoVec page decompression.
         // Heights are sequential, tx_index values derived from them are monotonically
         // increasing, so both cursors only advance forward.
         let mut first_tx_index_cursor = indexer.vecs.transactions.first_tx_index.cursor();
-        first_tx_index_cursor.advance(min);
+        first_tx_index_cursor.advance(start);
         let mut output_count_cursor = indexes.tx_index.output_count.cursor();
 
-        self.pool.truncate_if_needed_at(min)?;
-        self.pool_heights.truncate(min);
-
-        let len = indexer.vecs.blocks.coinbase_tag.len();
-        let mut next_height = min;
-
         indexer.vecs.blocks.coinbase_tag.try_for_each_range_at(
-            min,
-            len,
+            start,
+            end,
             |coinbase_tag| -> Result<()> {
                 let tx_index = first_tx_index_cursor.next().unwrap();
                 let out_start = first_txout_index.get(tx_index.to_usize());
@@ -174,38 +269,78 @@ impl Vecs {
                 output_count_cursor.advance(ti - output_count_cursor.position());
                 let output_count_val = output_count_cursor.next().unwrap();
 
-                let pool = (*out_start..(*out_start + *output_count_val))
-                    .map(TxOutIndex::from)
-                    .find_map(|txout_index| {
-                        let ot = output_type.get(txout_index.to_usize());
-                        let ti = usize::from(type_index.get(txout_index.to_usize()));
-                        match ot {
-                            OutputType::P2PK65 => Some(AddrBytes::from(p2pk65.get(ti))),
-                            OutputType::P2PK33 => Some(AddrBytes::from(p2pk33.get(ti))),
-                            OutputType::P2PKH => Some(AddrBytes::from(p2pkh.get(ti))),
-                            OutputType::P2SH => Some(AddrBytes::from(p2sh.get(ti))),
-                            OutputType::P2WPKH => Some(AddrBytes::from(p2wpkh.get(ti))),
-                            OutputType::P2WSH => Some(AddrBytes::from(p2wsh.get(ti))),
-                            OutputType::P2TR => Some(AddrBytes::from(p2tr.get(ti))),
-                            OutputType::P2A => Some(AddrBytes::from(p2a.get(ti))),
-                            _ => None,
-                        }
-                        .map(|bytes| Addr::try_from(&bytes).unwrap())
-                        .and_then(|addr| self.pools.find_from_addr(&addr))
-                    })
-                    .or_else(|| self.pools.find_from_coinbase_tag(&coinbase_tag.as_str()))
-                    .unwrap_or(unknown);
-
-                self.pool.push(pool.slug);
-                self.pool_heights.push(pool.slug, Height::from(next_height));
-                next_height += 1;
+                let height = Height::from(start + slugs.len());
+                let slug = if identify {
+                    let addrs: Vec<Addr> = (*out_start..(*out_start + *output_count_val))
+                        .map(TxOutIndex::from)
+                        .filter_map(|txout_index| {
+                            let ot = output_type.get(txout_index.to_usize());
+                            let ti = usize::from(type_index.get(txout_index.to_usize()));
+                            match ot {
+                                OutputType::P2PK65 => Some(AddrBytes::from(p2pk65.get(ti))),
+                                OutputType::P2PK33 => Some(AddrBytes::from(p2pk33.get(ti))),
+                                OutputType::P2PKH => Some(AddrBytes::from(p2pkh.get(ti))),
+                                OutputType::P2SH => Some(AddrBytes::from(p2sh.get(ti))),
+                                OutputType::P2WPKH => Some(AddrBytes::from(p2wpkh.get(ti))),
+                                OutputType::P2WSH => Some(AddrBytes::from(p2wsh.get(ti))),
+                                OutputType::P2TR => Some(AddrBytes::from(p2tr.get(ti))),
+                                OutputType::P2A => Some(AddrBytes::from(p2a.get(ti))),
+                                _ => None,
+                            }
+                            .map(|bytes| Addr::try_from(&bytes).unwrap())
+                        })
+                        .collect();
+                    self.pools
+                        .identify(height, &coinbase_tag.as_str(), &addrs)
+                        .slug
+                } else {
+                    PoolSlug::UNKNOWN
+                };
+
+                slugs.push(slug);
 
                 Ok(())
             },
         )?;
 
-        let _lock = exit.lock();
-        self.pool.write()?;
-        Ok(())
+        Ok(slugs)
+    }
+}
+
+/// First height whose pool differs between `fresh` and `stored`, with the
+/// pools blocks were taken from or given to from there on.
+fn reattribution(
+    fresh: &[PoolSlug],
+    stored: &[PoolSlug],
+) -> Option<(usize, FxHashSet<PoolSlug>)> {
+    let first = fresh.iter().zip(stored).position(|(a, b)| a != b)?;
+    let affected = fresh[first..]
+        .iter()
+        .zip(&stored[first..])
+        .filter(|(a, b)| a != b)
+        .flat_map(|(a, b)| [*a, *b])
+        .collect();
+    Some((first, affected))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn slugs(ids: &[u16]) -> Vec<PoolSlug> {
+        ids.iter().copied().map(PoolSlug::new).collect()
+    }
+
+    #[test]
+    fn unchanged_attributions_are_not_reattributed() {
+        assert!(reattribution(&slugs(&[1, 2, 3]), &slugs(&[1, 2, 3])).is_none());
+    }
+
+    #[test]
+    fn only_pools_of_changed_heights_are_reattributed() {
+        let (first, affected) =
+            reattribution(&slugs(&[1, 2, 3, 4, 5]), &slugs(&[1, 2, 7, 4, 8])).unwrap();
+        assert_eq!(first, 2);
+        assert_eq!(affected, slugs(&[3, 7, 5, 8]).into_iter().collect());
     }
 }
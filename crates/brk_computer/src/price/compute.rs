@@ -5,7 +5,7 @@ use brk_indexer::{Indexer, Lengths};
 use brk_oracle::{
     bin_to_cents, cents_to_bin, Config, Oracle, PaymentFilter, START_HEIGHT_FAST, START_HEIGHT_SLOW,
 };
-use brk_types::{Cents, OutputType, Sats, TxIndex, TxOutIndex};
+use brk_types::{Cents, OutputType, Sats, TxIndex, TxOutIndex, is_mainnet};
 use tracing::info;
 use vecdb::{AnyStoredVec, AnyVec, Exit, ReadableVec, StorageMode, VecIndex, WritableVec};
 
@@ -73,6 +73,10 @@ impl Vecs {
 
         let total_heights = indexer.vecs.blocks.timestamp.len();
 
+        if !is_mainnet() {
+            return self.compute_zero_prices(starting_height.to_usize(), total_heights, exit);
+        }
+
         if total_heights <= START_HEIGHT_SLOW {
             return Ok(());
         }
@@ -176,6 +180,27 @@ impl Vecs {
         Ok(())
     }
 
+    /// The oracle is calibrated on mainnet payment patterns, so other networks
+    /// get zero prices. Keeping the vec at full length preserves the height
+    /// alignment every price-derived vec relies on, exactly like the zeros
+    /// that precede the first mainnet exchange price.
+    fn compute_zero_prices(
+        &mut self,
+        starting_height: usize,
+        total_heights: usize,
+        exit: &Exit,
+    ) -> Result<()> {
+        let height = &mut self.spot.cents.height.inner;
+        height.truncate_if_needed_at(height.len().min(starting_height))?;
+        while height.len() < total_heights {
+            height.push(Cents::ZERO);
+        }
+
+        let _lock = exit.lock();
+        height.write()?;
+        Ok(())
+    }
+
     /// Feed a range of blocks from the indexer into an Oracle (skipping coinbase),
     /// returning per-block ref_bin values.
     ///
@@ -0,0 +1,66 @@
+#![doc = include_str!("../README.md")]
+
+use std::{sync::Arc, time::Duration};
+
+use brk_error::Result;
+use brk_query::AsyncQuery;
+use tokio::{net::TcpListener, time::sleep};
+use tracing::{debug, error, info};
+
+mod methods;
+mod rpc;
+mod session;
+mod status;
+
+use session::Session;
+
+pub const VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Electrum's customary plain TCP port on mainnet.
+pub const DEFAULT_PORT: u16 = 50001;
+
+/// Back-off after a failed `accept`, so running out of file
+/// descriptors doesn't spin the listener.
+const ACCEPT_BACKOFF: Duration = Duration::from_millis(100);
+
+pub struct ElectrumServer(Arc<Context>);
+
+/// Shared by every session.
+struct Context {
+    query: AsyncQuery,
+    /// Cap on confirmed UTXOs per script hash, same as the HTTP API's.
+    max_utxos: usize,
+}
+
+impl ElectrumServer {
+    pub fn new(query: &AsyncQuery, max_utxos: usize) -> Self {
+        Self(Arc::new(Context {
+            query: query.clone(),
+            max_utxos,
+        }))
+    }
+
+    pub async fn serve(self, port: u16) -> Result<()> {
+        let listener = TcpListener::bind(format!("0.0.0.0:{port}")).await?;
+
+        info!("Starting Electrum server on port {port}...");
+
+        loop {
+            let (stream, peer) = match listener.accept().await {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    error!("Electrum accept failed: {e}");
+                    sleep(ACCEPT_BACKOFF).await;
+                    continue;
+                }
+            };
+            let session = Session::new(self.0.clone());
+            tokio::spawn(async move {
+                match session.run(stream).await {
+                    Ok(()) => debug!(%peer, "Electrum session closed"),
+                    Err(e) => debug!(%peer, "Electrum session ended: {e}"),
+                }
+            });
+        }
+    }
+}
@@ -0,0 +1,221 @@
+//! Blocking method bodies, run on `spawn_blocking` threads through
+//! [`brk_query::AsyncQuery::run`].
+
+use std::cmp::Reverse;
+
+use bitcoin::consensus::encode::serialize_hex;
+use brk_error::{Error, Result};
+use brk_query::Query;
+use brk_types::{AddrBytes, BlockHash, FeeRate, Height, ScriptHash, Txid, Vout};
+use rustc_hash::FxHashSet;
+use serde_json::{Value, json};
+
+use crate::status::HistoryEntry;
+
+/// Most headers a single `blockchain.block.headers` call returns.
+pub const MAX_HEADERS: u32 = 2016;
+
+/// Satoshis per vbyte to the protocol's BTC per kilo-vbyte.
+const SAT_PER_VB_TO_BTC_PER_KVB: f64 = 1_000.0 / 100_000_000.0;
+
+/// Tip height and hash, the latter to notice same-height reorgs.
+pub fn tip(query: &Query) -> (Height, BlockHash) {
+    (query.height(), query.tip_blockhash())
+}
+
+/// `{ height, hex }` of the header at `height`, as returned by
+/// `blockchain.headers.subscribe`.
+pub fn header_notification(query: &Query, height: Height) -> Result<Value> {
+    Ok(json!({ "height": height, "hex": header_hex(query, height)? }))
+}
+
+pub fn header_hex(query: &Query, height: Height) -> Result<String> {
+    if height > query.height() {
+        return Err(Error::OutOfRange("Block height out of range".into()));
+    }
+    Ok(serialize_hex(&query.read_block_header(height)?))
+}
+
+/// Up to [`MAX_HEADERS`] concatenated headers from `start`, stopping at
+/// the tip.
+pub fn headers(query: &Query, start: Height, count: u32) -> Result<Value> {
+    let tip = query.height();
+    let end = (u32::from(start).saturating_add(count.min(MAX_HEADERS))).min(u32::from(tip) + 1);
+    let mut hex = String::new();
+    for height in u32::from(start)..end {
+        hex.push_str(&serialize_hex(
+            &query.read_block_header(Height::from(height))?,
+        ));
+    }
+    Ok(json!({
+        "count": end.saturating_sub(u32::from(start)),
+        "hex": hex,
+        "max": MAX_HEADERS,
+    }))
+}
+
+/// Fee rate in BTC/kvB to confirm within `blocks`, mapped onto the
+/// recommended tiers. `-1` when there's no mempool to estimate from.
+pub fn estimate_fee(query: &Query, blocks: u32) -> Value {
+    let Ok(fees) = query.recommended_fees() else {
+        return json!(-1);
+    };
+    let rate = match blocks {
+        0..=1 => fees.fastest_fee,
+        2..=3 => fees.half_hour_fee,
+        4..=6 => fees.hour_fee,
+        _ => fees.economy_fee,
+    };
+    json!(btc_per_kvb(rate))
+}
+
+pub fn relay_fee(query: &Query) -> Result<Value> {
+    Ok(json!(btc_per_kvb(query.recommended_fees()?.minimum_fee)))
+}
+
+/// `[[fee_rate, vsize], ...]` by descending rate, one bucket per
+/// projected block at its lowest rate.
+pub fn fee_histogram(query: &Query) -> Result<Value> {
+    Ok(Value::Array(
+        query
+            .mempool_blocks()?
+            .into_iter()
+            .map(|block| json!([f64::from(block.fee_range[0]), block.block_v_size]))
+            .collect(),
+    ))
+}
+
+fn btc_per_kvb(rate: FeeRate) -> f64 {
+    f64::from(rate) * SAT_PER_VB_TO_BTC_PER_KVB
+}
+
+/// Address behind `script_hash`, `None` if neither the chain nor the
+/// mempool has seen it.
+pub fn resolve(query: &Query, script_hash: &ScriptHash) -> Result<Option<AddrBytes>> {
+    match query.script_hash_addr(script_hash) {
+        Ok(bytes) => Ok(Some(bytes)),
+        Err(Error::UnknownAddr) => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Confirmed history in chain order, then the mempool part.
+pub fn history(query: &Query, bytes: &AddrBytes) -> Result<Vec<HistoryEntry>> {
+    let mut history: Vec<HistoryEntry> = query
+        .addr_bytes_history(bytes)?
+        .into_iter()
+        .map(|(tx_hash, height)| HistoryEntry {
+            tx_hash,
+            height: i64::from(u32::from(height)),
+            fee: None,
+        })
+        .collect();
+
+    let mempool = mempool_history(query, bytes);
+    if !mempool.is_empty() {
+        // A tx mined this instant can sit on both sides until the next
+        // mempool cycle.
+        let confirmed: FxHashSet<_> = history.iter().map(|entry| entry.tx_hash).collect();
+        history.extend(
+            mempool
+                .into_iter()
+                .filter(|entry| !confirmed.contains(&entry.tx_hash)),
+        );
+    }
+
+    Ok(history)
+}
+
+/// Mempool txs touching `bytes`, those with confirmed inputs only
+/// first, then by txid so the status hash is stable between calls.
+pub fn mempool_history(query: &Query, bytes: &AddrBytes) -> Vec<HistoryEntry> {
+    let Some(mempool) = query.mempool() else {
+        return vec![];
+    };
+    let mut entries: Vec<HistoryEntry> = mempool
+        .addr_txs(bytes, usize::MAX)
+        .into_iter()
+        .map(|tx| {
+            let unconfirmed_parent = tx
+                .input
+                .iter()
+                .any(|txin| mempool.contains_txid(&txin.txid));
+            HistoryEntry {
+                tx_hash: tx.txid,
+                height: if unconfirmed_parent { -1 } else { 0 },
+                fee: Some(tx.fee),
+            }
+        })
+        .collect();
+    entries.sort_unstable_by_key(|entry| (Reverse(entry.height), *entry.tx_hash));
+    entries
+}
+
+/// `{ confirmed, unconfirmed }` in sats, the latter signed since the
+/// mempool can spend more than it funds.
+pub fn balance(query: &Query, bytes: &AddrBytes) -> Result<Value> {
+    let confirmed = query.addr_bytes_chain_stats(bytes)?.map_or(0, |stats| {
+        u64::from(stats.funded_txo_sum) - u64::from(stats.spent_txo_sum)
+    });
+    let unconfirmed = query
+        .mempool()
+        .and_then(|mempool| mempool.addr_stats(bytes))
+        .map_or(0, |stats| {
+            u64::from(stats.funded_txo_sum) as i64 - u64::from(stats.spent_txo_sum) as i64
+        });
+    Ok(json!({ "confirmed": confirmed, "unconfirmed": unconfirmed }))
+}
+
+/// Confirmed UTXOs not spent in the mempool, then outputs funded by
+/// mempool txs with height `0`.
+pub fn list_unspent(query: &Query, bytes: &AddrBytes, max_utxos: usize) -> Result<Value> {
+    let mempool = query.mempool();
+    let is_unspent =
+        |txid: &Txid, vout: Vout| mempool.is_none_or(|m| m.lookup_spender(txid, vout).is_none());
+
+    let mut unspent: Vec<Value> = query
+        .addr_bytes_utxos(bytes, max_utxos)?
+        .into_iter()
+        .filter(|utxo| is_unspent(&utxo.txid, utxo.vout))
+        .map(|utxo| {
+            json!({
+                "tx_hash": utxo.txid,
+                "tx_pos": utxo.vout,
+                "height": utxo.status.block_height,
+                "value": utxo.value,
+            })
+        })
+        .collect();
+
+    if let Some(mempool) = mempool {
+        for tx in mempool.addr_txs(bytes, usize::MAX) {
+            for (vout, txout) in tx.output.iter().enumerate() {
+                let vout = Vout::from(vout);
+                if txout.addr_bytes().as_ref() == Some(bytes) && is_unspent(&tx.txid, vout) {
+                    unspent.push(json!({
+                        "tx_hash": tx.txid,
+                        "tx_pos": vout,
+                        "height": 0,
+                        "value": txout.value,
+                    }));
+                }
+            }
+        }
+    }
+
+    Ok(Value::Array(unspent))
+}
+
+/// `server.features`. `hosts` stays empty: the server doesn't know how
+/// it is reached and doesn't join the peer network.
+pub fn features(query: &Query) -> Result<Value> {
+    Ok(json!({
+        "genesis_hash": query.block_hash_by_height(Height::ZERO)?,
+        "hosts": {},
+        "protocol_min": crate::session::PROTOCOL_VERSION,
+        "protocol_max": crate::session::PROTOCOL_VERSION,
+        "pruning": null,
+        "server_version": format!("BRK {}", crate::VERSION),
+        "hash_function": "sha256",
+    }))
+}
@@ -0,0 +1,146 @@
+//! JSON-RPC 2.0 framing as spoken by Electrum clients.
+
+use brk_error::Error;
+use serde::{Deserialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+#[derive(Deserialize)]
+pub struct Request {
+    #[serde(default)]
+    pub id: Value,
+    pub method: String,
+    #[serde(default)]
+    pub params: Value,
+}
+
+/// JSON-RPC error. Besides the spec's own codes, ElectrumX answers
+/// `1` for requests it can't serve and `2` for backend failures, and
+/// clients match on those.
+#[derive(Debug)]
+pub struct RpcError {
+    code: i32,
+    message: String,
+}
+
+impl RpcError {
+    const PARSE_ERROR: i32 = -32700;
+    const INVALID_REQUEST: i32 = -32600;
+    const METHOD_NOT_FOUND: i32 = -32601;
+    const INVALID_PARAMS: i32 = -32602;
+    const BAD_REQUEST: i32 = 1;
+    const DAEMON_ERROR: i32 = 2;
+
+    pub fn parse(message: impl ToString) -> Self {
+        Self::new(Self::PARSE_ERROR, message)
+    }
+
+    pub fn invalid_request(message: impl ToString) -> Self {
+        Self::new(Self::INVALID_REQUEST, message)
+    }
+
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(Self::METHOD_NOT_FOUND, format!("unknown method '{method}'"))
+    }
+
+    pub fn invalid_params(message: impl ToString) -> Self {
+        Self::new(Self::INVALID_PARAMS, message)
+    }
+
+    pub fn bad_request(message: impl ToString) -> Self {
+        Self::new(Self::BAD_REQUEST, message)
+    }
+
+    fn new(code: i32, message: impl ToString) -> Self {
+        Self {
+            code,
+            message: message.to_string(),
+        }
+    }
+}
+
+impl From<Error> for RpcError {
+    fn from(error: Error) -> Self {
+        let code = match error {
+            Error::Parse(_)
+            | Error::InvalidTxid
+            | Error::InvalidAddr
+            | Error::UnknownTxid
+            | Error::NotFound(_)
+            | Error::OutOfRange(_)
+            | Error::TooManyUtxos => Self::BAD_REQUEST,
+            _ => Self::DAEMON_ERROR,
+        };
+        Self::new(code, error)
+    }
+}
+
+pub fn response(id: Value, result: Result<Value, RpcError>) -> Value {
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err(error) => json!({
+            "jsonrpc": "2.0",
+            "id": id,
+            "error": { "code": error.code, "message": error.message },
+        }),
+    }
+}
+
+pub fn notification(method: &str, params: Value) -> Value {
+    json!({ "jsonrpc": "2.0", "method": method, "params": params })
+}
+
+/// Request params, positional or named. Missing and `null` are the same.
+pub struct Params(Value);
+
+impl Params {
+    pub fn new(params: Value) -> Self {
+        Self(params)
+    }
+
+    pub fn required<T: DeserializeOwned>(&self, index: usize, name: &str) -> Result<T, RpcError> {
+        self.optional(index, name)?
+            .ok_or_else(|| RpcError::invalid_params(format!("missing parameter '{name}'")))
+    }
+
+    pub fn optional<T: DeserializeOwned>(
+        &self,
+        index: usize,
+        name: &str,
+    ) -> Result<Option<T>, RpcError> {
+        let value = match &self.0 {
+            Value::Array(values) => values.get(index),
+            Value::Object(values) => values.get(name),
+            _ => None,
+        };
+        match value {
+            None | Some(Value::Null) => Ok(None),
+            Some(value) => T::deserialize(value)
+                .map(Some)
+                .map_err(|e| RpcError::invalid_params(format!("invalid '{name}': {e}"))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn params_positional_and_named() {
+        let positional = Params::new(json!(["abc", 3]));
+        assert_eq!(positional.required::<String>(0, "a").unwrap(), "abc");
+        assert_eq!(positional.required::<u32>(1, "b").unwrap(), 3);
+        assert!(positional.optional::<u32>(2, "c").unwrap().is_none());
+
+        let named = Params::new(json!({ "b": 3, "c": null }));
+        assert_eq!(named.required::<u32>(1, "b").unwrap(), 3);
+        assert!(named.optional::<u32>(2, "c").unwrap().is_none());
+        assert!(named.required::<String>(0, "a").is_err());
+    }
+
+    #[test]
+    fn brk_errors_map_to_electrumx_codes() {
+        assert_eq!(RpcError::from(Error::UnknownTxid).code, 1);
+        assert_eq!(RpcError::from(Error::MempoolNotAvailable).code, 2);
+    }
+}
@@ -0,0 +1,413 @@
+//! One client connection: newline-delimited requests in, responses and
+//! subscription notifications out.
+
+use std::{io, sync::Arc, time::Duration};
+
+use brk_error::Result;
+use brk_mempool::Event;
+use brk_query::Query;
+use brk_types::{AddrBytes, BlockHash, Height, ScriptHash, Txid};
+use rustc_hash::FxHashMap;
+use serde_json::{Value, json};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{
+        TcpStream,
+        tcp::{OwnedReadHalf, OwnedWriteHalf},
+    },
+    sync::{
+        broadcast::{Receiver, error::RecvError},
+        mpsc,
+    },
+    time::sleep,
+};
+use tracing::debug;
+
+use crate::{
+    Context, methods,
+    rpc::{Params, Request, RpcError, notification, response},
+    status::status,
+};
+
+pub const PROTOCOL_VERSION: &str = "1.4";
+
+/// Longest request line accepted. Large enough for a
+/// `blockchain.transaction.broadcast` of a standard-size tx.
+const MAX_LINE: u64 = 1 << 21;
+
+/// Lines read ahead of the one being answered.
+const LINE_QUEUE: usize = 16;
+
+/// Script hashes a single session may subscribe to.
+const MAX_SUBSCRIPTIONS: usize = 10_000;
+
+/// Tip check interval when there's no mempool to wake sessions up.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+pub struct Session {
+    context: Arc<Context>,
+    tip: (Height, BlockHash),
+    headers: bool,
+    subscriptions: FxHashMap<ScriptHash, Subscription>,
+}
+
+/// What a script hash subscription was last notified with.
+struct Subscription {
+    /// `None` until the script hash shows up on chain or in the mempool.
+    bytes: Option<AddrBytes>,
+    mempool_hash: Option<u64>,
+    status: Option<String>,
+}
+
+impl Subscription {
+    fn load(query: &Query, script_hash: &ScriptHash) -> Result<Self> {
+        let Some(bytes) = methods::resolve(query, script_hash)? else {
+            return Ok(Self {
+                bytes: None,
+                mempool_hash: None,
+                status: None,
+            });
+        };
+        // Read before the history, so a change in between is caught on
+        // the next cycle instead of lost.
+        let mempool_hash = query.mempool().and_then(|m| m.addr_state_hash(&bytes));
+        let status = status(&methods::history(query, &bytes)?);
+        Ok(Self {
+            bytes: Some(bytes),
+            mempool_hash,
+            status,
+        })
+    }
+
+    /// Whether the mempool moved under the subscription since its last
+    /// load. Tip changes are checked separately.
+    fn is_stale(&self, query: &Query, script_hash: &ScriptHash) -> bool {
+        let Some(mempool) = query.mempool() else {
+            return false;
+        };
+        match &self.bytes {
+            Some(bytes) => mempool.addr_state_hash(bytes) != self.mempool_hash,
+            None => mempool.addr_by_script_hash(script_hash).is_some(),
+        }
+    }
+}
+
+impl Session {
+    pub(crate) fn new(context: Arc<Context>) -> Self {
+        let tip = context.query.sync(methods::tip);
+        Self {
+            context,
+            tip,
+            headers: false,
+            subscriptions: FxHashMap::default(),
+        }
+    }
+
+    pub async fn run(mut self, stream: TcpStream) -> Result<()> {
+        let (reader, mut writer) = stream.into_split();
+        // `read_line` isn't cancel-safe, so lines come through a channel
+        // that `select!` can drop a `recv` on.
+        let (sender, mut lines) = mpsc::channel(LINE_QUEUE);
+        tokio::spawn(read_lines(reader, sender));
+        let mut events = self
+            .context
+            .query
+            .sync(|q| q.mempool().map(|mempool| mempool.subscribe()));
+
+        loop {
+            let messages = tokio::select! {
+                line = lines.recv() => match line {
+                    Some(line) => self.handle_line(&line?).await.into_iter().collect(),
+                    None => return Ok(()),
+                },
+                _ = next_cycle(&mut events) => self.notifications().await,
+            };
+            write(&mut writer, &messages).await?;
+        }
+    }
+
+    async fn handle_line(&mut self, line: &[u8]) -> Option<Value> {
+        if line.trim_ascii().is_empty() {
+            return None;
+        }
+        let value = match serde_json::from_slice(line) {
+            Ok(value) => value,
+            Err(e) => return Some(response(Value::Null, Err(RpcError::parse(e)))),
+        };
+        Some(match value {
+            Value::Array(batch) if batch.is_empty() => {
+                response(Value::Null, Err(RpcError::invalid_request("empty batch")))
+            }
+            Value::Array(batch) => {
+                let mut responses = Vec::with_capacity(batch.len());
+                for request in batch {
+                    responses.push(self.handle_request(request).await);
+                }
+                Value::Array(responses)
+            }
+            request => self.handle_request(request).await,
+        })
+    }
+
+    async fn handle_request(&mut self, request: Value) -> Value {
+        match serde_json::from_value::<Request>(request) {
+            Ok(Request { id, method, params }) => {
+                response(id, self.dispatch(&method, Params::new(params)).await)
+            }
+            Err(e) => response(Value::Null, Err(RpcError::invalid_request(e))),
+        }
+    }
+
+    async fn dispatch(&mut self, method: &str, params: Params) -> Result<Value, RpcError> {
+        let query = &self.context.query;
+        match method {
+            "server.version" => Ok(json!([format!("BRK {}", crate::VERSION), PROTOCOL_VERSION])),
+            "server.banner" => Ok(json!(format!("BRK {} Electrum server", crate::VERSION))),
+            "server.donation_address" => Ok(json!("")),
+            "server.features" => Ok(query.run(methods::features).await?),
+            "server.peers.subscribe" => Ok(json!([])),
+            "server.ping" => Ok(Value::Null),
+
+            "blockchain.headers.subscribe" => {
+                self.headers = true;
+                let height = self.tip.0;
+                Ok(query
+                    .run(move |q| methods::header_notification(q, height))
+                    .await?)
+            }
+            "blockchain.block.header" => {
+                let height: Height = params.required(0, "height")?;
+                no_checkpoint(&params, 1)?;
+                Ok(json!(
+                    query.run(move |q| methods::header_hex(q, height)).await?
+                ))
+            }
+            "blockchain.block.headers" => {
+                let start: Height = params.required(0, "start_height")?;
+                let count: u32 = params.required(1, "count")?;
+                no_checkpoint(&params, 2)?;
+                Ok(query
+                    .run(move |q| methods::headers(q, start, count))
+                    .await?)
+            }
+
+            "blockchain.estimatefee" => {
+                let blocks: u32 = params.required(0, "number")?;
+                Ok(query.sync(|q| methods::estimate_fee(q, blocks)))
+            }
+            "blockchain.relayfee" => Ok(query.sync(methods::relay_fee)?),
+            "mempool.get_fee_histogram" => Ok(query.sync(methods::fee_histogram)?),
+
+            "blockchain.scripthash.get_balance" => {
+                let script_hash: ScriptHash = params.required(0, "scripthash")?;
+                Ok(query
+                    .run(move |q| match methods::resolve(q, &script_hash)? {
+                        Some(bytes) => methods::balance(q, &bytes),
+                        None => Ok(json!({ "confirmed": 0, "unconfirmed": 0 })),
+                    })
+                    .await?)
+            }
+            "blockchain.scripthash.get_history" => {
+                let script_hash: ScriptHash = params.required(0, "scripthash")?;
+                Ok(query
+                    .run(move |q| {
+                        let history = match methods::resolve(q, &script_hash)? {
+                            Some(bytes) => methods::history(q, &bytes)?,
+                            None => vec![],
+                        };
+                        Ok(json!(history))
+                    })
+                    .await?)
+            }
+            "blockchain.scripthash.get_mempool" => {
+                let script_hash: ScriptHash = params.required(0, "scripthash")?;
+                Ok(query
+                    .run(move |q| {
+                        let mempool = match methods::resolve(q, &script_hash)? {
+                            Some(bytes) => methods::mempool_history(q, &bytes),
+                            None => vec![],
+                        };
+                        Ok(json!(mempool))
+                    })
+                    .await?)
+            }
+            "blockchain.scripthash.listunspent" => {
+                let script_hash: ScriptHash = params.required(0, "scripthash")?;
+                let max_utxos = self.context.max_utxos;
+                Ok(query
+                    .run(move |q| match methods::resolve(q, &script_hash)? {
+                        Some(bytes) => methods::list_unspent(q, &bytes, max_utxos),
+                        None => Ok(json!([])),
+                    })
+                    .await?)
+            }
+            "blockchain.scripthash.subscribe" => {
+                let script_hash: ScriptHash = params.required(0, "scripthash")?;
+                if self.subscriptions.len() >= MAX_SUBSCRIPTIONS
+                    && !self.subscriptions.contains_key(&script_hash)
+                {
+                    return Err(RpcError::bad_request(format!(
+                        "too many subscriptions, max {MAX_SUBSCRIPTIONS} per session"
+                    )));
+                }
+                let subscription = query
+                    .run(move |q| Subscription::load(q, &script_hash))
+                    .await?;
+                let status = json!(subscription.status);
+                self.subscriptions.insert(script_hash, subscription);
+                Ok(status)
+            }
+            "blockchain.scripthash.unsubscribe" => {
+                let script_hash: ScriptHash = params.required(0, "scripthash")?;
+                Ok(json!(self.subscriptions.remove(&script_hash).is_some()))
+            }
+
+            "blockchain.transaction.get" => {
+                let txid: Txid = params.required(0, "tx_hash")?;
+                if params.optional::<bool>(1, "verbose")?.unwrap_or(false) {
+                    return Err(RpcError::bad_request(
+                        "verbose transactions are not supported",
+                    ));
+                }
+                Ok(json!(query.run(move |q| q.transaction_hex(&txid)).await?))
+            }
+            "blockchain.transaction.broadcast" => {
+                let hex: String = params.required(0, "raw_tx")?;
+                Ok(json!(
+                    query.run(move |q| q.broadcast_transaction(&hex)).await?
+                ))
+            }
+            "blockchain.transaction.get_merkle" => {
+                let txid: Txid = params.required(0, "tx_hash")?;
+                Ok(query
+                    .run(move |q| Ok(serde_json::to_value(q.merkle_proof(&txid)?)?))
+                    .await?)
+            }
+
+            _ => Err(RpcError::method_not_found(method)),
+        }
+    }
+
+    /// Notifications owed after a mempool cycle: the new tip to header
+    /// subscribers, then every script hash whose status changed. A
+    /// failed reload is retried on the next cycle.
+    async fn notifications(&mut self) -> Vec<Value> {
+        let query = &self.context.query;
+        let tip = query.sync(methods::tip);
+        let tip_moved = tip != self.tip;
+        self.tip = tip;
+
+        let mut messages = vec![];
+        if tip_moved && self.headers {
+            match query
+                .run(move |q| methods::header_notification(q, tip.0))
+                .await
+            {
+                Ok(header) => messages.push(notification(
+                    "blockchain.headers.subscribe",
+                    json!([header]),
+                )),
+                Err(e) => debug!("Electrum header notification failed: {e}"),
+            }
+        }
+
+        let stale: Vec<ScriptHash> = query.sync(|q| {
+            self.subscriptions
+                .iter()
+                .filter(|(script_hash, subscription)| {
+                    tip_moved || subscription.is_stale(q, script_hash)
+                })
+                .map(|(script_hash, _)| *script_hash)
+                .collect()
+        });
+        if stale.is_empty() {
+            return messages;
+        }
+
+        let reloaded = query
+            .run(move |q| {
+                stale
+                    .into_iter()
+                    .map(|script_hash| Ok((script_hash, Subscription::load(q, &script_hash)?)))
+                    .collect::<Result<Vec<_>>>()
+            })
+            .await;
+        match reloaded {
+            Ok(reloaded) => {
+                for (script_hash, subscription) in reloaded {
+                    let Some(previous) = self.subscriptions.get_mut(&script_hash) else {
+                        continue;
+                    };
+                    if previous.status != subscription.status {
+                        messages.push(notification(
+                            "blockchain.scripthash.subscribe",
+                            json!([script_hash, subscription.status]),
+                        ));
+                    }
+                    *previous = subscription;
+                }
+            }
+            Err(e) => debug!("Electrum subscription reload failed: {e}"),
+        }
+        messages
+    }
+}
+
+/// Checkpoint merkle proofs aren't supported, only `cp_height` 0.
+fn no_checkpoint(params: &Params, index: usize) -> Result<(), RpcError> {
+    match params.optional::<u32>(index, "cp_height")? {
+        None | Some(0) => Ok(()),
+        Some(_) => Err(RpcError::bad_request("checkpoint proofs are not supported")),
+    }
+}
+
+/// Resolves after the next mempool cycle, or [`POLL_INTERVAL`] without a
+/// mempool. Skipped cycles lose nothing, subscriptions diff against
+/// their own state.
+async fn next_cycle(events: &mut Option<Receiver<Arc<[Event]>>>) {
+    match events {
+        Some(receiver) => match receiver.recv().await {
+            Ok(_) | Err(RecvError::Lagged(_)) => {}
+            Err(RecvError::Closed) => *events = None,
+        },
+        None => sleep(POLL_INTERVAL).await,
+    }
+}
+
+/// Forwards request lines until EOF, a read error or an oversized line,
+/// the latter two ending the session.
+async fn read_lines(reader: OwnedReadHalf, sender: mpsc::Sender<io::Result<Vec<u8>>>) {
+    let mut reader = BufReader::new(reader);
+    loop {
+        let mut buffer = vec![];
+        let line = match (&mut reader)
+            .take(MAX_LINE)
+            .read_until(b'\n', &mut buffer)
+            .await
+        {
+            Ok(0) => return,
+            Ok(_) if buffer.last() != Some(&b'\n') && buffer.len() as u64 == MAX_LINE => Err(
+                io::Error::new(io::ErrorKind::InvalidData, "request line too long"),
+            ),
+            Ok(_) => Ok(buffer),
+            Err(e) => Err(e),
+        };
+        let failed = line.is_err();
+        if sender.send(line).await.is_err() || failed {
+            return;
+        }
+    }
+}
+
+async fn write(writer: &mut OwnedWriteHalf, messages: &[Value]) -> Result<()> {
+    if messages.is_empty() {
+        return Ok(());
+    }
+    let mut buffer = vec![];
+    for message in messages {
+        serde_json::to_writer(&mut buffer, message)?;
+        buffer.push(b'\n');
+    }
+    writer.write_all(&buffer).await?;
+    Ok(())
+}
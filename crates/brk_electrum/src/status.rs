@@ -0,0 +1,61 @@
+use bitcoin::hashes::{Hash, HashEngine, sha256};
+use brk_types::{Sats, Txid};
+use serde::Serialize;
+
+/// One `blockchain.scripthash.get_history` entry. `height` is `0` for a
+/// mempool tx with confirmed inputs only and `-1` for one spending
+/// another mempool tx.
+#[derive(Debug, Clone, Serialize)]
+pub struct HistoryEntry {
+    pub tx_hash: Txid,
+    pub height: i64,
+    /// Only set for mempool txs, as ElectrumX does.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fee: Option<Sats>,
+}
+
+/// Script hash status per the protocol: hex SHA256 of the concatenated
+/// `tx_hash:height:` of every history entry, in history order. `None`
+/// for an empty history.
+pub fn status(history: &[HistoryEntry]) -> Option<String> {
+    if history.is_empty() {
+        return None;
+    }
+    let mut engine = sha256::Hash::engine();
+    for entry in history {
+        engine.input(format!("{}:{}:", entry.tx_hash, entry.height).as_bytes());
+    }
+    Some(sha256::Hash::from_engine(engine).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn empty_history_has_no_status() {
+        assert_eq!(status(&[]), None);
+    }
+
+    #[test]
+    fn status_hashes_history_in_order() {
+        let a = Txid::from_str("4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b")
+            .unwrap();
+        let b = Txid::from_str("2bb85f4b004be6da54f766c17c1e855187327112c231ef2ff35ebad0ea67c69e")
+            .unwrap();
+        let entry = |tx_hash, height| HistoryEntry {
+            tx_hash,
+            height,
+            fee: None,
+        };
+
+        let expected = sha256::Hash::hash(format!("{a}:0:{b}:-1:").as_bytes()).to_string();
+        assert_eq!(status(&[entry(a, 0), entry(b, -1)]), Some(expected));
+        assert_ne!(
+            status(&[entry(a, 0), entry(b, -1)]),
+            status(&[entry(b, -1), entry(a, 0)])
+        );
+    }
+}
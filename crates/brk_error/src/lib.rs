@@ -77,6 +77,14 @@ pub enum Error {
     #[error(transparent)]
     Pco(#[from] pco::errors::PcoError),
 
+    #[cfg(feature = "arrow")]
+    #[error(transparent)]
+    Arrow(#[from] arrow_schema::ArrowError),
+
+    #[cfg(feature = "parquet")]
+    #[error(transparent)]
+    Parquet(#[from] parquet::errors::ParquetError),
+
     #[cfg(feature = "serde_json")]
     #[error(transparent)]
     SerdeJSON(#[from] serde_json::Error),
@@ -156,6 +164,12 @@ pub enum Error {
     #[error("Too many unspent transaction outputs (>1000).")]
     TooManyUtxos,
 
+    #[error("Too many used addresses in the wallet (>100000 per keychain).")]
+    TooManyAddrs,
+
+    #[error("Too many transactions for the address (>100000).")]
+    TooManyTxs,
+
     #[error("Deserialization error: {0}")]
     Deserialization(String),
 
@@ -20,6 +20,7 @@ mod constants;
 mod lengths;
 mod processor;
 mod readers;
+mod reorgs;
 mod safe_lengths;
 mod stores;
 mod vecs;
@@ -29,6 +30,7 @@ use processor::{BlockBuffers, BlockProcessor};
 use readers::Readers;
 
 pub use lengths::Lengths;
+pub use reorgs::Reorgs;
 pub use safe_lengths::SafeLengths;
 pub use stores::Stores;
 pub use vecs::*;
@@ -37,6 +39,7 @@ pub struct Indexer<M: StorageMode = Rw> {
     path: PathBuf,
     pub vecs: Vecs<M>,
     pub stores: Stores,
+    pub reorgs: Reorgs,
     safe_lengths: SafeLengths,
 }
 
@@ -83,6 +86,7 @@ impl Indexer {
         info!("Importing indexer...");
 
         let indexed_path = outputs_dir.join("indexed");
+        let reorgs_path = outputs_dir.join("reorgs.json");
 
         let try_import = || -> Result<Self> {
             let i = Instant::now();
@@ -102,6 +106,7 @@ impl Indexer {
                 path: indexed_path.clone(),
                 vecs,
                 stores,
+                reorgs: Reorgs::import(&reorgs_path),
                 safe_lengths,
             })
         };
@@ -168,6 +173,10 @@ impl Indexer {
 
         let (starting_lengths, prev_hash) = if let Some(hash) = last_blockhash {
             let (height, hash) = client.get_closest_valid_height(hash)?;
+            if let Some(local) = Lengths::from_local(&self.vecs, &self.stores) {
+                self.reorgs
+                    .record_if_needed(&self.vecs, &local, reader, client, height, hash)?;
+            }
             match Lengths::resume_at(height.incremented(), &self.vecs, &self.stores) {
                 Some(starting_lengths) => {
                     if starting_lengths.height > client.get_last_height()? {
@@ -389,6 +398,7 @@ impl ReadOnlyClone for Indexer {
             path: self.path.clone(),
             vecs: self.vecs.read_only_clone(),
             stores: self.stores.clone(),
+            reorgs: self.reorgs.clone(),
             safe_lengths: self.safe_lengths.clone(),
         }
     }
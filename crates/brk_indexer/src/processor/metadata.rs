@@ -36,6 +36,14 @@ impl BlockProcessor<'_> {
             .blocks
             .coinbase_tag
             .checked_push(height, self.block.coinbase_tag())?;
+        self.vecs
+            .blocks
+            .coinbase_witness_commitment
+            .checked_push(height, self.block.has_witness_commitment().into())?;
+        self.vecs
+            .blocks
+            .coinbase_rsk_tag
+            .checked_push(height, self.block.has_rsk_tag().into())?;
         self.vecs
             .blocks
             .difficulty
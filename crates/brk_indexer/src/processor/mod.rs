@@ -52,6 +52,8 @@ impl BlockProcessor<'_> {
         let addr_hash_stores = &mut self.stores.addr_type_to_addr_hash_to_addr_index;
         let addr_tx_index_stores = &mut self.stores.addr_type_to_addr_index_and_tx_index;
         let addr_outpoint_stores = &mut self.stores.addr_type_to_addr_index_and_unspent_outpoint;
+        let script_hash_stores = &mut self.stores.addr_type_to_script_hash_to_addr_index;
+        let script_hash_txout_store = &mut self.stores.script_hash_prefix_and_txout_index;
         let txid_prefix_store = &mut self.stores.txid_prefix_to_tx_index;
 
         let (finalize_result, metadata_result) = rayon::join(
@@ -65,6 +67,8 @@ impl BlockProcessor<'_> {
                     addr_hash_stores,
                     addr_tx_index_stores,
                     addr_outpoint_stores,
+                    script_hash_stores,
+                    script_hash_txout_store,
                     txouts,
                     same_block_spent_outpoints,
                     already_added,
@@ -2,8 +2,8 @@ use brk_cohort::ByAddrType;
 use brk_error::{Error, Result};
 use brk_store::Store;
 use brk_types::{
-    AddrBytes, AddrHash, AddrIndexOutPoint, AddrIndexTxIndex, OutPoint, OutputType, Sats, TxIndex,
-    TxOutIndex, TypeIndex, Unit, Vout,
+    AddrBytes, AddrHash, AddrIndexOutPoint, AddrIndexTxIndex, OutPoint, OutputType, Sats,
+    ScriptHash, ScriptHashPrefix, ScriptHashTxOutIndex, TxIndex, TxOutIndex, TypeIndex, Unit, Vout,
 };
 use rayon::prelude::*;
 use rustc_hash::{FxHashMap, FxHashSet};
@@ -114,6 +114,8 @@ pub(super) fn finalize_outputs(
     addr_hash_stores: &mut ByAddrType<Store<AddrHash, TypeIndex>>,
     addr_tx_index_stores: &mut ByAddrType<Store<AddrIndexTxIndex, Unit>>,
     addr_outpoint_stores: &mut ByAddrType<Store<AddrIndexOutPoint, Unit>>,
+    script_hash_stores: &mut ByAddrType<Store<ScriptHash, TypeIndex>>,
+    script_hash_txout_store: &mut Store<ScriptHashTxOutIndex, Unit>,
     txouts: Vec<ProcessedOutput>,
     same_block_spent_outpoints: &FxHashSet<OutPoint>,
     already_added_addr_hash: &mut ByAddrType<FxHashMap<AddrHash, TypeIndex>>,
@@ -158,31 +160,46 @@ pub(super) fn finalize_outputs(
                 addr_hash_stores
                     .get_mut_unwrap(addr_type)
                     .insert(addr_hash, ti);
+                script_hash_stores
+                    .get_mut_unwrap(addr_type)
+                    .insert(ScriptHash::from(&txout.script_pubkey), ti);
                 addrs.push_bytes_if_needed(ti, addr_bytes)?;
 
                 ti
             }
+        } else if output_type == OutputType::OpReturn {
+            scripts
+                .op_return
+                .to_tx_index
+                .checked_push(lengths.op_return_index, tx_index)?;
+            lengths.op_return_index.copy_then_increment()
         } else {
+            let script_hash = ScriptHash::from(&txout.script_pubkey);
+            script_hash_txout_store.insert(
+                ScriptHashTxOutIndex::from((ScriptHashPrefix::from(&script_hash), txout_index)),
+                Unit,
+            );
             match output_type {
                 OutputType::P2MS => {
                     scripts
                         .p2ms
                         .to_tx_index
                         .checked_push(lengths.p2ms_output_index, tx_index)?;
-                    lengths.p2ms_output_index.copy_then_increment()
-                }
-                OutputType::OpReturn => {
                     scripts
-                        .op_return
-                        .to_tx_index
-                        .checked_push(lengths.op_return_index, tx_index)?;
-                    lengths.op_return_index.copy_then_increment()
+                        .script_hash
+                        .p2ms
+                        .checked_push(lengths.p2ms_output_index, script_hash)?;
+                    lengths.p2ms_output_index.copy_then_increment()
                 }
                 OutputType::Empty => {
                     scripts
                         .empty
                         .to_tx_index
                         .checked_push(lengths.empty_output_index, tx_index)?;
+                    scripts
+                        .script_hash
+                        .empty
+                        .checked_push(lengths.empty_output_index, script_hash)?;
                     lengths.empty_output_index.copy_then_increment()
                 }
                 OutputType::Unknown => {
@@ -190,6 +207,10 @@ pub(super) fn finalize_outputs(
                         .unknown
                         .to_tx_index
                         .checked_push(lengths.unknown_output_index, tx_index)?;
+                    scripts
+                        .script_hash
+                        .unknown
+                        .checked_push(lengths.unknown_output_index, script_hash)?;
                     lengths.unknown_output_index.copy_then_increment()
                 }
                 _ => unreachable!(),
@@ -1,8 +1,10 @@
 use bitcoin::ScriptBuf;
 use brk_types::{
-    AddrBytes, OutputType, P2AAddrIndex, P2ABytes, P2PK33AddrIndex, P2PK33Bytes, P2PK65AddrIndex,
-    P2PK65Bytes, P2PKHAddrIndex, P2PKHBytes, P2SHAddrIndex, P2SHBytes, P2TRAddrIndex, P2TRBytes,
-    P2WPKHAddrIndex, P2WPKHBytes, P2WSHAddrIndex, P2WSHBytes, TxIndex, TxOutIndex, Txid, TypeIndex,
+    AddrBytes, EmptyOutputIndex, OutputType, P2AAddrIndex, P2ABytes, P2MSOutputIndex,
+    P2PK33AddrIndex, P2PK33Bytes, P2PK65AddrIndex, P2PK65Bytes, P2PKHAddrIndex, P2PKHBytes,
+    P2SHAddrIndex, P2SHBytes, P2TRAddrIndex, P2TRBytes, P2WPKHAddrIndex, P2WPKHBytes,
+    P2WSHAddrIndex, P2WSHBytes, ScriptHash, TxIndex, TxOutIndex, Txid, TypeIndex,
+    UnknownOutputIndex,
 };
 use vecdb::{BytesStrategy, VecReader};
 
@@ -37,6 +39,26 @@ impl AddrReaders {
     }
 }
 
+pub struct ScriptHashReaders {
+    pub empty: VecReader<EmptyOutputIndex, ScriptHash, BytesStrategy<ScriptHash>>,
+    pub p2ms: VecReader<P2MSOutputIndex, ScriptHash, BytesStrategy<ScriptHash>>,
+    pub unknown: VecReader<UnknownOutputIndex, ScriptHash, BytesStrategy<ScriptHash>>,
+}
+
+impl ScriptHashReaders {
+    /// Script hash of a non-address output, `None` for address and
+    /// op_return types or an index past the end.
+    pub fn get(&self, output_type: OutputType, type_index: TypeIndex) -> Option<ScriptHash> {
+        let idx = usize::from(type_index);
+        match output_type {
+            OutputType::Empty => self.empty.try_get(idx),
+            OutputType::P2MS => self.p2ms.try_get(idx),
+            OutputType::Unknown => self.unknown.try_get(idx),
+            _ => None,
+        }
+    }
+}
+
 /// Readers for vectors that need to be accessed during block processing.
 ///
 /// All fields use `VecReader` which caches the mmap base pointer for O(1)
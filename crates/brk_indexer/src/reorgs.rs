@@ -0,0 +1,428 @@
+//! Journal of the reorgs the indexer rolled back.
+//!
+//! Entries are captured right before the rollback, while the orphaned
+//! blocks are still on disk, and kept in a single JSON file next to the
+//! `indexed` directory so that a full reset of the indexer doesn't
+//! wipe them. The orphaned blocks themselves are read back from the blk
+//! files and archived raw in a `stale_blocks` directory alongside, one
+//! `{hash}.blk` file each. Stale blocks the indexer never indexed (lost
+//! races it didn't see) aren't archived.
+
+use std::{
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use brk_error::{OptionData, Result};
+use brk_reader::Reader;
+use brk_rpc::Client;
+use brk_types::{BlockHash, Height, Reorg, ReorgBlock, Timestamp, Txid};
+use parking_lot::RwLock;
+use rustc_hash::FxHashSet;
+use tracing::{info, warn};
+use vecdb::{ReadableVec, VecIndex};
+
+use crate::{Lengths, Vecs};
+
+#[derive(Clone)]
+pub struct Reorgs {
+    path: PathBuf,
+    blocks: PathBuf,
+    entries: Arc<RwLock<Vec<Reorg>>>,
+}
+
+impl Reorgs {
+    /// Loads the journal at `path`. A missing file is an empty journal,
+    /// an unreadable one is logged and started over.
+    pub fn import(path: &Path) -> Self {
+        let entries = match fs::read(path) {
+            Ok(bytes) => serde_json::from_slice(&bytes).unwrap_or_else(|e| {
+                warn!("Unreadable reorg journal {path:?}, starting over: {e}");
+                Vec::new()
+            }),
+            Err(_) => Vec::new(),
+        };
+        Self {
+            path: path.to_path_buf(),
+            blocks: path.with_file_name("stale_blocks"),
+            entries: Arc::new(RwLock::new(entries)),
+        }
+    }
+
+    /// Every recorded reorg, oldest first.
+    pub fn all(&self) -> Vec<Reorg> {
+        self.entries.read().clone()
+    }
+
+    /// The latest reorg that orphaned `hash`, with the block's height.
+    pub fn orphaning(&self, hash: &BlockHash) -> Option<(Reorg, Height)> {
+        self.entries.read().iter().rev().find_map(|reorg| {
+            let height = reorg.orphaned.iter().find(|b| b.hash == *hash)?.height;
+            Some((reorg.clone(), height))
+        })
+    }
+
+    /// Height `hash` had before a reorg orphaned it.
+    pub fn orphaned_height(&self, hash: &BlockHash) -> Option<Height> {
+        self.entries
+            .read()
+            .iter()
+            .rev()
+            .find_map(|reorg| Some(reorg.orphaned.iter().find(|b| b.hash == *hash)?.height))
+    }
+
+    /// Raw bytes of an archived orphaned block.
+    pub fn stale_block(&self, hash: &BlockHash) -> Option<Vec<u8>> {
+        fs::read(self.block_path(hash)).ok()
+    }
+
+    /// Journals the rollback of the blocks above `fork_height`, if any,
+    /// and archives them. Must run before the vecs are rolled back.
+    pub(crate) fn record_if_needed(
+        &self,
+        vecs: &Vecs,
+        local: &Lengths,
+        reader: &Reader,
+        client: &Client,
+        fork_height: Height,
+        fork_hash: BlockHash,
+    ) -> Result<()> {
+        let start = fork_height.incremented();
+        if start >= local.height {
+            return Ok(());
+        }
+        let reorg = match Self::capture(vecs, local, client, fork_height, fork_hash) {
+            Ok(reorg) => reorg,
+            Err(e) => {
+                warn!("Failed to capture the reorg at height {fork_height}, not journaled: {e}");
+                return Ok(());
+            }
+        };
+        info!(
+            "Reorg of depth {} at height {}, {} txs dropped",
+            reorg.depth,
+            reorg.fork_height,
+            reorg.dropped_txids.len()
+        );
+
+        if let Err(e) = self.archive(vecs, reader, &reorg.orphaned) {
+            warn!("Failed to archive orphaned blocks: {e}");
+        }
+
+        let mut entries = self.entries.write();
+        // A crash between journaling and rollback replays the same reorg.
+        if entries.iter().any(|e| {
+            e.fork_hash == reorg.fork_hash
+                && e.orphaned.first().map(|b| b.hash) == reorg.orphaned.first().map(|b| b.hash)
+        }) {
+            return Ok(());
+        }
+        entries.push(reorg);
+        if let Err(e) = self.write(&entries) {
+            warn!("Failed to write reorg journal {:?}: {e}", self.path);
+        }
+        Ok(())
+    }
+
+    fn capture(
+        vecs: &Vecs,
+        local: &Lengths,
+        client: &Client,
+        fork_height: Height,
+        fork_hash: BlockHash,
+    ) -> Result<Reorg> {
+        let start = fork_height.incremented().to_usize();
+        let end = local.height.to_usize();
+
+        let hashes = vecs.blocks.blockhash.collect_range_at(start, end);
+        let timestamps = vecs.blocks.timestamp.collect_range_at(start, end);
+        let orphaned: Vec<ReorgBlock> = hashes
+            .into_iter()
+            .zip(timestamps)
+            .enumerate()
+            .map(|(i, (hash, timestamp))| ReorgBlock {
+                height: Height::from(start + i),
+                hash,
+                timestamp,
+            })
+            .collect();
+
+        // Orphaned txs, coinbases (the first tx of each block) left out.
+        let first_tx_indexes = vecs
+            .transactions
+            .first_tx_index
+            .collect_range_at(start, end);
+        let tx_start = first_tx_indexes[0].to_usize();
+        let coinbases: FxHashSet<usize> = first_tx_indexes.iter().map(|i| i.to_usize()).collect();
+        let orphaned_txids: Vec<Txid> = vecs
+            .transactions
+            .txid
+            .collect_range_at(tx_start, local.tx_index.to_usize())
+            .into_iter()
+            .enumerate()
+            .filter(|(i, _)| !coinbases.contains(&(tx_start + i)))
+            .map(|(_, txid)| txid)
+            .collect();
+
+        let mut pending: FxHashSet<Txid> = orphaned_txids.iter().copied().collect();
+        let mut replacement = Vec::with_capacity(end - start);
+        let walked = client.get_last_height().and_then(|tip| {
+            Self::walk_replacement(
+                start..tip.incremented().to_usize(),
+                end,
+                |height| {
+                    let hash = client.get_block_hash(height)?;
+                    let block = client.get_block(&hash)?;
+                    Ok((hash, block))
+                },
+                &mut replacement,
+                &mut pending,
+            )
+        });
+        if let Err(e) = walked {
+            warn!("Failed to fetch the replacement chain, dropped txs may be overstated: {e}");
+        }
+
+        Ok(Reorg {
+            fork_height,
+            fork_hash,
+            depth: orphaned.len() as u32,
+            detected_at: Timestamp::now(),
+            orphaned,
+            replacement,
+            dropped_txids: orphaned_txids
+                .into_iter()
+                .filter(|txid| pending.contains(txid))
+                .collect(),
+        })
+    }
+
+    /// Walks the node's chain over `heights`, taking the txs of each block
+    /// out of `pending`. Blocks below `end`, at the orphaned heights, go to
+    /// `replacement`. Past them, stops once nothing is pending: an orphaned
+    /// tx can confirm in any later block, not only at the heights it left.
+    fn walk_replacement(
+        heights: Range<usize>,
+        end: usize,
+        mut fetch: impl FnMut(Height) -> Result<(BlockHash, bitcoin::Block)>,
+        replacement: &mut Vec<ReorgBlock>,
+        pending: &mut FxHashSet<Txid>,
+    ) -> Result<()> {
+        for height in heights {
+            if height >= end && pending.is_empty() {
+                break;
+            }
+            let height = Height::from(height);
+            let (hash, block) = fetch(height)?;
+            for tx in &block.txdata {
+                pending.remove(&Txid::from(tx.compute_txid()));
+            }
+            if height.to_usize() < end {
+                replacement.push(ReorgBlock {
+                    height,
+                    hash,
+                    timestamp: Timestamp::from(block.header.time),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    fn archive(&self, vecs: &Vecs, reader: &Reader, orphaned: &[ReorgBlock]) -> Result<()> {
+        fs::create_dir_all(&self.blocks)?;
+        for block in orphaned {
+            let position = vecs.blocks.position.collect_one(block.height).data()?;
+            let size = vecs.blocks.total.collect_one(block.height).data()?;
+            let bytes = reader.read_raw_bytes(position, *size as usize)?;
+            fs::write(self.block_path(&block.hash), bytes)?;
+        }
+        Ok(())
+    }
+
+    fn block_path(&self, hash: &BlockHash) -> PathBuf {
+        self.blocks.join(format!("{hash}.blk"))
+    }
+
+    fn write(&self, entries: &[Reorg]) -> Result<()> {
+        let tmp = self.path.with_extension("json.tmp");
+        fs::write(&tmp, serde_json::to_vec(entries)?)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash;
+
+    use super::*;
+
+    fn hash(n: u8) -> BlockHash {
+        BlockHash::from(bitcoin::BlockHash::from_byte_array([n; 32]))
+    }
+
+    fn block(height: u32, n: u8) -> ReorgBlock {
+        ReorgBlock {
+            height: Height::new(height),
+            hash: hash(n),
+            timestamp: Timestamp::new(1_700_000_000 + height),
+        }
+    }
+
+    fn reorg(fork_height: u32, orphaned: Vec<ReorgBlock>) -> Reorg {
+        Reorg {
+            fork_height: Height::new(fork_height),
+            fork_hash: hash(0),
+            depth: orphaned.len() as u32,
+            detected_at: Timestamp::new(1_700_000_000),
+            orphaned,
+            replacement: Vec::new(),
+            dropped_txids: Vec::new(),
+        }
+    }
+
+    fn journal(name: &str, entries: &[Reorg]) -> Reorgs {
+        let dir = std::env::temp_dir().join(format!("brk_reorgs_{name}_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("reorgs.json");
+        fs::write(&path, serde_json::to_vec(entries).unwrap()).unwrap();
+        Reorgs::import(&path)
+    }
+
+    #[test]
+    fn finds_the_latest_reorg_orphaning_a_hash() {
+        let reorgs = journal(
+            "latest",
+            &[
+                reorg(99, vec![block(100, 1), block(101, 2)]),
+                reorg(100, vec![block(101, 2)]),
+            ],
+        );
+        assert_eq!(reorgs.orphaned_height(&hash(2)), Some(Height::new(101)));
+        let (found, height) = reorgs.orphaning(&hash(2)).unwrap();
+        assert_eq!(found.fork_height, Height::new(100));
+        assert_eq!(height, Height::new(101));
+
+        let (found, height) = reorgs.orphaning(&hash(1)).unwrap();
+        assert_eq!(found.fork_height, Height::new(99));
+        assert_eq!(height, Height::new(100));
+    }
+
+    #[test]
+    fn unknown_hash_is_not_orphaned() {
+        let reorgs = journal("unknown", &[reorg(99, vec![block(100, 1)])]);
+        assert!(reorgs.orphaning(&hash(9)).is_none());
+        assert!(reorgs.orphaned_height(&hash(9)).is_none());
+        assert!(reorgs.stale_block(&hash(1)).is_none());
+    }
+
+    fn tx(n: u8) -> bitcoin::Transaction {
+        bitcoin::Transaction {
+            version: bitcoin::transaction::Version::TWO,
+            lock_time: bitcoin::absolute::LockTime::ZERO,
+            input: vec![bitcoin::TxIn {
+                previous_output: bitcoin::OutPoint::new(
+                    bitcoin::Txid::from_byte_array([n; 32]),
+                    0,
+                ),
+                ..Default::default()
+            }],
+            output: Vec::new(),
+        }
+    }
+
+    fn txid(n: u8) -> Txid {
+        Txid::from(tx(n).compute_txid())
+    }
+
+    /// Node chain whose block at height `h` holds the txs listed for it.
+    fn fetcher(
+        chain: Vec<(usize, Vec<u8>)>,
+        fetched: &mut Vec<usize>,
+    ) -> impl FnMut(Height) -> Result<(BlockHash, bitcoin::Block)> + '_ {
+        move |height| {
+            fetched.push(height.to_usize());
+            let mut block = bitcoin::blockdata::constants::genesis_block(bitcoin::Network::Bitcoin);
+            block.txdata = chain
+                .iter()
+                .find(|(h, _)| *h == height.to_usize())
+                .map(|(_, txs)| txs.iter().map(|n| tx(*n)).collect())
+                .unwrap_or_default();
+            Ok((hash(height.to_usize() as u8), block))
+        }
+    }
+
+    #[test]
+    fn orphaned_txs_confirmed_past_the_orphaned_heights_are_not_dropped() {
+        // Orphaned 100..102; tx 1 confirms at 101, tx 2 only at 103.
+        let mut pending: FxHashSet<Txid> = [txid(1), txid(2), txid(3)].into_iter().collect();
+        let mut replacement = Vec::new();
+        let mut fetched = Vec::new();
+        Reorgs::walk_replacement(
+            100..106,
+            102,
+            fetcher(vec![(101, vec![1]), (103, vec![2])], &mut fetched),
+            &mut replacement,
+            &mut pending,
+        )
+        .unwrap();
+        assert_eq!(pending, [txid(3)].into_iter().collect());
+        assert_eq!(
+            replacement.iter().map(|b| b.height).collect::<Vec<_>>(),
+            vec![Height::new(100), Height::new(101)]
+        );
+        assert_eq!(fetched, vec![100, 101, 102, 103, 104, 105]);
+    }
+
+    #[test]
+    fn walk_stops_past_the_orphaned_heights_once_nothing_is_pending() {
+        let mut pending: FxHashSet<Txid> = [txid(1), txid(2)].into_iter().collect();
+        let mut replacement = Vec::new();
+        let mut fetched = Vec::new();
+        Reorgs::walk_replacement(
+            100..110,
+            102,
+            fetcher(vec![(100, vec![1]), (102, vec![2])], &mut fetched),
+            &mut replacement,
+            &mut pending,
+        )
+        .unwrap();
+        assert!(pending.is_empty());
+        assert_eq!(fetched, vec![100, 101, 102]);
+        assert_eq!(replacement.len(), 2);
+    }
+
+    #[test]
+    fn walk_keeps_what_it_fetched_before_a_failure() {
+        let mut pending: FxHashSet<Txid> = [txid(1), txid(2)].into_iter().collect();
+        let mut replacement = Vec::new();
+        let mut fetched = Vec::new();
+        let mut fetch = fetcher(vec![(100, vec![1])], &mut fetched);
+        let result = Reorgs::walk_replacement(
+            100..103,
+            103,
+            |height| {
+                if height == Height::new(101) {
+                    return Err(brk_error::Error::Internal("node unreachable"));
+                }
+                fetch(height)
+            },
+            &mut replacement,
+            &mut pending,
+        );
+        assert!(result.is_err());
+        assert_eq!(pending, [txid(2)].into_iter().collect());
+        assert_eq!(replacement.len(), 1);
+    }
+
+    #[test]
+    fn unreadable_journal_starts_over() {
+        let dir = std::env::temp_dir().join(format!("brk_reorgs_bad_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("reorgs.json");
+        fs::write(&path, b"not json").unwrap();
+        assert!(Reorgs::import(&path).all().is_empty());
+    }
+}
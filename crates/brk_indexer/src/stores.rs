@@ -7,7 +7,8 @@ use brk_error::Result;
 use brk_store::{AnyStore, Kind, Mode, Store};
 use brk_types::{
     AddrHash, AddrIndexOutPoint, AddrIndexTxIndex, BlockHashPrefix, Height, OutPoint, OutputType,
-    TxIndex, TxOutIndex, TxidPrefix, TypeIndex, Unit, Version, Vout,
+    ScriptHash, ScriptHashPrefix, ScriptHashTxOutIndex, TxIndex, TxOutIndex, TxidPrefix, TypeIndex,
+    Unit, Version, Vout,
 };
 use fjall::{Database, PersistMode};
 use rayon::prelude::*;
@@ -25,7 +26,9 @@ pub struct Stores {
     pub addr_type_to_addr_hash_to_addr_index: ByAddrType<Store<AddrHash, TypeIndex>>,
     pub addr_type_to_addr_index_and_tx_index: ByAddrType<Store<AddrIndexTxIndex, Unit>>,
     pub addr_type_to_addr_index_and_unspent_outpoint: ByAddrType<Store<AddrIndexOutPoint, Unit>>,
+    pub addr_type_to_script_hash_to_addr_index: ByAddrType<Store<ScriptHash, TypeIndex>>,
     pub blockhash_prefix_to_height: Store<BlockHashPrefix, Height>,
+    pub script_hash_prefix_and_txout_index: Store<ScriptHashTxOutIndex, Unit>,
     pub txid_prefix_to_tx_index: Store<TxidPrefix, TxIndex>,
 }
 
@@ -85,6 +88,17 @@ impl Stores {
             )
         };
 
+        let create_script_hash_to_addr_index_store = |index| {
+            Store::import(
+                database_ref,
+                path,
+                &format!("s2i{}", index),
+                version,
+                Mode::PushOnly,
+                Kind::Random,
+            )
+        };
+
         let stores = Self {
             db: database.clone(),
 
@@ -97,6 +111,9 @@ impl Stores {
             addr_type_to_addr_index_and_unspent_outpoint: ByAddrType::new_with_index(
                 create_addr_index_to_unspent_outpoint_store,
             )?,
+            addr_type_to_script_hash_to_addr_index: ByAddrType::new_with_index(
+                create_script_hash_to_addr_index_store,
+            )?,
             blockhash_prefix_to_height: Store::import(
                 database_ref,
                 path,
@@ -105,6 +122,14 @@ impl Stores {
                 Mode::PushOnly,
                 Kind::Random,
             )?,
+            script_hash_prefix_and_txout_index: Store::import(
+                database_ref,
+                path,
+                "script_hash_prefix_and_txout_index",
+                version,
+                Mode::PushOnly,
+                Kind::Vec,
+            )?,
             txid_prefix_to_tx_index: Store::import_cached(
                 database_ref,
                 path,
@@ -129,6 +154,7 @@ impl Stores {
     fn iter_any(&self) -> impl Iterator<Item = &dyn AnyStore> {
         [
             &self.blockhash_prefix_to_height as &dyn AnyStore,
+            &self.script_hash_prefix_and_txout_index,
             &self.txid_prefix_to_tx_index,
         ]
         .into_iter()
@@ -147,11 +173,17 @@ impl Stores {
                 .values()
                 .map(|s| s as &dyn AnyStore),
         )
+        .chain(
+            self.addr_type_to_script_hash_to_addr_index
+                .values()
+                .map(|s| s as &dyn AnyStore),
+        )
     }
 
     fn par_iter_any_mut(&mut self) -> impl ParallelIterator<Item = &mut dyn AnyStore> {
         [
             &mut self.blockhash_prefix_to_height as &mut dyn AnyStore,
+            &mut self.script_hash_prefix_and_txout_index,
             &mut self.txid_prefix_to_tx_index,
         ]
         .into_par_iter()
@@ -170,6 +202,11 @@ impl Stores {
                 .par_values_mut()
                 .map(|s| s as &mut dyn AnyStore),
         )
+        .chain(
+            self.addr_type_to_script_hash_to_addr_index
+                .par_values_mut()
+                .map(|s| s as &mut dyn AnyStore),
+        )
     }
 
     pub fn commit(&mut self, height: Height) -> Result<()> {
@@ -202,6 +239,7 @@ impl Stores {
         }
 
         take!(self.blockhash_prefix_to_height);
+        take!(self.script_hash_prefix_and_txout_index);
         take!(self.txid_prefix_to_tx_index);
 
         for store in self.addr_type_to_addr_hash_to_addr_index.values_mut() {
@@ -216,6 +254,9 @@ impl Stores {
         {
             take!(store);
         }
+        for store in self.addr_type_to_script_hash_to_addr_index.values_mut() {
+            take!(store);
+        }
 
         Ok(tasks)
     }
@@ -249,6 +290,7 @@ impl Stores {
 
     fn is_empty(&self) -> Result<bool> {
         Ok(self.blockhash_prefix_to_height.is_empty()?
+            && self.script_hash_prefix_and_txout_index.is_empty()?
             && self.txid_prefix_to_tx_index.is_empty()?
             && self
                 .addr_type_to_addr_hash_to_addr_index
@@ -261,6 +303,10 @@ impl Stores {
             && self
                 .addr_type_to_addr_index_and_unspent_outpoint
                 .values()
+                .try_fold(true, |acc, s| s.is_empty().map(|empty| acc && empty))?
+            && self
+                .addr_type_to_script_hash_to_addr_index
+                .values()
                 .try_fold(true, |acc, s| s.is_empty().map(|empty| acc && empty))?)
     }
 
@@ -279,10 +325,13 @@ impl Stores {
         );
 
         for addr_type in OutputType::ADDR_TYPES {
-            for hash in vecs.iter_addr_hashes_from(addr_type, starting_lengths.height)? {
+            for bytes in vecs.iter_addr_bytes_from(addr_type, starting_lengths.height)? {
                 self.addr_type_to_addr_hash_to_addr_index
                     .get_mut_unwrap(addr_type)
-                    .remove(hash);
+                    .remove(AddrHash::from(&bytes));
+                self.addr_type_to_script_hash_to_addr_index
+                    .get_mut_unwrap(addr_type)
+                    .remove(ScriptHash::from(&bytes));
             }
         }
 
@@ -319,6 +368,7 @@ impl Stores {
         let tx_index_to_first_txout_index_reader = vecs.transactions.first_txout_index.reader();
         let txout_index_to_output_type_reader = vecs.outputs.output_type.reader();
         let txout_index_to_type_index_reader = vecs.outputs.type_index.reader();
+        let script_hash_readers = vecs.scripts.script_hash_readers();
 
         let mut addr_index_tx_index_to_remove: FxHashSet<(OutputType, TypeIndex, TxIndex)> =
             FxHashSet::default();
@@ -333,6 +383,17 @@ impl Stores {
 
         for (i, txout_index) in (rollback_start..rollback_end).enumerate() {
             let output_type = txout_index_to_output_type_reader.get(txout_index);
+            if OutputType::SCRIPT_HASH_TYPES.contains(&output_type) {
+                let type_index = txout_index_to_type_index_reader.get(txout_index);
+                if let Some(script_hash) = script_hash_readers.get(output_type, type_index) {
+                    self.script_hash_prefix_and_txout_index
+                        .remove(ScriptHashTxOutIndex::from((
+                            ScriptHashPrefix::from(&script_hash),
+                            TxOutIndex::from(txout_index),
+                        )));
+                }
+                continue;
+            }
             if !output_type.is_addr() {
                 continue;
             }
@@ -404,3 +465,96 @@ impl Stores {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::ScriptBuf;
+
+    use super::*;
+
+    fn outputs(stores: &Stores, prefix: ScriptHashPrefix) -> Vec<TxOutIndex> {
+        stores
+            .script_hash_prefix_and_txout_index
+            .prefix(prefix)
+            .map(|(key, _): (ScriptHashTxOutIndex, Unit)| key.txout_index())
+            .collect()
+    }
+
+    fn insert(stores: &mut Stores, prefix: ScriptHashPrefix, txout_index: u64) {
+        stores.script_hash_prefix_and_txout_index.insert(
+            ScriptHashTxOutIndex::from((prefix, TxOutIndex::from(txout_index))),
+            Unit,
+        );
+    }
+
+    #[test]
+    fn script_hash_outputs_roll_back_with_their_block() {
+        let dir =
+            std::env::temp_dir().join(format!("brk_stores_script_hash_{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let a = ScriptHashPrefix::from(ScriptHash::from(&ScriptBuf::new()));
+        let b = ScriptHashPrefix::from(ScriptHash::from(&ScriptBuf::from_bytes(vec![0x51])));
+        let txout = |i: u64| TxOutIndex::from(i);
+
+        let mut stores = Stores::forced_import(&dir, Version::ZERO).unwrap();
+        insert(&mut stores, a, 5);
+        insert(&mut stores, b, 6);
+        insert(&mut stores, a, 3);
+        stores.commit(Height::new(1)).unwrap();
+        assert_eq!(outputs(&stores, a), vec![txout(3), txout(5)]);
+
+        insert(&mut stores, a, 300);
+        insert(&mut stores, b, 301);
+        stores.commit(Height::new(2)).unwrap();
+        assert_eq!(outputs(&stores, a), vec![txout(3), txout(5), txout(300)]);
+
+        // What `rollback_outputs_and_inputs` does for block 2's outputs.
+        for (prefix, txout_index) in [(a, 300), (b, 301)] {
+            stores
+                .script_hash_prefix_and_txout_index
+                .remove(ScriptHashTxOutIndex::from((prefix, txout(txout_index))));
+        }
+        stores.commit(Height::new(1)).unwrap();
+        assert_eq!(outputs(&stores, a), vec![txout(3), txout(5)]);
+        assert_eq!(outputs(&stores, b), vec![txout(6)]);
+
+        drop(stores);
+        let stores = Stores::forced_import(&dir, Version::ZERO).unwrap();
+        assert_eq!(outputs(&stores, a), vec![txout(3), txout(5)]);
+        assert_eq!(outputs(&stores, b), vec![txout(6)]);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn script_hashes_sharing_a_prefix_keep_their_addr() {
+        let dir = std::env::temp_dir().join(format!(
+            "brk_stores_script_hash_addr_{}",
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&dir);
+        // Displayed reversed, so only the last byte differs.
+        let hash = |last: u8| {
+            format!("{last:02x}{}", "07".repeat(31))
+                .parse::<ScriptHash>()
+                .unwrap()
+        };
+        let (a, b) = (hash(1), hash(2));
+        assert_eq!(ScriptHashPrefix::from(&a), ScriptHashPrefix::from(&b));
+
+        let mut stores = Stores::forced_import(&dir, Version::ZERO).unwrap();
+        let store = stores
+            .addr_type_to_script_hash_to_addr_index
+            .get_mut_unwrap(OutputType::P2WPKH);
+        store.insert(a, TypeIndex::from(1u32));
+        store.insert(b, TypeIndex::from(2u32));
+        stores.commit(Height::new(1)).unwrap();
+
+        let store = stores
+            .addr_type_to_script_hash_to_addr_index
+            .get_unwrap(OutputType::P2WPKH);
+        let get = |hash| store.get(hash).unwrap().map(|cow| cow.into_owned());
+        assert_eq!(get(&a), Some(TypeIndex::from(1u32)));
+        assert_eq!(get(&b), Some(TypeIndex::from(2u32)));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,7 +1,7 @@
 use brk_error::Result;
 use brk_traversable::Traversable;
 use brk_types::{
-    AddrBytes, AddrHash, Height, OutputType, P2AAddrIndex, P2ABytes, P2PK33AddrIndex, P2PK33Bytes,
+    AddrBytes, Height, OutputType, P2AAddrIndex, P2ABytes, P2PK33AddrIndex, P2PK33Bytes,
     P2PK65AddrIndex, P2PK65Bytes, P2PKHAddrIndex, P2PKHBytes, P2SHAddrIndex, P2SHBytes,
     P2TRAddrIndex, P2TRBytes, P2WPKHAddrIndex, P2WPKHBytes, P2WSHAddrIndex, P2WSHBytes, TypeIndex,
     Version,
@@ -288,14 +288,14 @@ impl AddrsVecs {
         Ok(())
     }
 
-    /// Iterate address hashes starting from a given height (for rollback).
-    /// Returns an iterator of AddrHash values for all addresses of the given type
+    /// Iterate address bytes starting from a given height (for rollback).
+    /// Returns an iterator of AddrBytes values for all addresses of the given type
     /// that were added at or after the given height.
-    pub fn iter_hashes_from(
+    pub fn iter_bytes_from(
         &self,
         addr_type: OutputType,
         height: Height,
-    ) -> Result<Box<dyn Iterator<Item = AddrHash> + '_>> {
+    ) -> Result<Box<dyn Iterator<Item = AddrBytes> + '_>> {
         macro_rules! make_iter {
             ($addr:expr) => {{
                 match $addr.first_index.collect_one(height) {
@@ -303,15 +303,15 @@ impl AddrsVecs {
                         let reader = $addr.bytes.reader();
                         Ok(Box::new(std::iter::from_fn(move || {
                             reader.try_get(index.to_usize()).map(|typedbytes| {
-                                let bytes = AddrBytes::from(typedbytes);
                                 index.increment();
-                                AddrHash::from(&bytes)
+                                AddrBytes::from(typedbytes)
                             })
                         }))
-                            as Box<dyn Iterator<Item = AddrHash> + '_>)
+                            as Box<dyn Iterator<Item = AddrBytes> + '_>)
                     }
                     None => {
-                        Ok(Box::new(std::iter::empty()) as Box<dyn Iterator<Item = AddrHash> + '_>)
+                        Ok(Box::new(std::iter::empty())
+                            as Box<dyn Iterator<Item = AddrBytes> + '_>)
                     }
                 }
             }};
@@ -1,8 +1,8 @@
 use brk_error::Result;
 use brk_traversable::Traversable;
 use brk_types::{
-    BlkPosition, BlockHash, CoinbaseTag, Height, StoredF64, StoredU32, StoredU64, Timestamp,
-    Version, Weight,
+    BlkPosition, BlockHash, CoinbaseTag, Height, StoredBool, StoredF64, StoredU32, StoredU64,
+    Timestamp, Version, Weight,
 };
 use rayon::prelude::*;
 use vecdb::{
@@ -16,6 +16,10 @@ use crate::parallel_import;
 pub struct BlocksVecs<M: StorageMode = Rw> {
     pub blockhash: CachedVec<M::Stored<BytesVec<Height, BlockHash>>>,
     pub coinbase_tag: M::Stored<BytesVec<Height, CoinbaseTag>>,
+    /// Whether the coinbase has a BIP141 witness commitment output
+    pub coinbase_witness_commitment: M::Stored<PcoVec<Height, StoredBool>>,
+    /// Whether the coinbase has an RSK merged-mining `OP_RETURN`
+    pub coinbase_rsk_tag: M::Stored<PcoVec<Height, StoredBool>>,
     #[traversable(wrap = "difficulty", rename = "value")]
     pub difficulty: M::Stored<PcoVec<Height, StoredF64>>,
     /// Doesn't guarantee continuity due to possible reorgs and more generally the nature of mining
@@ -37,6 +41,8 @@ impl BlocksVecs {
         let (
             blockhash,
             coinbase_tag,
+            coinbase_witness_commitment,
+            coinbase_rsk_tag,
             difficulty,
             timestamp,
             total,
@@ -48,6 +54,8 @@ impl BlocksVecs {
         ) = parallel_import! {
             blockhash = BytesVec::forced_import(db, "blockhash", version),
             coinbase_tag = BytesVec::forced_import(db, "coinbase_tag", version),
+            coinbase_witness_commitment = PcoVec::forced_import(db, "coinbase_witness_commitment", version),
+            coinbase_rsk_tag = PcoVec::forced_import(db, "coinbase_rsk_tag", version),
             difficulty = PcoVec::forced_import(db, "difficulty", version),
             timestamp = PcoVec::forced_import(db, "timestamp", version),
             total_size = PcoVec::forced_import(db, "total_size", version),
@@ -60,6 +68,8 @@ impl BlocksVecs {
         Ok(Self {
             blockhash: CachedVec::wrap(blockhash),
             coinbase_tag,
+            coinbase_witness_commitment,
+            coinbase_rsk_tag,
             difficulty,
             timestamp: CachedVec::wrap(timestamp),
             total,
@@ -77,6 +87,10 @@ impl BlocksVecs {
             .truncate_if_needed_with_stamp(height, stamp)?;
         self.coinbase_tag
             .truncate_if_needed_with_stamp(height, stamp)?;
+        self.coinbase_witness_commitment
+            .truncate_if_needed_with_stamp(height, stamp)?;
+        self.coinbase_rsk_tag
+            .truncate_if_needed_with_stamp(height, stamp)?;
         self.difficulty
             .truncate_if_needed_with_stamp(height, stamp)?;
         self.timestamp
@@ -98,6 +112,8 @@ impl BlocksVecs {
         [
             &mut self.blockhash.inner as &mut dyn AnyStoredVec,
             &mut self.coinbase_tag,
+            &mut self.coinbase_witness_commitment,
+            &mut self.coinbase_rsk_tag,
             &mut self.difficulty,
             &mut self.timestamp.inner,
             &mut self.total,
@@ -114,6 +130,8 @@ impl BlocksVecs {
         [
             &self.blockhash.inner as &dyn AnyStoredVec,
             &self.coinbase_tag,
+            &self.coinbase_witness_commitment,
+            &self.coinbase_rsk_tag,
             &self.difficulty,
             &self.timestamp.inner,
             &self.total,
@@ -2,7 +2,7 @@ use std::path::Path;
 
 use brk_error::Result;
 use brk_traversable::Traversable;
-use brk_types::{AddrHash, Height, OutputType, Version};
+use brk_types::{AddrBytes, Height, OutputType, Version};
 use rayon::prelude::*;
 use vecdb::{AnyStoredVec, Database, Rw, Stamp, StorageMode};
 
@@ -153,12 +153,12 @@ impl Vecs {
         Ok(())
     }
 
-    pub fn iter_addr_hashes_from(
+    pub fn iter_addr_bytes_from(
         &self,
         addr_type: OutputType,
         height: Height,
-    ) -> Result<Box<dyn Iterator<Item = AddrHash> + '_>> {
-        self.addrs.iter_hashes_from(addr_type, height)
+    ) -> Result<Box<dyn Iterator<Item = AddrBytes> + '_>> {
+        self.addrs.iter_bytes_from(addr_type, height)
     }
 
     fn par_iter_mut_any_stored_vec(
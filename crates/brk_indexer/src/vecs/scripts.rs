@@ -1,17 +1,19 @@
 use brk_error::Result;
 use brk_traversable::Traversable;
 use brk_types::{
-    EmptyOutputIndex, Height, OpReturnIndex, P2MSOutputIndex, TxIndex, UnknownOutputIndex, Version,
+    EmptyOutputIndex, Height, OpReturnIndex, P2MSOutputIndex, ScriptHash, TxIndex,
+    UnknownOutputIndex, Version,
 };
 use rayon::prelude::*;
 use schemars::JsonSchema;
 use serde::Serialize;
 use vecdb::{
-    AnyStoredVec, Database, Formattable, ImportableVec, PcoVec, PcoVecValue, Rw, Stamp,
-    StorageMode, VecIndex, WritableVec,
+    AnyStoredVec, BytesVec, Database, Formattable, ImportableVec, PcoVec, PcoVecValue, Ro, Rw,
+    Stamp, StorageMode, VecIndex, WritableVec,
 };
 
 use crate::parallel_import;
+use crate::readers::ScriptHashReaders;
 
 #[derive(Traversable)]
 pub struct ScriptTypeVecs<
@@ -28,6 +30,17 @@ pub struct ScriptsVecs<M: StorageMode = Rw> {
     pub op_return: ScriptTypeVecs<OpReturnIndex, M>,
     pub p2ms: ScriptTypeVecs<P2MSOutputIndex, M>,
     pub unknown: ScriptTypeVecs<UnknownOutputIndex, M>,
+    #[traversable(hidden)]
+    pub script_hash: ScriptHashVecs<M>,
+}
+
+/// Full script hash of every output without an address, by type index.
+/// Lets a script hash store hit be verified past its 8-byte prefix.
+#[derive(Traversable)]
+pub struct ScriptHashVecs<M: StorageMode = Rw> {
+    pub empty: M::Stored<BytesVec<EmptyOutputIndex, ScriptHash>>,
+    pub p2ms: M::Stored<BytesVec<P2MSOutputIndex, ScriptHash>>,
+    pub unknown: M::Stored<BytesVec<UnknownOutputIndex, ScriptHash>>,
 }
 
 impl ScriptsVecs {
@@ -41,6 +54,9 @@ impl ScriptsVecs {
             op_return_index_to_tx_index,
             p2ms_output_index_to_tx_index,
             unknown_output_index_to_tx_index,
+            empty_output_index_to_script_hash,
+            p2ms_output_index_to_script_hash,
+            unknown_output_index_to_script_hash,
         ) = parallel_import! {
             first_empty_output_index = PcoVec::forced_import(db, "first_empty_output_index", version),
             first_op_return_index = PcoVec::forced_import(db, "first_op_return_index", version),
@@ -50,6 +66,9 @@ impl ScriptsVecs {
             op_return_index_to_tx_index = PcoVec::forced_import(db, "tx_index", version),
             p2ms_output_index_to_tx_index = PcoVec::forced_import(db, "tx_index", version),
             unknown_output_index_to_tx_index = PcoVec::forced_import(db, "tx_index", version),
+            empty_output_index_to_script_hash = BytesVec::forced_import(db, "empty_output_script_hash", version),
+            p2ms_output_index_to_script_hash = BytesVec::forced_import(db, "p2ms_output_script_hash", version),
+            unknown_output_index_to_script_hash = BytesVec::forced_import(db, "unknown_output_script_hash", version),
         };
         Ok(Self {
             empty: ScriptTypeVecs {
@@ -68,6 +87,11 @@ impl ScriptsVecs {
                 first_index: first_unknown_output_index,
                 to_tx_index: unknown_output_index_to_tx_index,
             },
+            script_hash: ScriptHashVecs {
+                empty: empty_output_index_to_script_hash,
+                p2ms: p2ms_output_index_to_script_hash,
+                unknown: unknown_output_index_to_script_hash,
+            },
         })
     }
 
@@ -104,6 +128,15 @@ impl ScriptsVecs {
         self.unknown
             .to_tx_index
             .truncate_if_needed_with_stamp(unknown_output_index, stamp)?;
+        self.script_hash
+            .empty
+            .truncate_if_needed_with_stamp(empty_output_index, stamp)?;
+        self.script_hash
+            .p2ms
+            .truncate_if_needed_with_stamp(p2ms_output_index, stamp)?;
+        self.script_hash
+            .unknown
+            .truncate_if_needed_with_stamp(unknown_output_index, stamp)?;
         Ok(())
     }
 
@@ -117,6 +150,9 @@ impl ScriptsVecs {
             &mut self.op_return.to_tx_index,
             &mut self.p2ms.to_tx_index,
             &mut self.unknown.to_tx_index,
+            &mut self.script_hash.empty,
+            &mut self.script_hash.p2ms,
+            &mut self.script_hash.unknown,
         ]
         .into_par_iter()
     }
@@ -131,7 +167,27 @@ impl ScriptsVecs {
             &self.op_return.to_tx_index,
             &self.p2ms.to_tx_index,
             &self.unknown.to_tx_index,
+            &self.script_hash.empty,
+            &self.script_hash.p2ms,
+            &self.script_hash.unknown,
         ]
         .into_iter()
     }
 }
+
+macro_rules! impl_script_hash_readers {
+    ($mode:ty) => {
+        impl ScriptsVecs<$mode> {
+            pub fn script_hash_readers(&self) -> ScriptHashReaders {
+                ScriptHashReaders {
+                    empty: self.script_hash.empty.reader(),
+                    p2ms: self.script_hash.p2ms.reader(),
+                    unknown: self.script_hash.unknown.reader(),
+                }
+            }
+        }
+    };
+}
+
+impl_script_hash_readers!(Rw);
+impl_script_hash_readers!(Ro);
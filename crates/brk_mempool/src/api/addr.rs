@@ -1,8 +1,11 @@
 //! Address-keyed reads.
 
-use std::cmp::Reverse;
+use std::{cmp::Reverse, slice};
 
-use brk_types::{AddrBytes, AddrMempoolStats, Timestamp, Transaction, TxidPrefix};
+use brk_types::{
+    AddrBytes, AddrMempoolStats, ScriptHash, Timestamp, Transaction, Txid, TxidPrefix,
+};
+use rustc_hash::FxHashSet;
 
 use crate::Mempool;
 
@@ -19,17 +22,42 @@ impl Mempool {
         self.read().addrs.get(addr).map(|e| e.stats.clone())
     }
 
+    /// Address with live mempool activity whose scriptPubKey hashes to
+    /// `script_hash`. Covers addresses never seen on chain, which the
+    /// indexer's script hash store can't resolve yet.
+    pub fn addr_by_script_hash(&self, script_hash: &ScriptHash) -> Option<AddrBytes> {
+        self.read().addrs.by_script_hash(script_hash).cloned()
+    }
+
+    /// Txids of the live mempool txs touching `addr`, unordered. Cheap
+    /// membership probe for watchers that don't need the bodies.
+    pub fn addr_txids(&self, addr: &AddrBytes) -> Vec<Txid> {
+        self.read()
+            .addrs
+            .get(addr)
+            .map(|e| e.txids.iter().copied().collect())
+            .unwrap_or_default()
+    }
+
     /// Live mempool txs touching `addr`, newest first by `first_seen`,
     /// capped at `limit`. Returns owned `Transaction`s.
     #[must_use]
     pub fn addr_txs(&self, addr: &AddrBytes, limit: usize) -> Vec<Transaction> {
+        self.addrs_txs(slice::from_ref(addr), limit)
+    }
+
+    /// `addr_txs` across several addresses, each tx once even when it
+    /// touches more than one of them.
+    #[must_use]
+    pub fn addrs_txs(&self, addrs: &[AddrBytes], limit: usize) -> Vec<Transaction> {
         let state = self.read();
-        let Some(entry) = state.addrs.get(addr) else {
-            return vec![];
-        };
-        let mut ordered: Vec<(Timestamp, &Transaction)> = entry
-            .txids
+        let txids: FxHashSet<&Txid> = addrs
             .iter()
+            .filter_map(|addr| state.addrs.get(addr))
+            .flat_map(|entry| &entry.txids)
+            .collect();
+        let mut ordered: Vec<(Timestamp, &Transaction)> = txids
+            .into_iter()
             .filter_map(|txid| {
                 let record = state.txs.record_by_prefix(&TxidPrefix::from(txid))?;
                 Some((record.entry.first_seen, &record.tx))
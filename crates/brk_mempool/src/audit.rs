@@ -0,0 +1,174 @@
+//! Block audits. When a cycle sees the tip move up by one block, the
+//! block is fetched and compared against the `blocks[0]` template of
+//! the previous cycle, the last projection made before it showed up.
+//! The last [`AUDIT_CAPACITY`] audits stay in memory. They also go to
+//! the history buffer for the computer's audit series and, with
+//! persistence, to disk.
+
+use std::{collections::VecDeque, sync::Arc};
+
+use brk_types::{BlockAudit, BlockHash, CheckedSub, Height, Sats, Txid, halving_interval, network};
+use tracing::warn;
+
+use crate::{Cycle, Mempool, Snapshot};
+
+/// About a week of blocks.
+const AUDIT_CAPACITY: usize = 1008;
+
+#[derive(Default)]
+pub struct Auditor {
+    /// Tip and projection of the previous cycle.
+    last: Option<(Height, Arc<Snapshot>)>,
+    recent: VecDeque<BlockAudit>,
+}
+
+impl Auditor {
+    /// Keeps `snapshot` as the latest projection and returns the
+    /// previous one when it was made for the block at `tip`. A jump of
+    /// several blocks or a same-height reorg returns `None`: the
+    /// projection was never meant for that block.
+    fn advance(&mut self, tip: Height, snapshot: &Arc<Snapshot>) -> Option<Arc<Snapshot>> {
+        let (prev_tip, prev) = self.last.replace((tip, snapshot.clone()))?;
+        (tip == prev_tip.incremented()).then_some(prev)
+    }
+
+    fn insert(&mut self, audit: BlockAudit) {
+        if self.recent.len() == AUDIT_CAPACITY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(audit);
+    }
+
+    fn get(&self, hash: &BlockHash) -> Option<&BlockAudit> {
+        self.recent.iter().rev().find(|audit| audit.hash == *hash)
+    }
+}
+
+impl Mempool {
+    /// Audit of the block `hash`, if it was mined while the driver was
+    /// running. Looks in memory first, then in the persisted audits.
+    pub fn block_audit(&self, hash: &BlockHash) -> Option<BlockAudit> {
+        if let Some(audit) = self.0.auditor.lock().get(hash) {
+            return Some(audit.clone());
+        }
+        self.0.persist.as_ref()?.load_audit(hash)
+    }
+
+    /// Audits `cycle`'s tip if it's the block the previous cycle
+    /// projected. A failed block fetch is logged and the block is left
+    /// unaudited.
+    pub(crate) fn audit(&self, cycle: &Cycle) {
+        let Some(projected) = self
+            .0
+            .auditor
+            .lock()
+            .advance(cycle.tip_height, &cycle.snapshot)
+        else {
+            return;
+        };
+
+        let block = match self.0.client.get_block(&cycle.tip_hash) {
+            Ok(block) => block,
+            Err(e) => {
+                warn!("block audit of {} failed: {e}", cycle.tip_hash);
+                return;
+            }
+        };
+        let mined: Vec<Txid> = block
+            .txdata
+            .iter()
+            .skip(1)
+            .map(|tx| Txid::from(tx.compute_txid()))
+            .collect();
+        let reward = Sats::from(
+            block
+                .txdata
+                .first()
+                .map(|coinbase| {
+                    coinbase
+                        .output
+                        .iter()
+                        .map(|o| o.value.to_sat())
+                        .sum::<u64>()
+                })
+                .unwrap_or_default(),
+        );
+        let fees = reward
+            .checked_sub(subsidy(cycle.tip_height, halving_interval(network())))
+            .unwrap_or_default();
+
+        let expected: Vec<Txid> = projected.block0_txids().collect();
+        let expected_fees = projected
+            .block_stats
+            .first()
+            .map(|b| b.total_fee)
+            .unwrap_or_default();
+        let audit = BlockAudit::new(
+            cycle.tip_height,
+            cycle.tip_hash,
+            &expected,
+            expected_fees,
+            &mined,
+            fees,
+        );
+
+        if let Some(persist) = &self.0.persist
+            && let Err(e) = persist.save_audit(&audit)
+        {
+            warn!("saving block audit of {} failed: {e}", audit.hash);
+        }
+        self.0.recorder.lock().audit(audit.clone());
+        self.0.auditor.lock().insert(audit);
+    }
+}
+
+/// Block subsidy at `height`, halving every `halving_interval` blocks.
+fn subsidy(height: Height, halving_interval: u32) -> Sats {
+    let halvings = *height / halving_interval;
+    Sats::from(
+        u64::from(Sats::FIFTY_BTC)
+            .checked_shr(halvings)
+            .unwrap_or_default(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use brk_types::Network;
+
+    use super::*;
+
+    #[test]
+    fn advance_hands_back_the_projection_for_the_next_block() {
+        let mut auditor = Auditor::default();
+        let first = Arc::new(Snapshot::default());
+        let second = Arc::new(Snapshot::default());
+
+        assert!(auditor.advance(Height::new(100), &first).is_none());
+        assert!(auditor.advance(Height::new(100), &first).is_none());
+        let projected = auditor.advance(Height::new(101), &second).unwrap();
+        assert!(Arc::ptr_eq(&projected, &first));
+        assert!(auditor.advance(Height::new(103), &second).is_none());
+    }
+
+    #[test]
+    fn subsidy_halves() {
+        let mainnet = halving_interval(Network::Bitcoin);
+        assert_eq!(subsidy(Height::new(0), mainnet), Sats::FIFTY_BTC);
+        assert_eq!(
+            subsidy(Height::new(840_000), mainnet),
+            Sats::from(312_500_000u64)
+        );
+        assert_eq!(subsidy(Height::new(64 * mainnet), mainnet), Sats::ZERO);
+    }
+
+    #[test]
+    fn subsidy_halves_faster_on_regtest() {
+        let regtest = halving_interval(Network::Regtest);
+        assert_eq!(subsidy(Height::new(149), regtest), Sats::FIFTY_BTC);
+        assert_eq!(
+            subsidy(Height::new(150), regtest),
+            Sats::from(2_500_000_000u64)
+        );
+    }
+}
@@ -1,23 +1,28 @@
 //! Cycle loop. `start_with` drives [`Mempool::tick_with`] every
-//! [`PERIOD`]. Each cycle is wrapped in `catch_unwind` so a panic
-//! doesn't freeze the snapshot. `parking_lot` locks don't poison.
+//! [`PERIOD`], or on ZMQ notifications when the `Mempool` was built
+//! with [`Mempool::new_with`]. Each cycle is wrapped in `catch_unwind`
+//! so a panic doesn't freeze the snapshot. `parking_lot` locks don't
+//! poison. A `Mempool` built with [`Mempool::new_persisted`] also
+//! checkpoints its state every [`SAVE_PERIOD`].
 
 use std::{
     any::Any,
     panic::{AssertUnwindSafe, catch_unwind},
-    sync::atomic::Ordering,
+    sync::{Arc, atomic::Ordering},
     thread,
     time::{Duration, Instant},
 };
 
 use brk_error::Result;
 use brk_types::{TxOut, Txid, Vout};
+use brk_zmq::{FALLBACK_PERIOD, Notifications};
 use rustc_hash::FxHashMap;
 use tracing::error;
 
 use crate::{
-    Inner, Mempool,
+    Emitter, Inner, Mempool,
     cycle::{Cycle, CycleDiff},
+    persist::SAVE_PERIOD,
     steps::{Applier, Fetched, Fetcher, Preparer, Prevouts},
 };
 
@@ -26,8 +31,10 @@ const PERIOD: Duration = Duration::from_millis(1000);
 impl Mempool {
     /// Infinite update loop with a 1s interval. Resolves
     /// confirmed-parent prevouts via the default `getrawtransaction`
-    /// resolver. Requires bitcoind started with `txindex=1`. Discards
-    /// per-cycle [`Cycle`] events - use [`Mempool::tick`] to consume them.
+    /// resolver. Requires bitcoind started with `txindex=1`. Per-cycle
+    /// [`Cycle`]s are only published as [`crate::Event`]s to
+    /// [`Mempool::subscribe`] receivers - use [`Mempool::tick`] to consume
+    /// them directly.
     pub fn start(&self) {
         self.start_with(Prevouts::rpc_resolver(self.0.client.clone()));
     }
@@ -39,6 +46,11 @@ impl Mempool {
     /// a 100ms cycle still ticks roughly every `PERIOD`. When work
     /// overruns `PERIOD`, the next cycle starts immediately.
     ///
+    /// With ZMQ enabled, a new block starts the next cycle at once while
+    /// other mempool notifications wait out `PERIOD`, so a burst of
+    /// `rawtx` still yields one cycle per `PERIOD`. Without any
+    /// notification the loop still ticks every [`FALLBACK_PERIOD`].
+    ///
     /// # Panics
     ///
     /// Panics if a driver is already running on this `Mempool` instance.
@@ -56,12 +68,21 @@ impl Mempool {
         {
             panic!("Mempool::start_with already running on this instance");
         }
+        let notifications = &self.0.notifications;
+        let mut emitter = Emitter::default();
+        let mut saved = Instant::now();
         loop {
             let started = Instant::now();
-            let outcome = catch_unwind(AssertUnwindSafe(|| {
-                if let Err(e) = self.tick_with(&resolver) {
-                    error!("update failed: {e}");
+            let block_seen = notifications.block().generation();
+            let mempool_seen = notifications.mempool().generation();
+            let outcome = catch_unwind(AssertUnwindSafe(|| match self.tick_with(&resolver) {
+                Ok(cycle) => {
+                    self.observe_tip(&cycle);
+                    self.audit(&cycle);
+                    self.record(&cycle);
+                    self.publish(&mut emitter, &cycle);
                 }
+                Err(e) => error!("update failed: {e}"),
             }));
             if let Err(payload) = outcome {
                 error!(
@@ -69,10 +90,52 @@ impl Mempool {
                     Self::panic_msg(&payload)
                 );
             }
-            if let Some(rest) = PERIOD.checked_sub(started.elapsed()) {
-                thread::sleep(rest);
-            }
+            self.checkpoint(&mut saved);
+            Self::wait(notifications, started, block_seen, mempool_seen);
+        }
+    }
+
+    /// Turns `cycle` into events for [`Mempool::subscribe`] receivers.
+    /// The emitter runs even without receivers so its change detection
+    /// stays relative to the previous cycle.
+    fn publish(&self, emitter: &mut Emitter, cycle: &Cycle) {
+        let events: Arc<[_]> = emitter.events(cycle).into();
+        // Only fails without receivers, nothing to do then.
+        let _ = self.0.events.send(events);
+    }
+
+    /// Rewrites the on-disk checkpoint once `SAVE_PERIOD` has passed
+    /// since the last one. No-op without persistence. A failed write
+    /// is logged and retried next period.
+    fn checkpoint(&self, saved: &mut Instant) {
+        let Some(persist) = &self.0.persist else {
+            return;
+        };
+        if saved.elapsed() < SAVE_PERIOD {
+            return;
+        }
+        *saved = Instant::now();
+        if let Err(e) = persist.save(&self.0.state, &self.snapshot()) {
+            error!("mempool checkpoint failed: {e}");
+        }
+        if let Err(e) = persist.save_estimator(&self.0.estimator) {
+            error!("fee estimator checkpoint failed: {e}");
+        }
+    }
+
+    /// Paces the loop after a cycle that began at `started`, with the
+    /// notification generations captured right before it.
+    fn wait(notifications: &Notifications, started: Instant, block_seen: u64, mempool_seen: u64) {
+        let rest = PERIOD.saturating_sub(started.elapsed());
+        if !notifications.is_enabled() {
+            thread::sleep(rest);
+            return;
+        }
+        if notifications.block().wait_past(block_seen, rest) != block_seen {
+            return;
         }
+        let timeout = FALLBACK_PERIOD.saturating_sub(started.elapsed());
+        notifications.mempool().wait_past(mempool_seen, timeout);
     }
 
     /// One sync cycle: fetch, prepare, apply, fill prevouts, rebuild.
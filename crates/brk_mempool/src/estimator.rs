@@ -0,0 +1,330 @@
+//! Confirmation-time fee estimator, in the spirit of Core's
+//! `estimatesmartfee`. Per fee-rate band, it counts how many blocks
+//! past txs waited between their `first_seen` and the block that
+//! confirmed them. Confirmed blocks are fed from outside (the indexer)
+//! through [`Mempool::confirm`]. Txs still waiting are read from the
+//! live pool at estimate time, so stuck txs count against their band.
+//!
+//! Counts decay by [`DECAY`] per block, so the estimator follows the
+//! fee market with a half-life of about a day. Waits are measured
+//! against the tips the driver saw and when, which is why txs first
+//! seen before the oldest logged tip are ignored.
+
+use std::collections::VecDeque;
+
+use brk_types::{
+    FeeEstimate, FeeEstimateBand, FeeEstimateLevel, FeeRate, Height, Timestamp, Txid, TxidPrefix,
+};
+use serde::{Deserialize, Serialize};
+
+use crate::{Cycle, Mempool};
+
+/// Longest confirmation target, in blocks.
+pub const MAX_TARGET: u32 = 1008;
+
+/// Lower bounds in sat/vB of the bands txs are grouped by.
+const BANDS: [f64; 25] = [
+    0.0, 0.5, 1.0, 1.5, 2.0, 3.0, 4.0, 5.0, 6.0, 8.0, 10.0, 12.0, 15.0, 20.0, 25.0, 30.0, 40.0,
+    50.0, 60.0, 80.0, 100.0, 150.0, 200.0, 300.0, 500.0,
+];
+
+/// Weight kept by a count per block.
+const DECAY: f64 = 0.995;
+
+/// Weighted samples a band needs before it's trusted.
+const MIN_SAMPLES: f64 = 10.0;
+
+/// Confidence levels of an estimate, in percent.
+const LEVELS: [u8; 3] = [50, 80, 95];
+
+#[derive(Serialize, Deserialize)]
+pub struct Estimator {
+    /// Tips with the time the driver first saw them, oldest first.
+    tips: VecDeque<(Timestamp, Height)>,
+    /// Last height fed through `confirm`.
+    confirmed_height: Option<Height>,
+    /// `confirmed[band][n - 1]`: weighted count of txs that took `n`
+    /// blocks. The last slot also holds every slower tx.
+    confirmed: Vec<Vec<f64>>,
+}
+
+impl Default for Estimator {
+    fn default() -> Self {
+        Self {
+            tips: VecDeque::new(),
+            confirmed_height: None,
+            confirmed: vec![vec![0.0; MAX_TARGET as usize]; BANDS.len()],
+        }
+    }
+}
+
+impl Estimator {
+    /// Whether a deserialized estimator matches the current bands and
+    /// targets.
+    pub fn is_valid(&self) -> bool {
+        self.confirmed.len() == BANDS.len()
+            && self
+                .confirmed
+                .iter()
+                .all(|counts| counts.len() == MAX_TARGET as usize)
+    }
+
+    fn tip(&self) -> Height {
+        self.tips.back().map(|(_, tip)| *tip).unwrap_or_default()
+    }
+
+    fn observe_tip(&mut self, at: Timestamp, tip: Height) {
+        if self.tips.back().is_some_and(|(_, last)| *last == tip) {
+            return;
+        }
+        self.tips.push_back((at, tip));
+        while self.tips.len() > MAX_TARGET as usize + 1 {
+            self.tips.pop_front();
+        }
+    }
+
+    /// Tip the driver knew at `at`. `None` before the oldest logged tip.
+    fn tip_at(&self, at: Timestamp) -> Option<Height> {
+        let i = self.tips.partition_point(|(seen, _)| *seen <= at);
+        i.checked_sub(1).map(|i| self.tips[i].1)
+    }
+
+    /// Decays the counts up to `height`. `false` for a height already
+    /// fed, whose txs would be counted twice.
+    fn advance_to(&mut self, height: Height) -> bool {
+        if let Some(last) = self.confirmed_height {
+            if height <= last {
+                return false;
+            }
+            let factor = DECAY.powi((*height - *last) as i32);
+            self.confirmed
+                .iter_mut()
+                .flatten()
+                .for_each(|count| *count *= factor);
+        }
+        self.confirmed_height = Some(height);
+        true
+    }
+
+    fn add(&mut self, height: Height, first_seen: Timestamp, rate: FeeRate) {
+        let Some(seen_at) = self.tip_at(first_seen) else {
+            return;
+        };
+        let blocks = (*height).saturating_sub(*seen_at).clamp(1, MAX_TARGET);
+        self.confirmed[band_of(rate)][blocks as usize - 1] += 1.0;
+    }
+
+    /// `live` yields the `first_seen` and effective rate of every tx
+    /// still in the pool. Estimated rates never go below `floor`.
+    fn estimate(
+        &self,
+        target: u32,
+        live: impl Iterator<Item = (Timestamp, FeeRate)>,
+        floor: FeeRate,
+    ) -> FeeEstimate {
+        let tip = self.tip();
+        let mut overdue = [0u32; BANDS.len()];
+        for (first_seen, rate) in live {
+            if let Some(seen_at) = self.tip_at(first_seen)
+                && (*tip).saturating_sub(*seen_at) >= target
+            {
+                overdue[band_of(rate)] += 1;
+            }
+        }
+
+        let bands: Vec<FeeEstimateBand> = BANDS
+            .iter()
+            .zip(&self.confirmed)
+            .zip(overdue)
+            .map(|((&lower, counts), overdue)| band(lower, counts, overdue, target))
+            .collect();
+        let estimates = LEVELS
+            .iter()
+            .map(|&confidence| FeeEstimateLevel {
+                confidence,
+                fee_rate: lowest_passing(&bands, confidence).map(|rate| rate.max(floor)),
+            })
+            .collect();
+
+        FeeEstimate {
+            target,
+            height: tip,
+            estimates,
+            bands,
+        }
+    }
+}
+
+fn band_of(rate: FeeRate) -> usize {
+    let rate = f64::from(rate);
+    BANDS
+        .iter()
+        .rposition(|&lower| rate >= lower)
+        .unwrap_or_default()
+}
+
+fn band(lower: f64, counts: &[f64], overdue: u32, target: u32) -> FeeEstimateBand {
+    let confirmed: f64 = counts.iter().sum();
+    let within: f64 = counts[..target as usize].iter().sum();
+    let total = confirmed + overdue as f64;
+
+    // Landing in the last slot means "at least MAX_TARGET", no answer.
+    let percentile = |p: u8| {
+        if confirmed < MIN_SAMPLES {
+            return None;
+        }
+        let goal = confirmed * p as f64 / 100.0;
+        let mut sum = 0.0;
+        counts[..counts.len() - 1]
+            .iter()
+            .position(|count| {
+                sum += count;
+                sum >= goal
+            })
+            .map(|i| i as u32 + 1)
+    };
+
+    FeeEstimateBand {
+        fee_rate: FeeRate::new(lower),
+        confirmed,
+        overdue,
+        success_rate: (total >= MIN_SAMPLES).then(|| within / total * 100.0),
+        blocks_p50: percentile(50),
+        blocks_p80: percentile(80),
+        blocks_p95: percentile(95),
+    }
+}
+
+/// Walks down from the top band and keeps the lowest one reaching
+/// `confidence`, stopping at the first trusted band that doesn't.
+fn lowest_passing(bands: &[FeeEstimateBand], confidence: u8) -> Option<FeeRate> {
+    let mut passing = None;
+    for band in bands.iter().rev() {
+        match band.success_rate {
+            Some(rate) if rate >= confidence as f64 => passing = Some(band.fee_rate),
+            Some(_) => break,
+            None => {}
+        }
+    }
+    passing
+}
+
+impl Mempool {
+    /// Fee rates that confirmed within `target` blocks (clamped to
+    /// `1..=MAX_TARGET`) at each confidence level, with the per-band
+    /// observations behind them.
+    pub fn fee_estimate(&self, target: u32) -> FeeEstimate {
+        let target = target.clamp(1, MAX_TARGET);
+        let snapshot = self.snapshot();
+        let live: Vec<(Timestamp, FeeRate)> = self
+            .read()
+            .txs
+            .records()
+            .map(|(prefix, record)| {
+                let rate = snapshot
+                    .chunk_rate_for(prefix)
+                    .unwrap_or_else(|| record.entry.fee_rate());
+                (record.entry.first_seen, rate)
+            })
+            .collect();
+        self.0
+            .estimator
+            .lock()
+            .estimate(target, live.into_iter(), snapshot.fees.minimum_fee)
+    }
+
+    /// Feeds the txs confirmed at `height` (coinbase excluded) to the
+    /// fee estimator. Txs the mempool never saw, or already forgot, are
+    /// skipped. Heights must come in increasing order: one already fed
+    /// is ignored.
+    pub fn confirm(&self, height: Height, txids: &[Txid]) {
+        let snapshot = self.snapshot();
+        let seen: Vec<(Timestamp, FeeRate)> = {
+            let state = self.read();
+            txids
+                .iter()
+                .filter_map(|txid| {
+                    if let Some(entry) = state.txs.entry(txid) {
+                        let rate = snapshot
+                            .chunk_rate_for(&TxidPrefix::from(txid))
+                            .unwrap_or_else(|| entry.fee_rate());
+                        return Some((entry.first_seen, rate));
+                    }
+                    state
+                        .graveyard
+                        .get_vanished(txid)
+                        .map(|tomb| (tomb.entry.first_seen, tomb.chunk_rate))
+                })
+                .collect()
+        };
+
+        let mut estimator = self.0.estimator.lock();
+        if !estimator.advance_to(height) {
+            return;
+        }
+        for (first_seen, rate) in seen {
+            estimator.add(height, first_seen, rate);
+        }
+    }
+
+    pub(crate) fn observe_tip(&self, cycle: &Cycle) {
+        self.0
+            .estimator
+            .lock()
+            .observe_tip(Timestamp::now(), cycle.tip_height);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(secs: u32) -> Timestamp {
+        Timestamp::new(1_700_000_000 + secs)
+    }
+
+    fn estimator() -> Estimator {
+        let mut e = Estimator::default();
+        for i in 0..10 {
+            e.observe_tip(at(i * 600), Height::new(100 + i));
+        }
+        e
+    }
+
+    #[test]
+    fn waits_are_measured_from_the_tip_at_first_seen() {
+        let mut e = estimator();
+        assert!(e.advance_to(Height::new(103)));
+        e.add(Height::new(103), at(650), FeeRate::new(10.0));
+        e.add(Height::new(103), at(1300), FeeRate::new(10.0));
+        // Before the oldest logged tip: ignored.
+        e.add(Height::new(103), Timestamp::new(1), FeeRate::new(10.0));
+
+        let counts = &e.confirmed[band_of(FeeRate::new(10.0))];
+        assert_eq!(counts[1], 1.0);
+        assert_eq!(counts[0], 1.0);
+        assert_eq!(counts.iter().sum::<f64>(), 2.0);
+        assert!(!e.advance_to(Height::new(103)));
+    }
+
+    #[test]
+    fn high_bands_pass_and_overdue_txs_sink_low_ones() {
+        let mut e = estimator();
+        e.advance_to(Height::new(109));
+        for _ in 0..20 {
+            e.add(Height::new(109), at(8 * 600), FeeRate::new(20.0));
+            e.add(Height::new(109), at(4 * 600), FeeRate::new(5.0));
+        }
+        let stuck = (0..20).map(|_| (at(0), FeeRate::new(2.0)));
+
+        let estimate = e.estimate(1, stuck, FeeRate::new(1.0));
+        let rates: Vec<_> = estimate.estimates.iter().map(|l| l.fee_rate).collect();
+        assert!(rates.iter().all(|r| *r == Some(FeeRate::new(20.0))));
+
+        let estimate = e.estimate(6, std::iter::empty(), FeeRate::new(1.0));
+        assert_eq!(estimate.estimates[2].fee_rate, Some(FeeRate::new(5.0)));
+        let band = &estimate.bands[band_of(FeeRate::new(5.0))];
+        assert_eq!(band.blocks_p50, Some(5));
+        assert_eq!(band.success_rate, Some(100.0));
+    }
+}
@@ -0,0 +1,125 @@
+//! Per-cycle sampling for the historical mempool series. The driver
+//! records one [`MempoolSample`] per cycle into a [`MempoolHistory`]
+//! buffer, which the computer drains on each pass via
+//! [`Mempool::take_history`] and writes to disk. Block audits ride
+//! along in the same buffer.
+
+use std::mem;
+
+use brk_types::{
+    BlockAudit, Height, MEMPOOL_FEE_BANDS, MempoolHistory, MempoolSample, Minute10, Timestamp,
+    VSize,
+};
+
+use crate::{Cycle, Mempool};
+
+#[derive(Default)]
+pub struct Recorder {
+    history: MempoolHistory,
+    /// Tip and sample of the previous cycle, so a new block can be
+    /// credited with the pool as it was right before it showed up.
+    last: Option<(Height, MempoolSample)>,
+}
+
+impl Recorder {
+    pub fn record(&mut self, at: Timestamp, tip: Height, sample: MempoolSample) {
+        if let Some((prev_tip, prev)) = self.last.take()
+            && tip > prev_tip
+        {
+            self.history.height.insert(tip, prev);
+        }
+        self.history
+            .minute10
+            .insert(Minute10::from_timestamp(at), sample.clone());
+        self.last = Some((tip, sample));
+    }
+
+    pub fn audit(&mut self, audit: BlockAudit) {
+        self.history.audit.insert(audit.height, audit);
+    }
+
+    fn take(&mut self) -> MempoolHistory {
+        mem::take(&mut self.history)
+    }
+}
+
+impl Cycle {
+    /// Pool totals, fee-rate bands and fee recommendations as of this
+    /// cycle.
+    pub fn sample(&self) -> MempoolSample {
+        let mut band_vsize = [VSize::default(); MEMPOOL_FEE_BANDS.len()];
+        for (rate, vsize) in &self.info.fee_histogram {
+            band_vsize[MempoolSample::band_of(*rate)] += *vsize;
+        }
+        let next_block = self.snapshot.block_stats.first();
+        MempoolSample {
+            count: self.info.count as u64,
+            vsize: self.info.vsize,
+            total_fee: self.info.total_fee,
+            band_vsize,
+            fees: self.snapshot.fees.clone(),
+            next_block_fee: next_block.map(|b| b.total_fee).unwrap_or_default(),
+            next_block_median_fee_rate: next_block.map(|b| b.fee_range[3]).unwrap_or_default(),
+        }
+    }
+}
+
+impl Mempool {
+    /// Samples the driver recorded since the previous call. Meant to be
+    /// drained regularly (the computer does it once per block); the
+    /// buffer grows by one entry per 10 minutes and per block until then.
+    pub fn take_history(&self) -> MempoolHistory {
+        self.0.recorder.lock().take()
+    }
+
+    pub(crate) fn record(&self, cycle: &Cycle) {
+        self.0
+            .recorder
+            .lock()
+            .record(Timestamp::now(), cycle.tip_height, cycle.sample());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use brk_types::Sats;
+
+    use super::*;
+
+    fn sample(count: u64) -> MempoolSample {
+        MempoolSample {
+            count,
+            vsize: VSize::from(count * 100),
+            total_fee: Sats::from(count * 1_000),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn new_tip_gets_the_last_sample_before_it() {
+        let mut r = Recorder::default();
+        let t = Timestamp::new(1_700_000_000);
+        r.record(t, Height::new(100), sample(1));
+        r.record(t, Height::new(100), sample(2));
+        r.record(t, Height::new(101), sample(3));
+
+        let history = r.take();
+        assert_eq!(history.height.len(), 1);
+        assert_eq!(history.height[&Height::new(101)].count, 2);
+        assert!(r.take().is_empty());
+    }
+
+    #[test]
+    fn minute10_bucket_keeps_its_latest_sample() {
+        let mut r = Recorder::default();
+        let t = Timestamp::new(1_700_000_400);
+        r.record(t, Height::new(100), sample(1));
+        r.record(Timestamp::new(*t + 60), Height::new(100), sample(2));
+        r.record(Timestamp::new(*t + 600), Height::new(100), sample(3));
+
+        let history = r.take();
+        let counts: Vec<u64> = history.minute10.values().map(|s| s.count).collect();
+        assert_eq!(counts, vec![2, 3]);
+        assert!(history.height.is_empty());
+    }
+}
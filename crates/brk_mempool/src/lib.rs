@@ -54,20 +54,37 @@
 //! A `Mempool` hosts at most one driver. Calling `start` / `start_with`
 //! a second time on the same instance panics. Spawn a separate
 //! `Mempool::new` if you need more loops.
+//!
+//! # Persistence
+//!
+//! [`Mempool::new_persisted`] checkpoints the live pool and the
+//! graveyard to a file and reloads it on startup, so `first_seen`
+//! times and RBF history survive restarts. It also keeps every
+//! [`brk_types::BlockAudit`] on disk, see [`Mempool::block_audit`].
 
-use std::sync::{Arc, atomic::AtomicBool};
+use std::{
+    path::Path,
+    sync::{Arc, atomic::AtomicBool},
+};
 
 use brk_rpc::Client;
-use parking_lot::{RwLock, RwLockReadGuard};
+use brk_zmq::Notifications;
+use parking_lot::{Mutex, RwLock, RwLockReadGuard};
+use tokio::sync::broadcast;
 
 mod api;
+mod audit;
 mod cycle;
 mod diagnostics;
 mod driver;
+mod estimator;
+mod history;
+mod persist;
 mod snapshot;
 mod state;
 mod steps;
 mod stores;
+mod stream;
 
 #[cfg(test)]
 mod test_support;
@@ -75,9 +92,15 @@ mod test_support;
 pub use api::{RbfForTx, RbfNode};
 pub use cycle::{AddedKind, Cycle, TxAdded, TxRemoved};
 pub use diagnostics::MempoolStats;
-pub use snapshot::Snapshot;
+pub use estimator::MAX_TARGET;
+pub use snapshot::{Candidate, Snapshot};
 pub use steps::TxRemoval;
+pub use stream::{Emitter, Event, EventKind, LeaveReason};
 
+use audit::Auditor;
+use estimator::Estimator;
+use history::Recorder;
+use persist::Persist;
 use snapshot::Rebuilder;
 use state::State;
 
@@ -87,18 +110,69 @@ pub struct Mempool(Arc<Inner>);
 
 struct Inner {
     client: Client,
+    notifications: Notifications,
     state: RwLock<State>,
     rebuilder: Rebuilder,
+    events: broadcast::Sender<Arc<[Event]>>,
     started: AtomicBool,
+    persist: Option<Persist>,
+    recorder: Mutex<Recorder>,
+    auditor: Mutex<Auditor>,
+    estimator: Mutex<Estimator>,
 }
 
+/// Cycles a [`Mempool::subscribe`] receiver can fall behind before it
+/// starts missing some.
+const EVENTS_CAPACITY: usize = 64;
+
 impl Mempool {
     pub fn new(client: &Client) -> Self {
+        Self::new_with(client, &Notifications::default())
+    }
+
+    /// Variant of `new` whose driver wakes on ZMQ `notifications`
+    /// instead of pulling on a fixed timer. Disabled notifications
+    /// behave exactly like `new`.
+    pub fn new_with(client: &Client, notifications: &Notifications) -> Self {
+        Self::build(
+            client,
+            notifications,
+            State::default(),
+            Estimator::default(),
+            None,
+        )
+    }
+
+    /// Variant of `new_with` that restores the checkpoint at `path`
+    /// (if any) and has the driver rewrite it every minute. Restored
+    /// txs wait in the graveyard until the first cycles revive the ones
+    /// bitcoind still has, keeping their original `first_seen`. The fee
+    /// estimator is restored and saved along with it.
+    pub fn new_persisted(client: &Client, notifications: &Notifications, path: &Path) -> Self {
+        let persist = Persist::new(path);
+        let state = persist.load();
+        let estimator = persist.load_estimator();
+        Self::build(client, notifications, state, estimator, Some(persist))
+    }
+
+    fn build(
+        client: &Client,
+        notifications: &Notifications,
+        state: State,
+        estimator: Estimator,
+        persist: Option<Persist>,
+    ) -> Self {
         Self(Arc::new(Inner {
             client: client.clone(),
-            state: RwLock::new(State::default()),
+            notifications: notifications.clone(),
+            state: RwLock::new(state),
             rebuilder: Rebuilder::default(),
+            events: broadcast::Sender::new(EVENTS_CAPACITY),
             started: AtomicBool::new(false),
+            persist,
+            recorder: Mutex::default(),
+            auditor: Mutex::default(),
+            estimator: Mutex::new(estimator),
         }))
     }
 
@@ -106,6 +180,14 @@ impl Mempool {
         self.0.rebuilder.snapshot()
     }
 
+    /// Events of every cycle run by the driver (`start` / `start_with`),
+    /// one batch per cycle. A receiver lagging more than 64 cycles
+    /// behind gets `RecvError::Lagged` and resumes from the oldest
+    /// batch still buffered.
+    pub fn subscribe(&self) -> broadcast::Receiver<Arc<[Event]>> {
+        self.0.events.subscribe()
+    }
+
     /// One-shot diagnostic counters captured under a single read guard.
     pub fn stats(&self) -> MempoolStats {
         MempoolStats::from(self)
@@ -132,12 +214,13 @@ mod test_helpers {
         /// touching the network. `simple_http` only parses the URL on init.
         pub(crate) fn for_test() -> Self {
             let client = Client::new(Client::default_url(), Auth::None).unwrap();
-            Self(Arc::new(Inner {
-                client,
-                state: RwLock::new(State::default()),
-                rebuilder: Rebuilder::default(),
-                started: AtomicBool::new(false),
-            }))
+            Self::build(
+                &client,
+                &Notifications::default(),
+                State::default(),
+                Estimator::default(),
+                None,
+            )
         }
 
         pub(crate) fn test_state_lock(&self) -> &RwLock<State> {
@@ -0,0 +1,433 @@
+//! On-disk checkpoint of the live pool and the graveyard, so
+//! `first_seen` times and RBF history survive a restart.
+//!
+//! The driver rewrites the file every [`SAVE_PERIOD`] (temp file +
+//! rename, so a crash mid-write keeps the previous checkpoint). A
+//! reload puts every record in the graveyard: live txs are buried as
+//! `Vanished` at load time, tombstones keep their original age and
+//! drop out once past `RETENTION`. The first cycles then reconcile
+//! against `getrawmempool` on their own: txids still listed come back
+//! through the Preparer's `Revived` path with their `first_seen`
+//! preserved, the rest age out like any other tombstone.
+//!
+//! Layout, little-endian: `MAGIC`, `VERSION: u32`, `saved_at: u64`,
+//! `count: u32`, then one record per tx:
+//!
+//! ```text
+//!   kind: u8 (0 live, 1 vanished, 2 replaced) [+ by: 32 bytes]
+//!   removed_at: u64 (unix seconds, `saved_at` for live txs)
+//!   txid: 32 bytes
+//!   fee, vsize, weight, size: u64
+//!   first_seen: u32, rbf: u8, chunk_rate: f64
+//!   raw: u32 len + consensus-encoded tx
+//!   per input: u8 flag [+ prevout value: u64, script: u32 len + bytes]
+//! ```
+//!
+//! `depends` is not stored: it only feeds the live projection, and a
+//! revived entry rebuilds it from the fresh `getmempoolentry`.
+//!
+//! Block audits live next to the checkpoint, one JSON file per block
+//! in a `block_audits` directory named after the block hash. They are
+//! written once and never pruned. The fee estimator is saved alongside
+//! the checkpoint as `fee_estimator.json`.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use bitcoin::{ScriptBuf, hashes::Hash};
+use brk_error::{Error, Result};
+use brk_types::{
+    BlockAudit, BlockHash, FeeRate, Sats, Timestamp, Transaction, TxOut, Txid, VSize, Weight,
+};
+use parking_lot::{Mutex, RwLock};
+use smallvec::SmallVec;
+use tracing::{info, warn};
+
+use crate::{
+    Snapshot, TxRemoval,
+    estimator::Estimator,
+    state::{State, TxEntry},
+    steps::TxAddition,
+    stores::{RETENTION, TxGraveyard, TxStore, TxTombstone},
+};
+
+/// How often the driver rewrites the checkpoint.
+pub const SAVE_PERIOD: Duration = Duration::from_secs(60);
+
+const MAGIC: &[u8; 4] = b"BRKM";
+const VERSION: u32 = 1;
+
+const LIVE: u8 = 0;
+const VANISHED: u8 = 1;
+const REPLACED: u8 = 2;
+
+pub struct Persist {
+    path: PathBuf,
+    audits: PathBuf,
+    estimator: PathBuf,
+}
+
+impl Persist {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            audits: path.with_file_name("block_audits"),
+            estimator: path.with_file_name("fee_estimator.json"),
+        }
+    }
+
+    /// State to start from. A missing file is a cold start. An
+    /// unreadable or stale-format one is logged and ignored: losing
+    /// history is better than refusing to run.
+    pub fn load(&self) -> State {
+        let bytes = match fs::read(&self.path) {
+            Ok(bytes) => bytes,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return State::default(),
+            Err(e) => {
+                warn!(
+                    "mempool checkpoint {:?} unreadable, starting empty: {e}",
+                    self.path
+                );
+                return State::default();
+            }
+        };
+        match decode(&bytes, now()) {
+            Ok(graveyard) => {
+                info!(
+                    "Restored {} mempool txs from {:?}",
+                    graveyard.tombstones_len(),
+                    self.path
+                );
+                State {
+                    graveyard,
+                    ..Default::default()
+                }
+            }
+            Err(e) => {
+                warn!("mempool checkpoint {:?} ignored: {e}", self.path);
+                State::default()
+            }
+        }
+    }
+
+    /// Encodes under a read guard, then writes with no lock held.
+    pub fn save(&self, lock: &RwLock<State>, snapshot: &Snapshot) -> Result<()> {
+        let bytes = encode(&lock.read(), snapshot, now());
+        if let Some(parent) = self.path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let tmp = self.path.with_extension("tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &self.path)?;
+        Ok(())
+    }
+
+    /// Saved estimator, or a fresh one when missing, unreadable or
+    /// built for other bands.
+    pub fn load_estimator(&self) -> Estimator {
+        fs::read(&self.estimator)
+            .ok()
+            .and_then(|bytes| serde_json::from_slice::<Estimator>(&bytes).ok())
+            .filter(Estimator::is_valid)
+            .unwrap_or_default()
+    }
+
+    pub fn save_estimator(&self, estimator: &Mutex<Estimator>) -> Result<()> {
+        let bytes = serde_json::to_vec(&*estimator.lock())?;
+        let tmp = self.estimator.with_extension("tmp");
+        fs::write(&tmp, bytes)?;
+        fs::rename(&tmp, &self.estimator)?;
+        Ok(())
+    }
+
+    pub fn save_audit(&self, audit: &BlockAudit) -> Result<()> {
+        fs::create_dir_all(&self.audits)?;
+        fs::write(self.audit_path(&audit.hash), serde_json::to_vec(audit)?)?;
+        Ok(())
+    }
+
+    /// `None` for a block that was never audited or whose file doesn't
+    /// parse.
+    pub fn load_audit(&self, hash: &BlockHash) -> Option<BlockAudit> {
+        let bytes = fs::read(self.audit_path(hash)).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+
+    fn audit_path(&self, hash: &BlockHash) -> PathBuf {
+        self.audits.join(format!("{hash}.json"))
+    }
+}
+
+fn now() -> u64 {
+    u64::from(Timestamp::now())
+}
+
+fn encode(state: &State, snapshot: &Snapshot, saved_at: u64) -> Vec<u8> {
+    let mut out = Vec::new();
+    out.extend_from_slice(MAGIC);
+    out.extend_from_slice(&VERSION.to_le_bytes());
+    out.extend_from_slice(&saved_at.to_le_bytes());
+    let count = state.graveyard.tombstones_len() + state.txs.len();
+    out.extend_from_slice(&(count as u32).to_le_bytes());
+
+    // Oldest first, so a reload can restore `order` as-is.
+    for tomb in state.graveyard.iter_oldest_first() {
+        match &tomb.removal {
+            TxRemoval::Vanished => out.push(VANISHED),
+            TxRemoval::Replaced { by } => {
+                out.push(REPLACED);
+                out.extend_from_slice(by.as_slice());
+            }
+        }
+        let removed_at = saved_at.saturating_sub(tomb.removed_at.elapsed().as_secs());
+        out.extend_from_slice(&removed_at.to_le_bytes());
+        encode_tx(&mut out, &tomb.tx, &tomb.entry, tomb.chunk_rate);
+    }
+    for (prefix, record) in state.txs.records() {
+        let chunk_rate = snapshot
+            .chunk_rate_for(prefix)
+            .unwrap_or_else(|| record.entry.fee_rate());
+        out.push(LIVE);
+        out.extend_from_slice(&saved_at.to_le_bytes());
+        encode_tx(&mut out, &record.tx, &record.entry, chunk_rate);
+    }
+    out
+}
+
+fn encode_tx(out: &mut Vec<u8>, tx: &Transaction, entry: &TxEntry, chunk_rate: FeeRate) {
+    out.extend_from_slice(entry.txid.as_slice());
+    out.extend_from_slice(&u64::from(entry.fee).to_le_bytes());
+    out.extend_from_slice(&u64::from(entry.vsize).to_le_bytes());
+    out.extend_from_slice(&entry.weight.to_le_bytes());
+    out.extend_from_slice(&entry.size.to_le_bytes());
+    out.extend_from_slice(&entry.first_seen.to_le_bytes());
+    out.push(entry.rbf as u8);
+    out.extend_from_slice(&f64::from(chunk_rate).to_le_bytes());
+    write_bytes(out, &tx.encode_bytes());
+    for txin in &tx.input {
+        match &txin.prevout {
+            Some(prevout) => {
+                out.push(1);
+                out.extend_from_slice(&u64::from(prevout.value).to_le_bytes());
+                write_bytes(out, prevout.script_pubkey.as_bytes());
+            }
+            None => out.push(0),
+        }
+    }
+}
+
+fn write_bytes(out: &mut Vec<u8>, bytes: &[u8]) {
+    out.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+    out.extend_from_slice(bytes);
+}
+
+fn decode(bytes: &[u8], now: u64) -> Result<TxGraveyard> {
+    let mut r = Reader(bytes);
+    if r.take(MAGIC.len())? != MAGIC {
+        return Err(Error::Parse("not a mempool checkpoint".to_string()));
+    }
+    let version = r.u32()?;
+    if version != VERSION {
+        return Err(Error::Parse(format!(
+            "checkpoint version {version}, expected {VERSION}"
+        )));
+    }
+    let _saved_at = r.u64()?;
+    let count = r.u32()?;
+
+    let load_instant = Instant::now();
+    let mut graveyard = TxGraveyard::default();
+    let mut live = Vec::new();
+    for _ in 0..count {
+        let kind = r.u8()?;
+        let removal = match kind {
+            LIVE | VANISHED => TxRemoval::Vanished,
+            REPLACED => TxRemoval::Replaced { by: r.txid()? },
+            _ => return Err(Error::Parse(format!("unknown record kind {kind}"))),
+        };
+        let removed_at = r.u64()?;
+        let (tx, entry, chunk_rate) = decode_tx(&mut r)?;
+        if kind == LIVE {
+            live.push((tx, entry, chunk_rate));
+            continue;
+        }
+        let age = Duration::from_secs(now.saturating_sub(removed_at));
+        if age >= RETENTION {
+            continue;
+        }
+        let Some(removed_at) = load_instant.checked_sub(age) else {
+            continue;
+        };
+        graveyard.restore(TxTombstone {
+            tx,
+            entry,
+            chunk_rate,
+            removal,
+            removed_at,
+        });
+    }
+    // Still-live txs count as just vanished: they get a full
+    // `RETENTION` to show up in `getrawmempool` again.
+    for (tx, entry, chunk_rate) in live {
+        graveyard.restore(TxTombstone {
+            tx,
+            entry,
+            chunk_rate,
+            removal: TxRemoval::Vanished,
+            removed_at: load_instant,
+        });
+    }
+    Ok(graveyard)
+}
+
+fn decode_tx(r: &mut Reader) -> Result<(Transaction, TxEntry, FeeRate)> {
+    let entry = TxEntry {
+        txid: r.txid()?,
+        fee: Sats::from(r.u64()?),
+        vsize: VSize::from(r.u64()?),
+        weight: Weight::from(r.u64()?),
+        size: r.u64()?,
+        depends: SmallVec::new(),
+        first_seen: Timestamp::new(r.u32()?),
+        rbf: r.u8()? != 0,
+    };
+    let chunk_rate = FeeRate::from(f64::from_le_bytes(r.array()?));
+    let raw = r.bytes()?;
+    let decoded: bitcoin::Transaction = bitcoin::consensus::deserialize(raw)
+        .map_err(|e| Error::Parse(format!("tx {}: {e}", entry.txid)))?;
+    let mut tx = TxAddition::build_tx(&entry, decoded, raw.len(), &TxStore::default());
+    for txin in &mut tx.input {
+        if r.u8()? != 0 {
+            let value = Sats::from(r.u64()?);
+            let script = ScriptBuf::from_bytes(r.bytes()?.to_vec());
+            txin.prevout = Some(TxOut::from((script, value)));
+        }
+    }
+    tx.refresh_sigops();
+    Ok((tx, entry, chunk_rate))
+}
+
+struct Reader<'a>(&'a [u8]);
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(Error::Parse("truncated mempool checkpoint".to_string()));
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().unwrap())
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.array()?))
+    }
+
+    fn u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.array()?))
+    }
+
+    fn txid(&mut self) -> Result<Txid> {
+        Ok(Txid::from(bitcoin::Txid::from_byte_array(self.array()?)))
+    }
+
+    fn bytes(&mut self) -> Result<&'a [u8]> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{fake_entry_info, fake_tx, p2wpkh_script};
+
+    fn tx_and_entry(seed: u8, first_seen: u32) -> (Transaction, TxEntry) {
+        let prev = Some(TxOut::from((p2wpkh_script(seed), Sats::from(5_000u64))));
+        let mut tx = fake_tx(seed, &[prev, None], &[(p2wpkh_script(seed + 1), 4_000)]);
+        tx.fee = Sats::from(1_000u64);
+        let mut info = fake_entry_info(tx.txid, 1_000, 100);
+        info.first_seen = Timestamp::new(first_seen);
+        let entry = TxEntry::new(&info, tx.encode_bytes().len() as u64, true);
+        (tx, entry)
+    }
+
+    #[test]
+    fn roundtrip_moves_live_txs_to_the_graveyard() {
+        let mut state = State::default();
+        let (live_tx, live_entry) = tx_and_entry(1, 1_700_000_000);
+        let (old_tx, old_entry) = tx_and_entry(3, 1_600_000_000);
+        let live = live_entry.txid;
+        let old = old_entry.txid;
+        state.txs.insert(live_tx, live_entry);
+        state.graveyard.bury(
+            old_tx,
+            old_entry,
+            FeeRate::from(7.0),
+            TxRemoval::Replaced { by: live },
+        );
+
+        let bytes = encode(&state, &Snapshot::default(), 2_000_000_000);
+        let graveyard = decode(&bytes, 2_000_000_010).expect("valid checkpoint");
+
+        let order: Vec<Txid> = graveyard
+            .iter_oldest_first()
+            .map(|t| t.entry.txid)
+            .collect();
+        assert_eq!(order, vec![old, live]);
+
+        let old_tomb = graveyard.get(&old).unwrap();
+        assert_eq!(old_tomb.replaced_by(), Some(&live));
+        assert_eq!(f64::from(old_tomb.chunk_rate), 7.0);
+        assert_eq!(old_tomb.entry.first_seen, Timestamp::new(1_600_000_000));
+
+        let live_tomb = graveyard.get_vanished(&live).unwrap();
+        assert_eq!(live_tomb.entry.first_seen, Timestamp::new(1_700_000_000));
+        assert!(live_tomb.entry.rbf);
+        assert_eq!(live_tomb.tx.fee, Sats::from(1_000u64));
+        assert_eq!(
+            live_tomb.tx.input[0].prevout.as_ref().map(|p| p.value),
+            Some(Sats::from(5_000u64))
+        );
+        assert!(live_tomb.tx.input[1].prevout.is_none());
+        assert_eq!(live_tomb.tx.output[0].value, Sats::from(4_000u64));
+    }
+
+    #[test]
+    fn tombstones_past_retention_are_dropped() {
+        let mut state = State::default();
+        let (tx, entry) = tx_and_entry(5, 1_700_000_000);
+        let txid = entry.txid;
+        state
+            .graveyard
+            .bury(tx, entry, FeeRate::from(1.0), TxRemoval::Vanished);
+
+        let saved_at = 2_000_000_000;
+        let bytes = encode(&state, &Snapshot::default(), saved_at);
+        let later = saved_at + RETENTION.as_secs();
+        assert!(decode(&bytes, saved_at).unwrap().get(&txid).is_some());
+        assert!(decode(&bytes, later).unwrap().get(&txid).is_none());
+    }
+
+    #[test]
+    fn truncated_or_foreign_files_are_rejected() {
+        let mut state = State::default();
+        let (tx, entry) = tx_and_entry(7, 1_700_000_000);
+        state.txs.insert(tx, entry);
+        let bytes = encode(&state, &Snapshot::default(), 2_000_000_000);
+
+        assert!(decode(&bytes[..bytes.len() - 1], 2_000_000_000).is_err());
+        assert!(decode(b"nope", 2_000_000_000).is_err());
+    }
+}
@@ -3,7 +3,7 @@
 
 use std::mem;
 
-use brk_types::TxidPrefix;
+use brk_types::{FeeRate, TxidPrefix};
 use rustc_hash::{FxBuildHasher, FxHashMap};
 use smallvec::SmallVec;
 
@@ -64,20 +64,26 @@ impl Snapshot {
 
     /// Walk every multi-tx connected component once and overwrite each
     /// member's `chunk_rate` with the linearized chunk's feerate.
-    /// Visited bitmap ensures each cluster is linearized exactly once.
     fn refresh_chunk_rates(snap_txs: &mut [SnapTx]) {
-        let n = snap_txs.len();
-        let mut visited = vec![false; n];
-        for seed in 0..n {
-            if visited[seed] {
+        Self::relinearize(snap_txs, (0..snap_txs.len()).map(TxIndex::from));
+    }
+
+    /// Re-linearize the components of `seeds` and overwrite their
+    /// members' `chunk_rate`. Singletons fall back to `fee/vsize`.
+    /// Visited bitmap ensures each cluster is linearized exactly once.
+    pub(super) fn relinearize(snap_txs: &mut [SnapTx], seeds: impl IntoIterator<Item = TxIndex>) {
+        let mut visited = vec![false; snap_txs.len()];
+        for seed in seeds {
+            if visited[seed.as_usize()] {
                 continue;
             }
-            let t = &snap_txs[seed];
+            let t = &mut snap_txs[seed.as_usize()];
             if t.parents.is_empty() && t.children.is_empty() {
-                visited[seed] = true;
+                t.chunk_rate = FeeRate::from((t.fee, t.vsize));
+                visited[seed.as_usize()] = true;
                 continue;
             }
-            let component = Cluster::walk(snap_txs, TxIndex::from(seed));
+            let component = Cluster::walk(snap_txs, seed);
             for &m in &component {
                 visited[m.as_usize()] = true;
             }
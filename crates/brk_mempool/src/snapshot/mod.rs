@@ -5,12 +5,14 @@ mod cpfp;
 mod fees;
 mod partition;
 mod rebuilder;
+mod simulate;
 mod snap_tx;
 mod tx_index;
 
 pub use block_stats::BlockStats;
 pub use cluster::Cluster;
 pub use rebuilder::Rebuilder;
+pub use simulate::Candidate;
 pub use snap_tx::SnapTx;
 pub use tx_index::TxIndex;
 
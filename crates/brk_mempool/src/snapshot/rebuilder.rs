@@ -24,7 +24,7 @@ use crate::State;
 
 use super::{Partitioner, Snapshot, TxIndex};
 
-const NUM_BLOCKS: usize = 8;
+pub(super) const NUM_BLOCKS: usize = 8;
 const HISTORY: usize = 10;
 
 #[derive(Default)]
@@ -0,0 +1,274 @@
+//! What-if placement of a tx that isn't in the pool.
+//!
+//! The candidate is spliced into a copy of the snapshot's txs: the
+//! live txs it conflicts with are evicted along with their
+//! descendants, it's linked to its in-pool parents, and every cluster
+//! it touches is re-linearized. The whole pool is then packed by
+//! descending chunk rate like the rebuilder packs blocks 1..N, Core's
+//! `getblocktemplate` having no say over a tx it never saw.
+
+use brk_types::{FeeRate, Sats, TxSimulation, Txid, TxidPrefix, VSize, Weight, find_seed_chunk};
+use rustc_hash::{FxBuildHasher, FxHashSet};
+use smallvec::SmallVec;
+
+use crate::Mempool;
+
+use super::{Cluster, Partitioner, SnapTx, Snapshot, TxIndex, rebuilder::NUM_BLOCKS};
+
+/// A tx to place in the projected blocks.
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    /// `None` for a tx only known by its fee and size.
+    pub txid: Option<Txid>,
+    pub fee: Sats,
+    pub vsize: VSize,
+    /// Unconfirmed parents. Txids not in the pool are ignored.
+    pub parents: Vec<Txid>,
+    /// Live txs spending the same outpoints.
+    pub conflicts: Vec<Txid>,
+}
+
+impl Mempool {
+    /// Projected placement of `candidate` against the latest snapshot.
+    pub fn simulate(&self, candidate: &Candidate) -> TxSimulation {
+        self.snapshot().simulate(candidate)
+    }
+}
+
+impl Snapshot {
+    pub fn simulate(&self, candidate: &Candidate) -> TxSimulation {
+        let mut txs = self.txs.clone();
+
+        let conflicts: Vec<TxIndex> = candidate
+            .conflicts
+            .iter()
+            .filter_map(|txid| self.live_idx(txid))
+            .collect();
+        let (evicted, mut touched) = Self::evict(&mut txs, &conflicts);
+
+        let seed = TxIndex::from(txs.len());
+        let parents: SmallVec<[TxIndex; 2]> = candidate
+            .parents
+            .iter()
+            .filter_map(|txid| self.live_idx(txid))
+            .filter(|idx| !evicted.contains(idx))
+            .collect();
+        for p in &parents {
+            txs[p.as_usize()].children.push(seed);
+        }
+        txs.push(SnapTx {
+            txid: candidate.txid.unwrap_or(Txid::COINBASE),
+            fee: candidate.fee,
+            vsize: candidate.vsize,
+            weight: Weight::from(candidate.vsize),
+            size: u64::from(candidate.vsize),
+            chunk_rate: FeeRate::from((candidate.fee, candidate.vsize)),
+            parents,
+            children: SmallVec::new(),
+        });
+        touched.insert(0, seed);
+        Self::relinearize(&mut txs, touched);
+
+        let (chunk, chunk_fee, chunk_vsize) = Self::seed_chunk(&txs, seed);
+        let blocks = Partitioner::partition(&txs, &evicted, NUM_BLOCKS);
+        let (block, position) = blocks
+            .iter()
+            .enumerate()
+            .find_map(|(b, block)| Some((b, block.iter().position(|&idx| idx == seed)?)))
+            .unwrap_or_default();
+
+        // Walk the next block without the candidate's chunk and stop
+        // where the chunk no longer fits: that tx is the one to outbid.
+        let mut used = VSize::default();
+        let next_block_fee_rate = blocks
+            .first()
+            .into_iter()
+            .flatten()
+            .filter(|idx| !chunk.contains(idx))
+            .map(|idx| &txs[idx.as_usize()])
+            .find(|t| {
+                used += t.vsize;
+                used + chunk_vsize > VSize::MAX_BLOCK
+            })
+            .map_or(self.fees.minimum_fee, |t| t.chunk_rate);
+        let next_block_fee_delta = if block == 0 {
+            Sats::ZERO
+        } else {
+            let needed = (f64::from(next_block_fee_rate) * u64::from(chunk_vsize) as f64).ceil();
+            Sats::from((needed as u64).saturating_sub(u64::from(chunk_fee)))
+        };
+
+        let mut replaces: Vec<Txid> = evicted
+            .iter()
+            .map(|idx| self.txs[idx.as_usize()].txid)
+            .collect();
+        replaces.sort_by_key(|txid| **txid);
+
+        TxSimulation {
+            fee: candidate.fee,
+            vsize: candidate.vsize,
+            chunk_rate: txs[seed.as_usize()].chunk_rate,
+            block: block as u32,
+            position: position as u32,
+            next_block_fee_rate,
+            next_block_fee_delta,
+            replaces,
+        }
+    }
+
+    /// Snapshot index of a live tx, with the full txid checked to rule
+    /// out prefix collisions.
+    fn live_idx(&self, txid: &Txid) -> Option<TxIndex> {
+        let idx = self.idx_of(&TxidPrefix::from(txid))?;
+        (self.txs[idx.as_usize()].txid == *txid).then_some(idx)
+    }
+
+    /// Evicts `conflicts` and their descendants by cutting them off
+    /// their remaining parents. Returns the evicted set and the parents
+    /// left behind, whose clusters need re-linearizing.
+    fn evict(txs: &mut [SnapTx], conflicts: &[TxIndex]) -> (FxHashSet<TxIndex>, Vec<TxIndex>) {
+        let mut evicted: FxHashSet<TxIndex> = FxHashSet::default();
+        let mut stack = conflicts.to_vec();
+        while let Some(idx) = stack.pop() {
+            if evicted.insert(idx) {
+                stack.extend(txs[idx.as_usize()].children.iter().copied());
+            }
+        }
+
+        let mut touched = Vec::new();
+        for &idx in &evicted {
+            for p in txs[idx.as_usize()].parents.clone() {
+                if evicted.contains(&p) {
+                    continue;
+                }
+                txs[p.as_usize()].children.retain(|c| !evicted.contains(c));
+                touched.push(p);
+            }
+        }
+        (evicted, touched)
+    }
+
+    /// Members of the chunk holding `seed`, with their total fee and
+    /// vsize.
+    fn seed_chunk(txs: &[SnapTx], seed: TxIndex) -> (FxHashSet<TxIndex>, Sats, VSize) {
+        let component = Cluster::walk(txs, seed);
+        let members: FxHashSet<TxIndex> = if component.len() <= 1 {
+            component.into_iter().collect()
+        } else {
+            let (members, chunks) = Cluster::linearize(txs, &component);
+            let local_of = Cluster::local_index(&members);
+            let (i, _) = find_seed_chunk(&chunks, local_of[&seed], FeeRate::default());
+            let mut chunk: FxHashSet<TxIndex> =
+                FxHashSet::with_capacity_and_hasher(members.len(), FxBuildHasher);
+            chunk.extend(
+                chunks[i as usize]
+                    .txs
+                    .iter()
+                    .map(|&local| members[u32::from(local) as usize]),
+            );
+            chunk
+        };
+        let fee = members.iter().map(|idx| txs[idx.as_usize()].fee).sum();
+        let vsize = members.iter().map(|idx| txs[idx.as_usize()].vsize).sum();
+        (members, fee, vsize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash;
+
+    use super::*;
+
+    fn snap_tx(seed: u8, fee: u64, vsize: u64) -> SnapTx {
+        let mut bytes = [0u8; 32];
+        bytes[0] = seed;
+        SnapTx {
+            txid: Txid::from(bitcoin::Txid::from_byte_array(bytes)),
+            fee: Sats::from(fee),
+            vsize: VSize::from(vsize),
+            weight: Weight::from(vsize * 4),
+            size: vsize,
+            chunk_rate: FeeRate::from((Sats::from(fee), VSize::from(vsize))),
+            parents: SmallVec::new(),
+            children: SmallVec::new(),
+        }
+    }
+
+    fn snapshot(mut txs: Vec<SnapTx>, links: &[(usize, usize)]) -> Snapshot {
+        for &(parent, child) in links {
+            txs[child].parents.push(TxIndex::from(parent));
+            txs[parent].children.push(TxIndex::from(child));
+        }
+        let len = txs.len();
+        Snapshot::relinearize(&mut txs, (0..len).map(TxIndex::from));
+        let prefix_to_idx = txs
+            .iter()
+            .enumerate()
+            .map(|(i, t)| (TxidPrefix::from(&t.txid), TxIndex::from(i)))
+            .collect();
+        Snapshot::build(txs, Vec::new(), prefix_to_idx, FeeRate::new(1.0))
+    }
+
+    fn candidate(fee: u64, vsize: u64) -> Candidate {
+        Candidate {
+            txid: None,
+            fee: Sats::from(fee),
+            vsize: VSize::from(vsize),
+            parents: Vec::new(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Two blocks' worth of 10 sat/vB txs.
+    fn full_pool() -> Vec<SnapTx> {
+        (1..=20).map(|i| snap_tx(i, 1_000_000, 100_000)).collect()
+    }
+
+    #[test]
+    fn low_fee_tx_queues_behind_the_pool() {
+        let snap = snapshot(full_pool(), &[]);
+        let sim = snap.simulate(&candidate(200, 100));
+
+        assert_eq!(sim.block, 2);
+        assert_eq!(sim.chunk_rate, FeeRate::new(2.0));
+        assert_eq!(sim.next_block_fee_rate, FeeRate::new(10.0));
+        assert_eq!(sim.next_block_fee_delta, Sats::from(800u64));
+    }
+
+    #[test]
+    fn child_lifts_its_parent_into_the_next_block() {
+        let mut txs = full_pool();
+        txs.push(snap_tx(21, 100, 100));
+        let snap = snapshot(txs, &[]);
+        let parent = snap.txs[20].txid;
+
+        let mut child = candidate(5_000, 100);
+        child.parents.push(parent);
+        let sim = snap.simulate(&child);
+
+        assert_eq!(sim.block, 0);
+        assert_eq!(sim.chunk_rate, FeeRate::new(25.5));
+        assert_eq!(sim.next_block_fee_delta, Sats::ZERO);
+    }
+
+    #[test]
+    fn replacement_evicts_conflicts_and_descendants() {
+        let txs = vec![
+            snap_tx(1, 1_000, 100),
+            snap_tx(2, 1_000, 100),
+            snap_tx(3, 500, 100),
+        ];
+        let snap = snapshot(txs, &[(0, 1)]);
+
+        let mut replacement = candidate(3_000, 100);
+        replacement.conflicts.push(snap.txs[0].txid);
+        let sim = snap.simulate(&replacement);
+
+        let mut expected = vec![snap.txs[0].txid, snap.txs[1].txid];
+        expected.sort_by_key(|txid| **txid);
+        assert_eq!(sim.replaces, expected);
+        assert_eq!((sim.block, sim.position), (0, 0));
+        assert_eq!(sim.next_block_fee_rate, FeeRate::new(1.0));
+    }
+}
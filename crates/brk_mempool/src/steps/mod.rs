@@ -7,5 +7,5 @@ mod prevouts;
 
 pub use applier::Applier;
 pub use fetcher::{Fetched, Fetcher};
-pub use preparer::{Preparer, TxRemoval};
+pub use preparer::{Preparer, TxAddition, TxRemoval};
 pub use prevouts::Prevouts;
@@ -40,13 +40,15 @@ impl TxAddition {
     ) -> Self {
         let total_size = tx.total_size();
         let rbf = tx.input.iter().any(|i| i.sequence.is_rbf());
-        let built = Self::build_tx(info, tx, total_size, mempool_txs);
         let entry = TxEntry::new(info, total_size as u64, rbf);
+        let built = Self::build_tx(&entry, tx, total_size, mempool_txs);
         Self::Fresh { tx: built, entry }
     }
 
-    fn build_tx(
-        info: &MempoolEntryInfo,
+    /// Also rebuilds tx bodies reloaded from a checkpoint, where
+    /// `mempool_txs` is empty and every prevout lands as `None`.
+    pub(crate) fn build_tx(
+        entry: &TxEntry,
         tx: bitcoin::Transaction,
         total_size: usize,
         mempool_txs: &TxStore,
@@ -58,13 +60,13 @@ impl TxAddition {
             .collect();
         let mut built = Transaction {
             index: None,
-            txid: info.txid,
+            txid: entry.txid,
             version: tx.version.into(),
             total_sigop_cost: SigOps::ZERO,
-            weight: info.weight,
+            weight: entry.weight,
             lock_time: tx.lock_time.into(),
             total_size,
-            fee: info.fee,
+            fee: entry.fee,
             input,
             output: tx.output.into_iter().map(TxOut::from).collect(),
             status: TxStatus::UNCONFIRMED,
@@ -3,7 +3,7 @@ use std::{
     hash::{Hash, Hasher},
 };
 
-use brk_types::{AddrBytes, AddrMempoolStats, Transaction, TxOut, Txid};
+use brk_types::{AddrBytes, AddrMempoolStats, ScriptHash, Transaction, TxOut, Txid};
 use rustc_hash::{FxHashMap, FxHasher};
 
 use crate::cycle::AddrTransitions;
@@ -13,15 +13,25 @@ mod addr_entry;
 pub use addr_entry::AddrEntry;
 
 #[derive(Default)]
-pub struct AddrTracker(FxHashMap<AddrBytes, AddrEntry>);
+pub struct AddrTracker {
+    entries: FxHashMap<AddrBytes, AddrEntry>,
+    /// Reverse index for Electrum-style lookups, kept in step with
+    /// `entries`.
+    script_hashes: FxHashMap<ScriptHash, AddrBytes>,
+}
 
 impl AddrTracker {
     pub fn get(&self, addr: &AddrBytes) -> Option<&AddrEntry> {
-        self.0.get(addr)
+        self.entries.get(addr)
+    }
+
+    /// Live address whose scriptPubKey hashes to `script_hash`.
+    pub fn by_script_hash(&self, script_hash: &ScriptHash) -> Option<&AddrBytes> {
+        self.script_hashes.get(script_hash)
     }
 
     pub fn len(&self) -> usize {
-        self.0.len()
+        self.entries.len()
     }
 
     pub fn add_tx(&mut self, transitions: &mut AddrTransitions, tx: &Transaction) {
@@ -56,7 +66,7 @@ impl AddrTracker {
     /// has no live mempool activity. Stable while the address is
     /// unchanged. Cheaper to recompute than to track invalidation.
     pub fn stats_hash(&self, addr: &AddrBytes) -> Option<u64> {
-        let entry = self.0.get(addr)?;
+        let entry = self.entries.get(addr)?;
         let mut hasher = FxHasher::default();
         entry.stats.hash(&mut hasher);
         Some(hasher.finish())
@@ -67,24 +77,14 @@ impl AddrTracker {
     /// previously `None` has been filled, and by `add_tx` for each
     /// resolved input. Inputs whose prevout doesn't resolve to an addr
     /// are no-ops.
-    pub fn add_input(
-        &mut self,
-        transitions: &mut AddrTransitions,
-        txid: &Txid,
-        prevout: &TxOut,
-    ) {
+    pub fn add_input(&mut self, transitions: &mut AddrTransitions, txid: &Txid, prevout: &TxOut) {
         let Some(bytes) = prevout.addr_bytes() else {
             return;
         };
         self.apply_add(transitions, bytes, txid, |stats| stats.sending(prevout));
     }
 
-    fn remove_input(
-        &mut self,
-        transitions: &mut AddrTransitions,
-        txid: &Txid,
-        prevout: &TxOut,
-    ) {
+    fn remove_input(&mut self, transitions: &mut AddrTransitions, txid: &Txid, prevout: &TxOut) {
         let Some(bytes) = prevout.addr_bytes() else {
             return;
         };
@@ -98,7 +98,7 @@ impl AddrTracker {
         txid: &Txid,
         update_stats: impl FnOnce(&mut AddrMempoolStats),
     ) {
-        match self.0.entry(bytes) {
+        match self.entries.entry(bytes) {
             MapEntry::Occupied(mut occupied) => {
                 let entry = occupied.get_mut();
                 entry.txids.insert(*txid);
@@ -111,6 +111,8 @@ impl AddrTracker {
                 entry.txids.insert(*txid);
                 update_stats(&mut entry.stats);
                 entry.stats.update_tx_count(entry.txids.len() as u32);
+                self.script_hashes
+                    .insert(ScriptHash::from(&key), key.clone());
                 transitions.record_enter(key);
             }
         }
@@ -123,7 +125,7 @@ impl AddrTracker {
         txid: &Txid,
         update_stats: impl FnOnce(&mut AddrMempoolStats),
     ) {
-        let MapEntry::Occupied(mut occupied) = self.0.entry(bytes) else {
+        let MapEntry::Occupied(mut occupied) = self.entries.entry(bytes) else {
             return;
         };
         let entry = occupied.get_mut();
@@ -132,6 +134,7 @@ impl AddrTracker {
         let len = entry.txids.len();
         if len == 0 {
             let (bytes, _) = occupied.remove_entry();
+            self.script_hashes.remove(&ScriptHash::from(&bytes));
             transitions.record_leave(bytes);
         } else {
             entry.stats.update_tx_count(len as u32);
@@ -179,7 +182,10 @@ mod tests {
         let prev_script = p2wpkh_script(3);
         let tx = fake_tx(
             2,
-            &[Some(TxOut::from((prev_script.clone(), Sats::from(4_000u64))))],
+            &[Some(TxOut::from((
+                prev_script.clone(),
+                Sats::from(4_000u64),
+            )))],
             &[(out_script.clone(), 3_500)],
         );
         let recv = addr_of(&out_script);
@@ -190,6 +196,7 @@ mod tests {
         assert_eq!(tracker.len(), 0);
         assert!(tracker.get(&recv).is_none());
         assert!(tracker.get(&spend).is_none());
+        assert!(tracker.by_script_hash(&ScriptHash::from(&recv)).is_none());
 
         // add+remove in the same cycle: enter/leave cancel out.
         let (enters, leaves) = transitions.into_vecs();
@@ -218,6 +225,20 @@ mod tests {
         assert_eq!(enters.len(), 1);
     }
 
+    #[test]
+    fn by_script_hash_finds_live_addr() {
+        let mut tracker = AddrTracker::default();
+        let mut transitions = AddrTransitions::default();
+        let script = p2wpkh_script(8);
+        let tx = fake_tx(8, &[], &[(script.clone(), 1_000)]);
+        tracker.add_tx(&mut transitions, &tx);
+
+        let found = tracker
+            .by_script_hash(&ScriptHash::from(&script))
+            .expect("addr indexed by script hash");
+        assert_eq!(found, &addr_of(&script));
+    }
+
     #[test]
     fn stats_hash_is_none_for_untracked_addr() {
         let tracker = AddrTracker::default();
@@ -251,7 +272,9 @@ mod tests {
 
         let tx_b = fake_tx(7, &[], &[(script, 2_222)]);
         tracker.add_tx(&mut transitions, &tx_b);
-        let after = tracker.stats_hash(&bytes).expect("tracked after second add");
+        let after = tracker
+            .stats_hash(&bytes)
+            .expect("tracked after second add");
         assert_ne!(before, after, "second funding tx must shift the hash");
     }
 }
@@ -12,5 +12,5 @@ mod tx_store;
 pub use addr_tracker::AddrTracker;
 pub use live_histograms::LiveHistograms;
 pub use outpoint_spends::OutpointSpends;
-pub use tx_graveyard::{TxGraveyard, TxTombstone};
+pub use tx_graveyard::{RETENTION, TxGraveyard, TxTombstone};
 pub use tx_store::TxStore;
@@ -12,7 +12,7 @@ pub use tombstone::TxTombstone;
 
 use crate::{TxRemoval, state::TxEntry};
 
-const RETENTION: Duration = Duration::from_hours(1);
+pub const RETENTION: Duration = Duration::from_hours(1);
 
 /// Recently-dropped txs retained for reappearance detection (Puller can revive
 /// them without RPC) and post-mine analytics (RBF/replacement chains, etc.).
@@ -107,6 +107,24 @@ impl TxGraveyard {
         self.order.push_back((removed_at, txid));
     }
 
+    /// Re-insert a tombstone reloaded from a checkpoint, keeping its
+    /// `removed_at`. Callers restore oldest first so `order` stays
+    /// sorted for `evict_old`.
+    pub fn restore(&mut self, tomb: TxTombstone) {
+        let txid = tomb.entry.txid;
+        self.order.push_back((tomb.removed_at, txid));
+        self.tombstones.insert(txid, tomb);
+    }
+
+    /// Every tombstone in bury order, oldest first. Stale `order`
+    /// entries are skipped the same way `evict_old` does.
+    pub fn iter_oldest_first(&self) -> impl Iterator<Item = &TxTombstone> {
+        self.order.iter().filter_map(|(t, txid)| {
+            let ts = self.tombstones.get(txid)?;
+            (ts.removed_at == *t).then_some(ts)
+        })
+    }
+
     /// Remove and return the tombstone, e.g. when the tx comes back to life.
     pub fn exhume(&mut self, txid: &Txid) -> Option<TxTombstone> {
         self.tombstones.remove(txid)
@@ -280,6 +298,37 @@ mod tests {
         assert!(g.get(&txid_b).is_some(), "fresh tombstone retained");
     }
 
+    #[test]
+    fn restore_keeps_bury_order_and_age() {
+        let mut g = TxGraveyard::default();
+        let (tx_a, entry_a, rate) = tomb_inputs(15);
+        let (tx_b, entry_b, _) = tomb_inputs(16);
+        let a = entry_a.txid;
+        let b = entry_b.txid;
+        let aged = Instant::now() - (RETENTION + Duration::from_secs(1));
+        g.restore(TxTombstone {
+            tx: tx_a,
+            entry: entry_a,
+            chunk_rate: rate,
+            removal: TxRemoval::Replaced { by: b },
+            removed_at: aged,
+        });
+        g.restore(TxTombstone {
+            tx: tx_b,
+            entry: entry_b,
+            chunk_rate: rate,
+            removal: TxRemoval::Vanished,
+            removed_at: Instant::now(),
+        });
+
+        let order: Vec<Txid> = g.iter_oldest_first().map(|t| t.entry.txid).collect();
+        assert_eq!(order, vec![a, b]);
+
+        g.evict_old();
+        assert!(g.get(&a).is_none(), "restored age still counts");
+        assert!(g.get(&b).is_some());
+    }
+
     #[test]
     fn re_bury_mid_retention_resets_age() {
         let mut g = TxGraveyard::default();
@@ -0,0 +1,100 @@
+//! Per-cycle event emitter. Owns the cycle-over-cycle memory used to
+//! turn the always-fresh `Cycle` into change-only events for `tip`,
+//! `block`, and `fees`.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+use brk_types::{Addr, AddrBytes, BlockHash, NextBlockHash, RecommendedFees, Txid};
+use rustc_hash::FxHashSet;
+use tracing::debug;
+
+use crate::{Cycle, Event};
+
+/// Cycle-over-cycle memory for change-event detection. `None` on the
+/// first cycle, so the very first `Tip` / `Block` / `Fees` always
+/// fires - downstream consumers get a baseline without a special-case
+/// "current state" RPC.
+///
+/// `prev_block0` is `None` on cold start so the first `block` event
+/// reports the entire template as `added` (one big event, then small
+/// deltas).
+#[derive(Default)]
+pub struct Emitter {
+    prev_tip_hash: Option<BlockHash>,
+    prev_next_block_hash: Option<NextBlockHash>,
+    prev_block0: Option<FxHashSet<Txid>>,
+    prev_fees: Option<RecommendedFees>,
+}
+
+impl Emitter {
+    /// Every event for one cycle, in stream order: per-tx, per-address,
+    /// state changes, then the `cycle` heartbeat last.
+    pub fn events(&mut self, cycle: &Cycle) -> Vec<Event> {
+        let t = now_secs();
+        let mut events = Vec::with_capacity(
+            cycle.added.len()
+                + cycle.removed.len()
+                + cycle.addr_enters.len()
+                + cycle.addr_leaves.len()
+                + 4,
+        );
+        events.extend(cycle.added.iter().map(|tx| Event::enter(t, tx)));
+        events.extend(cycle.removed.iter().map(|tx| Event::leave(t, tx)));
+        events.extend(
+            cycle
+                .addr_enters
+                .iter()
+                .filter_map(|bytes| Self::addr_event(t, bytes, Event::addr_enter)),
+        );
+        events.extend(
+            cycle
+                .addr_leaves
+                .iter()
+                .filter_map(|bytes| Self::addr_event(t, bytes, Event::addr_leave)),
+        );
+        if self.prev_tip_hash != Some(cycle.tip_hash) {
+            self.prev_tip_hash = Some(cycle.tip_hash);
+            events.push(Event::tip(t, cycle.tip_hash, cycle.tip_height));
+        }
+        let next_block_hash = cycle.snapshot.next_block_hash;
+        if self.prev_next_block_hash != Some(next_block_hash) {
+            self.prev_next_block_hash = Some(next_block_hash);
+            let current: FxHashSet<Txid> = cycle.snapshot.block0_txids().collect();
+            let (added, removed) = match &self.prev_block0 {
+                Some(prev) => (
+                    current.difference(prev).copied().collect(),
+                    prev.difference(&current).copied().collect(),
+                ),
+                None => (current.iter().copied().collect(), Vec::new()),
+            };
+            events.push(Event::block(t, next_block_hash, added, removed));
+            self.prev_block0 = Some(current);
+        }
+        if self.prev_fees.as_ref() != Some(&cycle.snapshot.fees) {
+            self.prev_fees = Some(cycle.snapshot.fees.clone());
+            events.push(Event::fees(t, &cycle.snapshot.fees));
+        }
+        events.push(Event::summary(t, cycle));
+        events
+    }
+
+    /// Render an `AddrBytes` and wrap it via `make_event`. Unrenderable
+    /// bytes (e.g. exotic non-standard scripts) are skipped so the
+    /// stream stays clean for downstream `jq`.
+    fn addr_event(t: f64, bytes: &AddrBytes, make_event: fn(f64, Addr) -> Event) -> Option<Event> {
+        match Addr::try_from(bytes) {
+            Ok(addr) => Some(make_event(t, addr)),
+            Err(e) => {
+                debug!("skipping addr event: {e}");
+                None
+            }
+        }
+    }
+}
+
+fn now_secs() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs_f64()
+}
@@ -1,14 +1,15 @@
-//! NDJSON event schema. One [`Event`] per line; consumers pipe to
-//! `jq` / `grep` to filter. Per-event fields are flat (no nested
-//! objects) so `jq -c 'select(...)'` works without `..` walks.
+//! Event schema. One JSON object per event; per-event fields are flat
+//! (no nested objects) so `jq -c 'select(...)'` works without `..`
+//! walks.
 
-use brk_mempool::{Cycle, TxAdded, TxRemoval, TxRemoved};
 use brk_types::{
     Addr, BlockHash, FeeRate, Height, NextBlockHash, RecommendedFees, Sats, Timestamp, Txid, VSize,
 };
 use serde::Serialize;
 
-#[derive(Serialize)]
+use crate::{Cycle, EventKind, TxAdded, TxRemoval, TxRemoved};
+
+#[derive(Debug, Clone, Serialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum Event {
     /// A tx entered the pool this cycle (either brand new or revived
@@ -80,7 +81,7 @@ pub enum Event {
     },
 }
 
-#[derive(Serialize)]
+#[derive(Debug, Clone, Copy, Serialize)]
 #[serde(tag = "reason", rename_all = "snake_case")]
 pub enum LeaveReason {
     Replaced { by: Txid },
@@ -88,6 +89,19 @@ pub enum LeaveReason {
 }
 
 impl Event {
+    pub fn kind(&self) -> EventKind {
+        match self {
+            Self::Enter { .. } => EventKind::Enter,
+            Self::Leave { .. } => EventKind::Leave,
+            Self::AddrEnter { .. } => EventKind::AddrEnter,
+            Self::AddrLeave { .. } => EventKind::AddrLeave,
+            Self::Tip { .. } => EventKind::Tip,
+            Self::Block { .. } => EventKind::Block,
+            Self::Fees { .. } => EventKind::Fees,
+            Self::Cycle { .. } => EventKind::Cycle,
+        }
+    }
+
     pub fn enter(t: f64, tx: &TxAdded) -> Self {
         Self::Enter {
             t,
@@ -121,7 +135,12 @@ impl Event {
     }
 
     pub fn block(t: f64, hash: NextBlockHash, added: Vec<Txid>, removed: Vec<Txid>) -> Self {
-        Self::Block { t, hash, added, removed }
+        Self::Block {
+            t,
+            hash,
+            added,
+            removed,
+        }
     }
 
     pub fn fees(t: f64, fees: &RecommendedFees) -> Self {
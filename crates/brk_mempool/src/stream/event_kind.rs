@@ -0,0 +1,61 @@
+use std::{fmt, str::FromStr};
+
+use brk_error::Error;
+use serde::{Deserialize, Serialize};
+
+/// Discriminant of an [`crate::Event`], same spelling as its `kind` tag.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Enter,
+    Leave,
+    AddrEnter,
+    AddrLeave,
+    Tip,
+    Block,
+    Fees,
+    Cycle,
+}
+
+impl EventKind {
+    pub const ALL: [Self; 8] = [
+        Self::Enter,
+        Self::Leave,
+        Self::AddrEnter,
+        Self::AddrLeave,
+        Self::Tip,
+        Self::Block,
+        Self::Fees,
+        Self::Cycle,
+    ];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Enter => "enter",
+            Self::Leave => "leave",
+            Self::AddrEnter => "addr_enter",
+            Self::AddrLeave => "addr_leave",
+            Self::Tip => "tip",
+            Self::Block => "block",
+            Self::Fees => "fees",
+            Self::Cycle => "cycle",
+        }
+    }
+}
+
+impl fmt::Display for EventKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for EventKind {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::ALL
+            .into_iter()
+            .find(|kind| kind.as_str() == s)
+            .ok_or_else(|| Error::Parse(format!("unknown event kind '{s}'")))
+    }
+}
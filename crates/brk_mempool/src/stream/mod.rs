@@ -0,0 +1,11 @@
+//! Change events derived from consecutive [`crate::Cycle`]s. Shared by
+//! `mmpl`'s NDJSON output and the server's `/api/stream`, and broadcast
+//! by the driver to every [`crate::Mempool::subscribe`] receiver.
+
+mod emitter;
+mod event;
+mod event_kind;
+
+pub use emitter::Emitter;
+pub use event::{Event, LeaveReason};
+pub use event_kind::EventKind;
@@ -0,0 +1,310 @@
+use std::ops::Range;
+
+use brk_error::{Error, OptionData, Result};
+use brk_types::{
+    Addr, AddrBalance, AddrBalanceHistory, AddrIndexTxIndex, Cents, Dollars, FundedAddrData,
+    Height, Index, OutputType, Sats, TxInIndex, TxIndex, TypeIndex, Unit,
+};
+use vecdb::{ReadableVec, VecIndex};
+
+use crate::Query;
+
+/// Most txs of an address replayed by a balance request.
+const MAX_ADDR_TXS: usize = 100_000;
+
+/// An output funding the address, or its spend.
+struct Flow {
+    height: Height,
+    value: Sats,
+    /// Price when the output was created, what the spend takes out of the
+    /// realized cap
+    price: Cents,
+    spent: bool,
+}
+
+impl Query {
+    /// Confirmed balance of `addr` at the end of block `height`, the tip if
+    /// `None`.
+    pub fn addr_balance(&self, addr: Addr, height: Option<Height>) -> Result<AddrBalance> {
+        let tip = self.height();
+        let height = height.unwrap_or(tip);
+        if height > tip {
+            return Err(Error::OutOfRange("Block height out of range".into()));
+        }
+
+        let (output_type, type_index) = self.resolve_addr(&addr)?;
+        let mut data = FundedAddrData::default();
+        for flow in self
+            .addr_flows(output_type, type_index)?
+            .iter()
+            .take_while(|flow| flow.height <= height)
+        {
+            apply(&mut data, flow)?;
+        }
+
+        Ok(AddrBalance {
+            height,
+            balance: data.balance(),
+            utxo_count: data.utxo_count(),
+            realized_price: data.realized_price().to_dollars(),
+        })
+    }
+
+    /// Confirmed balance of `addr` at the end of each `index` period, from
+    /// `from` (the one of its first funding if `None`) to `to` (the tip's
+    /// if `None`), both inclusive. `index` is `height` or date-based.
+    /// Errors past `max_weight`, like series requests.
+    pub fn addr_balance_history(
+        &self,
+        addr: Addr,
+        index: Index,
+        from: Option<usize>,
+        to: Option<usize>,
+        max_weight: usize,
+    ) -> Result<AddrBalanceHistory> {
+        if index != Index::Height && !index.is_date_based() {
+            return Err(Error::UnsupportedType(format!("index {}", index.name())));
+        }
+
+        let (output_type, type_index) = self.resolve_addr(&addr)?;
+        let flows = self.addr_flows(output_type, type_index)?;
+
+        let monotonic = &self.computer().indexes.timestamp.monotonic;
+        let period_of = |height: Height| -> Result<usize> {
+            if index == Index::Height {
+                return Ok(height.to_usize());
+            }
+            let timestamp = monotonic.collect_one(height).data()?;
+            index.timestamp_to_index(timestamp).data()
+        };
+
+        let mut history = AddrBalanceHistory {
+            index,
+            start: 0,
+            end: 0,
+            balance: vec![],
+            realized_price: vec![],
+        };
+        let Some(first) = flows.first() else {
+            return Ok(history);
+        };
+        let periods = history_periods(
+            period_of(first.height)?,
+            period_of(self.height())? + 1,
+            from,
+            to,
+            max_weight,
+        )?;
+        history.start = periods.start;
+        history.end = periods.end;
+        (history.balance, history.realized_price) = replay(&flows, periods, period_of)?;
+
+        Ok(history)
+    }
+
+    /// Every output funding the address and every spend of one, by height,
+    /// fundings first within a block. `TooManyTxs` past `MAX_ADDR_TXS`.
+    fn addr_flows(&self, output_type: OutputType, type_index: TypeIndex) -> Result<Vec<Flow>> {
+        let indexer = self.indexer();
+        let computer = self.computer();
+        let vecs = &indexer.vecs;
+        let safe = self.safe_lengths();
+
+        let store = indexer
+            .stores
+            .addr_type_to_addr_index_and_tx_index
+            .get(output_type)
+            .data()?;
+
+        let first_txout_index_reader = vecs.transactions.first_txout_index.reader();
+        let output_type_reader = vecs.outputs.output_type.reader();
+        let type_index_reader = vecs.outputs.type_index.reader();
+        let value_reader = vecs.outputs.value.reader();
+        let mut spent_txin = computer.outputs.spent.txin_index.cursor();
+        let mut spending_tx = vecs.inputs.tx_index.cursor();
+        let prices = &computer.price.spot.cents.height;
+
+        let tx_indexes: Vec<TxIndex> = store
+            .prefix(type_index)
+            .map(|(key, _): (AddrIndexTxIndex, Unit)| key.tx_index())
+            .filter(|tx_index| *tx_index < safe.tx_index)
+            .take(MAX_ADDR_TXS + 1)
+            .collect();
+        if tx_indexes.len() > MAX_ADDR_TXS {
+            return Err(Error::TooManyTxs);
+        }
+
+        let mut flows = vec![];
+        for tx_index in tx_indexes {
+            let start = first_txout_index_reader.get(tx_index.to_usize());
+            let next = tx_index.to_usize() + 1;
+            let end = if next < safe.tx_index.to_usize() {
+                first_txout_index_reader.get(next)
+            } else {
+                safe.txout_index
+            };
+
+            let mut height_and_price = None;
+            for txout_index in usize::from(start)..usize::from(end) {
+                if output_type_reader.get(txout_index) != output_type
+                    || type_index_reader.get(txout_index) != type_index
+                {
+                    continue;
+                }
+
+                let (height, price) = match height_and_price {
+                    Some(hp) => hp,
+                    None => {
+                        let height = self.confirmed_status_height(tx_index)?;
+                        let price = prices.collect_one(height).data()?;
+                        *height_and_price.insert((height, price))
+                    }
+                };
+                let value = value_reader.get(txout_index);
+                flows.push(Flow {
+                    height,
+                    value,
+                    price,
+                    spent: false,
+                });
+
+                let txin_index = spent_txin.get(txout_index).data()?;
+                if txin_index == TxInIndex::UNSPENT || txin_index >= safe.txin_index {
+                    continue;
+                }
+                let spending_tx_index = spending_tx.get(usize::from(txin_index)).data()?;
+                flows.push(Flow {
+                    height: self.confirmed_status_height(spending_tx_index)?,
+                    value,
+                    price,
+                    spent: true,
+                });
+            }
+        }
+
+        flows.sort_by_key(|flow| (flow.height, flow.spent));
+        Ok(flows)
+    }
+}
+
+fn apply(data: &mut FundedAddrData, flow: &Flow) -> Result<()> {
+    if flow.spent {
+        data.send(flow.value, flow.price)
+    } else {
+        data.receive(flow.value, flow.price);
+        Ok(())
+    }
+}
+
+/// Periods a history covers: the address's `first..end`, narrowed by the
+/// inclusive `from` and `to`. `WeightExceeded` when the two values per
+/// period add up past `max_weight`.
+fn history_periods(
+    first: usize,
+    end: usize,
+    from: Option<usize>,
+    to: Option<usize>,
+    max_weight: usize,
+) -> Result<Range<usize>> {
+    let start = from.map_or(first, |from| from.max(first));
+    let end = to.map_or(end, |to| to.saturating_add(1).min(end)).max(start);
+    let weight = (end - start) * (size_of::<Sats>() + size_of::<Dollars>());
+    if weight > max_weight {
+        return Err(Error::WeightExceeded {
+            requested: weight,
+            max: max_weight,
+        });
+    }
+    Ok(start..end)
+}
+
+/// Balance and realized price at the end of each of `periods`, replaying
+/// the sorted `flows`.
+fn replay(
+    flows: &[Flow],
+    periods: Range<usize>,
+    period_of: impl Fn(Height) -> Result<usize>,
+) -> Result<(Vec<Sats>, Vec<Dollars>)> {
+    let mut balance = Vec::with_capacity(periods.len());
+    let mut realized_price = Vec::with_capacity(periods.len());
+    let mut data = FundedAddrData::default();
+    let mut flows = flows.iter().peekable();
+    for period in periods {
+        while let Some(flow) = flows.peek() {
+            if period_of(flow.height)? > period {
+                break;
+            }
+            apply(&mut data, flow)?;
+            flows.next();
+        }
+        balance.push(data.balance());
+        realized_price.push(data.realized_price().to_dollars());
+    }
+    Ok((balance, realized_price))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flow(height: u32, sats: u64, dollars: u64, spent: bool) -> Flow {
+        Flow {
+            height: Height::new(height),
+            value: Sats::from(sats),
+            price: Cents::from(dollars * 100),
+            spent,
+        }
+    }
+
+    fn by_height(height: Height) -> Result<usize> {
+        Ok(height.to_usize())
+    }
+
+    #[test]
+    fn periods_default_to_first_funding_through_tip() {
+        assert_eq!(history_periods(10, 20, None, None, usize::MAX).unwrap(), 10..20);
+    }
+
+    #[test]
+    fn periods_are_narrowed_by_from_and_to() {
+        assert_eq!(history_periods(10, 20, Some(12), Some(15), usize::MAX).unwrap(), 12..16);
+        // Bounds outside the address's life are clamped to it.
+        assert_eq!(history_periods(10, 20, Some(0), Some(99), usize::MAX).unwrap(), 10..20);
+        // An inverted range is empty.
+        assert_eq!(history_periods(10, 20, Some(15), Some(12), usize::MAX).unwrap(), 15..15);
+    }
+
+    #[test]
+    fn periods_past_max_weight_are_rejected() {
+        assert!(history_periods(0, 100, None, None, 100 * 16).is_ok());
+        assert!(matches!(
+            history_periods(0, 100, None, None, 100 * 16 - 1),
+            Err(Error::WeightExceeded { requested: 1600, .. })
+        ));
+        assert!(history_periods(0, 100, Some(90), None, 10 * 16).is_ok());
+    }
+
+    #[test]
+    fn replay_tracks_balance_and_realized_price() {
+        let flows = [
+            flow(10, 1_000, 100, false),
+            flow(12, 3_000, 200, false),
+            flow(12, 1_000, 100, true),
+            flow(14, 3_000, 200, true),
+        ];
+        let (balance, realized_price) = replay(&flows, 10..16, by_height).unwrap();
+        assert_eq!(
+            balance,
+            [1_000u64, 1_000, 3_000, 3_000, 0, 0].map(Sats::from).to_vec()
+        );
+        assert_eq!(realized_price[0], Dollars::from(100.0));
+        assert_eq!(realized_price[2], Dollars::from(200.0));
+    }
+
+    #[test]
+    fn replay_from_a_later_period_applies_earlier_flows() {
+        let flows = [flow(10, 1_000, 100, false), flow(12, 500, 100, false)];
+        let (balance, _) = replay(&flows, 11..13, by_height).unwrap();
+        assert_eq!(balance, [1_000u64, 1_500].map(Sats::from).to_vec());
+    }
+}
@@ -1,7 +1,14 @@
 mod activity;
+mod balance;
 mod hash_prefix;
 mod mempool;
 mod resolve;
+mod rich_list;
+mod script_hash;
 mod stats;
 mod txs;
 mod utxos;
+mod wallet;
+mod watch;
+
+pub use watch::AddrWatch;
@@ -14,6 +14,35 @@ impl Query {
         Ok((output_type, type_index))
     }
 
+    /// `resolve_addr` for raw address bytes. `None` if the address was
+    /// never seen on chain, e.g. a fresh one with mempool activity only.
+    pub(super) fn resolve_addr_bytes(
+        &self,
+        bytes: &AddrBytes,
+    ) -> Result<Option<(OutputType, TypeIndex)>> {
+        let output_type = OutputType::from(bytes);
+        match self.type_index_for(output_type, &AddrHash::from(bytes)) {
+            Ok(type_index) => Ok(Some((output_type, type_index))),
+            Err(Error::UnknownAddr) => Ok(None),
+            Err(e) => Err(e),
+        }
+    }
+
+    /// Whether `bytes` ever received coins, on chain or in the mempool.
+    pub(crate) fn addr_bytes_seen(&self, bytes: &AddrBytes) -> Result<bool> {
+        if self
+            .mempool()
+            .is_some_and(|m| m.addr_stats(bytes).is_some())
+        {
+            return Ok(true);
+        }
+        Ok(self
+            .resolve_addr_bytes(bytes)?
+            .is_some_and(|(output_type, type_index)| {
+                type_index < self.safe_lengths().to_type_index(output_type)
+            }))
+    }
+
     /// Lookup the per-type index of an address by `(output_type, hash)`.
     /// Returns `UnknownAddr` if the hash is absent from the type's index.
     pub(super) fn type_index_for(
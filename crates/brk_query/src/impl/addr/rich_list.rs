@@ -0,0 +1,103 @@
+use std::{fs, path::PathBuf};
+
+use brk_error::{Error, Result};
+use brk_types::{Addr, Date, OutputType, RichList, RichListEntry, RichListRaw};
+
+use crate::Query;
+
+impl Query {
+    /// The `limit` largest funded addresses at the end of the last computed
+    /// block, of `addr_type` or of every type.
+    pub fn rich_list(&self, limit: usize, addr_type: Option<OutputType>) -> Result<RichList> {
+        let dir = self.rich_list_dir().join("states");
+        let latest = fs::read_dir(&dir)
+            .into_iter()
+            .flatten()
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse::<u32>().ok())
+            .max()
+            .ok_or_else(|| Error::NotFound("No rich list computed yet".into()))?;
+        let raw = RichListRaw::deserialize(&fs::read(dir.join(latest.to_string()))?)?;
+        self.build_rich_list(&raw, None, limit, addr_type)
+    }
+
+    /// The `limit` largest funded addresses at the end of `date`, of
+    /// `addr_type` or of every type.
+    pub fn rich_list_at(
+        &self,
+        date: Date,
+        limit: usize,
+        addr_type: Option<OutputType>,
+    ) -> Result<RichList> {
+        let path = self
+            .rich_list_dir()
+            .join("snapshots")
+            .join(date.to_string());
+        if !path.exists() {
+            return Err(Error::NotFound(format!("No rich list on {date}")));
+        }
+        let raw = RichListRaw::deserialize(&fs::read(&path)?)?;
+        self.build_rich_list(&raw, Some(date), limit, addr_type)
+    }
+
+    /// Dates with a rich list snapshot.
+    pub fn rich_list_dates(&self) -> Result<Vec<Date>> {
+        let dir = self.rich_list_dir().join("snapshots");
+        if !dir.exists() {
+            return Ok(vec![]);
+        }
+
+        let mut dates: Vec<Date> = fs::read_dir(&dir)?
+            .filter_map(|entry| entry.ok()?.file_name().to_str()?.parse().ok())
+            .collect();
+
+        dates.sort_unstable();
+        Ok(dates)
+    }
+
+    fn rich_list_dir(&self) -> PathBuf {
+        self.computer().distribution.states_path.join("rich_list")
+    }
+
+    fn build_rich_list(
+        &self,
+        raw: &RichListRaw,
+        date: Option<Date>,
+        limit: usize,
+        addr_type: Option<OutputType>,
+    ) -> Result<RichList> {
+        let ranked: Vec<_> = match addr_type {
+            Some(addr_type) if !addr_type.is_addr() => {
+                return Err(Error::UnsupportedType(addr_type.to_string()));
+            }
+            Some(addr_type) => raw
+                .of_type(addr_type)
+                .iter()
+                .take(limit)
+                .map(|&(type_index, balance)| (addr_type, type_index, balance))
+                .collect(),
+            None => raw.merged().into_iter().take(limit).collect(),
+        };
+
+        let addr_readers = self.indexer().vecs.addrs.addr_readers();
+        let entries = ranked
+            .into_iter()
+            .enumerate()
+            .map(|(i, (output_type, type_index, balance))| {
+                let script = addr_readers.script_pubkey(output_type, type_index);
+                Ok(RichListEntry {
+                    rank: i as u32 + 1,
+                    address: Addr::try_from((&script, output_type))?,
+                    addr_type: output_type,
+                    balance,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(RichList {
+            height: raw.height,
+            date,
+            addr_type,
+            entries,
+        })
+    }
+}
@@ -0,0 +1,317 @@
+use brk_error::{Error, OptionData, Result};
+use brk_types::{
+    AddrBytes, AddrMempoolStats, Height, OutputType, Sats, ScriptHash, ScriptHashChainStats,
+    ScriptHashPrefix, ScriptHashStats, ScriptHashTxOutIndex, Transaction, TxInIndex, TxIndex,
+    TxOutIndex, TxStatus, Txid, Unit, Utxo, Vout,
+};
+use rustc_hash::FxHashSet;
+use vecdb::{ReadableVec, VecIndex};
+
+use crate::Query;
+
+/// What a script hash resolves to.
+enum ScriptTarget {
+    /// Address-like script, served by the address indexes and mempool.
+    Addr(AddrBytes),
+    /// Script without an address and the confirmed outputs paying to
+    /// it, in chain order. Not tracked by the mempool.
+    Outputs(OutputType, Vec<TxOutIndex>),
+}
+
+/// A confirmed output paying to a script without an address.
+struct ScriptOutput {
+    tx_index: TxIndex,
+    txout_index: TxOutIndex,
+    value: Sats,
+    /// Confirmed tx spending it, `None` while unspent.
+    spender: Option<TxIndex>,
+}
+
+impl Query {
+    /// Address whose scriptPubKey hashes to `script_hash`, looked up on
+    /// chain first and then among live mempool addresses. Returns
+    /// `UnknownAddr` if neither has seen it.
+    pub fn script_hash_addr(&self, script_hash: &ScriptHash) -> Result<AddrBytes> {
+        if let Some(bytes) = self.script_hash_addr_chain(script_hash)? {
+            return Ok(bytes);
+        }
+        self.mempool()
+            .and_then(|m| m.addr_by_script_hash(script_hash))
+            .ok_or(Error::UnknownAddr)
+    }
+
+    pub fn script_hash_stats(&self, script_hash: ScriptHash) -> Result<ScriptHashStats> {
+        match self.resolve_script_hash(&script_hash)? {
+            ScriptTarget::Addr(bytes) => Ok(ScriptHashStats {
+                scripthash: script_hash,
+                script_type: OutputType::from(&bytes),
+                chain_stats: self
+                    .addr_bytes_chain_stats(&bytes)?
+                    .map(ScriptHashChainStats::from)
+                    .unwrap_or_default(),
+                mempool_stats: self
+                    .mempool()
+                    .and_then(|m| m.addr_stats(&bytes))
+                    .unwrap_or_default(),
+            }),
+            ScriptTarget::Outputs(script_type, txout_indexes) => {
+                let mut chain_stats = ScriptHashChainStats::default();
+                let mut tx_indexes = FxHashSet::default();
+                for output in self.script_outputs(&txout_indexes)? {
+                    chain_stats.funded_txo_count += 1;
+                    chain_stats.funded_txo_sum += output.value;
+                    tx_indexes.insert(output.tx_index);
+                    if let Some(spender) = output.spender {
+                        chain_stats.spent_txo_count += 1;
+                        chain_stats.spent_txo_sum += output.value;
+                        tx_indexes.insert(spender);
+                    }
+                }
+                chain_stats.tx_count = tx_indexes.len() as u32;
+                Ok(ScriptHashStats {
+                    scripthash: script_hash,
+                    script_type,
+                    chain_stats,
+                    mempool_stats: AddrMempoolStats::default(),
+                })
+            }
+        }
+    }
+
+    /// Confirmed txs funding or spending the script, newest first,
+    /// strictly older than `after_txid` if given.
+    pub fn script_hash_txs_chain(
+        &self,
+        script_hash: &ScriptHash,
+        after_txid: Option<Txid>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>> {
+        let tx_indexes = match self.resolve_script_hash(script_hash)? {
+            ScriptTarget::Addr(bytes) => match self.resolve_addr_bytes(&bytes)? {
+                Some((output_type, type_index)) => {
+                    self.txindices_of(output_type, type_index, after_txid, limit)?
+                }
+                None => vec![],
+            },
+            ScriptTarget::Outputs(_, txout_indexes) => {
+                let before = after_txid
+                    .map(|txid| self.resolve_tx_index(&txid))
+                    .transpose()?;
+                let mut tx_indexes: Vec<TxIndex> = self
+                    .script_outputs(&txout_indexes)?
+                    .into_iter()
+                    .flat_map(|output| [Some(output.tx_index), output.spender])
+                    .flatten()
+                    .filter(|tx_index| before.is_none_or(|before| *tx_index < before))
+                    .collect();
+                tx_indexes.sort_unstable_by(|a, b| b.cmp(a));
+                tx_indexes.dedup();
+                tx_indexes.truncate(limit);
+                tx_indexes
+            }
+        };
+        self.transactions_by_indices(&tx_indexes)
+    }
+
+    /// Mempool txs touching the script, newest first. Always empty for
+    /// scripts without an address, which the mempool doesn't track.
+    pub fn script_hash_mempool_txs(
+        &self,
+        script_hash: &ScriptHash,
+        limit: usize,
+    ) -> Result<Vec<Transaction>> {
+        let mempool = self.mempool().ok_or(Error::MempoolNotAvailable)?;
+        match self.resolve_script_hash(script_hash)? {
+            ScriptTarget::Addr(bytes) => Ok(mempool.addr_txs(&bytes, limit)),
+            ScriptTarget::Outputs(..) => Ok(vec![]),
+        }
+    }
+
+    pub fn script_hash_mempool_hash(&self, script_hash: &ScriptHash) -> Option<u64> {
+        let mempool = self.mempool()?;
+        let bytes = self.script_hash_addr(script_hash).ok()?;
+        mempool.addr_state_hash(&bytes)
+    }
+
+    /// Confirmed unspent outputs of the script.
+    pub fn script_hash_utxos(
+        &self,
+        script_hash: ScriptHash,
+        max_utxos: usize,
+    ) -> Result<Vec<Utxo>> {
+        let txout_indexes = match self.resolve_script_hash(&script_hash)? {
+            ScriptTarget::Addr(bytes) => return self.addr_bytes_utxos(&bytes, max_utxos),
+            ScriptTarget::Outputs(_, txout_indexes) => txout_indexes,
+        };
+
+        let unspent: Vec<ScriptOutput> = self
+            .script_outputs(&txout_indexes)?
+            .into_iter()
+            .filter(|output| output.spender.is_none())
+            .collect();
+        if unspent.len() > max_utxos {
+            return Err(Error::TooManyUtxos);
+        }
+
+        let vecs = &self.indexer().vecs;
+        let txid_reader = vecs.transactions.txid.reader();
+        let first_txout_index_reader = vecs.transactions.first_txout_index.reader();
+
+        let mut cached_status: Option<(Height, TxStatus)> = None;
+        let mut utxos = Vec::with_capacity(unspent.len());
+
+        for output in unspent {
+            let first_txout_index = first_txout_index_reader.get(output.tx_index.to_usize());
+            let vout = Vout::from(usize::from(output.txout_index) - usize::from(first_txout_index));
+
+            let height = self.confirmed_status_height(output.tx_index)?;
+            let status = if let Some((h, ref s)) = cached_status
+                && h == height
+            {
+                s.clone()
+            } else {
+                let s = self.confirmed_status_at(height)?;
+                cached_status = Some((height, s.clone()));
+                s
+            };
+
+            utxos.push(Utxo {
+                txid: txid_reader.get(output.tx_index.to_usize()),
+                vout,
+                status,
+                value: output.value,
+            });
+        }
+
+        Ok(utxos)
+    }
+
+    /// Confirmed txs funding or spending a script without an address,
+    /// from `from` on, in chain order. Empty for address scripts and
+    /// scripts no confirmed output pays to.
+    pub(super) fn script_hash_tx_indexes_from(
+        &self,
+        script_hash: &ScriptHash,
+        from: TxIndex,
+    ) -> Result<Vec<TxIndex>> {
+        let Some((_, txout_indexes)) = self.script_hash_outputs(script_hash)? else {
+            return Ok(vec![]);
+        };
+        let mut tx_indexes: Vec<TxIndex> = self
+            .script_outputs(&txout_indexes)?
+            .into_iter()
+            .flat_map(|output| [Some(output.tx_index), output.spender])
+            .flatten()
+            .filter(|tx_index| *tx_index >= from)
+            .collect();
+        tx_indexes.sort_unstable();
+        tx_indexes.dedup();
+        Ok(tx_indexes)
+    }
+
+    /// Address scripts go through the address indexes, anything else
+    /// through the outputs paying to it.
+    fn resolve_script_hash(&self, script_hash: &ScriptHash) -> Result<ScriptTarget> {
+        match self.script_hash_addr(script_hash) {
+            Ok(bytes) => return Ok(ScriptTarget::Addr(bytes)),
+            Err(Error::UnknownAddr) => {}
+            Err(e) => return Err(e),
+        }
+        self.script_hash_outputs(script_hash)?
+            .map(|(script_type, txout_indexes)| ScriptTarget::Outputs(script_type, txout_indexes))
+            .ok_or_else(|| {
+                Error::NotFound(
+                    "Script hash not found in the blockchain (no transaction history)".into(),
+                )
+            })
+    }
+
+    /// The store doesn't know the address type, so every type is probed.
+    fn script_hash_addr_chain(&self, script_hash: &ScriptHash) -> Result<Option<AddrBytes>> {
+        let indexer = self.indexer();
+        let lengths = self.safe_lengths();
+        let readers = indexer.vecs.addrs.addr_readers();
+
+        for addr_type in OutputType::ADDR_TYPES {
+            let Some(type_index) = indexer
+                .stores
+                .addr_type_to_script_hash_to_addr_index
+                .get(addr_type)
+                .data()?
+                .get(script_hash)?
+                .map(|cow| cow.into_owned())
+            else {
+                continue;
+            };
+            if type_index >= lengths.to_type_index(addr_type) {
+                continue;
+            }
+            let script = readers.script_pubkey(addr_type, type_index);
+            return AddrBytes::try_from((&script, addr_type)).map(Some);
+        }
+
+        Ok(None)
+    }
+
+    /// Outputs paying to a script without an address, each checked
+    /// against the full hash kept next to its type index. `None` if no
+    /// confirmed output pays to it.
+    fn script_hash_outputs(
+        &self,
+        script_hash: &ScriptHash,
+    ) -> Result<Option<(OutputType, Vec<TxOutIndex>)>> {
+        let indexer = self.indexer();
+        let txout_index_len = self.safe_lengths().txout_index;
+        let output_type_reader = indexer.vecs.outputs.output_type.reader();
+        let type_index_reader = indexer.vecs.outputs.type_index.reader();
+        let script_hash_readers = indexer.vecs.scripts.script_hash_readers();
+
+        let mut script_type = None;
+        let txout_indexes: Vec<TxOutIndex> = indexer
+            .stores
+            .script_hash_prefix_and_txout_index
+            .prefix(ScriptHashPrefix::from(script_hash))
+            .map(|(key, _): (ScriptHashTxOutIndex, Unit)| key.txout_index())
+            .filter(|txout_index| *txout_index < txout_index_len)
+            .filter(|txout_index| {
+                let output_type = output_type_reader.get(usize::from(*txout_index));
+                let type_index = type_index_reader.get(usize::from(*txout_index));
+                let hit = script_hash_readers.get(output_type, type_index) == Some(*script_hash);
+                if hit {
+                    script_type = Some(output_type);
+                }
+                hit
+            })
+            .collect();
+
+        Ok(script_type.map(|script_type| (script_type, txout_indexes)))
+    }
+
+    fn script_outputs(&self, txout_indexes: &[TxOutIndex]) -> Result<Vec<ScriptOutput>> {
+        let indexer = self.indexer();
+        let tx_index_len = self.safe_lengths().tx_index;
+        let value_reader = indexer.vecs.outputs.value.reader();
+        let txin_index_reader = self.computer().outputs.spent.txin_index.reader();
+        let mut tx_index_cursor = indexer.vecs.outputs.tx_index.cursor();
+        let mut spending_tx_cursor = indexer.vecs.inputs.tx_index.cursor();
+
+        txout_indexes
+            .iter()
+            .map(|&txout_index| {
+                let txin_index = txin_index_reader.get(usize::from(txout_index));
+                let spender = if txin_index == TxInIndex::UNSPENT {
+                    None
+                } else {
+                    Some(spending_tx_cursor.get(usize::from(txin_index)).data()?)
+                        .filter(|tx_index| *tx_index < tx_index_len)
+                };
+                Ok(ScriptOutput {
+                    tx_index: tx_index_cursor.get(usize::from(txout_index)).data()?,
+                    txout_index,
+                    value: value_reader.get(usize::from(txout_index)),
+                    spender,
+                })
+            })
+            .collect()
+    }
+}
@@ -3,7 +3,7 @@ use std::str::FromStr;
 use brk_error::{Error, Result};
 use brk_types::{
     Addr, AddrBytes, AddrChainStats, AddrHash, AddrStats, AnyAddrDataIndexEnum, Dollars,
-    OutputType, TypeIndex,
+    OutputType, TypeIndex, addr_labels,
 };
 
 use crate::Query;
@@ -24,6 +24,31 @@ impl Query {
         output_type: OutputType,
         type_index: TypeIndex,
     ) -> Result<AddrStats> {
+        Ok(AddrStats {
+            addr,
+            addr_type: output_type,
+            labels: addr_labels().names_of(&bytes),
+            chain_stats: self.chain_stats(output_type, type_index)?,
+            mempool_stats: self
+                .mempool()
+                .and_then(|m| m.addr_stats(&bytes))
+                .unwrap_or_default(),
+        })
+    }
+
+    /// Confirmed stats of `bytes`, `None` for an address never seen on
+    /// chain.
+    pub fn addr_bytes_chain_stats(&self, bytes: &AddrBytes) -> Result<Option<AddrChainStats>> {
+        self.resolve_addr_bytes(bytes)?
+            .map(|(output_type, type_index)| self.chain_stats(output_type, type_index))
+            .transpose()
+    }
+
+    pub(super) fn chain_stats(
+        &self,
+        output_type: OutputType,
+        type_index: TypeIndex,
+    ) -> Result<AddrChainStats> {
         if type_index >= self.safe_lengths().to_type_index(output_type) {
             return Err(Error::UnknownAddr);
         }
@@ -57,22 +82,14 @@ impl Query {
             }
         };
 
-        Ok(AddrStats {
-            addr,
-            addr_type: output_type,
-            chain_stats: AddrChainStats {
-                type_index,
-                funded_txo_count: addr_data.funded_txo_count,
-                funded_txo_sum: addr_data.received,
-                spent_txo_count: addr_data.spent_txo_count,
-                spent_txo_sum: addr_data.sent,
-                tx_count: addr_data.tx_count,
-                realized_price,
-            },
-            mempool_stats: self
-                .mempool()
-                .and_then(|m| m.addr_stats(&bytes))
-                .unwrap_or_default(),
+        Ok(AddrChainStats {
+            type_index,
+            funded_txo_count: addr_data.funded_txo_count,
+            funded_txo_sum: addr_data.received,
+            spent_txo_count: addr_data.spent_txo_count,
+            spent_txo_sum: addr_data.sent,
+            tx_count: addr_data.tx_count,
+            realized_price,
         })
     }
 }
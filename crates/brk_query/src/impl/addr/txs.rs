@@ -1,5 +1,8 @@
 use brk_error::{OptionData, Result};
-use brk_types::{Addr, AddrIndexTxIndex, Transaction, TxIndex, Txid, Unit};
+use brk_types::{
+    Addr, AddrBytes, AddrIndexTxIndex, Height, OutputType, Transaction, TxIndex, Txid, TypeIndex,
+    Unit,
+};
 use vecdb::VecIndex;
 
 use crate::Query;
@@ -29,17 +32,55 @@ impl Query {
             .collect())
     }
 
+    /// Whole confirmed history of `bytes`, oldest first, with the
+    /// height of each tx. Empty for an address never seen on chain.
+    pub fn addr_bytes_history(&self, bytes: &AddrBytes) -> Result<Vec<(Txid, Height)>> {
+        let Some((output_type, type_index)) = self.resolve_addr_bytes(bytes)? else {
+            return Ok(vec![]);
+        };
+
+        let store = self
+            .indexer()
+            .stores
+            .addr_type_to_addr_index_and_tx_index
+            .get(output_type)
+            .data()?;
+
+        let tx_index_len = self.safe_lengths().tx_index;
+        let txid_reader = self.indexer().vecs.transactions.txid.reader();
+        store
+            .prefix(type_index)
+            .map(|(key, _): (AddrIndexTxIndex, Unit)| key.tx_index())
+            .filter(|tx_index| *tx_index < tx_index_len)
+            .map(|tx_index| {
+                let txid = txid_reader.get(tx_index.to_usize());
+                Ok((txid, self.confirmed_status_height(tx_index)?))
+            })
+            .collect()
+    }
+
     fn addr_txindices(
         &self,
         addr: &Addr,
         after_txid: Option<Txid>,
         limit: usize,
     ) -> Result<Vec<TxIndex>> {
-        let stores = &self.indexer().stores;
-
         let (output_type, type_index) = self.resolve_addr(addr)?;
+        self.txindices_of(output_type, type_index, after_txid, limit)
+    }
 
-        let store = stores
+    /// Confirmed tx indexes of an address, newest first, strictly older
+    /// than `after_txid` if given.
+    pub(super) fn txindices_of(
+        &self,
+        output_type: OutputType,
+        type_index: TypeIndex,
+        after_txid: Option<Txid>,
+        limit: usize,
+    ) -> Result<Vec<TxIndex>> {
+        let store = self
+            .indexer()
+            .stores
             .addr_type_to_addr_index_and_tx_index
             .get(output_type)
             .data()?;
@@ -1,17 +1,37 @@
 use brk_error::{Error, OptionData, Result};
-use brk_types::{Addr, AddrIndexOutPoint, Height, TxIndex, TxStatus, Unit, Utxo, Vout};
+use brk_types::{
+    Addr, AddrBytes, AddrIndexOutPoint, Height, OutputType, TxIndex, TxStatus, TypeIndex, Unit,
+    Utxo, Vout,
+};
 use vecdb::VecIndex;
 
 use crate::Query;
 
 impl Query {
     pub fn addr_utxos(&self, addr: Addr, max_utxos: usize) -> Result<Vec<Utxo>> {
+        let (output_type, type_index) = self.resolve_addr(&addr)?;
+        self.utxos_of(output_type, type_index, max_utxos)
+    }
+
+    /// Confirmed UTXOs of `bytes`. Empty for an address never seen on
+    /// chain.
+    pub fn addr_bytes_utxos(&self, bytes: &AddrBytes, max_utxos: usize) -> Result<Vec<Utxo>> {
+        match self.resolve_addr_bytes(bytes)? {
+            Some((output_type, type_index)) => self.utxos_of(output_type, type_index, max_utxos),
+            None => Ok(vec![]),
+        }
+    }
+
+    pub(super) fn utxos_of(
+        &self,
+        output_type: OutputType,
+        type_index: TypeIndex,
+        max_utxos: usize,
+    ) -> Result<Vec<Utxo>> {
         let indexer = self.indexer();
         let stores = &indexer.stores;
         let vecs = &indexer.vecs;
 
-        let (output_type, type_index) = self.resolve_addr(&addr)?;
-
         let store = stores
             .addr_type_to_addr_index_and_unspent_outpoint
             .get(output_type)
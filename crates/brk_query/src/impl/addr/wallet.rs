@@ -0,0 +1,210 @@
+use brk_error::{Error, Result};
+use brk_types::{
+    Addr, AddrBytes, AddrMempoolStats, OutputType, Transaction, TxIndex, Txid, TypeIndex,
+    WalletAddr, WalletChainStats, WalletDescriptor, WalletKeychain, WalletStats, WalletUtxo,
+};
+use rustc_hash::FxHashSet;
+
+use crate::Query;
+
+/// Addresses derived per keychain before the scan gives up, so a wallet
+/// that keeps finding used addresses can't turn one request into an
+/// unbounded scan.
+const MAX_KEYCHAIN_ADDRS: u32 = 100_000;
+
+/// A used address found by the scan.
+struct ScannedAddr {
+    keychain: u32,
+    index: u32,
+    bytes: AddrBytes,
+    /// `None` if only seen in the mempool.
+    chain: Option<(OutputType, TypeIndex)>,
+    mempool_stats: Option<AddrMempoolStats>,
+}
+
+impl Query {
+    pub fn wallet_stats(
+        &self,
+        descriptor: &WalletDescriptor,
+        gap_limit: u32,
+    ) -> Result<WalletStats> {
+        let (keychains, scanned) = self.wallet_scan(descriptor, gap_limit)?;
+
+        let mut chain_stats = WalletChainStats::default();
+        let mut chain_txs = FxHashSet::default();
+        let mut mempool_stats = AddrMempoolStats::default();
+        let mut mempool_txs = FxHashSet::default();
+        let mut addrs = Vec::with_capacity(scanned.len());
+
+        for addr in scanned {
+            let addr_chain_stats = match addr.chain {
+                Some((output_type, type_index)) => {
+                    let stats = self.chain_stats(output_type, type_index)?;
+                    chain_stats.funded_txo_count += stats.funded_txo_count;
+                    chain_stats.funded_txo_sum += stats.funded_txo_sum;
+                    chain_stats.spent_txo_count += stats.spent_txo_count;
+                    chain_stats.spent_txo_sum += stats.spent_txo_sum;
+                    chain_txs.extend(self.txindices_of(
+                        output_type,
+                        type_index,
+                        None,
+                        usize::MAX,
+                    )?);
+                    Some(stats)
+                }
+                None => None,
+            };
+            if let Some(stats) = &addr.mempool_stats {
+                mempool_stats.funded_txo_count += stats.funded_txo_count;
+                mempool_stats.funded_txo_sum += stats.funded_txo_sum;
+                mempool_stats.spent_txo_count += stats.spent_txo_count;
+                mempool_stats.spent_txo_sum += stats.spent_txo_sum;
+                if let Some(mempool) = self.mempool() {
+                    mempool_txs.extend(mempool.addr_txids(&addr.bytes));
+                }
+            }
+            addrs.push(WalletAddr {
+                addr: Addr::try_from(&addr.bytes)?,
+                keychain: addr.keychain,
+                index: addr.index,
+                chain_stats: addr_chain_stats,
+                mempool_stats: addr.mempool_stats,
+            });
+        }
+        chain_stats.tx_count = chain_txs.len() as u32;
+        mempool_stats.tx_count = mempool_txs.len() as u32;
+
+        Ok(WalletStats {
+            addr_type: descriptor.output_type(),
+            chain_stats,
+            mempool_stats,
+            keychains,
+            addrs,
+        })
+    }
+
+    /// Confirmed txs of the whole wallet, newest first, strictly older
+    /// than `after_txid` if given. A tx touching several addresses is
+    /// returned once.
+    pub fn wallet_txs_chain(
+        &self,
+        descriptor: &WalletDescriptor,
+        gap_limit: u32,
+        after_txid: Option<Txid>,
+        limit: usize,
+    ) -> Result<Vec<Transaction>> {
+        let (_, scanned) = self.wallet_scan(descriptor, gap_limit)?;
+
+        let mut tx_indexes: Vec<TxIndex> = vec![];
+        for (output_type, type_index) in scanned.iter().filter_map(|addr| addr.chain) {
+            tx_indexes.extend(self.txindices_of(output_type, type_index, after_txid, limit)?);
+        }
+        tx_indexes.sort_unstable_by(|a, b| b.cmp(a));
+        tx_indexes.dedup();
+        tx_indexes.truncate(limit);
+
+        self.transactions_by_indices(&tx_indexes)
+    }
+
+    /// Mempool txs of the whole wallet, newest first.
+    pub fn wallet_mempool_txs(
+        &self,
+        descriptor: &WalletDescriptor,
+        gap_limit: u32,
+        limit: usize,
+    ) -> Result<Vec<Transaction>> {
+        let mempool = self.mempool().ok_or(Error::MempoolNotAvailable)?;
+        let (_, scanned) = self.wallet_scan(descriptor, gap_limit)?;
+        let addrs: Vec<AddrBytes> = scanned
+            .into_iter()
+            .filter(|addr| addr.mempool_stats.is_some())
+            .map(|addr| addr.bytes)
+            .collect();
+        Ok(mempool.addrs_txs(&addrs, limit))
+    }
+
+    /// Confirmed UTXOs of the whole wallet, by keychain then index.
+    pub fn wallet_utxos(
+        &self,
+        descriptor: &WalletDescriptor,
+        gap_limit: u32,
+        max_utxos: usize,
+    ) -> Result<Vec<WalletUtxo>> {
+        let (_, scanned) = self.wallet_scan(descriptor, gap_limit)?;
+
+        let mut utxos = vec![];
+        for addr in scanned {
+            let Some((output_type, type_index)) = addr.chain else {
+                continue;
+            };
+            let remaining = max_utxos.saturating_sub(utxos.len());
+            let addr_utxos = self.utxos_of(output_type, type_index, remaining)?;
+            if addr_utxos.is_empty() {
+                continue;
+            }
+            let wallet_addr = Addr::try_from(&addr.bytes)?;
+            utxos.extend(addr_utxos.into_iter().map(|utxo| WalletUtxo {
+                addr: wallet_addr.clone(),
+                keychain: addr.keychain,
+                index: addr.index,
+                utxo,
+            }));
+        }
+
+        Ok(utxos)
+    }
+
+    /// Derives each keychain from index 0 until `gap_limit` addresses in
+    /// a row have neither on-chain nor mempool activity.
+    fn wallet_scan(
+        &self,
+        descriptor: &WalletDescriptor,
+        gap_limit: u32,
+    ) -> Result<(Vec<WalletKeychain>, Vec<ScannedAddr>)> {
+        let output_type = descriptor.output_type();
+        let type_index_len = self.safe_lengths().to_type_index(output_type);
+        let mempool = self.mempool();
+
+        let mut keychains = Vec::with_capacity(descriptor.keychain_count());
+        let mut scanned = vec![];
+
+        for keychain in 0..descriptor.keychain_count() {
+            let mut summary = WalletKeychain::default();
+            let mut gap = 0;
+
+            for (index, script) in (0..).zip(descriptor.script_pubkeys(keychain)) {
+                if gap >= gap_limit {
+                    break;
+                }
+                if index >= MAX_KEYCHAIN_ADDRS {
+                    return Err(Error::TooManyAddrs);
+                }
+
+                let bytes = AddrBytes::try_from((&script, output_type))?;
+                let chain = self
+                    .resolve_addr_bytes(&bytes)?
+                    .filter(|(_, type_index)| *type_index < type_index_len);
+                let mempool_stats = mempool.and_then(|m| m.addr_stats(&bytes));
+                if chain.is_none() && mempool_stats.is_none() {
+                    gap += 1;
+                    continue;
+                }
+
+                gap = 0;
+                summary.used_count += 1;
+                summary.next_index = index + 1;
+                scanned.push(ScannedAddr {
+                    keychain: keychain as u32,
+                    index,
+                    bytes,
+                    chain,
+                    mempool_stats,
+                });
+            }
+
+            keychains.push(summary);
+        }
+
+        Ok((keychains, scanned))
+    }
+}
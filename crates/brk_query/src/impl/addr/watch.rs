@@ -0,0 +1,559 @@
+use std::{collections::hash_map::Entry, str::FromStr};
+
+use brk_error::{Error, OptionData, Result};
+use brk_mempool::Mempool;
+use brk_types::{
+    Addr, AddrBytes, AddrHash, AddrIndexTxIndex, AddrWatchEvent, BlockHash, Height, OutputType,
+    ScriptHash, TxIndex, Txid, TypeIndex, Unit,
+};
+use rustc_hash::FxHashMap;
+use vecdb::{ReadableVec, VecIndex};
+
+use crate::Query;
+
+/// Blocks re-scanned below the previous tip once it left the best chain,
+/// so txs re-mined at reused tx indices aren't missed. Also how long a
+/// fully confirmed tx is kept around to report its reorg.
+const REORG_REWIND: u32 = 10;
+
+/// State of one address watch-list subscription, advanced by
+/// [`Query::addr_watch_poll`]. Only activity from its creation on is
+/// reported.
+pub struct AddrWatch {
+    addrs: Vec<WatchedAddr>,
+    confirmations: u32,
+    txs: FxHashMap<Txid, WatchedTx>,
+    /// Tip at the previous poll, to catch reorgs of blocks that held no
+    /// tracked tx but may hold a watched one once re-mined.
+    tip: (Height, BlockHash),
+}
+
+/// A watch-list entry as subscribed, and reported back the same way.
+#[derive(Clone)]
+enum Subject {
+    Addr(Addr),
+    ScriptHash(ScriptHash),
+}
+
+struct WatchedAddr {
+    subject: Subject,
+    /// `None` for a script hash until the chain or the mempool shows
+    /// its address, and for good if its script has none.
+    bytes: Option<AddrBytes>,
+    /// `None` until the address first shows up on chain.
+    type_index: Option<TypeIndex>,
+    /// Next tx index to scan in the address's confirmed history.
+    cursor: TxIndex,
+}
+
+struct WatchedTx {
+    subjects: Vec<Subject>,
+    /// Confirming block, `None` while in the mempool.
+    block: Option<(Height, BlockHash)>,
+    /// Last reported count, capped at the watch's target.
+    confirmations: u32,
+}
+
+impl Query {
+    /// Starts watching `addrs` and `script_hashes` until their txs are
+    /// `confirmations` deep.
+    pub fn addr_watch(
+        &self,
+        addrs: Vec<Addr>,
+        script_hashes: Vec<ScriptHash>,
+        confirmations: u32,
+    ) -> Result<AddrWatch> {
+        let cursor = self.safe_lengths().tx_index;
+        let watched = |subject, bytes| WatchedAddr {
+            subject,
+            bytes,
+            type_index: None,
+            cursor,
+        };
+        let mut watched_addrs = addrs
+            .into_iter()
+            .map(|addr| {
+                let bytes = AddrBytes::from_str(&addr)?;
+                Ok(watched(Subject::Addr(addr), Some(bytes)))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        watched_addrs.extend(
+            script_hashes
+                .into_iter()
+                .map(|script_hash| watched(Subject::ScriptHash(script_hash), None)),
+        );
+        Ok(AddrWatch {
+            addrs: watched_addrs,
+            confirmations: confirmations.max(1),
+            txs: FxHashMap::default(),
+            tip: (self.height(), self.tip_blockhash()),
+        })
+    }
+
+    /// Events since the previous poll: reorgs, confirmation bumps and
+    /// drops of tracked txs first, then new mempool and chain arrivals.
+    pub fn addr_watch_poll(&self, watch: &mut AddrWatch) -> Result<Vec<AddrWatchEvent>> {
+        let mut events = Vec::new();
+        let tip = self.height();
+        watch.rewind(self, tip)?;
+        watch.resolve(self)?;
+        watch.refresh(self, tip, &mut events)?;
+        if let Some(mempool) = self.mempool() {
+            watch.mempool_arrivals(mempool, &mut events);
+        }
+        watch.chain_arrivals(self, tip, &mut events)?;
+        watch.tip = (tip, self.tip_blockhash());
+        Ok(events)
+    }
+
+    /// Best-chain hash at `height`, `None` above `tip`.
+    fn block_hash_at(&self, height: Height, tip: Height) -> Result<Option<BlockHash>> {
+        if height > tip {
+            return Ok(None);
+        }
+        self.indexer()
+            .vecs
+            .blocks
+            .blockhash
+            .collect_one(height)
+            .data()
+            .map(Some)
+    }
+}
+
+impl AddrWatch {
+    /// Moves every scan cursor back below the previous tip if it is no
+    /// longer in the best chain.
+    fn rewind(&mut self, query: &Query, tip: Height) -> Result<()> {
+        let (height, hash) = self.tip;
+        if query.block_hash_at(height, tip)? == Some(hash) {
+            return Ok(());
+        }
+        let from = Height::from((*height).saturating_sub(REORG_REWIND)).min(tip);
+        let first_tx_index = query
+            .indexer()
+            .vecs
+            .transactions
+            .first_tx_index
+            .collect_one(from)
+            .data()?;
+        for watched in &mut self.addrs {
+            watched.cursor = watched.cursor.min(first_tx_index);
+            // Rolled back addresses get a fresh index when seen again.
+            watched.type_index = None;
+        }
+        Ok(())
+    }
+
+    /// Looks up the address of script hashes not resolved yet. Scripts
+    /// without an address stay unresolved and are followed on chain only.
+    fn resolve(&mut self, query: &Query) -> Result<()> {
+        for watched in &mut self.addrs {
+            let (Subject::ScriptHash(script_hash), None) = (&watched.subject, &watched.bytes)
+            else {
+                continue;
+            };
+            match query.script_hash_addr(script_hash) {
+                Ok(bytes) => watched.bytes = Some(bytes),
+                Err(Error::UnknownAddr) => {}
+                Err(e) => return Err(e),
+            }
+        }
+        Ok(())
+    }
+
+    /// Re-checks every tracked tx against the current chain and mempool.
+    fn refresh(
+        &mut self,
+        query: &Query,
+        tip: Height,
+        events: &mut Vec<AddrWatchEvent>,
+    ) -> Result<()> {
+        let mempool = query.mempool();
+        let target = self.confirmations;
+        let mut dropped = Vec::new();
+
+        for (txid, tx) in &mut self.txs {
+            if let Some((height, _)) = tx.block {
+                let current = query.block_hash_at(height, tip)?;
+                events.extend(tx.follow(*txid, current, tip, target));
+                continue;
+            }
+
+            if mempool.is_some_and(|m| m.contains_txid(txid)) {
+                continue;
+            }
+            match query.resolve_tx(txid) {
+                Ok((_, height)) => {
+                    let hash = query.block_hash_at(height, tip)?.data()?;
+                    events.extend(tx.confirm(*txid, height, hash, tip, target));
+                }
+                // Mined but not indexed yet while its tombstone says so.
+                Err(Error::UnknownTxid)
+                    if mempool
+                        .and_then(|m| m.with_vanished_tx(txid, |_| ()))
+                        .is_some() => {}
+                Err(Error::UnknownTxid) => dropped.push(*txid),
+                Err(e) => return Err(e),
+            }
+        }
+
+        for txid in dropped {
+            if let Some(tx) = self.txs.remove(&txid) {
+                events.push(tx.dropped(txid));
+            }
+        }
+
+        self.txs.retain(|_, tx| !tx.settled(tip, target));
+        Ok(())
+    }
+
+    /// Starts tracking mempool txs touching a watched address.
+    fn mempool_arrivals(&mut self, mempool: &Mempool, events: &mut Vec<AddrWatchEvent>) {
+        let mut seen: FxHashMap<Txid, WatchedTx> = FxHashMap::default();
+        for watched in &self.addrs {
+            let Some(bytes) = &watched.bytes else {
+                continue;
+            };
+            for txid in mempool.addr_txids(bytes) {
+                self.txs
+                    .get_mut(&txid)
+                    .unwrap_or_else(|| seen.entry(txid).or_insert_with(WatchedTx::new))
+                    .add_subject(&watched.subject);
+            }
+        }
+        for (txid, tx) in seen {
+            events.push(tx.seen(txid));
+            self.txs.insert(txid, tx);
+        }
+    }
+
+    /// Scans each watched address's confirmed history past its cursor,
+    /// catching txs mined without going through the mempool first.
+    fn chain_arrivals(
+        &mut self,
+        query: &Query,
+        tip: Height,
+        events: &mut Vec<AddrWatchEvent>,
+    ) -> Result<()> {
+        let indexer = query.indexer();
+        let tx_index_len = query.safe_lengths().tx_index;
+        let txid_reader = indexer.vecs.transactions.txid.reader();
+        let target = self.confirmations;
+        let mut arrivals: FxHashMap<Txid, (TxIndex, WatchedTx)> = FxHashMap::default();
+
+        for watched in &mut self.addrs {
+            watched.cursor = watched.cursor.min(tx_index_len);
+            for tx_index in watched.chain_tx_indices(query, tx_index_len)? {
+                let txid = txid_reader.get(tx_index.to_usize());
+                match self.txs.get_mut(&txid) {
+                    Some(tx) => {
+                        tx.add_subject(&watched.subject);
+                        if tx.block.is_none() {
+                            let height = query.confirmed_status_height(tx_index)?;
+                            let hash = query.block_hash_at(height, tip)?.data()?;
+                            events.extend(tx.confirm(txid, height, hash, tip, target));
+                        }
+                    }
+                    None => match arrivals.entry(txid) {
+                        Entry::Occupied(mut entry) => {
+                            entry.get_mut().1.add_subject(&watched.subject)
+                        }
+                        Entry::Vacant(entry) => {
+                            let mut tx = WatchedTx::new();
+                            tx.add_subject(&watched.subject);
+                            entry.insert((tx_index, tx));
+                        }
+                    },
+                }
+            }
+            watched.cursor = tx_index_len;
+        }
+
+        for (txid, (tx_index, mut tx)) in arrivals {
+            let height = query.confirmed_status_height(tx_index)?;
+            let hash = query.block_hash_at(height, tip)?.data()?;
+            events.extend(tx.confirm(txid, height, hash, tip, target));
+            self.txs.insert(txid, tx);
+        }
+        Ok(())
+    }
+}
+
+impl WatchedAddr {
+    /// Confirmed txs from the cursor up to `tx_index_len`, through the
+    /// address indexes, or the script's outputs if it has no address.
+    fn chain_tx_indices(&mut self, query: &Query, tx_index_len: TxIndex) -> Result<Vec<TxIndex>> {
+        let Some(bytes) = &self.bytes else {
+            return match &self.subject {
+                Subject::ScriptHash(script_hash) => {
+                    query.script_hash_tx_indexes_from(script_hash, self.cursor)
+                }
+                Subject::Addr(_) => Ok(vec![]),
+            };
+        };
+        let output_type = OutputType::from(bytes);
+        if self.type_index.is_none() {
+            self.type_index = match query.type_index_for(output_type, &AddrHash::from(bytes)) {
+                Ok(type_index) => Some(type_index),
+                Err(Error::UnknownAddr) => None,
+                Err(e) => return Err(e),
+            };
+        }
+        let Some(type_index) = self.type_index else {
+            return Ok(vec![]);
+        };
+
+        let store = query
+            .indexer()
+            .stores
+            .addr_type_to_addr_index_and_tx_index
+            .get(output_type)
+            .data()?;
+        let from = AddrIndexTxIndex::from((type_index, self.cursor));
+        let to = AddrIndexTxIndex::from((type_index, tx_index_len));
+        Ok(store
+            .range(from..to)
+            .map(|(key, _): (AddrIndexTxIndex, Unit)| key.tx_index())
+            .collect())
+    }
+}
+
+impl WatchedTx {
+    fn new() -> Self {
+        Self {
+            subjects: vec![],
+            block: None,
+            confirmations: 0,
+        }
+    }
+
+    /// Records the confirming block and returns a `Confirmed` event if
+    /// the capped confirmation count moved.
+    fn confirm(
+        &mut self,
+        txid: Txid,
+        height: Height,
+        hash: BlockHash,
+        tip: Height,
+        target: u32,
+    ) -> Option<AddrWatchEvent> {
+        self.block = Some((height, hash));
+        let confirmations = ((*tip).saturating_sub(*height) + 1).min(target);
+        if confirmations <= self.confirmations {
+            return None;
+        }
+        self.confirmations = confirmations;
+        let (addrs, script_hashes) = self.split_subjects();
+        Some(AddrWatchEvent::Confirmed {
+            txid,
+            addrs,
+            script_hashes,
+            confirmations,
+            block_height: height,
+            block_hash: hash,
+        })
+    }
+
+    /// Checks a mined tx against `current`, the best-chain hash now at
+    /// its height: back to unconfirmed with a `Reorged` event if its
+    /// block left, else [`Self::confirm`].
+    fn follow(
+        &mut self,
+        txid: Txid,
+        current: Option<BlockHash>,
+        tip: Height,
+        target: u32,
+    ) -> Option<AddrWatchEvent> {
+        let (height, hash) = self.block?;
+        if current == Some(hash) {
+            return self.confirm(txid, height, hash, tip, target);
+        }
+        self.block = None;
+        self.confirmations = 0;
+        let (addrs, script_hashes) = self.split_subjects();
+        Some(AddrWatchEvent::Reorged {
+            txid,
+            addrs,
+            script_hashes,
+            block_height: height,
+            block_hash: hash,
+        })
+    }
+
+    /// At the target depth and past the reorg window, done with.
+    fn settled(&self, tip: Height, target: u32) -> bool {
+        self.block.is_some_and(|(height, _)| {
+            self.confirmations >= target && *height + REORG_REWIND < *tip
+        })
+    }
+
+    fn seen(&self, txid: Txid) -> AddrWatchEvent {
+        let (addrs, script_hashes) = self.split_subjects();
+        AddrWatchEvent::Seen {
+            txid,
+            addrs,
+            script_hashes,
+        }
+    }
+
+    fn dropped(&self, txid: Txid) -> AddrWatchEvent {
+        let (addrs, script_hashes) = self.split_subjects();
+        AddrWatchEvent::Dropped {
+            txid,
+            addrs,
+            script_hashes,
+        }
+    }
+
+    fn add_subject(&mut self, subject: &Subject) {
+        let known = self.subjects.iter().any(|known| match (known, subject) {
+            (Subject::Addr(a), Subject::Addr(b)) => **a == **b,
+            (Subject::ScriptHash(a), Subject::ScriptHash(b)) => a == b,
+            _ => false,
+        });
+        if !known {
+            self.subjects.push(subject.clone());
+        }
+    }
+
+    fn split_subjects(&self) -> (Vec<Addr>, Vec<ScriptHash>) {
+        let mut addrs = vec![];
+        let mut script_hashes = vec![];
+        for subject in &self.subjects {
+            match subject {
+                Subject::Addr(addr) => addrs.push(addr.clone()),
+                Subject::ScriptHash(script_hash) => script_hashes.push(*script_hash),
+            }
+        }
+        (addrs, script_hashes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TXID: &str = "4a5e1e4baab89f3a32518a88c31bc87f618f76673e2cc77ab2127b7afdeda33b";
+    const HASH_A: &str = "000000000019d6689c085ae165831e934ff763ae46a2a6c172b3f1b60a8ce26f";
+    const HASH_B: &str = "00000000839a8e6886ab5951d76f411475428afc90947ee320161bbf18eb6048";
+    const SCRIPT_HASH: &str = "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161";
+
+    fn txid() -> Txid {
+        Txid::from_str(TXID).unwrap()
+    }
+
+    fn hash(s: &str) -> BlockHash {
+        BlockHash::from_str(s).unwrap()
+    }
+
+    fn h(height: u32) -> Height {
+        Height::from(height)
+    }
+
+    fn confirmations(event: Option<AddrWatchEvent>) -> Option<u32> {
+        event.map(|event| match event {
+            AddrWatchEvent::Confirmed { confirmations, .. } => confirmations,
+            event => panic!("expected a confirmation, got {event:?}"),
+        })
+    }
+
+    #[test]
+    fn confirmations_only_move_up_to_the_target() {
+        let mut tx = WatchedTx::new();
+        let a = hash(HASH_A);
+        assert_eq!(
+            confirmations(tx.confirm(txid(), h(100), a, h(100), 6)),
+            Some(1)
+        );
+        assert_eq!(
+            confirmations(tx.confirm(txid(), h(100), a, h(100), 6)),
+            None
+        );
+        assert_eq!(
+            confirmations(tx.follow(txid(), Some(a), h(102), 6)),
+            Some(3)
+        );
+        assert_eq!(
+            confirmations(tx.follow(txid(), Some(a), h(200), 6)),
+            Some(6)
+        );
+        assert_eq!(confirmations(tx.follow(txid(), Some(a), h(201), 6)), None);
+    }
+
+    #[test]
+    fn buried_arrival_reports_its_capped_depth() {
+        let mut tx = WatchedTx::new();
+        let event = tx.confirm(txid(), h(100), hash(HASH_A), h(104), 3);
+        assert_eq!(confirmations(event), Some(3));
+    }
+
+    #[test]
+    fn reorg_resets_and_reconfirms() {
+        let mut tx = WatchedTx::new();
+        let (a, b) = (hash(HASH_A), hash(HASH_B));
+        tx.confirm(txid(), h(100), a, h(101), 6);
+        assert_eq!(tx.confirmations, 2);
+
+        let Some(AddrWatchEvent::Reorged {
+            block_height,
+            block_hash,
+            ..
+        }) = tx.follow(txid(), Some(b), h(101), 6)
+        else {
+            panic!("replaced block not reported");
+        };
+        assert_eq!((block_height, block_hash), (h(100), a));
+        assert_eq!((tx.block, tx.confirmations), (None, 0));
+        assert!(tx.follow(txid(), Some(b), h(101), 6).is_none());
+
+        assert_eq!(
+            confirmations(tx.confirm(txid(), h(101), b, h(101), 6)),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn shortened_chain_is_a_reorg() {
+        let mut tx = WatchedTx::new();
+        tx.confirm(txid(), h(100), hash(HASH_A), h(100), 6);
+        assert!(matches!(
+            tx.follow(txid(), None, h(99), 6),
+            Some(AddrWatchEvent::Reorged { .. })
+        ));
+    }
+
+    #[test]
+    fn settles_past_the_reorg_window_only() {
+        let mut tx = WatchedTx::new();
+        assert!(!tx.settled(h(1_000), 1));
+        tx.confirm(txid(), h(100), hash(HASH_A), h(102), 6);
+        assert!(!tx.settled(h(200), 6));
+        tx.confirm(txid(), h(100), hash(HASH_A), h(105), 6);
+        assert!(!tx.settled(h(100 + REORG_REWIND), 6));
+        assert!(tx.settled(h(101 + REORG_REWIND), 6));
+    }
+
+    #[test]
+    fn subjects_are_reported_once_as_subscribed() {
+        let mut tx = WatchedTx::new();
+        let addr = Subject::Addr(Addr::from("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa".to_string()));
+        let script_hash = Subject::ScriptHash(ScriptHash::from_str(SCRIPT_HASH).unwrap());
+        tx.add_subject(&addr);
+        tx.add_subject(&script_hash);
+        tx.add_subject(&addr);
+        tx.add_subject(&script_hash);
+
+        let AddrWatchEvent::Seen {
+            addrs,
+            script_hashes,
+            ..
+        } = tx.seen(txid())
+        else {
+            unreachable!()
+        };
+        assert_eq!(addrs.len(), 1);
+        assert_eq!(script_hashes.len(), 1);
+        assert_eq!(script_hashes[0].to_string(), SCRIPT_HASH);
+    }
+}
@@ -0,0 +1,155 @@
+use bitcoin::{consensus::encode, hex::DisplayHex};
+use brk_error::{Error, OptionData, Result};
+use brk_types::{
+    BlockCoinbase, BlockHash, CoinbaseTag, Height, MergedMining, PoolSlug, TxOut, Txid,
+};
+use vecdb::ReadableVec;
+
+use crate::Query;
+
+impl Query {
+    /// Layout of the block's coinbase: scriptSig, outputs, witness
+    /// commitment and merged-mining tags, with the pool it's attributed to.
+    pub fn block_coinbase(&self, hash: &BlockHash) -> Result<BlockCoinbase> {
+        let height = match self.height_by_hash(hash) {
+            Err(Error::NotFound(_)) => return self.stale_block_coinbase(hash),
+            height => height?,
+        };
+        let tx_index = self
+            .indexer()
+            .vecs
+            .transactions
+            .first_tx_index
+            .collect_one(height)
+            .data()?;
+        let tx: bitcoin::Transaction =
+            encode::deserialize(&self.transaction_raw_by_index(tx_index)?)
+                .map_err(|_| Error::Internal("Failed to decode coinbase"))?;
+
+        let pool = self
+            .computer()
+            .pools
+            .pool
+            .collect_one(height)
+            .unwrap_or_default();
+        coinbase_layout(tx, height, pool)
+    }
+}
+
+/// Layout of the coinbase `tx` of the block at `height`, mined by `pool`.
+pub(super) fn coinbase_layout(
+    tx: bitcoin::Transaction,
+    height: Height,
+    pool: PoolSlug,
+) -> Result<BlockCoinbase> {
+    let script_sig = &tx.input.first().data()?.script_sig;
+    let tag = CoinbaseTag::from(script_sig.as_bytes());
+
+    let mut merged_mining = Vec::new();
+    if tx
+        .output
+        .iter()
+        .any(|out| BlockCoinbase::is_rsk_tag(&out.script_pubkey))
+    {
+        merged_mining.push(MergedMining::Rsk);
+    }
+    if tag.has_auxpow() {
+        merged_mining.push(MergedMining::Namecoin);
+    }
+
+    Ok(BlockCoinbase {
+        txid: Txid::from(tx.compute_txid()),
+        height,
+        pool,
+        script_sig: script_sig.as_bytes().to_lower_hex_string(),
+        tag: tag.as_str(),
+        extranonce_size: tag.extranonce_size(),
+        payout_count: tx
+            .output
+            .iter()
+            .filter(|out| out.value.to_sat() > 0)
+            .count() as u32,
+        witness_commitment: tx
+            .output
+            .iter()
+            .any(|out| BlockCoinbase::is_witness_commitment(&out.script_pubkey)),
+        merged_mining,
+        outputs: tx.output.into_iter().map(TxOut::from).collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        Amount, OutPoint, ScriptBuf, Sequence, WPubkeyHash, Witness, absolute::LockTime,
+        hashes::Hash, script::Builder, transaction::Version,
+    };
+
+    use super::*;
+
+    fn op_return(data: &[u8]) -> ScriptBuf {
+        let mut bytes = vec![0x6a, data.len() as u8];
+        bytes.extend_from_slice(data);
+        ScriptBuf::from_bytes(bytes)
+    }
+
+    fn coinbase(output: Vec<(u64, ScriptBuf)>) -> bitcoin::Transaction {
+        bitcoin::Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![bitcoin::TxIn {
+                previous_output: OutPoint::null(),
+                script_sig: Builder::new()
+                    .push_int(840_000)
+                    .push_slice(b"/test pool/")
+                    .into_script(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: output
+                .into_iter()
+                .map(|(sats, script_pubkey)| bitcoin::TxOut {
+                    value: Amount::from_sat(sats),
+                    script_pubkey,
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn layout_reads_commitment_merged_mining_and_payouts() {
+        let mut commitment = vec![0xaa, 0x21, 0xa9, 0xed];
+        commitment.extend([0; 32]);
+        let mut rsk = b"RSKBLOCK:".to_vec();
+        rsk.extend([1; 32]);
+        let tx = coinbase(vec![
+            (
+                312_500_000,
+                ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([7; 20])),
+            ),
+            (0, op_return(&commitment)),
+            (0, op_return(&rsk)),
+        ]);
+        let txid = Txid::from(tx.compute_txid());
+
+        let layout = coinbase_layout(tx, Height::new(840_000), PoolSlug::default()).unwrap();
+        assert_eq!(layout.txid, txid);
+        assert_eq!(layout.height, Height::new(840_000));
+        assert_eq!(layout.payout_count, 1);
+        assert!(layout.witness_commitment);
+        assert_eq!(layout.merged_mining, vec![MergedMining::Rsk]);
+        assert_eq!(layout.outputs.len(), 3);
+        assert!(layout.tag.contains("/test pool/"));
+    }
+
+    #[test]
+    fn layout_without_commitment_or_tags() {
+        let tx = coinbase(vec![(
+            5_000_000_000,
+            ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([7; 20])),
+        )]);
+        let layout = coinbase_layout(tx, Height::new(1), PoolSlug::default()).unwrap();
+        assert!(!layout.witness_commitment);
+        assert!(layout.merged_mining.is_empty());
+    }
+}
@@ -5,7 +5,7 @@ use bitcoin::hex::DisplayHex;
 use brk_error::{Error, OptionData, Result};
 use brk_types::{
     BlockExtras, BlockHash, BlockHashPrefix, BlockHeader, BlockInfo, BlockInfoV1, BlockPool,
-    FeeRate, Height, PoolSlug, Sats, Timestamp, TxIndex, VSize, pools,
+    FeeRate, Height, PoolSlug, Sats, Timestamp, TxIndex, VSize, network, pools,
 };
 use vecdb::{ReadableVec, VecIndex};
 
@@ -37,10 +37,12 @@ struct Coinbase {
 }
 
 impl Query {
-    /// Block by hash. Unknown hash → 404 via `height_by_hash`.
+    /// Block by hash, archived stale blocks included. Unknown hash → 404.
     pub fn block(&self, hash: &BlockHash) -> Result<BlockInfo> {
-        let height = self.height_by_hash(hash)?;
-        self.block_by_height(height)
+        match self.height_by_hash(hash) {
+            Err(Error::NotFound(_)) => self.stale_block_info(hash),
+            height => self.block_by_height(height?),
+        }
     }
 
     /// Block by height. Height past tip (or pre-genesis) → `OutOfRange`.
@@ -70,7 +72,10 @@ impl Query {
     /// Hex-encoded 80-byte block header. Decode-then-encode roundtrip
     /// doubles as a corruption check on the on-disk bytes.
     pub fn block_header_hex(&self, hash: &BlockHash) -> Result<String> {
-        let height = self.height_by_hash(hash)?;
+        let height = match self.height_by_hash(hash) {
+            Err(Error::NotFound(_)) => return self.stale_block_header_hex(hash),
+            height => height?,
+        };
         if height >= self.safe_lengths().height {
             return Err(Error::OutOfRange("Block height out of range".into()));
         }
@@ -183,6 +188,7 @@ impl Query {
                 weight: weights[i],
                 previous_block_hash: header.previous_block_hash,
                 median_time,
+                status: None,
             });
         }
 
@@ -399,7 +405,7 @@ impl Query {
             let pool_slug = pool_slugs[i];
             let pool = all_pools.get(pool_slug);
 
-            let miner_names = if pool_slug == PoolSlug::Ocean {
+            let miner_names = if pool_slug == PoolSlug::OCEAN {
                 Self::parse_datum_miner_names(&scriptsig_bytes)
             } else {
                 None
@@ -422,6 +428,7 @@ impl Query {
                 weight,
                 previous_block_hash: header.previous_block_hash,
                 median_time,
+                status: None,
             };
 
             let total_input_amt = input_volumes[i];
@@ -685,7 +692,7 @@ impl Query {
             .output
             .iter()
             .filter_map(|output| {
-                bitcoin::Address::from_script(&output.script_pubkey, bitcoin::Network::Bitcoin)
+                bitcoin::Address::from_script(&output.script_pubkey, network())
                     .ok()
                     .map(|a| a.to_string())
             })
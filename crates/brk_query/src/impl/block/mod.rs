@@ -1,5 +1,8 @@
+mod coinbase;
 mod info;
 mod raw;
+mod reorgs;
+mod stale;
 mod status;
 mod timestamp;
 mod txs;
@@ -6,8 +6,10 @@ use crate::Query;
 
 impl Query {
     pub fn block_raw(&self, hash: &BlockHash) -> Result<Vec<u8>> {
-        let height = self.height_by_hash(hash)?;
-        self.block_raw_by_height(height)
+        match self.height_by_hash(hash) {
+            Err(Error::NotFound(_)) => self.stale_block_raw(hash),
+            height => self.block_raw_by_height(height?),
+        }
     }
 
     fn block_raw_by_height(&self, height: Height) -> Result<Vec<u8>> {
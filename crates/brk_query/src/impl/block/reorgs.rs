@@ -0,0 +1,12 @@
+use brk_types::Reorg;
+
+use crate::Query;
+
+impl Query {
+    /// Reorgs the indexer rolled back, most recent first.
+    pub fn reorgs(&self) -> Vec<Reorg> {
+        let mut reorgs = self.indexer().reorgs.all();
+        reorgs.reverse();
+        reorgs
+    }
+}
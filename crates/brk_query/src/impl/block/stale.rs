@@ -0,0 +1,298 @@
+//! Orphaned blocks archived by the indexer's reorg journal. The
+//! hash-keyed block endpoints fall back to them once the best chain no
+//! longer has the hash.
+
+use bitcoin::consensus::encode;
+use brk_error::{Error, Result};
+use brk_types::{
+    Addr, BlockCoinbase, BlockHash, BlockHeader, BlockInfo, BlockStatus, BlockTxIndex,
+    CoinbaseTag, RawLockTime, SigOps, Timestamp, Transaction, TxIn, TxOut, TxStatus, Txid, Vout,
+    Weight, pools,
+};
+use vecdb::{ReadableVec, VecIndex};
+
+use crate::Query;
+
+use super::{
+    super::raw_tx::{Prevouts, outpoint},
+    coinbase::coinbase_layout,
+};
+
+impl Query {
+    pub(super) fn stale_block_info(&self, hash: &BlockHash) -> Result<BlockInfo> {
+        let (reorg, height) = self
+            .indexer()
+            .reorgs
+            .orphaning(hash)
+            .ok_or_else(not_found)?;
+        let bytes = self.stale_block_raw(hash)?;
+        let block = decode_block(&bytes)?;
+        let header = BlockHeader::from(block.header);
+
+        // Median time past over the best chain up to the fork, then the
+        // orphaned branch up to the block.
+        let start = height.to_usize().saturating_sub(10);
+        let fork_end = reorg.fork_height.to_usize() + 1;
+        let mut window = if start < fork_end {
+            self.indexer()
+                .vecs
+                .blocks
+                .timestamp
+                .collect_range_at(start, fork_end)
+        } else {
+            Vec::new()
+        };
+        window.extend(
+            reorg
+                .orphaned
+                .iter()
+                .filter(|b| b.height <= height && b.height.to_usize() >= start)
+                .map(|b| b.timestamp),
+        );
+        window.sort_unstable();
+
+        Ok(BlockInfo {
+            id: *hash,
+            height,
+            version: header.version,
+            timestamp: Timestamp::from(block.header.time),
+            bits: header.bits,
+            nonce: header.nonce,
+            difficulty: block.header.difficulty_float(),
+            merkle_root: header.merkle_root,
+            tx_count: block.txdata.len() as u32,
+            size: bytes.len() as u64,
+            weight: Weight::from(block.weight()),
+            previous_block_hash: header.previous_block_hash,
+            median_time: window[window.len() / 2],
+            status: Some(BlockStatus::not_in_best_chain()),
+        })
+    }
+
+    pub(super) fn stale_block_raw(&self, hash: &BlockHash) -> Result<Vec<u8>> {
+        self.indexer()
+            .reorgs
+            .stale_block(hash)
+            .ok_or_else(not_found)
+    }
+
+    pub(super) fn stale_block_header_hex(&self, hash: &BlockHash) -> Result<String> {
+        let block = decode_block(&self.stale_block_raw(hash)?)?;
+        Ok(encode::serialize_hex(&block.header))
+    }
+
+    pub(super) fn stale_block_txids(&self, hash: &BlockHash) -> Result<Vec<Txid>> {
+        let block = decode_block(&self.stale_block_raw(hash)?)?;
+        Ok(block
+            .txdata
+            .iter()
+            .map(|tx| Txid::from(tx.compute_txid()))
+            .collect())
+    }
+
+    /// Up to `count` txs of the stale block from `start_index`, with
+    /// the prevouts the best chain, the mempool or the block itself
+    /// still know. Unconfirmed, since the block left the chain.
+    pub(super) fn stale_block_txs(
+        &self,
+        hash: &BlockHash,
+        start_index: BlockTxIndex,
+        count: u32,
+    ) -> Result<Vec<Transaction>> {
+        let block = decode_block(&self.stale_block_raw(hash)?)?;
+        let start = usize::from(start_index);
+        if start >= block.txdata.len() {
+            return Err(Error::OutOfRange(
+                "start index past last transaction in block".into(),
+            ));
+        }
+        let end = (start + count as usize).min(block.txdata.len());
+
+        let mut prevouts = block_outputs(&block.txdata[..end]);
+        block.txdata[start..end]
+            .iter()
+            .map(|tx| {
+                if !tx.is_coinbase() {
+                    for (key, prevout) in self.resolve_prevouts(tx)? {
+                        prevouts.entry(key).or_insert(prevout);
+                    }
+                }
+                stale_transaction(tx, &prevouts)
+            })
+            .collect()
+    }
+
+    /// Coinbase layout of the stale block, attributed from its own tag
+    /// and payout addresses.
+    pub(super) fn stale_block_coinbase(&self, hash: &BlockHash) -> Result<BlockCoinbase> {
+        let height = self
+            .indexer()
+            .reorgs
+            .orphaned_height(hash)
+            .ok_or_else(not_found)?;
+        let block = decode_block(&self.stale_block_raw(hash)?)?;
+        let tx = block
+            .txdata
+            .into_iter()
+            .next()
+            .ok_or(Error::Internal("Stale block without a coinbase"))?;
+        let tag = CoinbaseTag::from(tx.input.first().map_or(&[][..], |i| i.script_sig.as_bytes()));
+        let addrs: Vec<Addr> = tx
+            .output
+            .iter()
+            .filter_map(|out| Addr::try_from(&out.script_pubkey).ok())
+            .collect();
+        let pool = pools().identify(height, &tag.as_str(), &addrs).slug;
+        coinbase_layout(tx, height, pool)
+    }
+}
+
+fn not_found() -> Error {
+    Error::NotFound("Block not found".into())
+}
+
+/// Outputs of `txs` by outpoint, for spends chained within a block.
+fn block_outputs(txs: &[bitcoin::Transaction]) -> Prevouts {
+    let mut outputs = Prevouts::default();
+    for tx in txs {
+        let txid = Txid::from(tx.compute_txid());
+        for (vout, out) in tx.output.iter().enumerate() {
+            if let Ok(vout) = u16::try_from(vout) {
+                outputs.insert((txid, Vout::from(vout)), TxOut::from(out.clone()));
+            }
+        }
+    }
+    outputs
+}
+
+/// `tx` as served for a stale block: no index, unconfirmed, with a fee
+/// only when every prevout is known.
+fn stale_transaction(tx: &bitcoin::Transaction, prevouts: &Prevouts) -> Result<Transaction> {
+    let is_coinbase = tx.is_coinbase();
+    let input = tx
+        .input
+        .iter()
+        .map(|txin| {
+            let (txid, vout, prevout) = if is_coinbase {
+                (Txid::COINBASE, Vout::MAX, None)
+            } else {
+                let key = outpoint(txin)?;
+                (key.0, key.1, prevouts.get(&key).cloned())
+            };
+            Ok(TxIn {
+                is_coinbase,
+                prevout,
+                txid,
+                vout,
+                script_sig: txin.script_sig.clone(),
+                script_sig_asm: (),
+                witness: txin.witness.clone().into(),
+                sequence: txin.sequence.into(),
+                inner_redeem_script_asm: (),
+                inner_witness_script_asm: (),
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    let mut transaction = Transaction {
+        index: None,
+        txid: tx.compute_txid().into(),
+        version: tx.version.into(),
+        lock_time: RawLockTime::from(tx.lock_time),
+        input,
+        output: tx.output.iter().cloned().map(TxOut::from).collect(),
+        total_size: tx.total_size(),
+        weight: Weight::from(tx.weight()),
+        total_sigop_cost: SigOps::ZERO,
+        fee: Default::default(),
+        status: TxStatus::UNCONFIRMED,
+    };
+    transaction.refresh_sigops();
+    transaction.compute_fee();
+    Ok(transaction)
+}
+
+fn decode_block(bytes: &[u8]) -> Result<bitcoin::Block> {
+    encode::deserialize(bytes).map_err(|_| Error::Internal("Failed to decode stale block"))
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        Amount, OutPoint, ScriptBuf, Sequence, WPubkeyHash, Witness, absolute::LockTime,
+        hashes::Hash, transaction::Version,
+    };
+    use brk_types::Sats;
+
+    use super::*;
+
+    fn p2wpkh() -> ScriptBuf {
+        ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([7; 20]))
+    }
+
+    fn tx(previous_output: OutPoint, values: &[u64]) -> bitcoin::Transaction {
+        bitcoin::Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![bitcoin::TxIn {
+                previous_output,
+                script_sig: ScriptBuf::from_bytes(vec![0x51]),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: values
+                .iter()
+                .map(|&sats| bitcoin::TxOut {
+                    value: Amount::from_sat(sats),
+                    script_pubkey: p2wpkh(),
+                })
+                .collect(),
+        }
+    }
+
+    /// Coinbase, a spend of an unknown prevout, and a spend of the
+    /// coinbase's second output.
+    fn block() -> Vec<bitcoin::Transaction> {
+        let coinbase = tx(OutPoint::null(), &[100_000, 50_000]);
+        let orphan = tx(
+            OutPoint::new(bitcoin::Txid::from_byte_array([9; 32]), 0),
+            &[1_000],
+        );
+        let child = tx(OutPoint::new(coinbase.compute_txid(), 1), &[49_000]);
+        vec![coinbase, orphan, child]
+    }
+
+    #[test]
+    fn block_outputs_index_every_output() {
+        let txs = block();
+        let outputs = block_outputs(&txs);
+        assert_eq!(outputs.len(), 4);
+        let key = (Txid::from(txs[0].compute_txid()), Vout::from(1u16));
+        assert_eq!(outputs[&key].value, Sats::from(50_000u64));
+    }
+
+    #[test]
+    fn stale_transactions_resolve_in_block_prevouts() {
+        let txs = block();
+        let prevouts = block_outputs(&txs);
+
+        let coinbase = stale_transaction(&txs[0], &prevouts).unwrap();
+        assert!(coinbase.input[0].is_coinbase);
+        assert!(coinbase.input[0].prevout.is_none());
+        assert_eq!(coinbase.fee, Sats::ZERO);
+        assert!(!coinbase.status.confirmed);
+        assert!(coinbase.index.is_none());
+
+        let orphan = stale_transaction(&txs[1], &prevouts).unwrap();
+        assert!(orphan.input[0].prevout.is_none());
+        assert_eq!(orphan.fee, Sats::ZERO);
+
+        let child = stale_transaction(&txs[2], &prevouts).unwrap();
+        assert_eq!(child.txid, Txid::from(txs[2].compute_txid()));
+        assert_eq!(
+            child.input[0].prevout.as_ref().map(|p| p.value),
+            Some(Sats::from(50_000u64))
+        );
+        assert_eq!(child.fee, Sats::from(1_000u64));
+    }
+}
@@ -1,12 +1,16 @@
-use brk_error::{OptionData, Result};
+use brk_error::{Error, OptionData, Result};
 use brk_types::{BlockHash, BlockStatus, Height};
 
 use crate::Query;
 
 impl Query {
     pub fn block_status(&self, hash: &BlockHash) -> Result<BlockStatus> {
-        let height = self.height_by_hash(hash)?;
-        self.block_status_by_height(height)
+        match self.height_by_hash(hash) {
+            Err(Error::NotFound(_)) => self
+                .stale_block_raw(hash)
+                .map(|_| BlockStatus::not_in_best_chain()),
+            height => self.block_status_by_height(height?),
+        }
     }
 
     fn block_status_by_height(&self, height: Height) -> Result<BlockStatus> {
@@ -13,25 +13,31 @@ use vecdb::{ReadableVec, VecIndex};
 use crate::Query;
 
 impl Query {
-    /// All txids in the block, canonical order (coinbase first).
-    /// `NotFound` if the hash is unknown (or only collides on the 8-byte
-    /// prefix), `OutOfRange` if the resolved height is past the indexed tip.
-    /// Unpaginated by design.
+    /// All txids in the block, canonical order (coinbase first), archived
+    /// stale blocks included. `NotFound` if the hash is unknown (or only
+    /// collides on the 8-byte prefix), `OutOfRange` if the resolved height
+    /// is past the indexed tip. Unpaginated by design.
     pub fn block_txids(&self, hash: &BlockHash) -> Result<Vec<Txid>> {
-        let height = self.height_by_hash(hash)?;
-        self.block_txids_by_height(height)
+        match self.height_by_hash(hash) {
+            Err(Error::NotFound(_)) => self.stale_block_txids(hash),
+            height => self.block_txids_by_height(height?),
+        }
     }
 
     /// Up to `count` transactions from the block, starting at the in-block
     /// offset `start_index` (0 = coinbase). `OutOfRange` when `start_index`
     /// is past the last tx in the block. Caller (route layer) sets `count`.
+    /// Archived stale blocks are served unconfirmed.
     pub fn block_txs(
         &self,
         hash: &BlockHash,
         start_index: BlockTxIndex,
         count: u32,
     ) -> Result<Vec<Transaction>> {
-        let height = self.height_by_hash(hash)?;
+        let height = match self.height_by_hash(hash) {
+            Err(Error::NotFound(_)) => return self.stale_block_txs(hash, start_index, count),
+            height => height?,
+        };
         let (first, tx_count) = self.block_tx_range(height)?;
         let start: usize = start_index.into();
         if start >= tx_count {
@@ -51,8 +57,14 @@ impl Query {
     /// the 8-byte prefix; `OutOfRange` if `index` is past the last tx in
     /// the block.
     pub fn block_txid_at_index(&self, hash: &BlockHash, index: BlockTxIndex) -> Result<Txid> {
-        let height = self.height_by_hash(hash)?;
-        self.block_txid_at_index_by_height(height, index.into())
+        match self.height_by_hash(hash) {
+            Err(Error::NotFound(_)) => self
+                .stale_block_txids(hash)?
+                .get(usize::from(index))
+                .copied()
+                .ok_or(Error::OutOfRange("Transaction index out of range".into())),
+            height => self.block_txid_at_index_by_height(height?, index.into()),
+        }
     }
 
     // === Helper methods ===
@@ -0,0 +1,366 @@
+//! Parquet and Arrow IPC encodings of a series query.
+//!
+//! Each series becomes one typed column next to the index column, so
+//! `Sats` stay `UInt64`, `StoredF32` stays `Float32` and so on. Values are
+//! read through the vec's JSON writer, the only typed access a
+//! `dyn AnyExportableVec` offers, and decoded straight into Arrow arrays.
+
+use std::sync::Arc;
+
+use arrow_array::{
+    ArrayRef, BooleanArray, Date32Array, Float32Array, Float64Array, Int8Array, Int16Array,
+    Int32Array, Int64Array, RecordBatch, StringArray, TimestampSecondArray, UInt8Array,
+    UInt16Array, UInt32Array, UInt64Array,
+};
+use arrow_ipc::writer::FileWriter;
+use arrow_schema::{Field, Schema};
+use brk_error::Result;
+use brk_types::{Date, Index, Timestamp};
+use parquet::{
+    arrow::ArrowWriter,
+    basic::{Compression, ZstdLevel},
+    file::properties::WriterProperties,
+};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use vecdb::AnyExportableVec;
+
+use crate::Query;
+
+const SECONDS_PER_DAY: u32 = 86_400;
+
+/// Arrow type a series' values are decoded into.
+#[derive(Clone, Copy)]
+enum ColumnType {
+    UInt8,
+    UInt16,
+    UInt32,
+    UInt64,
+    Int8,
+    Int16,
+    Int32,
+    Int64,
+    Float32,
+    Float64,
+    Boolean,
+    Timestamp,
+    Date,
+    /// Unlisted type: numbers are widened to 64 bits, anything else is
+    /// kept as its JSON text.
+    Inferred,
+}
+
+impl ColumnType {
+    /// Matches on the short type name, unwrapping `Close<Dollars>` and
+    /// the truncated `Dollars>` form to the inner type.
+    fn of(vec: &dyn AnyExportableVec) -> Self {
+        let name = vec.value_type_to_string();
+        let name = name.rsplit('<').next().unwrap_or(name);
+        match name.trim_end_matches('>') {
+            "StoredU8" | "TxVersion" | "Halving" | "Month3" | "Month6" | "Year1" | "Year10" => {
+                Self::UInt8
+            }
+            "StoredU16" | "BasisPoints16" | "Vout" | "Epoch" | "Day1" | "Week1" | "Month1"
+            | "Year" => Self::UInt16,
+            "StoredU32" | "BasisPoints32" | "CentsCompact" | "Height" | "SigOps"
+            | "RawLockTime" | "TxIndex" | "TypeIndex" => Self::UInt32,
+            "StoredU64" | "Sats" | "Cents" | "Weight" | "VSize" | "TxOutIndex" => Self::UInt64,
+            "StoredI8" => Self::Int8,
+            "StoredI16" | "BasisPointsSigned16" => Self::Int16,
+            "BasisPointsSigned32" => Self::Int32,
+            "StoredI64" | "SatsSigned" | "CentsSigned" => Self::Int64,
+            "StoredF32" => Self::Float32,
+            "StoredF64" | "Dollars" | "Bitcoin" | "FeeRate" | "SatsFract" => Self::Float64,
+            "StoredBool" => Self::Boolean,
+            "Timestamp" => Self::Timestamp,
+            "Date" => Self::Date,
+            _ => Self::Inferred,
+        }
+    }
+}
+
+impl Query {
+    pub(crate) fn columns_to_parquet(
+        index: Index,
+        columns: &[&dyn AnyExportableVec],
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<u8>> {
+        Self::batch_to_parquet(&Self::columns_to_record_batch(index, columns, start, end)?)
+    }
+
+    pub(crate) fn columns_to_arrow(
+        index: Index,
+        columns: &[&dyn AnyExportableVec],
+        start: usize,
+        end: usize,
+    ) -> Result<Vec<u8>> {
+        Self::batch_to_arrow(&Self::columns_to_record_batch(index, columns, start, end)?)
+    }
+
+    pub(crate) fn batch_to_parquet(batch: &RecordBatch) -> Result<Vec<u8>> {
+        let props = WriterProperties::builder()
+            .set_compression(Compression::ZSTD(ZstdLevel::default()))
+            .build();
+        let mut buf = Vec::new();
+        let mut writer = ArrowWriter::try_new(&mut buf, batch.schema(), Some(props))?;
+        writer.write(batch)?;
+        writer.close()?;
+        Ok(buf)
+    }
+
+    pub(crate) fn batch_to_arrow(batch: &RecordBatch) -> Result<Vec<u8>> {
+        let mut buf = Vec::new();
+        let mut writer = FileWriter::try_new(&mut buf, &batch.schema())?;
+        writer.write(batch)?;
+        writer.finish()?;
+        drop(writer);
+        Ok(buf)
+    }
+
+    fn columns_to_record_batch(
+        index: Index,
+        columns: &[&dyn AnyExportableVec],
+        start: usize,
+        end: usize,
+    ) -> Result<RecordBatch> {
+        let mut buf = Vec::new();
+        let series = columns
+            .iter()
+            .map(|column| {
+                buf.clear();
+                column.write_json(Some(start), Some(end), &mut buf)?;
+                Ok((
+                    column.name(),
+                    Self::json_to_array(ColumnType::of(*column), &buf)?,
+                ))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Self::record_batch(index, start, end, series)
+    }
+
+    /// Index column first, then a UTC `timestamp` column for date-based
+    /// indexes, then one column per series.
+    pub(crate) fn record_batch(
+        index: Index,
+        start: usize,
+        end: usize,
+        series: Vec<(&str, ArrayRef)>,
+    ) -> Result<RecordBatch> {
+        let mut fields = Vec::with_capacity(series.len() + 2);
+        let mut arrays: Vec<ArrayRef> = Vec::with_capacity(series.len() + 2);
+
+        let mut push = |name: &str, array: ArrayRef| {
+            fields.push(Field::new(
+                name,
+                array.data_type().clone(),
+                array.null_count() > 0,
+            ));
+            arrays.push(array);
+        };
+
+        push(
+            index.name(),
+            Arc::new(UInt64Array::from_iter_values(
+                (start..end).map(|i| i as u64),
+            )),
+        );
+
+        if index.is_date_based() {
+            let timestamps = (start..end)
+                .map(|i| index.index_to_timestamp(i).map(|ts| i64::from(*ts)))
+                .collect::<Vec<_>>();
+            push(
+                "timestamp",
+                Arc::new(TimestampSecondArray::from(timestamps).with_timezone("UTC")),
+            );
+        }
+
+        for (name, array) in series {
+            push(name, array);
+        }
+
+        Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+    }
+
+    /// Decodes a JSON array written by `write_json`. Non-finite floats and
+    /// other missing values were written as `null` and become nulls.
+    fn json_to_array(column_type: ColumnType, json: &[u8]) -> Result<ArrayRef> {
+        let array: ArrayRef = match column_type {
+            ColumnType::UInt8 => Arc::new(UInt8Array::from(decode::<u8>(json)?)),
+            ColumnType::UInt16 => Arc::new(UInt16Array::from(decode::<u16>(json)?)),
+            ColumnType::UInt32 => Arc::new(UInt32Array::from(decode::<u32>(json)?)),
+            ColumnType::UInt64 => Arc::new(UInt64Array::from(decode::<u64>(json)?)),
+            ColumnType::Int8 => Arc::new(Int8Array::from(decode::<i8>(json)?)),
+            ColumnType::Int16 => Arc::new(Int16Array::from(decode::<i16>(json)?)),
+            ColumnType::Int32 => Arc::new(Int32Array::from(decode::<i32>(json)?)),
+            ColumnType::Int64 => Arc::new(Int64Array::from(decode::<i64>(json)?)),
+            ColumnType::Float32 => Arc::new(Float32Array::from(decode::<f32>(json)?)),
+            ColumnType::Float64 => Arc::new(Float64Array::from(decode::<f64>(json)?)),
+            ColumnType::Boolean => Arc::new(BooleanArray::from(decode::<bool>(json)?)),
+            ColumnType::Timestamp => {
+                Arc::new(TimestampSecondArray::from(decode::<i64>(json)?).with_timezone("UTC"))
+            }
+            ColumnType::Date => Arc::new(Date32Array::from(
+                decode::<Date>(json)?
+                    .into_iter()
+                    .map(|date| date.map(|date| (*Timestamp::from(date) / SECONDS_PER_DAY) as i32))
+                    .collect::<Vec<_>>(),
+            )),
+            ColumnType::Inferred => Self::inferred_array(serde_json::from_slice(json)?),
+        };
+        Ok(array)
+    }
+
+    /// `UInt64` if every value fits, else `Int64`, else `Float64`, else
+    /// the JSON text of each value.
+    fn inferred_array(values: Vec<Value>) -> ArrayRef {
+        let present = || values.iter().filter(|v| !v.is_null());
+        if present().all(Value::is_u64) {
+            Arc::new(UInt64Array::from(
+                values.iter().map(Value::as_u64).collect::<Vec<_>>(),
+            ))
+        } else if present().all(Value::is_i64) {
+            Arc::new(Int64Array::from(
+                values.iter().map(Value::as_i64).collect::<Vec<_>>(),
+            ))
+        } else if present().all(Value::is_number) {
+            Arc::new(Float64Array::from(
+                values.iter().map(Value::as_f64).collect::<Vec<_>>(),
+            ))
+        } else {
+            Arc::new(StringArray::from(
+                values
+                    .iter()
+                    .map(|v| match v {
+                        Value::Null => None,
+                        Value::String(s) => Some(s.clone()),
+                        v => Some(v.to_string()),
+                    })
+                    .collect::<Vec<_>>(),
+            ))
+        }
+    }
+}
+
+fn decode<T: DeserializeOwned>(json: &[u8]) -> Result<Vec<Option<T>>> {
+    Ok(serde_json::from_slice(json)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use arrow_array::Array;
+    use arrow_ipc::reader::FileReader;
+    use arrow_schema::{DataType, TimeUnit};
+    use parquet::arrow::arrow_reader::{
+        ParquetRecordBatchReader, ParquetRecordBatchReaderBuilder,
+    };
+
+    use super::*;
+
+    /// The parquet reader wants a file, not a buffer.
+    fn read_parquet(bytes: Vec<u8>) -> ParquetRecordBatchReaderBuilder<std::fs::File> {
+        static COUNT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "brk_columnar_{}_{}.parquet",
+            std::process::id(),
+            COUNT.fetch_add(1, Ordering::Relaxed)
+        ));
+        std::fs::write(&path, bytes).unwrap();
+        ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(&path).unwrap()).unwrap()
+    }
+
+    /// Decodes `json` as `column_type`, writes it as Parquet and Arrow and
+    /// checks both read back unchanged.
+    fn roundtrip(column_type: ColumnType, json: &str, data_type: DataType) -> ArrayRef {
+        let array = Query::json_to_array(column_type, json.as_bytes()).unwrap();
+        assert_eq!(array.data_type(), &data_type, "{json}");
+
+        let batch =
+            Query::record_batch(Index::Height, 0, array.len(), vec![("v", array.clone())])
+                .unwrap();
+
+        let parquet = Query::batch_to_parquet(&batch).unwrap();
+        let mut reader: ParquetRecordBatchReader = read_parquet(parquet)
+            .build()
+            .unwrap();
+        let read = reader
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(read.column(1).as_ref(), array.as_ref(), "parquet {json}");
+
+        let arrow = Query::batch_to_arrow(&batch).unwrap();
+        let read = FileReader::try_new(std::io::Cursor::new(arrow), None)
+            .unwrap()
+            .next()
+            .unwrap()
+            .unwrap();
+        assert_eq!(read.column(1).as_ref(), array.as_ref(), "arrow {json}");
+
+        array
+    }
+
+    #[test]
+    fn unsigned_columns_roundtrip() {
+        roundtrip(ColumnType::UInt8, "[0,255,null]", DataType::UInt8);
+        roundtrip(ColumnType::UInt16, "[0,65535]", DataType::UInt16);
+        roundtrip(ColumnType::UInt32, "[840000,null]", DataType::UInt32);
+        roundtrip(ColumnType::UInt64, "[2100000000000000,0]", DataType::UInt64);
+    }
+
+    #[test]
+    fn signed_columns_roundtrip() {
+        roundtrip(ColumnType::Int8, "[-128,127]", DataType::Int8);
+        roundtrip(ColumnType::Int16, "[-1,null]", DataType::Int16);
+        roundtrip(ColumnType::Int32, "[-2147483648,7]", DataType::Int32);
+        roundtrip(ColumnType::Int64, "[-5000000000,5]", DataType::Int64);
+    }
+
+    #[test]
+    fn float_columns_roundtrip() {
+        roundtrip(ColumnType::Float32, "[1.5,null,-0.25]", DataType::Float32);
+        roundtrip(ColumnType::Float64, "[65000.12,null]", DataType::Float64);
+    }
+
+    #[test]
+    fn boolean_column_roundtrips_from_stored_bool_json() {
+        let array = roundtrip(ColumnType::Boolean, "[true,false,null]", DataType::Boolean);
+        let array = array.as_any().downcast_ref::<BooleanArray>().unwrap();
+        assert!(array.value(0));
+        assert!(!array.value(1));
+        assert!(array.is_null(2));
+    }
+
+    #[test]
+    fn time_columns_roundtrip() {
+        roundtrip(
+            ColumnType::Timestamp,
+            "[1231006505,null]",
+            DataType::Timestamp(TimeUnit::Second, Some("UTC".into())),
+        );
+        let array = roundtrip(ColumnType::Date, r#"["2009-01-03",null]"#, DataType::Date32);
+        let array = array.as_any().downcast_ref::<Date32Array>().unwrap();
+        assert_eq!(array.value(0), 14_247);
+    }
+
+    #[test]
+    fn inferred_columns_roundtrip() {
+        roundtrip(ColumnType::Inferred, "[1,2,null]", DataType::UInt64);
+        roundtrip(ColumnType::Inferred, "[-1,2]", DataType::Int64);
+        roundtrip(ColumnType::Inferred, "[0.5,2]", DataType::Float64);
+        roundtrip(ColumnType::Inferred, r#"["a",{"b":1},null]"#, DataType::Utf8);
+    }
+
+    #[test]
+    fn empty_query_is_a_valid_file() {
+        let parquet = Query::columns_to_parquet(Index::Day1, &[], 0, 0).unwrap();
+        assert_eq!(read_parquet(parquet).schema().fields().len(), 2);
+
+        let arrow = Query::columns_to_arrow(Index::Height, &[], 0, 0).unwrap();
+        let reader = FileReader::try_new(std::io::Cursor::new(arrow), None).unwrap();
+        assert_eq!(reader.schema().fields().len(), 1);
+    }
+}
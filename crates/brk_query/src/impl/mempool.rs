@@ -1,10 +1,11 @@
 use crate::Query;
 use brk_error::{Error, Result};
-use brk_mempool::{Mempool, RbfForTx, RbfNode};
+use brk_mempool::{MAX_TARGET, Mempool, RbfForTx, RbfNode};
 use brk_types::{
-    BlockTemplate, BlockTemplateDiff, CheckedSub, FeeRate, MempoolBlock, MempoolInfo,
-    MempoolRecentTx, NextBlockHash, OutputType, RbfResponse, RbfTx, RecommendedFees,
-    ReplacementNode, Sats, Timestamp, TxOut, TxOutIndex, Txid, TxidPrefix, TypeIndex, Vout,
+    BlockAudit, BlockHash, BlockTemplate, BlockTemplateDiff, CheckedSub, FeeEstimate, FeeRate,
+    Height, MempoolBlock, MempoolInfo, MempoolRecentTx, NextBlockHash, OutputType, RbfResponse,
+    RbfTx, RecommendedFees, ReplacementNode, Sats, Timestamp, TxOut, TxOutIndex, Txid, TxidPrefix,
+    TypeIndex, Vout,
 };
 use rustc_hash::FxHashMap;
 
@@ -27,6 +28,35 @@ impl Query {
         self.require_mempool().map(|m| m.fees())
     }
 
+    /// Fee rates that historically confirmed within `target` blocks,
+    /// learned from past mempool txs.
+    pub fn fee_estimate(&self, target: u32) -> Result<FeeEstimate> {
+        if !(1..=MAX_TARGET).contains(&target) {
+            return Err(Error::Parse(format!(
+                "target must be between 1 and {MAX_TARGET}, got {target}"
+            )));
+        }
+        Ok(self.require_mempool()?.fee_estimate(target))
+    }
+
+    /// Feeds every block from `from` up to the indexed tip to the
+    /// mempool's fee estimator. No-op without a mempool. Only the last
+    /// `MAX_TARGET` blocks can hold txs the estimator still measures,
+    /// so a first sync from genesis doesn't walk the whole chain.
+    pub fn confirm_mempool_txs(&self, from: Height) -> Result<()> {
+        let Some(mempool) = self.mempool() else {
+            return Ok(());
+        };
+        let tip = self.height();
+        let from = from.max(Height::new((*tip).saturating_sub(MAX_TARGET)));
+        for height in *from..=*tip {
+            let height = Height::new(height);
+            let txids = self.block_txids_by_height(height)?;
+            mempool.confirm(height, txids.get(1..).unwrap_or_default());
+        }
+        Ok(())
+    }
+
     pub fn mempool_blocks(&self) -> Result<Vec<MempoolBlock>> {
         let mempool = self.require_mempool()?;
         Ok(mempool
@@ -177,4 +207,13 @@ impl Query {
             .block_template_diff(since)
             .ok_or_else(|| Error::NotFound(format!("unknown since hash: {since}")))
     }
+
+    /// Block `hash` compared against the template projected for it.
+    /// `NotFound` for blocks mined while the mempool driver wasn't
+    /// running.
+    pub fn block_audit(&self, hash: &BlockHash) -> Result<BlockAudit> {
+        self.require_mempool()?
+            .block_audit(hash)
+            .ok_or_else(|| Error::NotFound(format!("no audit for block {hash}")))
+    }
 }
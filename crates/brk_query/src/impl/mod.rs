@@ -1,12 +1,18 @@
 mod addr;
 mod block;
+mod columnar;
 mod cpfp;
 mod mempool;
 mod mining;
 mod oracle;
 mod price;
+mod raw_tx;
 mod series;
+mod series_expr;
+mod simulate;
 mod tx;
+mod tx_analysis;
 mod urpd;
 
+pub use addr::AddrWatch;
 pub use series::ResolvedQuery;
@@ -6,7 +6,7 @@ use brk_indexer::Lengths;
 use brk_oracle::{
     Config, HistogramEma, HistogramEmaCompact, HistogramRaw, Oracle, cents_to_bin, sats_to_bin,
 };
-use brk_types::{Day1, Dollars, TxOutIndex};
+use brk_types::{Day1, Dollars, TxOutIndex, is_mainnet};
 use vecdb::{AnyVec, ReadableVec, VecIndex};
 
 use crate::Query;
@@ -108,8 +108,14 @@ impl Query {
 
     /// Tip oracle warmed over the last `window_size` committed blocks, seeded
     /// from the last committed price. Cached per tip height; rebuilt on advance
-    /// or reorg.
+    /// or reorg. 404s off mainnet, where the stored prices are all zero.
     fn cached_oracle(&self) -> Result<Arc<Oracle>> {
+        if !is_mainnet() {
+            return Err(Error::NotFound(
+                "oracle prices are only available on mainnet".to_string(),
+            ));
+        }
+
         let safe = self.safe_lengths();
         let height = safe.height;
 
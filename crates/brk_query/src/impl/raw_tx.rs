@@ -0,0 +1,223 @@
+//! Raw txs supplied by callers rather than read from the chain or the
+//! pool: decoding, prevout resolution and sizing of unsigned inputs.
+//! Shared by the dry-run and the decode/analyze endpoints.
+
+use bitcoin::consensus::encode;
+use brk_error::{Error, Result};
+use brk_types::{OutputType, TxOut, Txid, Vout, Weight};
+use rustc_hash::FxHashMap;
+
+use crate::Query;
+
+/// Segwit marker and flag, once per tx with any witness.
+const WITNESS_HEADER_WEIGHT: u64 = 2;
+
+pub(super) type Prevouts = FxHashMap<(Txid, Vout), TxOut>;
+
+pub(super) fn parse_tx_hex(hex: &str) -> Result<bitcoin::Transaction> {
+    let tx: bitcoin::Transaction = encode::deserialize_hex(hex.trim())
+        .map_err(|e| Error::Parse(format!("invalid tx hex: {e}")))?;
+    for input in &tx.input {
+        outpoint(input)?;
+    }
+    Ok(tx)
+}
+
+/// `(txid, vout)` spent by `input`. Errors on a vout past `u16`, which
+/// no spendable output has.
+pub(super) fn outpoint(input: &bitcoin::TxIn) -> Result<(Txid, Vout)> {
+    let prev = input.previous_output;
+    let vout = u16::try_from(prev.vout)
+        .map_err(|_| Error::Parse(format!("invalid outpoint {}:{}", prev.txid, prev.vout)))?;
+    Ok((Txid::from(prev.txid), Vout::from(vout)))
+}
+
+impl Query {
+    /// Prevouts of `tx`'s inputs, from live mempool parents first and
+    /// the indexer for the rest. Unknown ones are left out.
+    pub(super) fn resolve_prevouts(&self, tx: &bitcoin::Transaction) -> Result<Prevouts> {
+        let mut prevouts = Prevouts::default();
+        let mut holes = Vec::new();
+        for input in &tx.input {
+            let (txid, vout) = outpoint(input)?;
+            let in_mempool = self.mempool().and_then(|m| {
+                m.with_tx(&txid, |parent| {
+                    parent.output.get(usize::from(vout)).cloned()
+                })
+            });
+            match in_mempool {
+                Some(Some(prevout)) => {
+                    prevouts.insert((txid, vout), prevout);
+                }
+                Some(None) => {}
+                None => holes.push((txid, vout)),
+            }
+        }
+        prevouts.extend(self.indexer_prevout_resolver()(&holes));
+        Ok(prevouts)
+    }
+
+    /// Live mempool txs spending the same outpoints as `tx`, `tx`
+    /// itself excluded.
+    pub(super) fn mempool_conflicts(&self, tx: &bitcoin::Transaction) -> Result<Vec<Txid>> {
+        let Some(mempool) = self.mempool() else {
+            return Ok(Vec::new());
+        };
+        let own = Txid::from(tx.compute_txid());
+        let mut conflicts = Vec::new();
+        for input in &tx.input {
+            let (txid, vout) = outpoint(input)?;
+            if let Some((spender, _)) = mempool.lookup_spender(&txid, vout)
+                && spender != own
+                && !conflicts.contains(&spender)
+            {
+                conflicts.push(spender);
+            }
+        }
+        Ok(conflicts)
+    }
+}
+
+/// Weight of `tx` once signed. Inputs without a scriptSig or witness
+/// get the signature weight of their prevout's type.
+pub(super) fn signed_weight(tx: &bitcoin::Transaction, prevouts: &Prevouts) -> Weight {
+    let mut weight = tx.weight().to_wu();
+    let mut has_witness = tx.input.iter().any(|input| !input.witness.is_empty());
+    for input in &tx.input {
+        if !input.script_sig.is_empty() || !input.witness.is_empty() {
+            continue;
+        }
+        let Some(prevout) = outpoint(input).ok().and_then(|key| prevouts.get(&key)) else {
+            continue;
+        };
+        let (script_sig, witness) = satisfaction_weight(prevout.type_());
+        weight += script_sig;
+        if witness > 0 {
+            weight += witness;
+            if !has_witness {
+                weight += WITNESS_HEADER_WEIGHT;
+                has_witness = true;
+            }
+        }
+    }
+    Weight::from(weight)
+}
+
+/// `(script_sig, witness)` weight an unsigned input of type
+/// `output_type` gains once signed, for the single-key types. Script
+/// spends can't be sized without their script and count as zero.
+fn satisfaction_weight(output_type: OutputType) -> (u64, u64) {
+    match output_type {
+        // <sig> <pubkey>
+        OutputType::P2PKH => (107 * 4, 0),
+        // Items count, then <sig> <pubkey>.
+        OutputType::P2WPKH => (0, 108),
+        // Nested P2WPKH: the redeem script push plus the same witness.
+        OutputType::P2SH => (23 * 4, 108),
+        // Key path: items count, then a Schnorr <sig>.
+        OutputType::P2TR => (0, 66),
+        _ => (0, 0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        Amount, OutPoint, PubkeyHash, ScriptBuf, Sequence, Witness, absolute::LockTime,
+        hashes::Hash, transaction::Version,
+    };
+
+    use super::*;
+
+    fn prev_txid() -> bitcoin::Txid {
+        bitcoin::Txid::from_byte_array([1; 32])
+    }
+
+    fn unsigned(output: ScriptBuf) -> bitcoin::Transaction {
+        bitcoin::Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![bitcoin::TxIn {
+                previous_output: OutPoint::new(prev_txid(), 0),
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::MAX,
+                witness: Witness::new(),
+            }],
+            output: vec![bitcoin::TxOut {
+                value: Amount::from_sat(50_000),
+                script_pubkey: output,
+            }],
+        }
+    }
+
+    fn prevouts(script_pubkey: ScriptBuf) -> Prevouts {
+        let mut prevouts = Prevouts::default();
+        prevouts.insert(
+            (Txid::from(prev_txid()), Vout::from(0u16)),
+            TxOut::from(bitcoin::TxOut {
+                value: Amount::from_sat(100_000),
+                script_pubkey,
+            }),
+        );
+        prevouts
+    }
+
+    fn p2pkh() -> ScriptBuf {
+        ScriptBuf::new_p2pkh(&PubkeyHash::from_byte_array([2; 20]))
+    }
+
+    fn p2wpkh() -> ScriptBuf {
+        ScriptBuf::new_p2wpkh(&bitcoin::WPubkeyHash::from_byte_array([3; 20]))
+    }
+
+    #[test]
+    fn outpoint_rejects_vout_past_u16() {
+        let mut tx = unsigned(p2wpkh());
+        assert!(outpoint(&tx.input[0]).is_ok());
+        tx.input[0].previous_output.vout = u32::from(u16::MAX) + 1;
+        assert!(outpoint(&tx.input[0]).is_err());
+    }
+
+    #[test]
+    fn satisfaction_weight_by_type() {
+        assert_eq!(satisfaction_weight(OutputType::P2PKH), (428, 0));
+        assert_eq!(satisfaction_weight(OutputType::P2WPKH), (0, 108));
+        assert_eq!(satisfaction_weight(OutputType::P2SH), (92, 108));
+        assert_eq!(satisfaction_weight(OutputType::P2TR), (0, 66));
+        assert_eq!(satisfaction_weight(OutputType::P2WSH), (0, 0));
+    }
+
+    #[test]
+    fn signed_weight_matches_a_signed_p2wpkh_spend() {
+        let tx = unsigned(p2wpkh());
+        let mut signed = tx.clone();
+        signed.input[0].witness = Witness::from_slice(&[vec![0u8; 72], vec![0u8; 33]]);
+        assert_eq!(
+            *signed_weight(&tx, &prevouts(p2wpkh())),
+            signed.weight().to_wu()
+        );
+    }
+
+    #[test]
+    fn signed_weight_matches_a_signed_p2pkh_spend() {
+        let tx = unsigned(p2wpkh());
+        let mut signed = tx.clone();
+        signed.input[0].script_sig = bitcoin::script::Builder::new()
+            .push_slice([0u8; 72])
+            .push_slice([0u8; 33])
+            .into_script();
+        assert_eq!(
+            *signed_weight(&tx, &prevouts(p2pkh())),
+            signed.weight().to_wu()
+        );
+    }
+
+    #[test]
+    fn signed_weight_leaves_signed_and_unknown_inputs_alone() {
+        let mut tx = unsigned(p2wpkh());
+        assert_eq!(*signed_weight(&tx, &Prevouts::default()), tx.weight().to_wu());
+
+        tx.input[0].witness = Witness::from_slice(&[vec![0u8; 64]]);
+        assert_eq!(*signed_weight(&tx, &prevouts(p2wpkh())), tx.weight().to_wu());
+    }
+}
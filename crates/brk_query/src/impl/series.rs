@@ -5,8 +5,8 @@ use brk_traversable::TreeNode;
 use brk_types::{
     BlockHashPrefix, CacheClass, Date, DetailedSeriesCount, Epoch, Format, Halving, Height, Index,
     IndexInfo, LegacyValue, Limit, Output, OutputLegacy, PaginatedSeries, Pagination, RangeIndex,
-    RangeMap, SearchQuery, SeriesData, SeriesInfo, SeriesName, SeriesOutput, SeriesOutputLegacy,
-    SeriesSelection, Timestamp, Version,
+    RangeMap, SearchQuery, SeriesData, SeriesExpr, SeriesInfo, SeriesName, SeriesOutput,
+    SeriesOutputLegacy, SeriesSelection, Timestamp, Version,
 };
 use parking_lot::RwLock;
 use vecdb::{AnyExportableVec, ReadableVec};
@@ -200,6 +200,7 @@ impl Query {
             end,
             hash_prefix,
             stable_count,
+            expr: None,
         })
     }
 
@@ -262,7 +263,11 @@ impl Query {
 
     /// Format a resolved query (expensive).
     /// Call after ETag/cache checks to avoid unnecessary work.
-    pub fn format(&self, resolved: ResolvedQuery) -> Result<SeriesOutput> {
+    pub fn format(&self, mut resolved: ResolvedQuery) -> Result<SeriesOutput> {
+        if let Some(expr) = resolved.expr.take() {
+            return Self::format_expr(resolved, expr, false);
+        }
+
         let ResolvedQuery {
             vecs,
             format,
@@ -276,6 +281,8 @@ impl Query {
 
         let output = match format {
             Format::CSV => Output::CSV(Self::columns_to_csv(&vecs, start, end)?),
+            Format::Parquet => Output::Parquet(Self::columns_to_parquet(index, &vecs, start, end)?),
+            Format::Arrow => Output::Arrow(Self::columns_to_arrow(index, &vecs, start, end)?),
             Format::JSON => {
                 let count = end.saturating_sub(start);
                 Output::Json(Self::write_json_array(&vecs, count, 256, |v, buf| {
@@ -295,9 +302,13 @@ impl Query {
 
     /// Format a resolved query as raw data (just the JSON values, no SeriesData wrapper).
     /// Single vec → `[v1,v2,...]`. Multi-vec → `[[v1,v2],[v3,v4],...]`.
-    /// CSV output is identical to `format` (no wrapper distinction for CSV).
-    pub fn format_raw(&self, resolved: ResolvedQuery) -> Result<SeriesOutput> {
-        if resolved.format == Format::CSV {
+    /// CSV, Parquet and Arrow output is identical to `format` (no wrapper
+    /// distinction outside JSON).
+    pub fn format_raw(&self, mut resolved: ResolvedQuery) -> Result<SeriesOutput> {
+        if let Some(expr) = resolved.expr.take() {
+            return Self::format_expr(resolved, expr, true);
+        }
+        if resolved.format != Format::JSON {
             return self.format(resolved);
         }
 
@@ -429,6 +440,7 @@ impl Query {
         let ResolvedQuery {
             vecs,
             format,
+            index,
             version,
             total,
             start,
@@ -437,8 +449,15 @@ impl Query {
         } = resolved;
 
         if vecs.is_empty() {
+            let output = match OutputLegacy::empty(format) {
+                Some(output) => output,
+                None if format == Format::Parquet => {
+                    OutputLegacy::Parquet(Self::columns_to_parquet(index, &[], 0, 0)?)
+                }
+                None => OutputLegacy::Arrow(Self::columns_to_arrow(index, &[], 0, 0)?),
+            };
             return Ok(SeriesOutputLegacy {
-                output: OutputLegacy::default(format),
+                output,
                 version: Version::ZERO,
                 total: 0,
                 start: 0,
@@ -451,6 +470,10 @@ impl Query {
 
         let output = match format {
             Format::CSV => OutputLegacy::CSV(Self::columns_to_csv(&vecs, start, end)?),
+            Format::Parquet => {
+                OutputLegacy::Parquet(Self::columns_to_parquet(index, &vecs, start, end)?)
+            }
+            Format::Arrow => OutputLegacy::Arrow(Self::columns_to_arrow(index, &vecs, start, end)?),
             Format::JSON => {
                 if vecs.len() == 1 {
                     let col = vecs[0];
@@ -488,7 +511,8 @@ impl Query {
 /// A resolved series query ready for formatting.
 /// Carries the vecs plus the metadata callers need to derive an etag or cache
 /// policy. `stable_count` is `None` for indexes whose entries can mutate
-/// retroactively (Funded/Empty addr).
+/// retroactively (Funded/Empty addr). With `expr` set, `vecs` are the series
+/// the expression references and the output is the expression itself.
 pub struct ResolvedQuery {
     pub vecs: Vec<&'static dyn AnyExportableVec>,
     pub format: Format,
@@ -499,11 +523,16 @@ pub struct ResolvedQuery {
     pub end: usize,
     pub hash_prefix: BlockHashPrefix,
     pub stable_count: Option<usize>,
+    pub expr: Option<SeriesExpr>,
 }
 
 impl ResolvedQuery {
-    pub fn csv_filename(&self) -> String {
+    /// Attachment filename for non-JSON formats, e.g. `price-day1.parquet`.
+    pub fn filename(&self) -> String {
+        if self.expr.is_some() {
+            return format!("expr-{}.{}", self.index, self.format);
+        }
         let names: Vec<_> = self.vecs.iter().map(|v| v.name()).collect();
-        format!("{}-{}.csv", names.join("_"), self.index)
+        format!("{}-{}.{}", names.join("_"), self.index, self.format)
     }
 }
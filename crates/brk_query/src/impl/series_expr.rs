@@ -0,0 +1,127 @@
+use std::{str::FromStr, sync::Arc};
+
+use arrow_array::{ArrayRef, Float64Array};
+use brk_computer::SeriesExprEval;
+use brk_error::{Error, Result};
+use brk_types::{Format, Output, SeriesData, SeriesExpr, SeriesExprSelection, SeriesOutput};
+
+use crate::{Query, ResolvedQuery};
+
+/// Value type reported for evaluated expressions.
+const EXPR_VALUE_TYPE: &str = "f64";
+
+impl Query {
+    /// Resolves the series the expression references like `resolve`, then
+    /// checks the weight again over the points read before `start` to warm
+    /// up windows and lags.
+    pub fn resolve_expr(
+        &self,
+        params: SeriesExprSelection,
+        max_weight: usize,
+    ) -> Result<ResolvedQuery> {
+        let expr = SeriesExpr::from_str(&params.q)?;
+        let mut resolved = self.resolve(params.selection(expr.series_list()), max_weight)?;
+
+        let read_start = resolved.start.saturating_sub(expr.lookback());
+        let weight = Self::weight(
+            &resolved.vecs,
+            Some(read_start as i64),
+            Some(resolved.end as i64),
+        );
+        if weight > max_weight {
+            return Err(Error::WeightExceeded {
+                requested: weight,
+                max: max_weight,
+            });
+        }
+
+        resolved.expr = Some(expr);
+        Ok(resolved)
+    }
+
+    /// Evaluates the expression and formats it as a single `f64` column.
+    /// `raw` drops the `SeriesData` wrapper from JSON output.
+    pub(crate) fn format_expr(
+        resolved: ResolvedQuery,
+        expr: SeriesExpr,
+        raw: bool,
+    ) -> Result<SeriesOutput> {
+        let ResolvedQuery {
+            vecs,
+            format,
+            index,
+            version,
+            total,
+            start,
+            end,
+            ..
+        } = resolved;
+
+        let read_start = start.saturating_sub(expr.lookback());
+        let mut buf = Vec::new();
+        let leaves = expr
+            .series()
+            .into_iter()
+            .zip(vecs)
+            .map(|(name, vec)| {
+                buf.clear();
+                vec.write_json(Some(read_start), Some(end), &mut buf)?;
+                let values: Vec<Option<f64>> = serde_json::from_slice(&buf)
+                    .map_err(|_| Error::Parse(format!("Series '{name}' is not numeric")))?;
+                Ok((name, values))
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut values = expr.eval(&leaves, end - read_start);
+        values.drain(..start - read_start);
+
+        let output = match format {
+            Format::JSON => {
+                let data = serde_json::to_vec(&values)?;
+                if raw {
+                    Output::Json(data)
+                } else {
+                    let mut buf = Vec::with_capacity(data.len() + 256);
+                    SeriesData::serialize_data(
+                        version,
+                        index,
+                        EXPR_VALUE_TYPE,
+                        start,
+                        end,
+                        &data,
+                        &mut buf,
+                    );
+                    Output::Json(buf)
+                }
+            }
+            Format::CSV => {
+                let mut csv = format!("\"{expr}\"\n");
+                for value in &values {
+                    if let Some(value) = value {
+                        csv.push_str(&value.to_string());
+                    }
+                    csv.push('\n');
+                }
+                Output::CSV(csv)
+            }
+            Format::Parquet | Format::Arrow => {
+                let name = expr.to_string();
+                let array: ArrayRef = Arc::new(Float64Array::from(values));
+                let batch = Self::record_batch(index, start, end, vec![(name.as_str(), array)])?;
+                if format == Format::Parquet {
+                    Output::Parquet(Self::batch_to_parquet(&batch)?)
+                } else {
+                    Output::Arrow(Self::batch_to_arrow(&batch)?)
+                }
+            }
+        };
+
+        Ok(SeriesOutput {
+            output,
+            version,
+            total,
+            start,
+            end,
+        })
+    }
+}
@@ -0,0 +1,80 @@
+//! Tx dry runs against the projected mempool blocks. A raw tx is
+//! decoded and priced from its prevouts (live parents first, then the
+//! indexer), a bare fee rate and vsize is taken as is. Placement
+//! itself is done by `brk_mempool`.
+
+use brk_error::{Error, Result};
+use brk_mempool::{Candidate, Mempool};
+use brk_types::{CheckedSub, Sats, TxSimulation, TxSimulationRequest, Txid, VSize};
+
+use crate::Query;
+
+use super::raw_tx::{outpoint, parse_tx_hex, signed_weight};
+
+impl Query {
+    pub fn simulate_tx(&self, request: TxSimulationRequest) -> Result<TxSimulation> {
+        let mempool = self.mempool().ok_or(Error::MempoolNotAvailable)?;
+        let candidate = match request {
+            TxSimulationRequest {
+                hex: Some(hex),
+                fee_rate: None,
+                vsize: None,
+                ..
+            } => self.raw_tx_candidate(mempool, &hex)?,
+            TxSimulationRequest {
+                hex: None,
+                fee_rate: Some(fee_rate),
+                vsize: Some(vsize),
+                parents,
+            } => {
+                if vsize == VSize::default() {
+                    return Err(Error::Parse("vsize must be positive".to_string()));
+                }
+                let fee = (f64::from(fee_rate) * u64::from(vsize) as f64).ceil() as u64;
+                Candidate {
+                    txid: None,
+                    fee: Sats::from(fee),
+                    vsize,
+                    parents,
+                    conflicts: Vec::new(),
+                }
+            }
+            _ => {
+                return Err(Error::Parse(
+                    "expected either `hex`, or `feeRate` and `vsize`".to_string(),
+                ));
+            }
+        };
+        Ok(mempool.simulate(&candidate))
+    }
+
+    fn raw_tx_candidate(&self, mempool: &Mempool, hex: &str) -> Result<Candidate> {
+        let tx = parse_tx_hex(hex)?;
+        let prevouts = self.resolve_prevouts(&tx)?;
+
+        let mut parents = Vec::new();
+        let mut input_value = Sats::ZERO;
+        for input in &tx.input {
+            let (txid, vout) = outpoint(input)?;
+            let prevout = prevouts
+                .get(&(txid, vout))
+                .ok_or_else(|| Error::NotFound(format!("prevout {txid}:{vout}")))?;
+            input_value += prevout.value;
+            if mempool.contains_txid(&txid) && !parents.contains(&txid) {
+                parents.push(txid);
+            }
+        }
+        let output_value = Sats::from(tx.output.iter().map(|o| o.value.to_sat()).sum::<u64>());
+        let fee = input_value
+            .checked_sub(output_value)
+            .ok_or_else(|| Error::Parse("outputs exceed inputs".to_string()))?;
+
+        Ok(Candidate {
+            txid: Some(Txid::from(tx.compute_txid())),
+            fee,
+            vsize: VSize::from(signed_weight(&tx, &prevouts)),
+            parents,
+            conflicts: self.mempool_conflicts(&tx)?,
+        })
+    }
+}
@@ -338,7 +338,7 @@ impl Query {
             .expect("transactions_by_indices returns one tx per input index"))
     }
 
-    fn transaction_raw_by_index(&self, tx_index: TxIndex) -> Result<Vec<u8>> {
+    pub(crate) fn transaction_raw_by_index(&self, tx_index: TxIndex) -> Result<Vec<u8>> {
         let indexer = self.indexer();
         let total_size = indexer
             .vecs
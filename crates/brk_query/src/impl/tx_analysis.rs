@@ -0,0 +1,462 @@
+//! Review of raw txs and PSBTs before they're signed or broadcast:
+//! fee, size, RBF signaling, address reuse, mempool conflicts, and the
+//! sighash and standardness problems bitcoind would otherwise only
+//! surface as a rejection.
+
+use std::str::FromStr;
+
+use bitcoin::{
+    ecdsa,
+    hex::FromHex,
+    psbt::{self, Psbt, PsbtSighashType},
+    script::Instruction,
+    sighash::{EcdsaSighashType, TapSighashType},
+    taproot,
+};
+use brk_error::{Error, Result};
+use brk_types::{
+    CheckedSub, FeeRate, OutputType, Sats, SigOps, Transaction, TxAnalysis, TxIn, TxOut, TxStatus,
+    VSize, Weight,
+};
+
+use crate::Query;
+
+use super::raw_tx::{Prevouts, outpoint, parse_tx_hex, signed_weight};
+
+/// Bitcoin Core's standardness limits.
+const MAX_STANDARD_TX_WEIGHT: u64 = 400_000;
+const MAX_STANDARD_TX_SIGOPS_COST: u32 = 16_000;
+const MIN_STANDARD_TX_NONWITNESS_SIZE: usize = 65;
+const MAX_STANDARD_SCRIPTSIG_SIZE: usize = 1_650;
+
+/// bitcoind's default `maxfeerate`, 0.1 BTC/kvB.
+const MAX_FEE_RATE: FeeRate = FeeRate::new(10_000.0);
+
+impl Query {
+    /// Decodes a raw tx, signed or not, and reviews it.
+    pub fn decode_tx(&self, hex: &str) -> Result<TxAnalysis> {
+        let tx = parse_tx_hex(hex)?;
+        let prevouts = self.resolve_prevouts(&tx)?;
+        self.analyze_tx(tx, prevouts, Vec::new())
+    }
+
+    /// Decodes a PSBT, base64 or hex, and reviews the tx it would
+    /// produce. Finalized inputs count as signed. UTXOs carried by the
+    /// PSBT stand in for prevouts the node doesn't know, and are
+    /// checked against the ones it does.
+    pub fn analyze_psbt(&self, psbt: &str) -> Result<TxAnalysis> {
+        let psbt = parse_psbt(psbt)?;
+        let mut tx = psbt.unsigned_tx.clone();
+        let mut prevouts = self.resolve_prevouts(&tx)?;
+        let mut warnings = Vec::new();
+
+        for (i, (txin, input)) in tx.input.iter_mut().zip(&psbt.inputs).enumerate() {
+            if let Some(script_sig) = &input.final_script_sig {
+                txin.script_sig = script_sig.clone();
+            }
+            if let Some(witness) = &input.final_script_witness {
+                txin.witness = witness.clone();
+            }
+
+            let key = outpoint(txin)?;
+            if let Some(carried) = carried_utxo(i, txin, input, &mut warnings) {
+                match prevouts.get(&key) {
+                    Some(known)
+                        if known.value != carried.value
+                            || known.script_pubkey != carried.script_pubkey =>
+                    {
+                        warnings.push(format!(
+                            "input {i} carries a UTXO that doesn't match the chain"
+                        ));
+                    }
+                    Some(_) => {}
+                    None => {
+                        prevouts.insert(key, carried);
+                    }
+                }
+            }
+
+            if let Some(sighash) = input.sighash_type
+                && sighash != PsbtSighashType::from(EcdsaSighashType::All)
+                && sighash != PsbtSighashType::from(TapSighashType::Default)
+            {
+                warnings.push(format!("input {i} requests {sighash}"));
+            }
+            let partial = input
+                .partial_sigs
+                .values()
+                .filter_map(ecdsa_sighash)
+                .chain(input.tap_key_sig.iter().filter_map(taproot_sighash))
+                .chain(input.tap_script_sigs.values().filter_map(taproot_sighash));
+            for sighash in partial {
+                warnings.push(format!("input {i} is signed with {sighash}"));
+            }
+        }
+
+        self.analyze_tx(tx, prevouts, warnings)
+    }
+
+    fn analyze_tx(
+        &self,
+        tx: bitcoin::Transaction,
+        prevouts: Prevouts,
+        mut warnings: Vec<String>,
+    ) -> Result<TxAnalysis> {
+        let weight = signed_weight(&tx, &prevouts);
+        let vsize = VSize::from(weight);
+
+        let mut input_value = Some(Sats::ZERO);
+        let mut input = Vec::with_capacity(tx.input.len());
+        for (i, txin) in tx.input.iter().enumerate() {
+            let (txid, vout) = outpoint(txin)?;
+            let prevout = prevouts.get(&(txid, vout)).cloned();
+            match &prevout {
+                Some(prevout) => {
+                    input_value = input_value.map(|value| value + prevout.value);
+                    if let Some(sighash) = signed_sighash(txin, prevout.type_()) {
+                        warnings.push(format!("input {i} is signed with {sighash}"));
+                    }
+                }
+                None => {
+                    input_value = None;
+                    warnings.push(format!("prevout {txid}:{vout} of input {i} is unknown"));
+                }
+            }
+            if txin.script_sig.len() > MAX_STANDARD_SCRIPTSIG_SIZE {
+                warnings.push(format!(
+                    "input {i} scriptSig is over {MAX_STANDARD_SCRIPTSIG_SIZE} bytes"
+                ));
+            }
+            if !txin.script_sig.is_push_only() {
+                warnings.push(format!("input {i} scriptSig isn't push-only"));
+            }
+            input.push(TxIn {
+                is_coinbase: false,
+                prevout,
+                txid,
+                vout,
+                script_sig: txin.script_sig.clone(),
+                script_sig_asm: (),
+                witness: txin.witness.clone().into(),
+                sequence: txin.sequence.into(),
+                inner_redeem_script_asm: (),
+                inner_witness_script_asm: (),
+            });
+        }
+        let output: Vec<TxOut> = tx.output.iter().cloned().map(TxOut::from).collect();
+
+        let output_value: Sats = output.iter().map(|out| out.value).sum();
+        let fee = input_value.and_then(|value| value.checked_sub(output_value));
+        if input_value.is_some() && fee.is_none() {
+            warnings.push("outputs exceed inputs".to_string());
+        }
+        let fee_rate = fee.map(|fee| FeeRate::from((fee, vsize)));
+        if let Some(rate) = fee_rate {
+            if let Some(min) = self.mempool().map(|m| m.fees().minimum_fee)
+                && rate < min
+            {
+                warnings.push(format!(
+                    "fee rate {rate} sat/vB is below the mempool minimum of {min} sat/vB"
+                ));
+            }
+            if rate > MAX_FEE_RATE {
+                warnings.push(format!(
+                    "fee rate {rate} sat/vB is above bitcoind's default maxfeerate of {MAX_FEE_RATE} sat/vB"
+                ));
+            }
+        }
+
+        let mut built = Transaction {
+            index: None,
+            txid: tx.compute_txid().into(),
+            version: tx.version.into(),
+            lock_time: tx.lock_time.into(),
+            input,
+            output,
+            total_size: tx.total_size(),
+            weight: Weight::from(tx.weight()),
+            total_sigop_cost: SigOps::ZERO,
+            fee: fee.unwrap_or_default(),
+            status: TxStatus::UNCONFIRMED,
+        };
+        built.refresh_sigops();
+        warnings.extend(standardness_warnings(&tx, weight, built.total_sigop_cost));
+
+        let mut checked = Vec::new();
+        let mut reused_addrs = Vec::new();
+        for out in &built.output {
+            if checked.contains(&&out.script_pubkey) {
+                continue;
+            }
+            checked.push(&out.script_pubkey);
+            if let Some(bytes) = out.addr_bytes()
+                && self.addr_bytes_seen(&bytes)?
+                && let Some(addr) = out.addr()
+            {
+                reused_addrs.push(addr);
+            }
+        }
+
+        Ok(TxAnalysis {
+            fee,
+            vsize,
+            fee_rate,
+            complete: tx
+                .input
+                .iter()
+                .all(|txin| !txin.script_sig.is_empty() || !txin.witness.is_empty()),
+            rbf: tx.input.iter().any(|txin| txin.sequence.is_rbf()),
+            reused_addrs,
+            conflicts: self.mempool_conflicts(&tx)?,
+            warnings,
+            tx: built,
+        })
+    }
+}
+
+/// UTXO a PSBT input carries for its prevout. A non-witness UTXO whose
+/// txid isn't the one the input spends is ignored with a warning,
+/// since its amounts could be anything.
+fn carried_utxo(
+    i: usize,
+    txin: &bitcoin::TxIn,
+    input: &psbt::Input,
+    warnings: &mut Vec<String>,
+) -> Option<TxOut> {
+    if let Some(witness_utxo) = &input.witness_utxo {
+        return Some(TxOut::from(witness_utxo.clone()));
+    }
+    let prev = input.non_witness_utxo.as_ref()?;
+    if prev.compute_txid() != txin.previous_output.txid {
+        warnings.push(format!(
+            "input {i} carries a non-witness UTXO from another tx, ignored"
+        ));
+        return None;
+    }
+    let vout = usize::try_from(txin.previous_output.vout).ok()?;
+    prev.output.get(vout).cloned().map(TxOut::from)
+}
+
+fn parse_psbt(psbt: &str) -> Result<Psbt> {
+    let psbt = psbt.trim();
+    let parsed = match Vec::<u8>::from_hex(psbt) {
+        Ok(bytes) => Psbt::deserialize(&bytes).map_err(|e| e.to_string()),
+        Err(_) => Psbt::from_str(psbt).map_err(|e| e.to_string()),
+    };
+    parsed.map_err(|e| Error::Parse(format!("invalid PSBT: {e}")))
+}
+
+/// Non-default sighash of the signature `input` carries, for the
+/// single-key spends where the signature sits at a known place.
+fn signed_sighash(input: &bitcoin::TxIn, output_type: OutputType) -> Option<String> {
+    let witness = &input.witness;
+    match output_type {
+        OutputType::P2PKH => {
+            let Some(Ok(Instruction::PushBytes(sig))) = input.script_sig.instructions().next()
+            else {
+                return None;
+            };
+            ecdsa_sighash(&ecdsa::Signature::from_slice(sig.as_bytes()).ok()?)
+        }
+        OutputType::P2WPKH | OutputType::P2SH if witness.len() == 2 => {
+            ecdsa_sighash(&ecdsa::Signature::from_slice(witness.nth(0)?).ok()?)
+        }
+        // Key path: a lone signature, the annex aside.
+        OutputType::P2TR if witness.len() - usize::from(witness.taproot_annex().is_some()) == 1 => {
+            taproot_sighash(&taproot::Signature::from_slice(witness.nth(0)?).ok()?)
+        }
+        _ => None,
+    }
+}
+
+fn ecdsa_sighash(sig: &ecdsa::Signature) -> Option<String> {
+    (sig.sighash_type != EcdsaSighashType::All).then(|| sig.sighash_type.to_string())
+}
+
+fn taproot_sighash(sig: &taproot::Signature) -> Option<String> {
+    (!matches!(
+        sig.sighash_type,
+        TapSighashType::Default | TapSighashType::All
+    ))
+    .then(|| sig.sighash_type.to_string())
+}
+
+/// Policy checks on the tx as a whole and its outputs. `weight` counts
+/// unsigned inputs as signed.
+fn standardness_warnings(tx: &bitcoin::Transaction, weight: Weight, sigops: SigOps) -> Vec<String> {
+    let mut warnings = Vec::new();
+    if !(1..=3).contains(&tx.version.0) {
+        warnings.push(format!("version {} is non-standard", tx.version.0));
+    }
+    if *weight > MAX_STANDARD_TX_WEIGHT {
+        warnings.push(format!(
+            "weight {weight} is over the standard limit of {MAX_STANDARD_TX_WEIGHT}"
+        ));
+    }
+    if u32::from(sigops) > MAX_STANDARD_TX_SIGOPS_COST {
+        warnings.push(format!(
+            "sigop cost {} is over the standard limit of {MAX_STANDARD_TX_SIGOPS_COST}",
+            u32::from(sigops)
+        ));
+    }
+    if tx.base_size() < MIN_STANDARD_TX_NONWITNESS_SIZE {
+        warnings.push(format!(
+            "non-witness size {} is under the standard minimum of {MIN_STANDARD_TX_NONWITNESS_SIZE} bytes",
+            tx.base_size()
+        ));
+    }
+    for (i, out) in tx.output.iter().enumerate() {
+        match OutputType::from(&out.script_pubkey) {
+            OutputType::OpReturn => {}
+            OutputType::Empty | OutputType::Unknown => {
+                warnings.push(format!("output {i} has a non-standard script"));
+            }
+            _ if out.value < out.script_pubkey.minimal_non_dust() => {
+                warnings.push(format!("output {i} is dust at {} sats", out.value.to_sat()));
+            }
+            _ => {}
+        }
+    }
+    warnings
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::{
+        Amount, OutPoint, ScriptBuf, Sequence, WPubkeyHash, Witness, absolute::LockTime,
+        hashes::Hash, hex::DisplayHex, transaction::Version,
+    };
+
+    use super::*;
+
+    fn p2wpkh() -> ScriptBuf {
+        ScriptBuf::new_p2wpkh(&WPubkeyHash::from_byte_array([3; 20]))
+    }
+
+    fn tx(previous_output: OutPoint, output: Vec<bitcoin::TxOut>) -> bitcoin::Transaction {
+        bitcoin::Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: vec![bitcoin::TxIn {
+                previous_output,
+                script_sig: ScriptBuf::new(),
+                sequence: Sequence::ENABLE_RBF_NO_LOCKTIME,
+                witness: Witness::new(),
+            }],
+            output,
+        }
+    }
+
+    fn out(sats: u64, script_pubkey: ScriptBuf) -> bitcoin::TxOut {
+        bitcoin::TxOut {
+            value: Amount::from_sat(sats),
+            script_pubkey,
+        }
+    }
+
+    fn spend(prev: &bitcoin::Transaction) -> bitcoin::Transaction {
+        tx(
+            OutPoint::new(prev.compute_txid(), 0),
+            vec![out(90_000, p2wpkh())],
+        )
+    }
+
+    fn funding() -> bitcoin::Transaction {
+        tx(
+            OutPoint::new(bitcoin::Txid::from_byte_array([1; 32]), 0),
+            vec![out(100_000, p2wpkh())],
+        )
+    }
+
+    #[test]
+    fn parse_psbt_accepts_base64_and_hex() {
+        let psbt = Psbt::from_unsigned_tx(spend(&funding())).unwrap();
+        let base64 = psbt.to_string();
+        let hex = psbt.serialize().to_lower_hex_string();
+
+        assert_eq!(parse_psbt(&base64).unwrap(), psbt);
+        assert_eq!(parse_psbt(&format!(" {hex}\n")).unwrap(), psbt);
+        assert!(parse_psbt("not a psbt").is_err());
+        assert!(parse_psbt("deadbeef").is_err());
+    }
+
+    #[test]
+    fn carried_utxo_uses_a_matching_non_witness_utxo() {
+        let prev = funding();
+        let spend = spend(&prev);
+        let input = psbt::Input {
+            non_witness_utxo: Some(prev.clone()),
+            ..Default::default()
+        };
+        let mut warnings = Vec::new();
+        let carried = carried_utxo(0, &spend.input[0], &input, &mut warnings).unwrap();
+        assert_eq!(carried.value, Sats::from(100_000u64));
+        assert!(warnings.is_empty());
+    }
+
+    #[test]
+    fn carried_utxo_ignores_a_non_witness_utxo_from_another_tx() {
+        let prev = funding();
+        let spend = spend(&prev);
+        let mut forged = prev.clone();
+        forged.output[0].value = Amount::from_sat(10_000_000);
+        let input = psbt::Input {
+            non_witness_utxo: Some(forged),
+            ..Default::default()
+        };
+        let mut warnings = Vec::new();
+        assert!(carried_utxo(0, &spend.input[0], &input, &mut warnings).is_none());
+        assert_eq!(warnings.len(), 1);
+    }
+
+    #[test]
+    fn carried_utxo_prefers_the_witness_utxo() {
+        let spend = spend(&funding());
+        let input = psbt::Input {
+            witness_utxo: Some(out(42_000, p2wpkh())),
+            ..Default::default()
+        };
+        let mut warnings = Vec::new();
+        let carried = carried_utxo(0, &spend.input[0], &input, &mut warnings).unwrap();
+        assert_eq!(carried.value, Sats::from(42_000u64));
+    }
+
+    #[test]
+    fn standard_tx_has_no_warnings() {
+        let tx = spend(&funding());
+        assert!(standardness_warnings(&tx, Weight::from(tx.weight()), SigOps::ZERO).is_empty());
+    }
+
+    #[test]
+    fn standardness_warnings_flag_each_policy() {
+        let mut tx = spend(&funding());
+        tx.version = Version(4);
+        tx.output.push(out(1, p2wpkh()));
+        tx.output.push(out(1_000, ScriptBuf::new()));
+
+        let warnings = standardness_warnings(
+            &tx,
+            Weight::from(MAX_STANDARD_TX_WEIGHT + 1),
+            SigOps::from(MAX_STANDARD_TX_SIGOPS_COST + 1),
+        );
+        assert_eq!(
+            warnings,
+            vec![
+                "version 4 is non-standard".to_string(),
+                format!("weight {} is over the standard limit of {MAX_STANDARD_TX_WEIGHT}", MAX_STANDARD_TX_WEIGHT + 1),
+                format!("sigop cost {} is over the standard limit of {MAX_STANDARD_TX_SIGOPS_COST}", MAX_STANDARD_TX_SIGOPS_COST + 1),
+                "output 1 is dust at 1 sats".to_string(),
+                "output 2 has a non-standard script".to_string(),
+            ]
+        );
+    }
+
+    #[test]
+    fn standardness_warnings_flag_tiny_txs() {
+        let mut tx = spend(&funding());
+        tx.output = vec![out(0, ScriptBuf::new_op_return([]))];
+        let warnings = standardness_warnings(&tx, Weight::from(tx.weight()), SigOps::ZERO);
+        assert_eq!(warnings.len(), 1);
+        assert!(warnings[0].starts_with("non-witness size"));
+    }
+}
@@ -24,7 +24,7 @@ mod r#impl;
 
 #[cfg(feature = "tokio")]
 pub use r#async::*;
-pub use r#impl::ResolvedQuery;
+pub use r#impl::{AddrWatch, ResolvedQuery};
 pub use vecs::Vecs;
 
 #[derive(Clone)]
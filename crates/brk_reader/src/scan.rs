@@ -1,17 +1,18 @@
 use std::ops::ControlFlow;
 
-use brk_types::{BlkMetadata, BlkPosition};
+use brk_types::{BlkMetadata, BlkPosition, network};
 
 use crate::{XORBytes, XORIndex, xor_bytes::XOR_LEN};
 
-const MAGIC_BYTES: [u8; 4] = [0xF9, 0xBE, 0xB4, 0xD9];
+const MAGIC_LEN: usize = 4;
 
 /// Returns the position immediately after the matched magic, or
 /// `None` if no match. Advances `xor_i` by the bytes consumed either
 /// way.
 pub(crate) fn find_magic(bytes: &[u8], xor_i: &mut XORIndex, xor_bytes: XORBytes) -> Option<usize> {
+    let magic = network().magic().to_bytes();
     let len = bytes.len();
-    if len < MAGIC_BYTES.len() {
+    if len < MAGIC_LEN {
         xor_i.add_assign(len);
         return None;
     }
@@ -19,19 +20,19 @@ pub(crate) fn find_magic(bytes: &[u8], xor_i: &mut XORIndex, xor_bytes: XORBytes
     let xb = *xor_bytes;
     let mut phase = xor_i.phase();
     let mut i = 0;
-    let stop = len - MAGIC_BYTES.len();
+    let stop = len - MAGIC_LEN;
 
     while i <= stop {
-        if bytes[i] ^ xb[phase] == MAGIC_BYTES[0] {
+        if bytes[i] ^ xb[phase] == magic[0] {
             let p1 = (phase + 1) & (XOR_LEN - 1);
             let p2 = (phase + 2) & (XOR_LEN - 1);
             let p3 = (phase + 3) & (XOR_LEN - 1);
-            if bytes[i + 1] ^ xb[p1] == MAGIC_BYTES[1]
-                && bytes[i + 2] ^ xb[p2] == MAGIC_BYTES[2]
-                && bytes[i + 3] ^ xb[p3] == MAGIC_BYTES[3]
+            if bytes[i + 1] ^ xb[p1] == magic[1]
+                && bytes[i + 2] ^ xb[p2] == magic[2]
+                && bytes[i + 3] ^ xb[p3] == magic[3]
             {
-                xor_i.set_phase(phase + MAGIC_BYTES.len());
-                return Some(i + MAGIC_BYTES.len());
+                xor_i.set_phase(phase + MAGIC_LEN);
+                return Some(i + MAGIC_LEN);
             }
         }
         phase = (phase + 1) & (XOR_LEN - 1);
@@ -61,7 +62,7 @@ pub(crate) fn scan_bytes(
     let mut i = 0;
 
     while let Some(off) = find_magic(&buf[i..], &mut xor_i, xor_bytes) {
-        first_magic.get_or_insert(i + off - MAGIC_BYTES.len());
+        first_magic.get_or_insert(i + off - MAGIC_LEN);
         i += off;
         if i + 4 > buf.len() {
             break;
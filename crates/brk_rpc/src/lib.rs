@@ -5,6 +5,7 @@ use std::{
     time::Duration,
 };
 
+use bitcoin::Network;
 use brk_error::Result;
 use brk_types::{Sats, Txid, Weight};
 
@@ -64,6 +65,29 @@ impl Client {
         "http://localhost:8332"
     }
 
+    /// Default RPC port of `network`, as in Bitcoin Core's chainparams.
+    pub fn default_port(network: Network) -> u16 {
+        match network {
+            Network::Bitcoin => 8332,
+            Network::Testnet => 18332,
+            Network::Testnet4 => 48332,
+            Network::Signet => 38332,
+            Network::Regtest => 18443,
+        }
+    }
+
+    /// Subdirectory Bitcoin Core keeps `network`'s data in, `None` for
+    /// mainnet which lives at the root of the data directory.
+    pub fn network_dir_name(network: Network) -> Option<&'static str> {
+        match network {
+            Network::Bitcoin => None,
+            Network::Testnet => Some("testnet3"),
+            Network::Testnet4 => Some("testnet4"),
+            Network::Signet => Some("signet"),
+            Network::Regtest => Some("regtest"),
+        }
+    }
+
     pub fn default_bitcoin_path() -> PathBuf {
         if env::consts::OS == "macos" {
             Self::default_mac_bitcoin_path()
@@ -83,3 +107,22 @@ impl Client {
             .join("Bitcoin")
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn network_defaults_match_bitcoin_core() {
+        for (network, port, dir) in [
+            (Network::Bitcoin, 8332, None),
+            (Network::Testnet, 18332, Some("testnet3")),
+            (Network::Testnet4, 48332, Some("testnet4")),
+            (Network::Signet, 38332, Some("signet")),
+            (Network::Regtest, 18443, Some("regtest")),
+        ] {
+            assert_eq!(Client::default_port(network), port);
+            assert_eq!(Client::network_dir_name(network), dir);
+        }
+    }
+}
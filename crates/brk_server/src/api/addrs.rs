@@ -1,26 +1,34 @@
 use aide::axum::{ApiRouter, routing::get_with};
 use axum::{
-    extract::{Path, State},
+    extract::{Path, Query, State},
     http::{HeaderMap, Uri},
 };
+use brk_error::{Error, Result};
 use brk_types::{
-    AddrHashPrefixMatches, AddrStats, AddrValidation, Transaction, Utxo, Version,
+    AddrBalance, AddrBalanceHistory, AddrHashPrefixMatches, AddrStats, AddrValidation, Date,
+    RichList, ScriptHashStats, Transaction, Utxo, Version,
 };
 
 use crate::{
     AppState, CacheStrategy,
     extended::TransformResponseExtended,
-    params::{AddrAfterTxidParam, AddrHashPrefixParam, AddrParam, Empty, ValidateAddrParam},
+    params::{
+        AddrAfterTxidParam, AddrBalanceQuery, AddrHashPrefixParam, AddrHistoryQuery, AddrParam,
+        Empty, RichListQuery, ScriptHashAfterTxidParam, ScriptHashParam, ValidateAddrParam,
+    },
 };
 
 /// Esplora `/txs` and `/txs/chain` page sizes. Wire-protocol constants from
 /// mempool.space/esplora, not deployment policy. `/txs` returns up to
 /// `MEMPOOL_PAGE` mempool entries plus a chain page sized to reach
 /// `TXS_TOTAL_TARGET` total, floored at `CHAIN_PAGE`.
-const MEMPOOL_PAGE: usize = 50;
-const CHAIN_PAGE: usize = 25;
+pub(super) const MEMPOOL_PAGE: usize = 50;
+pub(super) const CHAIN_PAGE: usize = 25;
 const TXS_TOTAL_TARGET: usize = 50;
 
+const DEFAULT_RICH_LIST_LIMIT: usize = 100;
+const MAX_RICH_LIST_LIMIT: usize = 1_000;
+
 pub trait AddrRoutes {
     fn add_addr_routes(self) -> Self;
 }
@@ -200,6 +208,199 @@ impl AddrRoutes for ApiRouter<AppState> {
                 .server_error()
             ),
         )
+        .api_route(
+            "/api/address/{address}/balance",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Path(path): Path<AddrParam>,
+                Query(params): Query<AddrBalanceQuery>,
+                State(state): State<AppState>
+            | {
+                state.respond_json(&headers, CacheStrategy::Tip, &uri, move |q| q.addr_balance(path.addr, params.height)).await
+            }, |op| op
+                .id("get_address_balance")
+                .addrs_tag()
+                .summary("Address balance at a height")
+                .description("Get the confirmed balance of an address at the end of a block, the tip if `height` is omitted, with its unspent output count and realized price (average cost basis, each output valued at the price of its block). Computed from the address's transactions, for audits and proof-of-reserves reconciliation.")
+                .json_response::<AddrBalance>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/address/{address}/history",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Path(path): Path<AddrParam>,
+                Query(params): Query<AddrHistoryQuery>,
+                State(state): State<AppState>
+            | {
+                let max_weight = state.max_weight;
+                state.respond_json(&headers, CacheStrategy::Tip, &uri, move |q| q.addr_balance_history(path.addr, params.index, params.from, params.to, max_weight)).await
+            }, |op| op
+                .id("get_address_balance_history")
+                .addrs_tag()
+                .summary("Address balance history")
+                .description("Get the confirmed balance and realized price of an address at the end of each period of `index` (`height` or date-based, e.g. `day1`), from the period of its first funding to the current one, or between `from` and `to`. Values line up with series data: the `n`-th is for index `start + n`. Ranges over the server's request weight limit are rejected, and so are addresses with more than 100,000 transactions.")
+                .json_response::<AddrBalanceHistory>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/scripthash/{hash}",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Path(path): Path<ScriptHashParam>,
+                _: Empty,
+                State(state): State<AppState>
+            | {
+                let strategy = state.script_hash_strategy(&path.hash);
+                state.respond_json(&headers, strategy, &uri, move |q| q.script_hash_stats(path.hash)).await
+            }, |op| op
+                .id("get_scripthash")
+                .addrs_tag()
+                .summary("Script hash information")
+                .description("Retrieve balance and transaction counts for any script, including those without an address (P2MS, empty, non-standard). `hash` is the SHA256 of the scriptPubKey in byte-reversed hex, as in the Electrum protocol. Mempool stats are only tracked for address types.\n\n*[Esplora docs](https://github.com/Blockstream/esplora/blob/master/API.md#get-scripthashhash)*")
+                .json_response::<ScriptHashStats>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/scripthash/{hash}/txs",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Path(path): Path<ScriptHashParam>,
+                _: Empty,
+                State(state): State<AppState>
+            | {
+                let strategy = state.script_hash_strategy(&path.hash);
+                state.respond_json(&headers, strategy, &uri, move |q| {
+                    let mempool_txs = if q.mempool().is_some() {
+                        q.script_hash_mempool_txs(&path.hash, MEMPOOL_PAGE)?
+                    } else {
+                        Vec::new()
+                    };
+                    let chain_limit = TXS_TOTAL_TARGET.saturating_sub(mempool_txs.len()).max(CHAIN_PAGE);
+                    let chain_txs = q.script_hash_txs_chain(&path.hash, None, chain_limit)?;
+                    let mut out = mempool_txs;
+                    out.extend(chain_txs);
+                    Ok(out)
+                }).await
+            }, |op| op
+                .id("get_scripthash_txs")
+                .addrs_tag()
+                .summary("Script hash transactions")
+                .description("Get transaction history for a script, newest first, paged like `/address/{address}/txs`. To paginate further confirmed history, use `/scripthash/{hash}/txs/chain/{last_seen_txid}`.\n\n*[Esplora docs](https://github.com/Blockstream/esplora/blob/master/API.md#get-scripthashhashtxs)*")
+                .json_response::<Vec<Transaction>>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/scripthash/{hash}/txs/chain",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Path(path): Path<ScriptHashParam>,
+                _: Empty,
+                State(state): State<AppState>
+            | {
+                state.respond_json(&headers, CacheStrategy::Tip, &uri, move |q| q.script_hash_txs_chain(&path.hash, None, CHAIN_PAGE)).await
+            }, |op| op
+                .id("get_scripthash_confirmed_txs")
+                .addrs_tag()
+                .summary("Script hash confirmed transactions")
+                .description("Get the first 25 confirmed transactions for a script. For pagination, use the path-style form `/txs/chain/{last_seen_txid}`.\n\n*[Esplora docs](https://github.com/Blockstream/esplora/blob/master/API.md#get-scripthashhashtxschainlast_seen_txid)*")
+                .json_response::<Vec<Transaction>>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/scripthash/{hash}/txs/chain/{after_txid}",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Path(path): Path<ScriptHashAfterTxidParam>,
+                _: Empty,
+                State(state): State<AppState>
+            | {
+                state.respond_json(&headers, CacheStrategy::Tip, &uri, move |q| q.script_hash_txs_chain(&path.hash, Some(path.after_txid), CHAIN_PAGE)).await
+            }, |op| op
+                .id("get_scripthash_confirmed_txs_after")
+                .addrs_tag()
+                .summary("Script hash confirmed transactions (paginated)")
+                .description("Get the next 25 confirmed transactions strictly older than `after_txid`.\n\n*[Esplora docs](https://github.com/Blockstream/esplora/blob/master/API.md#get-scripthashhashtxschainlast_seen_txid)*")
+                .json_response::<Vec<Transaction>>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/scripthash/{hash}/txs/mempool",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Path(path): Path<ScriptHashParam>,
+                _: Empty,
+                State(state): State<AppState>
+            | {
+                let hash = state.sync(|q| q.script_hash_mempool_hash(&path.hash)).unwrap_or(0);
+                state.respond_json(&headers, CacheStrategy::MempoolHash(hash), &uri, move |q| q.script_hash_mempool_txs(&path.hash, MEMPOOL_PAGE)).await
+            }, |op| op
+                .id("get_scripthash_mempool_txs")
+                .addrs_tag()
+                .summary("Script hash mempool transactions")
+                .description("Get unconfirmed transactions for a script from the mempool, newest first (up to 50). Always empty for scripts without an address, which the mempool doesn't track.\n\n*[Esplora docs](https://github.com/Blockstream/esplora/blob/master/API.md#get-scripthashhashtxsmempool)*")
+                .json_response::<Vec<Transaction>>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/scripthash/{hash}/utxo",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Path(path): Path<ScriptHashParam>,
+                _: Empty,
+                State(state): State<AppState>
+            | {
+                let strategy = state.script_hash_strategy(&path.hash);
+                let max_utxos = state.max_utxos;
+                state.respond_json(&headers, strategy, &uri, move |q| q.script_hash_utxos(path.hash, max_utxos)).await
+            }, |op| op
+                .id("get_scripthash_utxos")
+                .addrs_tag()
+                .summary("Script hash UTXOs")
+                .description("Get confirmed unspent transaction outputs (UTXOs) for a script. Returns txid, vout, value, and confirmation status for each UTXO.\n\n*[Esplora docs](https://github.com/Blockstream/esplora/blob/master/API.md#get-scripthashhashutxo)*")
+                .json_response::<Vec<Utxo>>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
         .api_route(
             "/api/v1/validate-address/{address}",
             get_with(async |
@@ -220,5 +421,65 @@ impl AddrRoutes for ApiRouter<AppState> {
                 .server_error()
             ),
         )
+        .api_route(
+            "/api/v1/richlist",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Query(params): Query<RichListQuery>,
+                State(state): State<AppState>
+            | {
+                let strategy = params
+                    .date
+                    .map_or(CacheStrategy::Tip, |date| state.date_strategy(Version::ONE, date));
+                state.respond_json(&headers, strategy, &uri, move |q| {
+                    let limit = rich_list_limit(params.limit)?;
+                    match params.date {
+                        Some(date) => q.rich_list_at(date, limit, params.addr_type),
+                        None => q.rich_list(limit, params.addr_type),
+                    }
+                }).await
+            }, |op| op
+                .id("get_rich_list")
+                .addrs_tag()
+                .summary("Rich list")
+                .description(&format!("The largest funded addresses by balance, ranked across every address type or within `type` (e.g. `p2pkh`, `v0_p2wpkh`, `v1_p2tr`). Returns the last computed block by default, or the end of `date` from the daily snapshots.\n\n`limit` defaults to {DEFAULT_RICH_LIST_LIMIT}, max {MAX_RICH_LIST_LIMIT}. Every address is ranked separately: addresses controlled by the same entity aren't merged."))
+                .json_response::<RichList>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/v1/richlist/dates",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                _: Empty,
+                State(state): State<AppState>
+            | {
+                state.respond_json(&headers, CacheStrategy::Tip, &uri, |q| q.rich_list_dates()).await
+            }, |op| op
+                .id("get_rich_list_dates")
+                .addrs_tag()
+                .summary("Rich list dates")
+                .description("Dates with a rich list snapshot, oldest first. Pass any of them as `date` to `/api/v1/richlist`.")
+                .json_response::<Vec<Date>>()
+                .not_modified()
+                .server_error()
+            ),
+        )
+    }
+}
+
+/// Validates the rich list limit.
+fn rich_list_limit(limit: Option<usize>) -> Result<usize> {
+    let limit = limit.unwrap_or(DEFAULT_RICH_LIST_LIMIT);
+    if limit == 0 || limit > MAX_RICH_LIST_LIMIT {
+        return Err(Error::Parse(format!(
+            "limit must be between 1 and {MAX_RICH_LIST_LIMIT}"
+        )));
     }
+    Ok(limit)
 }
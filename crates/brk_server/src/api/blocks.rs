@@ -4,8 +4,8 @@ use axum::{
     http::{HeaderMap, Uri},
 };
 use brk_types::{
-    BlockHash, BlockInfo, BlockInfoV1, BlockStatus, BlockTimestamp, BlockTxIndex, Height, Hex,
-    Transaction, Txid, Version,
+    BlockAudit, BlockCoinbase, BlockHash, BlockInfo, BlockInfoV1, BlockStatus, BlockTimestamp,
+    BlockTxIndex, Height, Hex, Reorg, Transaction, Txid, Version,
 };
 
 use crate::{
@@ -39,7 +39,7 @@ impl BlockRoutes for ApiRouter<AppState> {
                             .blocks_tag()
                             .summary("Block information")
                             .description(
-                                "Retrieve block information by block hash. Returns block metadata including height, timestamp, difficulty, size, weight, and transaction count.\n\n*[Mempool.space docs](https://mempool.space/docs/api/rest#get-block)*",
+                                "Retrieve block information by block hash. Returns block metadata including height, timestamp, difficulty, size, weight, and transaction count. Stale blocks orphaned by a reorg the indexer saw are served too, with `status.in_best_chain` set to `false`.\n\n*[Mempool.space docs](https://mempool.space/docs/api/rest#get-block)*",
                             )
                             .json_response::<BlockInfo>()
                             .not_modified()
@@ -72,6 +72,42 @@ impl BlockRoutes for ApiRouter<AppState> {
                     },
                 ),
             )
+            .api_route(
+                "/api/v1/block/{hash}/audit",
+                get_with(
+                    async |uri: Uri, headers: HeaderMap, Path(path): Path<BlockHashParam>, _: Empty, State(state): State<AppState>| {
+                        state.respond_json(&headers, state.mempool_strategy(), &uri, move |q| q.block_audit(&path.hash)).await
+                    },
+                    |op| {
+                        op.id("get_block_audit")
+                            .blocks_tag()
+                            .summary("Block audit")
+                            .description("Compares a mined block against the template the mempool projected for it right before it was seen: `missing` lists template transactions the block left out, `added` block transactions that weren't in the template (coinbase excluded). `feeDelta` is the block's fees minus the template's, `matchRate` the percentage of template transactions that were mined. Returns `404` for blocks mined while the mempool monitor wasn't running.")
+                            .json_response::<BlockAudit>()
+                            .not_modified()
+                            .bad_request()
+                            .not_found()
+                            .server_error()
+                    },
+                ),
+            )
+            .api_route(
+                "/api/v1/reorgs",
+                get_with(
+                    async |uri: Uri, headers: HeaderMap, _: Empty, State(state): State<AppState>| {
+                        state.respond_json(&headers, CacheStrategy::Tip, &uri, move |q| Ok(q.reorgs())).await
+                    },
+                    |op| {
+                        op.id("get_reorgs")
+                            .blocks_tag()
+                            .summary("Reorgs")
+                            .description("Chain reorganizations seen by the indexer, most recent first: the fork point, the orphaned blocks and the ones that replaced them, and `droppedTxids`, the transactions of the orphaned blocks that the replacement chain doesn't confirm (coinbases excluded). Reorgs that happened while the indexer wasn't running aren't recorded.")
+                            .json_response::<Vec<Reorg>>()
+                            .not_modified()
+                            .server_error()
+                    },
+                ),
+            )
             .api_route(
                 "/api/block/{hash}/header",
                 get_with(
@@ -92,6 +128,26 @@ impl BlockRoutes for ApiRouter<AppState> {
                     },
                 ),
             )
+            .api_route(
+                "/api/block/{hash}/coinbase",
+                get_with(
+                    async |uri: Uri, headers: HeaderMap, Path(path): Path<BlockHashParam>, _: Empty, State(state): State<AppState>| {
+                        let strategy = state.block_strategy(Version::ONE, &path.hash);
+                        state.respond_json(&headers, strategy, &uri, move |q| q.block_coinbase(&path.hash)).await
+                    },
+                    |op| {
+                        op.id("get_block_coinbase")
+                            .blocks_tag()
+                            .summary("Block coinbase")
+                            .description("How the block's coinbase is built: scriptSig, extranonce size, outputs and `payoutCount` (outputs carrying value, high for pools paying miners from the coinbase), witness commitment, merged-mining tags (`rsk`, `namecoin`) and the pool the block is attributed to. The same fields are computed per block as the `coinbase_*` series, and per pool as `{pool}_coinbase_*`.")
+                            .json_response::<BlockCoinbase>()
+                            .not_modified()
+                            .bad_request()
+                            .not_found()
+                            .server_error()
+                    },
+                ),
+            )
             .api_route(
                 "/api/block-height/{height}",
                 get_with(
@@ -177,7 +233,7 @@ impl BlockRoutes for ApiRouter<AppState> {
                             .blocks_tag()
                             .summary("Block status")
                             .description(
-                                "Retrieve the status of a block. Returns whether the block is in the best chain and, if so, its height and the hash of the next block.\n\n*[Mempool.space docs](https://mempool.space/docs/api/rest#get-block-status)*",
+                                "Retrieve the status of a block. Returns whether the block is in the best chain and, if so, its height and the hash of the next block. Archived stale blocks return `in_best_chain: false`.\n\n*[Mempool.space docs](https://mempool.space/docs/api/rest#get-block-status)*",
                             )
                             .json_response::<BlockStatus>()
                             .not_modified()
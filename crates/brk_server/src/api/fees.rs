@@ -1,11 +1,15 @@
 use aide::axum::{ApiRouter, routing::get_with};
 use axum::{
-    extract::State,
+    extract::{Query, State},
     http::{HeaderMap, Uri},
 };
-use brk_types::{MempoolBlock, RecommendedFees};
+use brk_types::{FeeEstimate, MempoolBlock, RecommendedFees};
 
-use crate::{AppState, extended::TransformResponseExtended, params::Empty};
+use crate::{
+    AppState,
+    extended::TransformResponseExtended,
+    params::{Empty, FeeEstimateParam},
+};
 
 pub trait FeesRoutes {
     fn add_fees_routes(self) -> Self;
@@ -76,5 +80,30 @@ impl FeesRoutes for ApiRouter<AppState> {
                 },
             ),
         )
+        .api_route(
+            "/api/v1/fees/estimate",
+            get_with(
+                async |uri: Uri,
+                       headers: HeaderMap,
+                       Query(params): Query<FeeEstimateParam>,
+                       State(state): State<AppState>| {
+                    state
+                        .respond_json(&headers, state.mempool_strategy(), &uri, move |q| {
+                            q.fee_estimate(params.target)
+                        })
+                        .await
+                },
+                |op| {
+                    op.id("get_fee_estimate")
+                        .fees_tag()
+                        .summary("Fee estimate by confirmation target")
+                        .description("Fee rates that historically got transactions confirmed within `target` blocks, learned from how long past mempool transactions took to confirm (like Bitcoin Core's `estimatesmartfee`). `estimates` gives the lowest rate reaching 50%, 80% and 95% confidence; `bands` shows the observations per fee-rate band, including how many blocks 50%, 80% and 95% of their confirmed transactions took. Transactions still waiting past the target count as failures. `target` ranges from 1 to 1008.")
+                        .json_response::<FeeEstimate>()
+                        .not_modified()
+                        .bad_request()
+                        .server_error()
+                },
+            ),
+        )
     }
 }
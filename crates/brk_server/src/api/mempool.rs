@@ -1,15 +1,19 @@
-use aide::axum::{ApiRouter, routing::get_with};
+use aide::axum::{
+    ApiRouter,
+    routing::{get_with, post_with},
+};
 use axum::{
+    Json,
     extract::{Path, State},
     http::{HeaderMap, Uri},
 };
 use brk_types::{
     BlockTemplate, BlockTemplateDiff, Dollars, MempoolInfo, MempoolRecentTx, NextBlockHash,
-    ReplacementNode, Txid,
+    ReplacementNode, TxSimulation, TxSimulationRequest, Txid,
 };
 
 use crate::{
-    AppState,
+    AppState, Error,
     extended::TransformResponseExtended,
     params::{Empty, NextBlockHashParam},
 };
@@ -208,5 +212,35 @@ impl MempoolRoutes for ApiRouter<AppState> {
                 },
             ),
         )
+        .api_route(
+            "/api/v1/mempool/simulate",
+            post_with(
+                async |_: Empty, State(state): State<AppState>, Json(request): Json<TxSimulationRequest>| {
+                    state
+                        .run(move |q| q.simulate_tx(request))
+                        .await
+                        .map(Json)
+                        .map_err(Error::from)
+                },
+                |op| {
+                    op.id("post_mempool_simulate")
+                        .mempool_tag()
+                        .summary("Simulate transaction")
+                        .description(
+                            "Dry run of a transaction against the projected mempool blocks. \
+                            The body is either `{\"hex\": ...}`, a raw signed or unsigned \
+                            transaction, or `{\"feeRate\": ..., \"vsize\": ..., \"parents\": [...]}`. \
+                            The transaction is linearized with its in-mempool cluster, replaces \
+                            the mempool transactions it conflicts with, and is packed with the \
+                            rest of the pool by chunk rate. Returns its projected block, its \
+                            effective chunk rate and the extra fee it needs to make the next block.",
+                        )
+                        .json_response::<TxSimulation>()
+                        .bad_request()
+                        .not_found()
+                        .server_error()
+                },
+            ),
+        )
     }
 }
@@ -175,6 +175,7 @@ impl ApiMetricsLegacyRoutes for ApiRouter<AppState> {
                     )
                     .json_response::<Vec<SeriesData>>()
                     .csv_response()
+                    .columnar_response()
                     .not_modified(),
             ),
         )
@@ -233,6 +234,7 @@ impl ApiMetricsLegacyRoutes for ApiRouter<AppState> {
                     )
                     .json_response::<SeriesData>()
                     .csv_response()
+                    .columnar_response()
                     .not_modified()
                     .not_found(),
             ),
@@ -262,6 +264,7 @@ impl ApiMetricsLegacyRoutes for ApiRouter<AppState> {
                     )
                     .json_response::<Vec<serde_json::Value>>()
                     .csv_response()
+                    .columnar_response()
                     .not_modified()
                     .not_found(),
             ),
@@ -29,8 +29,11 @@ mod oracle;
 mod series;
 mod series_legacy;
 mod server;
+mod stream;
 mod transactions;
 mod urpd;
+mod wallet;
+mod watch;
 
 use addrs::AddrRoutes;
 use blocks::BlockRoutes;
@@ -40,7 +43,10 @@ use mempool::MempoolRoutes;
 use mining::MiningRoutes;
 pub use openapi::*;
 use oracle::OracleRoutes;
+use stream::StreamRoutes;
 use transactions::TxRoutes;
+use wallet::WalletRoutes;
+use watch::WatchRoutes;
 
 pub trait ApiRoutes {
     fn add_api_routes(self) -> Self;
@@ -55,10 +61,13 @@ impl ApiRoutes for ApiRouter<AppState> {
             .add_metrics_legacy_routes()
             .add_general_routes()
             .add_addr_routes()
+            .add_wallet_routes()
             .add_block_routes()
             .add_mining_routes()
             .add_fees_routes()
             .add_mempool_routes()
+            .add_stream_routes()
+            .add_watch_routes()
             .add_oracle_routes()
             .add_tx_routes()
             .api_route(
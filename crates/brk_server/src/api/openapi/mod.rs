@@ -30,7 +30,7 @@ pub fn create_openapi() -> OpenApi {
 
 - **[Mempool.space](https://mempool.space/docs/api/rest) compatible**: Blocks, transactions, addresses, mining, fees, and mempool endpoints match the mempool.space REST API
 - **Series**: Thousands of on-chain time-series across multiple indexes (date, block height, etc.)
-- **Multiple formats**: JSON and CSV output
+- **Multiple formats**: JSON, CSV, Parquet and Arrow IPC output
 - **LLM-optimized**: [`/llms.txt`](/llms.txt) for discovery, [`/api.json`](/api.json) compact OpenAPI spec for tool use (full spec at [`/openapi.json`](/openapi.json))
 
 ### Quick start
@@ -103,7 +103,7 @@ All errors return structured JSON with a consistent format:
             name: "Series".to_string(),
             description: Some(
                 "Access thousands of Bitcoin network time-series data. Query historical statistics \
-                across various indexes (date, week, month, block height) with JSON or CSV output.\n\n\
+                across various indexes (date, week, month, block height) with JSON, CSV, Parquet or Arrow IPC output.\n\n\
                 **Note:** Series names are subject to change while the project is in active development."
                     .to_string(),
             ),
@@ -1,8 +1,8 @@
 //! Live `/api/series/*` API: catalog, search, info, single-series, bulk.
 //!
 //! Holds the shared `serve` helper used by every series endpoint that returns
-//! a formatted body (single + raw + bulk + the legacy module's deprecated
-//! handler in `series_legacy.rs`).
+//! a formatted body (single + raw + bulk + expr + the legacy module's
+//! deprecated handler in `series_legacy.rs`).
 
 use aide::axum::{ApiRouter, routing::get_with};
 use axum::{
@@ -16,8 +16,8 @@ use brk_query::{Query as BrkQuery, ResolvedQuery};
 use brk_traversable::TreeNode;
 use brk_types::{
     DataRangeFormat, Format, IndexInfo, Output, PaginatedSeries, Pagination, SearchQuery,
-    SeriesCount, SeriesData, SeriesInfo, SeriesNameWithIndex, SeriesOutput, SeriesSelection,
-    Version,
+    SeriesCount, SeriesData, SeriesExprSelection, SeriesInfo, SeriesNameWithIndex, SeriesOutput,
+    SeriesSelection, Version,
 };
 
 use crate::{
@@ -37,12 +37,30 @@ pub(super) async fn serve(
     headers: HeaderMap,
     params: SeriesSelection,
     to_bytes: impl FnOnce(&BrkQuery, ResolvedQuery) -> BrkResult<Bytes> + Send + 'static,
+) -> Result<Response> {
+    serve_resolved(
+        state,
+        uri,
+        headers,
+        move |q, max_weight| q.resolve(params, max_weight),
+        to_bytes,
+    )
+    .await
+}
+
+/// [`serve`] with a custom resolve step, given the server's max weight.
+async fn serve_resolved(
+    state: AppState,
+    uri: Uri,
+    headers: HeaderMap,
+    resolve: impl FnOnce(&BrkQuery, usize) -> BrkResult<ResolvedQuery> + Send + 'static,
+    to_bytes: impl FnOnce(&BrkQuery, ResolvedQuery) -> BrkResult<Bytes> + Send + 'static,
 ) -> Result<Response> {
     let max_weight = state.max_weight;
-    let resolved = state.run(move |q| q.resolve(params, max_weight)).await?;
+    let resolved = state.run(move |q| resolve(q, max_weight)).await?;
 
     let format = resolved.format;
-    let csv_filename = resolved.csv_filename();
+    let filename = resolved.filename();
     let cache_params = CacheParams::series(
         resolved.version,
         resolved.start,
@@ -58,9 +76,17 @@ pub(super) async fn serve(
             cache_params,
             move |h| match format {
                 Format::CSV => {
-                    h.insert_content_disposition_attachment(&csv_filename);
+                    h.insert_content_disposition_attachment(&filename);
                     h.insert_content_type_text_csv();
                 }
+                Format::Parquet => {
+                    h.insert_content_disposition_attachment(&filename);
+                    h.insert_content_type_application_parquet();
+                }
+                Format::Arrow => {
+                    h.insert_content_disposition_attachment(&filename);
+                    h.insert_content_type_application_arrow();
+                }
                 Format::JSON => h.insert_content_type_application_json(),
             },
             move |q| to_bytes(q, resolved),
@@ -71,7 +97,7 @@ pub(super) async fn serve(
 fn output_to_bytes(out: SeriesOutput) -> BrkResult<Bytes> {
     Ok(match out.output {
         Output::CSV(s) => Bytes::from(s),
-        Output::Json(v) => Bytes::from(v),
+        Output::Json(v) | Output::Parquet(v) | Output::Arrow(v) => Bytes::from(v),
     })
 }
 
@@ -99,6 +125,22 @@ async fn data_raw_handler(
     .await
 }
 
+async fn expr_handler(
+    uri: Uri,
+    headers: HeaderMap,
+    Query(params): Query<SeriesExprSelection>,
+    State(state): State<AppState>,
+) -> Result<Response> {
+    serve_resolved(
+        state,
+        uri,
+        headers,
+        move |q, max_weight| q.resolve_expr(params, max_weight),
+        |q, r| output_to_bytes(q.format(r)?),
+    )
+    .await
+}
+
 pub trait ApiSeriesRoutes {
     fn add_series_routes(self) -> Self;
 }
@@ -257,10 +299,11 @@ impl ApiSeriesRoutes for ApiRouter<AppState> {
                     .summary("Get series data")
                     .description(
                         "Fetch data for a specific series at the given index. \
-                        Use query parameters to filter by date range and format (json/csv)."
+                        Use query parameters to filter by date range and format (json/csv/parquet/arrow)."
                     )
                     .json_response::<SeriesData>()
                     .csv_response()
+                    .columnar_response()
                     .not_modified()
                     .not_found(),
             ),
@@ -293,6 +336,7 @@ impl ApiSeriesRoutes for ApiRouter<AppState> {
                     )
                     .json_response::<Vec<serde_json::Value>>()
                     .csv_response()
+                    .columnar_response()
                     .not_modified()
                     .not_found(),
             ),
@@ -387,8 +431,36 @@ impl ApiSeriesRoutes for ApiRouter<AppState> {
                     )
                     .json_response::<Vec<SeriesData>>()
                     .csv_response()
+                    .columnar_response()
                     .not_modified(),
             ),
         )
+        .api_route(
+            "/api/series/expr",
+            get_with(
+                |uri, headers, query, state| async move {
+                    expr_handler(uri, headers, query, state).await.into_response()
+                },
+                |op| op
+                    .id("get_series_expr")
+                    .series_tag()
+                    .summary("Series expression")
+                    .description(
+                        "Evaluate an expression over existing series at the given index, e.g. \
+                        `q=sma(realized_cap_lth / market_cap, 30)`. Supports `+ - * /`, numbers, parentheses and \
+                        the functions `sma(x, n)`, `ema(x, n)`, `stddev(x, n)`, `zscore(x, n)`, `lag(x, n)` and \
+                        `pct_change(x, n)` (percent, `n` defaults to 1 for the last two). Windows reach back before \
+                        `start` as needed; `ema` always starts from the first value. Missing inputs and divisions by \
+                        zero give null. Returns a SeriesData object of type `f64`, with the same range and format \
+                        parameters as `get_series`."
+                    )
+                    .json_response::<SeriesData>()
+                    .csv_response()
+                    .columnar_response()
+                    .not_modified()
+                    .bad_request()
+                    .not_found(),
+            ),
+        )
     }
 }
@@ -53,6 +53,7 @@ fn legacy_bytes(q: &BrkQuery, r: brk_query::ResolvedQuery) -> BrkResult<Bytes> {
     Ok(match q.format_legacy(r)?.output {
         OutputLegacy::CSV(s) => Bytes::from(s),
         OutputLegacy::Json(v) => Bytes::from(v.to_vec()),
+        OutputLegacy::Parquet(v) | OutputLegacy::Arrow(v) => Bytes::from(v),
     })
 }
 
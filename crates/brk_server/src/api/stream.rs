@@ -0,0 +1,287 @@
+use std::{collections::VecDeque, convert::Infallible, sync::Arc, time::Duration};
+
+use aide::axum::ApiRouter;
+use axum::{
+    extract::{Query, State},
+    response::{
+        IntoResponse, Response,
+        sse::{self, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use brk_error::Error as BrkError;
+use brk_mempool::{Event, EventKind, LeaveReason};
+use brk_types::{Addr, FeeRate, Txid};
+use futures_util::stream;
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+use crate::{AppState, Error, Result, params::StreamParams};
+
+/// Comment line sent when a subscription is idle, so proxies don't
+/// close the connection. Cycles run every second or so, with `cycle`
+/// heartbeats, unless filtered out.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+pub trait StreamRoutes {
+    fn add_stream_routes(self) -> Self;
+}
+
+impl StreamRoutes for ApiRouter<AppState> {
+    /// Not part of the OpenAPI spec: an endless `text/event-stream`
+    /// doesn't fit the generated request/response clients.
+    fn add_stream_routes(self) -> Self {
+        self.route(
+            "/api/stream",
+            get(
+                async |Query(params): Query<StreamParams>, State(state): State<AppState>| {
+                    subscribe(&state, params)
+                },
+            ),
+        )
+    }
+}
+
+fn subscribe(state: &AppState, params: StreamParams) -> Result<Response> {
+    let filter = Filter::try_from(params)?;
+    let receiver = state
+        .sync(|q| q.mempool().map(|mempool| mempool.subscribe()))
+        .ok_or(BrkError::MempoolNotAvailable)?;
+
+    let subscription = Subscription {
+        receiver,
+        filter,
+        pending: VecDeque::new(),
+    };
+    let events = stream::unfold(subscription, |mut subscription| async move {
+        let event = subscription.next().await?;
+        Some((Ok::<_, Infallible>(event), subscription))
+    });
+
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::new().interval(KEEP_ALIVE))
+        .into_response())
+}
+
+/// One client's view of the mempool event broadcast.
+struct Subscription {
+    receiver: Receiver<Arc<[Event]>>,
+    filter: Filter,
+    pending: VecDeque<sse::Event>,
+}
+
+impl Subscription {
+    /// Next SSE event to send, `None` once the mempool driver is gone.
+    /// A client too slow to keep up gets a `lagged` event with the
+    /// number of skipped cycles and resumes from there.
+    async fn next(&mut self) -> Option<sse::Event> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            match self.receiver.recv().await {
+                Ok(events) => self.pending.extend(
+                    events
+                        .iter()
+                        .filter(|event| self.filter.matches(event))
+                        .filter_map(|event| sse::Event::default().json_data(event).ok()),
+                ),
+                Err(RecvError::Lagged(skipped)) => {
+                    return Some(
+                        sse::Event::default()
+                            .event("lagged")
+                            .data(skipped.to_string()),
+                    );
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
+
+/// Validated [`StreamParams`].
+struct Filter {
+    kinds: Option<Vec<EventKind>>,
+    txid: Option<Txid>,
+    addr: Option<Addr>,
+    min_rate: Option<FeeRate>,
+}
+
+impl TryFrom<StreamParams> for Filter {
+    type Error = Error;
+
+    fn try_from(params: StreamParams) -> Result<Self> {
+        let kinds = params
+            .kinds
+            .map(|kinds| {
+                kinds
+                    .split(',')
+                    .map(|kind| kind.trim().parse::<EventKind>())
+                    .collect::<brk_error::Result<Vec<_>>>()
+            })
+            .transpose()
+            .map_err(|e| Error::bad_request(e.to_string()))?;
+        // Events carry canonical encodings, normalize the user's spelling
+        // (e.g. an uppercase bech32 address) the same way.
+        let addr = params
+            .addr
+            .map(|addr| Addr::try_from(&addr.script()?))
+            .transpose()
+            .map_err(|e| Error::bad_request(e.to_string()))?;
+        Ok(Self {
+            kinds,
+            txid: params.txid,
+            addr,
+            min_rate: params.min_rate,
+        })
+    }
+}
+
+impl Filter {
+    fn matches(&self, event: &Event) -> bool {
+        if self
+            .kinds
+            .as_ref()
+            .is_some_and(|kinds| !kinds.contains(&event.kind()))
+        {
+            return false;
+        }
+        match event {
+            Event::Enter { txid, rate, .. } => {
+                self.txid.is_none_or(|wanted| *txid == wanted) && self.rate_matches(*rate)
+            }
+            Event::Leave {
+                txid, reason, rate, ..
+            } => {
+                self.txid.is_none_or(|wanted| {
+                    *txid == wanted
+                        || matches!(reason, LeaveReason::Replaced { by } if *by == wanted)
+                }) && self.rate_matches(*rate)
+            }
+            Event::AddrEnter { addr, .. } | Event::AddrLeave { addr, .. } => {
+                self.addr.as_ref().is_none_or(|wanted| **addr == **wanted)
+            }
+            Event::Tip { .. } | Event::Block { .. } | Event::Fees { .. } | Event::Cycle { .. } => {
+                true
+            }
+        }
+    }
+
+    fn rate_matches(&self, rate: FeeRate) -> bool {
+        self.min_rate.is_none_or(|min| rate >= min)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use brk_types::{Sats, Timestamp, VSize};
+
+    use super::*;
+
+    const T1: &str = "0000000000000000000000000000000000000000000000000000000000000001";
+    const T2: &str = "0000000000000000000000000000000000000000000000000000000000000002";
+
+    fn params() -> StreamParams {
+        StreamParams {
+            kinds: None,
+            txid: None,
+            addr: None,
+            min_rate: None,
+        }
+    }
+
+    fn filter(params: StreamParams) -> Filter {
+        match Filter::try_from(params) {
+            Ok(filter) => filter,
+            Err(_) => panic!("invalid stream params"),
+        }
+    }
+
+    fn enter(txid: &str, rate: f64) -> Event {
+        Event::Enter {
+            t: 0.0,
+            txid: Txid::from_str(txid).unwrap(),
+            vsize: VSize::from(100u64),
+            fee: Sats::from(100u64),
+            rate: FeeRate::new(rate),
+            first_seen: Timestamp::from(0u32),
+        }
+    }
+
+    fn replaced(txid: &str, by: &str) -> Event {
+        Event::Leave {
+            t: 0.0,
+            txid: Txid::from_str(txid).unwrap(),
+            reason: LeaveReason::Replaced {
+                by: Txid::from_str(by).unwrap(),
+            },
+            rate: FeeRate::new(1.0),
+        }
+    }
+
+    fn addr_enter(addr: &str) -> Event {
+        Event::AddrEnter {
+            t: 0.0,
+            addr: Addr::from(addr.to_string()),
+        }
+    }
+
+    #[test]
+    fn no_filter_keeps_everything() {
+        let filter = filter(params());
+        assert!(filter.matches(&enter(T1, 1.0)));
+        assert!(filter.matches(&replaced(T1, T2)));
+    }
+
+    #[test]
+    fn kinds_filter() {
+        let filter = filter(StreamParams {
+            kinds: Some("leave, addr_enter".to_string()),
+            ..params()
+        });
+        assert!(!filter.matches(&enter(T1, 1.0)));
+        assert!(filter.matches(&replaced(T1, T2)));
+
+        assert!(
+            Filter::try_from(StreamParams {
+                kinds: Some("enter,nope".to_string()),
+                ..params()
+            })
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn txid_filter_follows_replacements() {
+        let filter = filter(StreamParams {
+            txid: Some(Txid::from_str(T2).unwrap()),
+            ..params()
+        });
+        assert!(!filter.matches(&enter(T1, 1.0)));
+        assert!(filter.matches(&enter(T2, 1.0)));
+        assert!(filter.matches(&replaced(T1, T2)));
+    }
+
+    #[test]
+    fn min_rate_filter() {
+        let filter = filter(StreamParams {
+            min_rate: Some(FeeRate::new(10.0)),
+            ..params()
+        });
+        assert!(!filter.matches(&enter(T1, 9.9)));
+        assert!(filter.matches(&enter(T1, 10.0)));
+    }
+
+    #[test]
+    fn addr_filter_normalizes_case() {
+        let addr = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+        let filter = filter(StreamParams {
+            addr: Some(Addr::from(addr.to_uppercase())),
+            ..params()
+        });
+        assert!(filter.matches(&addr_enter(addr)));
+        assert!(!filter.matches(&addr_enter("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa")));
+        assert!(filter.matches(&enter(T1, 1.0)));
+    }
+}
@@ -3,12 +3,14 @@ use aide::axum::{
     routing::{get_with, post_with},
 };
 use axum::{
+    Json,
     extract::{Path, State},
     http::{HeaderMap, Uri},
     response::Response,
 };
 use brk_types::{
-    CpfpInfo, Hex, MerkleProof, RbfResponse, Transaction, TxOutspend, TxStatus, Txid, Version,
+    CpfpInfo, Hex, MerkleProof, RbfResponse, Transaction, TxAnalysis, TxOutspend, TxStatus, Txid,
+    Version,
 };
 
 use crate::{
@@ -315,5 +317,45 @@ impl TxRoutes for ApiRouter<AppState> {
                 },
             ),
         )
+        .api_route(
+            "/api/tx/decode",
+            post_with(
+                async |_: Empty, State(state): State<AppState>, body: String| {
+                    state.run(move |q| q.decode_tx(&body))
+                        .await
+                        .map(Json)
+                        .map_err(Error::from)
+                },
+                |op| {
+                    op.id("post_tx_decode")
+                        .transactions_tag()
+                        .summary("Decode transaction")
+                        .description("Decode a raw transaction, signed or unsigned, provided as hex in the request body, without broadcasting it. Prevouts are resolved from the mempool and the indexer. Returns the transaction along with its fee, fee rate, vsize (unsigned inputs sized with an estimated signature), RBF signaling, output addresses that were already used, conflicting mempool transactions, and sighash and standardness warnings.")
+                        .json_response::<TxAnalysis>()
+                        .bad_request()
+                        .server_error()
+                },
+            ),
+        )
+        .api_route(
+            "/api/psbt/analyze",
+            post_with(
+                async |_: Empty, State(state): State<AppState>, body: String| {
+                    state.run(move |q| q.analyze_psbt(&body))
+                        .await
+                        .map(Json)
+                        .map_err(Error::from)
+                },
+                |op| {
+                    op.id("post_psbt_analyze")
+                        .transactions_tag()
+                        .summary("Analyze PSBT")
+                        .description("Review a PSBT, provided as base64 or hex in the request body, before signing it. Same report as `POST /api/tx/decode` for the transaction it would produce, with finalized inputs counted as signed. UTXOs carried by the PSBT fill in prevouts the node doesn't know and are checked against the ones it does. Requested and partial-signature sighash types other than the default are flagged.")
+                        .json_response::<TxAnalysis>()
+                        .bad_request()
+                        .server_error()
+                },
+            ),
+        )
     }
 }
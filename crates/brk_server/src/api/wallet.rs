@@ -0,0 +1,135 @@
+use std::str::FromStr;
+
+use aide::axum::{ApiRouter, routing::get_with};
+use axum::{
+    extract::{Query, State},
+    http::{HeaderMap, Uri},
+};
+use brk_error::{Error, Result};
+use brk_types::{Transaction, WalletDescriptor, WalletStats, WalletUtxo};
+
+use super::addrs::{CHAIN_PAGE, MEMPOOL_PAGE};
+use crate::{
+    AppState, CacheStrategy,
+    extended::TransformResponseExtended,
+    params::{WalletParams, WalletTxsParams},
+};
+
+const DEFAULT_GAP_LIMIT: u32 = 20;
+const MAX_GAP_LIMIT: u32 = 1_000;
+
+pub trait WalletRoutes {
+    fn add_wallet_routes(self) -> Self;
+}
+
+impl WalletRoutes for ApiRouter<AppState> {
+    fn add_wallet_routes(self) -> Self {
+        self.api_route(
+            "/api/wallet",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Query(params): Query<WalletParams>,
+                State(state): State<AppState>
+            | {
+                let strategy = state.chain_and_mempool_strategy();
+                state.respond_json(&headers, strategy, &uri, move |q| {
+                    let (descriptor, gap_limit) = parse(&params.descriptor, params.gap_limit)?;
+                    q.wallet_stats(&descriptor, gap_limit)
+                }).await
+            }, |op| op
+                .id("get_wallet")
+                .addrs_tag()
+                .summary("Wallet information")
+                .description("Scan an HD wallet and return its combined balance, transaction counts and used addresses. `descriptor` is an xpub, ypub or zpub (receive `/0/*` and change `/1/*` keychains) or a `pkh`, `wpkh`, `sh(wpkh)` or `tr` output descriptor with an optional `<0;1>` multipath step. Each keychain is derived until `gap_limit` addresses in a row have no on-chain or mempool activity.")
+                .json_response::<WalletStats>()
+                .not_modified()
+                .bad_request()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/wallet/utxo",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Query(params): Query<WalletParams>,
+                State(state): State<AppState>
+            | {
+                let max_utxos = state.max_utxos;
+                state.respond_json(&headers, CacheStrategy::Tip, &uri, move |q| {
+                    let (descriptor, gap_limit) = parse(&params.descriptor, params.gap_limit)?;
+                    q.wallet_utxos(&descriptor, gap_limit, max_utxos)
+                }).await
+            }, |op| op
+                .id("get_wallet_utxos")
+                .addrs_tag()
+                .summary("Wallet UTXOs")
+                .description("Get the confirmed unspent transaction outputs of an HD wallet, each with the address, keychain and derivation index holding it.")
+                .json_response::<Vec<WalletUtxo>>()
+                .not_modified()
+                .bad_request()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/wallet/txs/chain",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Query(params): Query<WalletTxsParams>,
+                State(state): State<AppState>
+            | {
+                state.respond_json(&headers, CacheStrategy::Tip, &uri, move |q| {
+                    let (descriptor, gap_limit) = parse(&params.descriptor, params.gap_limit)?;
+                    q.wallet_txs_chain(&descriptor, gap_limit, params.after_txid, CHAIN_PAGE)
+                }).await
+            }, |op| op
+                .id("get_wallet_confirmed_txs")
+                .addrs_tag()
+                .summary("Wallet confirmed transactions")
+                .description("Get 25 confirmed transactions of an HD wallet, newest first, each once even when it touches several wallet addresses. To paginate, pass the last txid of the previous page as `after_txid`.")
+                .json_response::<Vec<Transaction>>()
+                .not_modified()
+                .bad_request()
+                .not_found()
+                .server_error()
+            ),
+        )
+        .api_route(
+            "/api/wallet/txs/mempool",
+            get_with(async |
+                uri: Uri,
+                headers: HeaderMap,
+                Query(params): Query<WalletParams>,
+                State(state): State<AppState>
+            | {
+                let strategy = state.mempool_strategy();
+                state.respond_json(&headers, strategy, &uri, move |q| {
+                    let (descriptor, gap_limit) = parse(&params.descriptor, params.gap_limit)?;
+                    q.wallet_mempool_txs(&descriptor, gap_limit, MEMPOOL_PAGE)
+                }).await
+            }, |op| op
+                .id("get_wallet_mempool_txs")
+                .addrs_tag()
+                .summary("Wallet mempool transactions")
+                .description("Get unconfirmed transactions of an HD wallet from the mempool, newest first (up to 50).")
+                .json_response::<Vec<Transaction>>()
+                .not_modified()
+                .bad_request()
+                .server_error()
+            ),
+        )
+    }
+}
+
+/// Validates the descriptor and the gap limit.
+fn parse(descriptor: &str, gap_limit: Option<u32>) -> Result<(WalletDescriptor, u32)> {
+    let gap_limit = gap_limit.unwrap_or(DEFAULT_GAP_LIMIT);
+    if gap_limit == 0 || gap_limit > MAX_GAP_LIMIT {
+        return Err(Error::Parse(format!(
+            "gap_limit must be between 1 and {MAX_GAP_LIMIT}"
+        )));
+    }
+    Ok((WalletDescriptor::from_str(descriptor)?, gap_limit))
+}
@@ -0,0 +1,235 @@
+use std::{collections::VecDeque, convert::Infallible, str::FromStr, sync::Arc, time::Duration};
+
+use aide::axum::ApiRouter;
+use axum::{
+    extract::{Query, State},
+    response::{
+        IntoResponse, Response,
+        sse::{self, KeepAlive, Sse},
+    },
+    routing::get,
+};
+use brk_error::Error as BrkError;
+use brk_mempool::Event;
+use brk_query::AddrWatch;
+use brk_types::{Addr, AddrBytes, ScriptHash};
+use futures_util::stream;
+use tokio::sync::broadcast::{Receiver, error::RecvError};
+
+use crate::{AppState, Error, Result, params::WatchParams};
+
+const MAX_ADDRS: usize = 500;
+const DEFAULT_CONFIRMATIONS: u32 = 6;
+const MAX_CONFIRMATIONS: u32 = 100;
+
+/// Comment line sent between events, so proxies don't close the
+/// connection of a quiet watch-list.
+const KEEP_ALIVE: Duration = Duration::from_secs(15);
+
+pub trait WatchRoutes {
+    fn add_watch_routes(self) -> Self;
+}
+
+impl WatchRoutes for ApiRouter<AppState> {
+    /// Not part of the OpenAPI spec, same as `/api/stream`.
+    fn add_watch_routes(self) -> Self {
+        self.route(
+            "/api/address/watch",
+            get(
+                async |Query(params): Query<WatchParams>, State(state): State<AppState>| {
+                    subscribe(&state, params)
+                },
+            ),
+        )
+    }
+}
+
+fn subscribe(state: &AppState, params: WatchParams) -> Result<Response> {
+    let (addrs, script_hashes, confirmations) = parse(params)?;
+    let receiver = state
+        .sync(|q| q.mempool().map(|mempool| mempool.subscribe()))
+        .ok_or(BrkError::MempoolNotAvailable)?;
+    let watch = state.sync(|q| q.addr_watch(addrs, script_hashes, confirmations))?;
+
+    let watcher = Watcher {
+        state: state.clone(),
+        receiver,
+        watch: Some(watch),
+        pending: VecDeque::new(),
+    };
+    let events = stream::unfold(watcher, |mut watcher| async move {
+        let event = watcher.next().await?;
+        Some((Ok::<_, Infallible>(event), watcher))
+    });
+
+    Ok(Sse::new(events)
+        .keep_alive(KeepAlive::new().interval(KEEP_ALIVE))
+        .into_response())
+}
+
+/// Validates the comma-separated address and script hash lists and the
+/// confirmation target.
+fn parse(params: WatchParams) -> Result<(Vec<Addr>, Vec<ScriptHash>, u32)> {
+    let addrs = split(params.addresses.as_deref())
+        .map(|addr| {
+            AddrBytes::from_str(addr)
+                .map(|_| Addr::from(addr.to_string()))
+                .map_err(|_| Error::bad_request(format!("invalid address '{addr}'")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let script_hashes = split(params.scripthashes.as_deref())
+        .map(|script_hash| {
+            ScriptHash::from_str(script_hash)
+                .map_err(|_| Error::bad_request(format!("invalid script hash '{script_hash}'")))
+        })
+        .collect::<Result<Vec<_>>>()?;
+    match addrs.len() + script_hashes.len() {
+        0 => return Err(Error::bad_request("no address or script hash to watch")),
+        n if n > MAX_ADDRS => {
+            return Err(Error::bad_request(format!(
+                "too many entries, max {MAX_ADDRS} per watch-list"
+            )));
+        }
+        _ => {}
+    }
+    let confirmations = params.confirmations.unwrap_or(DEFAULT_CONFIRMATIONS);
+    if !(1..=MAX_CONFIRMATIONS).contains(&confirmations) {
+        return Err(Error::bad_request(format!(
+            "confirmations must be between 1 and {MAX_CONFIRMATIONS}"
+        )));
+    }
+    Ok((addrs, script_hashes, confirmations))
+}
+
+fn split(list: Option<&str>) -> impl Iterator<Item = &str> {
+    list.unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|entry| !entry.is_empty())
+}
+
+/// One client's watch-list, polled after every mempool cycle. Cycles
+/// follow new blocks closely, so confirmations are picked up as fast
+/// as mempool changes.
+struct Watcher {
+    state: AppState,
+    receiver: Receiver<Arc<[Event]>>,
+    /// Taken while a poll runs on the blocking pool.
+    watch: Option<AddrWatch>,
+    pending: VecDeque<sse::Event>,
+}
+
+impl Watcher {
+    /// Next SSE event to send, `None` once the mempool driver is gone.
+    /// A failed poll is reported as an `error` event and retried on the
+    /// next cycle. One that panicked lost the watch-list: it is reported
+    /// the same way, then the stream ends.
+    async fn next(&mut self) -> Option<sse::Event> {
+        loop {
+            if let Some(event) = self.pending.pop_front() {
+                return Some(event);
+            }
+            match self.receiver.recv().await {
+                // Polls diff against the watch's own state, skipped
+                // cycles lose nothing.
+                Ok(_) | Err(RecvError::Lagged(_)) => {}
+                Err(RecvError::Closed) => return None,
+            }
+            let mut watch = self.watch.take()?;
+            let polled = self
+                .state
+                .run(move |q| {
+                    let events = q.addr_watch_poll(&mut watch);
+                    Ok((watch, events))
+                })
+                .await;
+            let (watch, events) = match polled {
+                Ok(polled) => polled,
+                Err(e) => return Some(error_event(e)),
+            };
+            self.watch = Some(watch);
+            match events {
+                Ok(events) => self.pending.extend(
+                    events
+                        .iter()
+                        .filter_map(|event| sse::Event::default().json_data(event).ok()),
+                ),
+                Err(e) => return Some(error_event(e)),
+            }
+        }
+    }
+}
+
+fn error_event(e: BrkError) -> sse::Event {
+    sse::Event::default().event("error").data(e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn params(addresses: &str, confirmations: Option<u32>) -> WatchParams {
+        WatchParams {
+            addresses: Some(addresses.to_string()),
+            scripthashes: None,
+            confirmations,
+        }
+    }
+
+    #[test]
+    fn parses_list_and_default_confirmations() {
+        let Ok((addrs, script_hashes, confirmations)) = parse(params(
+            "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq, 1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa,",
+            None,
+        )) else {
+            panic!("valid watch params rejected");
+        };
+        assert_eq!(addrs.len(), 2);
+        assert!(script_hashes.is_empty());
+        assert_eq!(confirmations, DEFAULT_CONFIRMATIONS);
+    }
+
+    #[test]
+    fn parses_script_hashes_alone_or_with_addresses() {
+        const SCRIPT_HASH: &str =
+            "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161";
+        let only = WatchParams {
+            addresses: None,
+            scripthashes: Some(SCRIPT_HASH.to_string()),
+            confirmations: None,
+        };
+        let Ok((addrs, script_hashes, _)) = parse(only) else {
+            panic!("script hash watch rejected");
+        };
+        assert!(addrs.is_empty());
+        assert_eq!(script_hashes[0].to_string(), SCRIPT_HASH);
+
+        let mut both = params("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", None);
+        both.scripthashes = Some(format!("{SCRIPT_HASH},{SCRIPT_HASH}"));
+        assert!(
+            matches!(parse(both), Ok((addrs, script_hashes, _)) if addrs.len() == 1 && script_hashes.len() == 2)
+        );
+
+        let mut bad = params("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", None);
+        bad.scripthashes = Some("8b01".to_string());
+        assert!(parse(bad).is_err());
+    }
+
+    #[test]
+    fn caps_addresses_and_script_hashes_together() {
+        let addresses = vec!["1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa"; MAX_ADDRS].join(",");
+        assert!(parse(params(&addresses, None)).is_ok());
+        let mut over = params(&addresses, None);
+        over.scripthashes =
+            Some("8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161".to_string());
+        assert!(parse(over).is_err());
+    }
+
+    #[test]
+    fn rejects_invalid_empty_and_out_of_range() {
+        assert!(parse(params("nope", None)).is_err());
+        assert!(parse(params(" , ", None)).is_err());
+        assert!(parse(params("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", Some(0))).is_err());
+        assert!(parse(params("1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa", Some(101))).is_err());
+    }
+}
@@ -54,7 +54,9 @@ fn error_status(e: &BrkError) -> StatusCode {
         | BrkError::NoSeries
         | BrkError::SeriesUnsupportedIndex { .. }
         | BrkError::WeightExceeded { .. }
-        | BrkError::TooManyUtxos => StatusCode::BAD_REQUEST,
+        | BrkError::TooManyUtxos
+        | BrkError::TooManyAddrs
+        | BrkError::TooManyTxs => StatusCode::BAD_REQUEST,
 
         BrkError::UnknownAddr
         | BrkError::UnknownTxid
@@ -82,6 +84,8 @@ fn error_code(e: &BrkError) -> &'static str {
         BrkError::SeriesUnsupportedIndex { .. } => "series_unsupported_index",
         BrkError::WeightExceeded { .. } => "weight_exceeded",
         BrkError::TooManyUtxos => "too_many_utxos",
+        BrkError::TooManyAddrs => "too_many_addrs",
+        BrkError::TooManyTxs => "too_many_txs",
         BrkError::UnknownAddr => "unknown_addr",
         BrkError::UnknownTxid => "unknown_txid",
         BrkError::NotFound(_) => "not_found",
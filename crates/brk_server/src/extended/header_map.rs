@@ -14,6 +14,8 @@ pub trait HeaderMapExtended {
 
     fn insert_content_type_application_json(&mut self);
     fn insert_content_type_text_csv(&mut self);
+    fn insert_content_type_application_parquet(&mut self);
+    fn insert_content_type_application_arrow(&mut self);
 
     fn insert_vary_accept_encoding(&mut self);
 
@@ -64,6 +66,20 @@ impl HeaderMapExtended for HeaderMap {
         self.insert(header::CONTENT_TYPE, "text/csv".parse().unwrap());
     }
 
+    fn insert_content_type_application_parquet(&mut self) {
+        self.insert(
+            header::CONTENT_TYPE,
+            "application/vnd.apache.parquet".parse().unwrap(),
+        );
+    }
+
+    fn insert_content_type_application_arrow(&mut self) {
+        self.insert(
+            header::CONTENT_TYPE,
+            "application/vnd.apache.arrow.file".parse().unwrap(),
+        );
+    }
+
     fn insert_vary_accept_encoding(&mut self) {
         self.insert(header::VARY, "Accept-Encoding".parse().unwrap());
     }
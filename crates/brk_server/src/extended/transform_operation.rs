@@ -39,6 +39,8 @@ pub trait TransformResponseExtended<'t> {
     fn binary_response(self) -> Self;
     /// 200 with text/csv content type (adds CSV as alternative response format)
     fn csv_response(self) -> Self;
+    /// 200 with Parquet and Arrow IPC content types (adds both as alternative response formats)
+    fn columnar_response(self) -> Self;
     /// 400
     fn bad_request(self) -> Self;
     /// 404
@@ -142,6 +144,24 @@ impl<'t> TransformResponseExtended<'t> for TransformOperation<'t> {
         self
     }
 
+    fn columnar_response(mut self) -> Self {
+        // Add Parquet and Arrow IPC content types to existing 200 response
+        if let Some(responses) = &mut self.inner_mut().responses
+            && let Some(ReferenceOr::Item(response)) =
+                responses.responses.get_mut(&StatusCode::Code(200))
+        {
+            for content_type in [
+                "application/vnd.apache.parquet",
+                "application/vnd.apache.arrow.file",
+            ] {
+                response
+                    .content
+                    .insert(content_type.into(), MediaType::default());
+            }
+        }
+        self
+    }
+
     fn bad_request(self) -> Self {
         self.response_with::<400, Json<ErrorBody>, _>(|res| {
             res.description("Invalid request parameters")
@@ -0,0 +1,28 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use brk_types::{Height, Index};
+
+/// Query parameters for `/api/address/{address}/balance`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AddrBalanceQuery {
+    /// Block height to take the balance at, the tip if omitted
+    #[schemars(example = Height::new(840000))]
+    pub height: Option<Height>,
+}
+
+/// Query parameters for `/api/address/{address}/history`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct AddrHistoryQuery {
+    /// `height` or a date-based index (`day1`, `week1`, `month1`, ...)
+    pub index: Index,
+
+    /// First period to return, inclusive, the one of the first funding if
+    /// omitted
+    pub from: Option<usize>,
+
+    /// Last period to return, inclusive, the current one if omitted
+    pub to: Option<usize>,
+}
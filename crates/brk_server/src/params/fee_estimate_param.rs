@@ -0,0 +1,11 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Query parameters for `/api/v1/fees/estimate`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct FeeEstimateParam {
+    /// Confirmation target in blocks, from 1 to 1008
+    #[schemars(example = 6)]
+    pub target: u32,
+}
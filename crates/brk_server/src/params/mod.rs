@@ -1,4 +1,5 @@
 mod addr_after_txid_param;
+mod addr_balance_params;
 mod addr_hash_prefix_param;
 mod addr_param;
 mod block_count_param;
@@ -6,11 +7,16 @@ mod blockhash_param;
 mod blockhash_start_index;
 mod blockhash_tx_index;
 mod empty;
+mod fee_estimate_param;
 mod height_or_date_param;
 mod height_param;
 mod next_block_hash_param;
 mod pool_slug_param;
+mod rich_list_params;
+mod script_hash_after_txid_param;
+mod script_hash_param;
 mod series_param;
+mod stream_params;
 mod time_period_param;
 mod timestamp_param;
 mod tx_index_param;
@@ -19,8 +25,11 @@ mod txid_vout;
 mod txids_param;
 mod urpd_params;
 mod validate_addr_param;
+mod wallet_params;
+mod watch_params;
 
 pub use addr_after_txid_param::*;
+pub use addr_balance_params::*;
 pub use addr_hash_prefix_param::*;
 pub use addr_param::*;
 pub use block_count_param::*;
@@ -28,11 +37,16 @@ pub use blockhash_param::*;
 pub use blockhash_start_index::*;
 pub use blockhash_tx_index::*;
 pub use empty::*;
+pub use fee_estimate_param::*;
 pub use height_or_date_param::*;
 pub use height_param::*;
 pub use next_block_hash_param::*;
 pub use pool_slug_param::*;
+pub use rich_list_params::*;
+pub use script_hash_after_txid_param::*;
+pub use script_hash_param::*;
 pub use series_param::*;
+pub use stream_params::*;
 pub use time_period_param::*;
 pub use timestamp_param::*;
 pub use tx_index_param::*;
@@ -41,3 +55,5 @@ pub use txid_vout::*;
 pub use txids_param::*;
 pub use urpd_params::*;
 pub use validate_addr_param::*;
+pub use wallet_params::*;
+pub use watch_params::*;
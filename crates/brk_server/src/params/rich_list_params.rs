@@ -0,0 +1,20 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use brk_types::{Date, OutputType};
+
+/// Query parameters for `/api/v1/richlist`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct RichListQuery {
+    /// Addresses to return, largest first. Default: 100, max: 1000
+    pub limit: Option<usize>,
+
+    /// Address type to rank (`p2pkh`, `v0_p2wpkh`, `v1_p2tr`, ...), every type if omitted
+    #[serde(rename = "type")]
+    pub addr_type: Option<OutputType>,
+
+    /// Day of the snapshot to read, the last computed block if omitted
+    #[schemars(with = "Option<String>", example = &"2024-01-01")]
+    pub date: Option<Date>,
+}
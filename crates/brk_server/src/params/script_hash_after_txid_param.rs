@@ -0,0 +1,14 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use brk_types::{ScriptHash, Txid};
+
+/// Script hash + last-seen txid path parameters (Esplora-style pagination)
+#[derive(Deserialize, JsonSchema)]
+pub struct ScriptHashAfterTxidParam {
+    /// SHA256 of the scriptPubKey, byte-reversed hex as in the Electrum protocol
+    pub hash: ScriptHash,
+
+    /// Last txid from the previous page (return transactions strictly older than this)
+    pub after_txid: Txid,
+}
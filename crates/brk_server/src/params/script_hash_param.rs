@@ -0,0 +1,11 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use brk_types::ScriptHash;
+
+/// Script hash path parameter
+#[derive(Deserialize, JsonSchema)]
+pub struct ScriptHashParam {
+    /// SHA256 of the scriptPubKey, byte-reversed hex as in the Electrum protocol
+    pub hash: ScriptHash,
+}
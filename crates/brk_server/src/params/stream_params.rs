@@ -0,0 +1,25 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use brk_types::{Addr, FeeRate, Txid};
+
+/// Query parameters for `/api/stream`. Every filter is optional and only
+/// applies to the events carrying that attribute, others pass through.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct StreamParams {
+    /// Comma-separated event kinds to keep (`enter`, `leave`, `addr_enter`,
+    /// `addr_leave`, `tip`, `block`, `fees`, `cycle`). Default: all
+    pub kinds: Option<String>,
+
+    /// Keep only `enter`/`leave` events of this transaction, or of the
+    /// transactions it replaced
+    pub txid: Option<Txid>,
+
+    /// Keep only `addr_enter`/`addr_leave` events of this address
+    #[serde(rename = "address")]
+    pub addr: Option<Addr>,
+
+    /// Keep only `enter`/`leave` events at or above this fee rate (sat/vB)
+    pub min_rate: Option<FeeRate>,
+}
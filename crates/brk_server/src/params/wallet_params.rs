@@ -0,0 +1,29 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use brk_types::Txid;
+
+/// Query parameters for the `/api/wallet` endpoints.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WalletParams {
+    /// Extended public key (xpub, ypub, zpub) or output descriptor (`pkh`, `wpkh`, `sh(wpkh)`, `tr`)
+    pub descriptor: String,
+
+    /// Unused addresses in a row that end a keychain scan. Default: 20, max: 1000
+    pub gap_limit: Option<u32>,
+}
+
+/// Query parameters for `/api/wallet/txs/chain`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WalletTxsParams {
+    /// Extended public key (xpub, ypub, zpub) or output descriptor (`pkh`, `wpkh`, `sh(wpkh)`, `tr`)
+    pub descriptor: String,
+
+    /// Unused addresses in a row that end a keychain scan. Default: 20, max: 1000
+    pub gap_limit: Option<u32>,
+
+    /// Last txid from the previous page (return transactions strictly older than this)
+    pub after_txid: Option<Txid>,
+}
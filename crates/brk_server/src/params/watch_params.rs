@@ -0,0 +1,17 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+/// Query parameters for `/api/address/watch`.
+#[derive(Deserialize, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct WatchParams {
+    /// Comma-separated addresses to watch
+    pub addresses: Option<String>,
+
+    /// Comma-separated Electrum script hashes to watch, for scripts with
+    /// or without an address. At most 500 entries with `addresses`
+    pub scripthashes: Option<String>,
+
+    /// Confirmations after which a transaction stops being reported. Default: 6, max: 100
+    pub confirmations: Option<u32>,
+}
@@ -6,7 +6,7 @@ use axum::{
 };
 use brk_query::AsyncQuery;
 use brk_types::{
-    Addr, BlockHash, BlockHashPrefix, Date, Height, ONE_HOUR_IN_SEC, PoolSlug,
+    Addr, BlockHash, BlockHashPrefix, Date, Height, ONE_HOUR_IN_SEC, PoolSlug, ScriptHash,
     Timestamp as BrkTimestamp, Txid, Version,
 };
 use derive_more::Deref;
@@ -101,6 +101,13 @@ impl AppState {
         })
     }
 
+    /// `MempoolHash` while the script has mempool activity, `Tip`
+    /// otherwise. Scripts without an address are never in the mempool.
+    pub fn script_hash_strategy(&self, script_hash: &ScriptHash) -> CacheStrategy {
+        self.sync(|q| q.script_hash_mempool_hash(script_hash))
+            .map_or(CacheStrategy::Tip, CacheStrategy::MempoolHash)
+    }
+
     /// `Immutable` if the block is >6 deep (status stable), `Tip` otherwise.
     /// For block status which changes when the next block arrives.
     pub fn block_status_strategy(&self, version: Version, hash: &BlockHash) -> CacheStrategy {
@@ -173,6 +180,16 @@ impl AppState {
         CacheStrategy::MempoolHash(hash)
     }
 
+    /// `mempool_strategy` when a mempool is attached, `Tip` otherwise. For
+    /// responses mixing chain and mempool data of many addresses.
+    pub fn chain_and_mempool_strategy(&self) -> CacheStrategy {
+        if self.sync(|q| q.mempool().is_some()) {
+            self.mempool_strategy()
+        } else {
+            CacheStrategy::Tip
+        }
+    }
+
     /// Shared response pipeline: etag short-circuit, body computation on the
     /// query thread, header assembly. Used by [`AppState::respond`]
     /// (strategy-driven) and the series endpoint (which builds [`CacheParams`]
@@ -7,7 +7,7 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use vecdb::Formattable;
 
-use crate::AddrBytes;
+use crate::{AddrBytes, network};
 
 use super::OutputType;
 
@@ -52,7 +52,7 @@ impl TryFrom<(&ScriptBuf, OutputType)> for Addr {
                 Ok(Self(bytes_to_hex(bytes.as_slice())))
             }
             _ if output_type.is_addr() => {
-                let addr = bitcoin::Address::from_script(script, bitcoin::Network::Bitcoin)
+                let addr = bitcoin::Address::from_script(script, network())
                     .map_err(|_| Error::InvalidAddr)?;
                 Ok(Self(addr.to_string()))
             }
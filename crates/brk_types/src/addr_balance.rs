@@ -0,0 +1,23 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Dollars, Height, Sats};
+
+/// Confirmed balance of an address at the end of a block
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AddrBalance {
+    /// Block height the balance is taken at
+    #[schemars(example = Height::new(840000))]
+    pub height: Height,
+
+    /// Amount held in satoshis
+    #[schemars(example = Sats::new(15007599040))]
+    pub balance: Sats,
+
+    /// Number of unspent transaction outputs held
+    #[schemars(example = 3)]
+    pub utxo_count: u32,
+
+    /// Realized price (average cost basis) in USD of the balance
+    pub realized_price: Dollars,
+}
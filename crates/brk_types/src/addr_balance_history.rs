@@ -0,0 +1,22 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Dollars, Index, Sats};
+
+/// Confirmed balance of an address at the end of each period, from the
+/// one of its first funding to the current one.
+///
+/// Laid out like series data: the `n`-th values are for index `start + n`.
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct AddrBalanceHistory {
+    /// The index type used for this query
+    pub index: Index,
+    /// Start index (inclusive) of the returned range
+    pub start: usize,
+    /// End index (exclusive) of the returned range
+    pub end: usize,
+    /// Amount held in satoshis
+    pub balance: Vec<Sats>,
+    /// Realized price (average cost basis) in USD of the balance
+    pub realized_price: Vec<Dollars>,
+}
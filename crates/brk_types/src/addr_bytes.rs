@@ -1,11 +1,11 @@
 use std::str::FromStr;
 
-use bitcoin::{Network, PublicKey, ScriptBuf, opcodes, script::Builder};
+use bitcoin::{PublicKey, ScriptBuf, opcodes, script::Builder};
 use brk_error::Error;
 
 use super::{
     OutputType, P2ABytes, P2PK33Bytes, P2PK65Bytes, P2PKHBytes, P2SHBytes, P2TRBytes, P2WPKHBytes,
-    P2WSHBytes,
+    P2WSHBytes, network,
 };
 
 #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
@@ -201,7 +201,7 @@ impl AddrBytes {
     /// Parse an address string to a ScriptBuf
     pub fn addr_to_script(addr: &str) -> Result<ScriptBuf, Error> {
         if let Ok(addr) = bitcoin::Address::from_str(addr) {
-            if !addr.is_valid_for_network(Network::Bitcoin) {
+            if !addr.is_valid_for_network(network()) {
                 return Err(Error::InvalidNetwork);
             }
             let addr = addr.assume_checked();
@@ -0,0 +1,22 @@
+use serde::{Deserialize, Serialize};
+
+/// Address labels, declared in `labels.toml` and applied with
+/// [`crate::init_addr_labels`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddrLabelList {
+    #[serde(default)]
+    pub labels: Vec<AddrLabelDefinition>,
+}
+
+/// A label and the addresses carrying it. An address may carry several
+/// labels, and a label declared twice gets the addresses of both.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct AddrLabelDefinition {
+    /// Lowercase letters, digits and underscores, it's part of the
+    /// label's series names
+    pub name: String,
+
+    pub addresses: Vec<String>,
+}
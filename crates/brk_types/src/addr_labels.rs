@@ -0,0 +1,192 @@
+use std::{str::FromStr, sync::OnceLock};
+
+use brk_error::{Error, Result};
+use rustc_hash::FxHashMap;
+
+use crate::{AddrBytes, AddrLabelList, OutputType};
+
+static ADDR_LABELS: OnceLock<AddrLabels> = OnceLock::new();
+
+/// A label and its addresses, deduplicated and sorted.
+#[derive(Debug)]
+pub struct AddrLabel {
+    pub name: String,
+    pub addrs: Vec<AddrBytes>,
+    fingerprint: u64,
+}
+
+impl AddrLabel {
+    /// Hash of the label's addresses. Series computed under another
+    /// fingerprint are stale.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct AddrLabels {
+    labels: Vec<AddrLabel>,
+    by_addr: FxHashMap<AddrBytes, Vec<usize>>,
+}
+
+impl AddrLabels {
+    fn build(list: &AddrLabelList) -> Result<Self> {
+        let mut by_name: Vec<(String, Vec<AddrBytes>)> = Vec::new();
+
+        for def in &list.labels {
+            let valid = !def.name.is_empty()
+                && def
+                    .name
+                    .bytes()
+                    .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit() || b == b'_');
+            if !valid {
+                return Err(Error::Parse(format!(
+                    "invalid label `{}`: use lowercase letters, digits and underscores",
+                    def.name
+                )));
+            }
+
+            let addrs = def
+                .addresses
+                .iter()
+                .map(|addr| {
+                    AddrBytes::from_str(addr).map_err(|_| {
+                        Error::Parse(format!("invalid address `{addr}` in label `{}`", def.name))
+                    })
+                })
+                .collect::<Result<Vec<_>>>()?;
+
+            match by_name.iter_mut().find(|(name, _)| *name == def.name) {
+                Some((_, existing)) => existing.extend(addrs),
+                None => by_name.push((def.name.clone(), addrs)),
+            }
+        }
+
+        by_name.sort_unstable_by(|(a, _), (b, _)| a.cmp(b));
+
+        let mut by_addr: FxHashMap<AddrBytes, Vec<usize>> = FxHashMap::default();
+        let labels = by_name
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, mut addrs))| {
+                addrs.sort_unstable();
+                addrs.dedup();
+
+                let mut bytes = Vec::new();
+                for addr in &addrs {
+                    bytes.push(OutputType::from(addr) as u8);
+                    bytes.extend_from_slice(addr.as_slice());
+                    by_addr.entry(addr.clone()).or_default().push(i);
+                }
+
+                AddrLabel {
+                    name,
+                    addrs,
+                    fingerprint: rapidhash::v3::rapidhash_v3(&bytes),
+                }
+            })
+            .collect();
+
+        Ok(Self { labels, by_addr })
+    }
+
+    /// Labels sorted by name.
+    pub fn iter(&self) -> impl Iterator<Item = &AddrLabel> + '_ {
+        self.labels.iter()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.labels.is_empty()
+    }
+
+    /// Names of the labels `addr` carries, sorted.
+    pub fn names_of(&self, addr: &AddrBytes) -> Vec<String> {
+        self.by_addr.get(addr).map_or_else(Vec::new, |indexes| {
+            indexes
+                .iter()
+                .map(|i| self.labels[*i].name.clone())
+                .collect()
+        })
+    }
+}
+
+/// Loads the address labels. Call once at startup, before
+/// [`addr_labels`] is first used: later calls are ignored (first-wins),
+/// so library users that never call it get no labels.
+pub fn init_addr_labels(list: &AddrLabelList) -> Result<()> {
+    let _ = ADDR_LABELS.set(AddrLabels::build(list)?);
+    Ok(())
+}
+
+pub fn addr_labels() -> &'static AddrLabels {
+    ADDR_LABELS.get_or_init(AddrLabels::default)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AddrLabelDefinition;
+
+    const GENESIS: &str = "1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa";
+    const P2SH: &str = "3J98t1WpEZ73CNmQviecrnyiWrnqRhWNLy";
+    const P2WPKH: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+
+    fn build(labels: &[(&str, &[&str])]) -> Result<AddrLabels> {
+        AddrLabels::build(&AddrLabelList {
+            labels: labels
+                .iter()
+                .map(|(name, addresses)| AddrLabelDefinition {
+                    name: name.to_string(),
+                    addresses: addresses.iter().map(|a| a.to_string()).collect(),
+                })
+                .collect(),
+        })
+    }
+
+    fn bytes(addr: &str) -> AddrBytes {
+        AddrBytes::from_str(addr).unwrap()
+    }
+
+    #[test]
+    fn labels_are_sorted_and_merged_by_name() {
+        let labels = build(&[
+            ("treasury", &[P2WPKH]),
+            ("exchange", &[P2SH, GENESIS]),
+            ("exchange", &[GENESIS]),
+        ])
+        .unwrap();
+
+        let names: Vec<_> = labels.iter().map(|l| l.name.as_str()).collect();
+        assert_eq!(names, ["exchange", "treasury"]);
+        let exchange = labels.iter().next().unwrap();
+        assert_eq!(exchange.addrs.len(), 2);
+        assert!(exchange.addrs.is_sorted());
+    }
+
+    #[test]
+    fn an_address_carries_every_label_listing_it() {
+        let labels = build(&[("treasury", &[P2SH]), ("exchange", &[P2SH, GENESIS])]).unwrap();
+        assert_eq!(labels.names_of(&bytes(P2SH)), ["exchange", "treasury"]);
+        assert_eq!(labels.names_of(&bytes(GENESIS)), ["exchange"]);
+        assert!(labels.names_of(&bytes(P2WPKH)).is_empty());
+    }
+
+    #[test]
+    fn fingerprint_follows_the_address_list_only() {
+        let fingerprint = |labels: AddrLabels| labels.iter().next().unwrap().fingerprint();
+        let a = fingerprint(build(&[("exchange", &[GENESIS, P2SH])]).unwrap());
+        let reordered = fingerprint(build(&[("exchange", &[P2SH, GENESIS, P2SH])]).unwrap());
+        let changed = fingerprint(build(&[("exchange", &[GENESIS])]).unwrap());
+        assert_eq!(a, reordered);
+        assert_ne!(a, changed);
+    }
+
+    #[test]
+    fn invalid_names_and_addresses_are_rejected() {
+        assert!(build(&[("Exchange", &[GENESIS])]).is_err());
+        assert!(build(&[("", &[GENESIS])]).is_err());
+        assert!(build(&[("cold-wallet", &[GENESIS])]).is_err());
+        assert!(build(&[("exchange", &["not an address"])]).is_err());
+        assert!(build(&[]).unwrap().is_empty());
+    }
+}
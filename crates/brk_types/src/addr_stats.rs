@@ -15,6 +15,11 @@ pub struct AddrStats {
     /// Address type (p2pkh, p2sh, v0_p2wpkh, v0_p2wsh, v1_p2tr, etc.)
     pub addr_type: OutputType,
 
+    /// Labels of the address, from the server's `labels.toml`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    #[schemars(example = vec!["exchange".to_string()])]
+    pub labels: Vec<String>,
+
     /// Statistics for confirmed transactions on the blockchain
     pub chain_stats: AddrChainStats,
 
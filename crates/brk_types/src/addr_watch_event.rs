@@ -0,0 +1,69 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Addr, BlockHash, Height, ScriptHash, Txid};
+
+/// Progress of a transaction touching one or more watched addresses,
+/// as streamed by `/api/address/watch`. Every variant carries the
+/// watched addresses, and script hashes if any, the transaction pays to
+/// or spends from, as they were subscribed.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum AddrWatchEvent {
+    /// Seen in the mempool
+    Seen {
+        txid: Txid,
+        #[serde(rename = "addresses")]
+        addrs: Vec<Addr>,
+        #[serde(
+            rename = "scripthashes",
+            default,
+            skip_serializing_if = "Vec::is_empty"
+        )]
+        script_hashes: Vec<ScriptHash>,
+    },
+    /// Confirmation count changed, from 1 up to the subscription's target.
+    /// A tx first found already buried reports its current depth, capped
+    /// at the target.
+    Confirmed {
+        txid: Txid,
+        #[serde(rename = "addresses")]
+        addrs: Vec<Addr>,
+        #[serde(
+            rename = "scripthashes",
+            default,
+            skip_serializing_if = "Vec::is_empty"
+        )]
+        script_hashes: Vec<ScriptHash>,
+        confirmations: u32,
+        block_height: Height,
+        block_hash: BlockHash,
+    },
+    /// The block that confirmed the tx left the best chain. Tracking
+    /// resumes: expect `confirmed` once re-mined, or `dropped`.
+    Reorged {
+        txid: Txid,
+        #[serde(rename = "addresses")]
+        addrs: Vec<Addr>,
+        #[serde(
+            rename = "scripthashes",
+            default,
+            skip_serializing_if = "Vec::is_empty"
+        )]
+        script_hashes: Vec<ScriptHash>,
+        block_height: Height,
+        block_hash: BlockHash,
+    },
+    /// Left the mempool without confirming (replaced, expired or evicted)
+    Dropped {
+        txid: Txid,
+        #[serde(rename = "addresses")]
+        addrs: Vec<Addr>,
+        #[serde(
+            rename = "scripthashes",
+            default,
+            skip_serializing_if = "Vec::is_empty"
+        )]
+        script_hashes: Vec<ScriptHash>,
+    },
+}
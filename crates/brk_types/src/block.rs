@@ -3,7 +3,7 @@ use derive_more::Deref;
 
 use crate::BlkMetadata;
 
-use super::{BlockHash, CoinbaseTag, Height};
+use super::{BlockCoinbase, BlockHash, CoinbaseTag, Height};
 
 /// Raw block bytes and per-tx offsets for fast txid hashing.
 /// Present when block was parsed from blk*.dat files, absent for RPC blocks.
@@ -118,6 +118,20 @@ impl Block {
             .as_bytes();
         CoinbaseTag::from(bytes)
     }
+
+    pub fn has_witness_commitment(&self) -> bool {
+        self.coinbase_outputs()
+            .any(|out| BlockCoinbase::is_witness_commitment(&out.script_pubkey))
+    }
+
+    pub fn has_rsk_tag(&self) -> bool {
+        self.coinbase_outputs()
+            .any(|out| BlockCoinbase::is_rsk_tag(&out.script_pubkey))
+    }
+
+    fn coinbase_outputs(&self) -> impl Iterator<Item = &bitcoin::TxOut> {
+        self.txdata.first().into_iter().flat_map(|tx| &tx.output)
+    }
 }
 
 impl From<(Height, bitcoin::Block)> for Block {
@@ -0,0 +1,132 @@
+use rustc_hash::FxHashSet;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{BlockHash, Height, Sats, SatsSigned, Txid};
+
+/// A mined block compared against the template the mempool projected
+/// for it right before it was seen. Returned by
+/// `GET /api/v1/block/{hash}/audit`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockAudit {
+    pub height: Height,
+    pub hash: BlockHash,
+
+    /// Template txs the block left out
+    pub missing: Vec<Txid>,
+
+    /// Block txs that weren't in the template (coinbase excluded)
+    pub added: Vec<Txid>,
+
+    /// Number of txs in the template
+    pub expected_tx_count: u32,
+
+    /// Number of txs in the block (coinbase excluded)
+    pub actual_tx_count: u32,
+
+    /// Total fees of the template in satoshis
+    pub expected_fees: Sats,
+
+    /// Total fees of the block in satoshis
+    pub actual_fees: Sats,
+
+    /// `actual_fees - expected_fees` in satoshis
+    pub fee_delta: SatsSigned,
+
+    /// Share of the template's txs that made it into the block, in
+    /// percent. 100 when the template was empty.
+    pub match_rate: f64,
+}
+
+impl BlockAudit {
+    /// `expected` and `mined` are txids in template and block order,
+    /// `mined` without its coinbase.
+    pub fn new(
+        height: Height,
+        hash: BlockHash,
+        expected: &[Txid],
+        expected_fees: Sats,
+        mined: &[Txid],
+        actual_fees: Sats,
+    ) -> Self {
+        let in_block: FxHashSet<&Txid> = mined.iter().collect();
+        let in_template: FxHashSet<&Txid> = expected.iter().collect();
+
+        let missing: Vec<Txid> = expected
+            .iter()
+            .filter(|txid| !in_block.contains(txid))
+            .copied()
+            .collect();
+        let added = mined
+            .iter()
+            .filter(|txid| !in_template.contains(txid))
+            .copied()
+            .collect();
+
+        let match_rate = if expected.is_empty() {
+            100.0
+        } else {
+            (expected.len() - missing.len()) as f64 / expected.len() as f64 * 100.0
+        };
+
+        Self {
+            height,
+            hash,
+            missing,
+            added,
+            expected_tx_count: expected.len() as u32,
+            actual_tx_count: mined.len() as u32,
+            expected_fees,
+            actual_fees,
+            fee_delta: SatsSigned::from(actual_fees) - SatsSigned::from(expected_fees),
+            match_rate,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use bitcoin::hashes::Hash;
+
+    use super::*;
+
+    fn txid(byte: u8) -> Txid {
+        Txid::from(bitcoin::Txid::from_byte_array([byte; 32]))
+    }
+
+    #[test]
+    fn new_splits_missing_and_added() {
+        let expected = [txid(1), txid(2), txid(3), txid(4)];
+        let mined = [txid(2), txid(1), txid(5), txid(4)];
+        let audit = BlockAudit::new(
+            Height::new(800_000),
+            BlockHash::default(),
+            &expected,
+            Sats::new(10_000),
+            &mined,
+            Sats::new(9_000),
+        );
+
+        assert_eq!(audit.missing, vec![txid(3)]);
+        assert_eq!(audit.added, vec![txid(5)]);
+        assert_eq!(audit.fee_delta, SatsSigned::new(-1_000));
+        assert_eq!(audit.match_rate, 75.0);
+    }
+
+    #[test]
+    fn empty_template_fully_matches() {
+        let audit = BlockAudit::new(
+            Height::new(800_000),
+            BlockHash::default(),
+            &[],
+            Sats::ZERO,
+            &[txid(1)],
+            Sats::new(500),
+        );
+
+        assert!(audit.missing.is_empty());
+        assert_eq!(audit.added, vec![txid(1)]);
+        assert_eq!(audit.match_rate, 100.0);
+    }
+}
@@ -0,0 +1,66 @@
+use bitcoin::Script;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Height, PoolSlug, TxOut, Txid};
+
+/// `OP_RETURN OP_PUSHBYTES_36 aa21a9ed`, followed by the 32-byte commitment
+const WITNESS_COMMITMENT_PREFIX: [u8; 6] = [0x6a, 0x24, 0xaa, 0x21, 0xa9, 0xed];
+const RSK_TAG: &[u8] = b"RSKBLOCK:";
+
+/// How a block's coinbase is laid out and who it pays. Returned by
+/// `GET /api/block/{hash}/coinbase`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockCoinbase {
+    pub txid: Txid,
+    pub height: Height,
+
+    /// Pool the block is attributed to
+    pub pool: PoolSlug,
+
+    /// Coinbase scriptSig, hex
+    pub script_sig: String,
+
+    /// scriptSig bytes as text, as in the `coinbase_tag` series
+    pub tag: String,
+
+    /// Extranonce size in bytes, guessed from the scriptSig pushes, 0 if
+    /// not found
+    pub extranonce_size: u32,
+
+    pub outputs: Vec<TxOut>,
+
+    /// Outputs carrying value, above 1 for pools paying their miners
+    /// (FPPS or otherwise) straight from the coinbase
+    pub payout_count: u32,
+
+    /// Whether an output is a BIP141 witness commitment
+    pub witness_commitment: bool,
+
+    /// Sidechains and altcoins merge-mined by the block
+    pub merged_mining: Vec<MergedMining>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "lowercase")]
+pub enum MergedMining {
+    /// `RSKBLOCK:` tag in an `OP_RETURN` output
+    Rsk,
+    /// AuxPoW marker in the scriptSig
+    Namecoin,
+}
+
+impl BlockCoinbase {
+    pub fn is_witness_commitment(script: &Script) -> bool {
+        script.len() >= 38 && script.as_bytes().starts_with(&WITNESS_COMMITMENT_PREFIX)
+    }
+
+    pub fn is_rsk_tag(script: &Script) -> bool {
+        script.is_op_return()
+            && script
+                .as_bytes()
+                .windows(RSK_TAG.len())
+                .any(|w| w == RSK_TAG)
+    }
+}
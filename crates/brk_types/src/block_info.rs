@@ -1,7 +1,7 @@
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
-use crate::{BlockHash, Height, Timestamp, Weight};
+use crate::{BlockHash, BlockStatus, Height, Timestamp, Weight};
 
 /// Block information matching mempool.space /api/block/{hash}
 #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
@@ -43,4 +43,7 @@ pub struct BlockInfo {
     /// Median time of the last 11 blocks
     #[serde(rename = "mediantime")]
     pub median_time: Timestamp,
+    /// Only set, with `in_best_chain: false`, for archived stale blocks
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<BlockStatus>,
 }
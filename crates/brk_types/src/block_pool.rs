@@ -9,7 +9,7 @@ use crate::PoolSlug;
 pub struct BlockPool {
     /// Unique pool identifier
     #[schemars(example = 44)]
-    pub id: u8,
+    pub id: u16,
 
     /// Pool name
     #[schemars(example = &"Foundry USA")]
@@ -1,8 +1,11 @@
+use bitcoin::{Script, script::Instruction};
 use derive_more::Deref;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use vecdb::{Bytes, Formattable};
 
+const AUXPOW_MAGIC: [u8; 4] = [0xfa, 0xbe, b'm', b'm'];
+
 /// Coinbase scriptSig tag for pool identification.
 ///
 /// Stored as a fixed 101-byte record (1 byte length + 100 bytes data).
@@ -36,8 +39,43 @@ impl CoinbaseTag {
     /// Returns the tag as a string, decoding each byte as its latin-1/Unicode codepoint.
     #[inline]
     pub fn as_str(&self) -> String {
+        self.as_bytes().iter().map(|&b| b as char).collect()
+    }
+
+    #[inline]
+    pub fn as_bytes(&self) -> &[u8] {
         let len = (self.0[0] as usize).min(100);
-        self.0[1..1 + len].iter().map(|&b| b as char).collect()
+        &self.0[1..1 + len]
+    }
+
+    /// Size of the extranonce, taken to be the first push after the
+    /// BIP34 height (or the difficulty bits, before BIP34) that isn't
+    /// printable text or an AuxPoW commitment. 0 when every push is text or the script doesn't
+    /// parse that far. Pools that splice the extranonce into a text push
+    /// aren't caught.
+    pub fn extranonce_size(&self) -> u32 {
+        Script::from_bytes(self.as_bytes())
+            .instructions()
+            .skip(1)
+            .map_while(Result::ok)
+            .find_map(|instruction| match instruction {
+                Instruction::PushBytes(push)
+                    if !push.is_empty()
+                        && !push.as_bytes().starts_with(&AUXPOW_MAGIC)
+                        && !push.as_bytes().iter().all(|b| (0x20..=0x7e).contains(b)) =>
+                {
+                    Some(push.len() as u32)
+                }
+                _ => None,
+            })
+            .unwrap_or(0)
+    }
+
+    /// Whether the tag carries the AuxPoW merged-mining marker
+    /// (`fabe6d6d`) that Namecoin, and the chains merge-mined along
+    /// with it, commit to.
+    pub fn has_auxpow(&self) -> bool {
+        self.as_bytes().windows(4).any(|w| w == AUXPOW_MAGIC)
     }
 }
 
@@ -91,3 +129,46 @@ impl Formattable for CoinbaseTag {
         buf.push(b'"');
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tag(hex: &str) -> CoinbaseTag {
+        let bytes: Vec<u8> = (0..hex.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&hex[i..i + 2], 16).unwrap())
+            .collect();
+        CoinbaseTag::from(bytes.as_slice())
+    }
+
+    // Genesis block: bits, a one byte extranonce, then The Times headline.
+    const GENESIS: &str = "04ffff001d0104455468652054696d65732030332f4a616e2f32303039204368616e63656c6c6f72206f6e206272696e6b206f66207365636f6e64206261696c6f757420666f722062616e6b73";
+
+    // Height 840000, an AuxPoW commitment (marker, merkle root, size,
+    // nonce), the pool's text and an 8 byte extranonce.
+    const MERGE_MINED: &str = "0340d10c2cfabe6d6d9a3c1f6e1c8b2f4d7e0a5b3c9d8e7f6a5b4c3d2e1f0a9b8c7d6e5f4a3b2c1d0e0100000000000000082f5669614254432f08a1b2c3d4e5f60718";
+
+    #[test]
+    fn genesis_extranonce_follows_the_bits() {
+        let tag = tag(GENESIS);
+        assert!(tag.as_str().contains("Chancellor on brink"));
+        assert_eq!(tag.extranonce_size(), 1);
+        assert!(!tag.has_auxpow());
+    }
+
+    #[test]
+    fn auxpow_commitment_is_not_the_extranonce() {
+        let tag = tag(MERGE_MINED);
+        assert!(tag.has_auxpow());
+        assert_eq!(tag.extranonce_size(), 8);
+    }
+
+    #[test]
+    fn text_only_tags_have_no_extranonce() {
+        // Height 1, then "/slush/".
+        assert_eq!(tag("0101072f736c7573682f").extranonce_size(), 0);
+        // A push running past the end of the script stops the scan.
+        assert_eq!(tag("0340d10c4c").extranonce_size(), 0);
+    }
+}
@@ -0,0 +1,18 @@
+use serde::Deserialize;
+
+use crate::Index;
+
+/// Named series computed from an expression, declared in `series.toml`
+/// and stored next to the built-in series.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CustomSeries {
+    /// Series name, must not be taken by a built-in series
+    pub name: String,
+
+    /// Index the series is computed at, `height` or a date-based index
+    pub index: Index,
+
+    /// Expression over built-in series, same syntax as `/api/series/expr`
+    pub expr: String,
+}
@@ -0,0 +1,98 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use vecdb::{Bytes, Formattable};
+
+use crate::{OutputType, Sats, TypeIndex};
+
+/// Node of the address clustering union-find, one per address. A root,
+/// whose parent is the address itself, also holds its entity's totals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
+pub struct EntityNode {
+    /// Address type of the parent
+    pub parent_type: OutputType,
+    /// Index of the parent within its type
+    pub parent_index: TypeIndex,
+    /// Addresses in the entity, roots only
+    pub addr_count: u32,
+    /// Balance of the entity, roots only
+    pub balance: Sats,
+}
+
+impl EntityNode {
+    /// A single-address entity with nothing in it yet
+    pub fn root(output_type: OutputType, type_index: TypeIndex) -> Self {
+        Self {
+            parent_type: output_type,
+            parent_index: type_index,
+            addr_count: 1,
+            balance: Sats::ZERO,
+        }
+    }
+
+    #[inline]
+    pub fn parent(&self) -> (OutputType, TypeIndex) {
+        (self.parent_type, self.parent_index)
+    }
+
+    #[inline]
+    pub fn is_root_of(&self, output_type: OutputType, type_index: TypeIndex) -> bool {
+        self.parent() == (output_type, type_index)
+    }
+}
+
+impl std::fmt::Display for EntityNode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "parent: {}:{}, addr_count: {}, balance: {}",
+            self.parent_type, self.parent_index, self.addr_count, self.balance
+        )
+    }
+}
+
+impl Formattable for EntityNode {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        use std::fmt::Write;
+        let mut s = String::new();
+        write!(s, "{}", self).unwrap();
+        buf.extend_from_slice(s.as_bytes());
+    }
+
+    fn fmt_csv(&self, f: &mut String) -> std::fmt::Result {
+        let start = f.len();
+        self.fmt_into(f);
+        if f.as_bytes()[start..].contains(&b',') {
+            f.insert(start, '"');
+            f.push('"');
+        }
+        Ok(())
+    }
+
+    fn fmt_json(&self, buf: &mut Vec<u8>) {
+        buf.push(b'"');
+        self.write_to(buf);
+        buf.push(b'"');
+    }
+}
+
+impl Bytes for EntityNode {
+    type Array = [u8; 17];
+
+    fn to_bytes(&self) -> Self::Array {
+        let mut arr = [0u8; 17];
+        arr[0..1].copy_from_slice(self.parent_type.to_bytes().as_ref());
+        arr[1..5].copy_from_slice(u32::from(self.parent_index).to_bytes().as_ref());
+        arr[5..9].copy_from_slice(self.addr_count.to_bytes().as_ref());
+        arr[9..17].copy_from_slice(self.balance.to_bytes().as_ref());
+        arr
+    }
+
+    fn from_bytes(bytes: &[u8]) -> vecdb::Result<Self> {
+        Ok(Self {
+            parent_type: OutputType::from_bytes(&bytes[0..1])?,
+            parent_index: TypeIndex::new(u32::from_bytes(&bytes[1..5])?),
+            addr_count: u32::from_bytes(&bytes[5..9])?,
+            balance: Sats::from_bytes(&bytes[9..17])?,
+        })
+    }
+}
@@ -0,0 +1,60 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{FeeRate, Height};
+
+/// Fee rate estimate for a confirmation target, learned from how long
+/// past mempool txs took to confirm. Returned by
+/// `GET /api/v1/fees/estimate?target=N`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimate {
+    /// Confirmation target in blocks
+    pub target: u32,
+
+    /// Chain tip the estimate was made at
+    pub height: Height,
+
+    /// Lowest fee rate that confirmed within `target` blocks at each
+    /// confidence level, highest confidence last
+    pub estimates: Vec<FeeEstimateLevel>,
+
+    /// Observations per fee-rate band, lowest band first
+    pub bands: Vec<FeeEstimateBand>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimateLevel {
+    /// Required share of txs confirmed within the target, in percent
+    pub confidence: u8,
+
+    /// `None` when no band reaches the confidence with enough samples
+    pub fee_rate: Option<FeeRate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct FeeEstimateBand {
+    /// Lower bound of the band in sat/vB
+    pub fee_rate: FeeRate,
+
+    /// Confirmed txs, weighted down with age
+    pub confirmed: f64,
+
+    /// Live txs that have already waited more than `target` blocks
+    pub overdue: u32,
+
+    /// Share of txs confirmed within `target` blocks, overdue ones
+    /// counting as failures, in percent. `None` with too few samples.
+    pub success_rate: Option<f64>,
+
+    /// Blocks within which 50% of the confirmed txs confirmed
+    pub blocks_p50: Option<u32>,
+
+    /// Blocks within which 80% of the confirmed txs confirmed
+    pub blocks_p80: Option<u32>,
+
+    /// Blocks within which 95% of the confirmed txs confirmed
+    pub blocks_p95: Option<u32>,
+}
@@ -13,6 +13,12 @@ pub enum Format {
     JSON,
     #[serde(alias = "csv")]
     CSV,
+    /// Apache Parquet file with one typed column per series
+    #[serde(alias = "parquet")]
+    Parquet,
+    /// Apache Arrow IPC file with one typed column per series
+    #[serde(alias = "arrow")]
+    Arrow,
 }
 
 impl fmt::Display for Format {
@@ -20,6 +26,8 @@ impl fmt::Display for Format {
         match self {
             Format::JSON => write!(f, "json"),
             Format::CSV => write!(f, "csv"),
+            Format::Parquet => write!(f, "parquet"),
+            Format::Arrow => write!(f, "arrow"),
         }
     }
 }
@@ -30,8 +38,11 @@ impl From<Option<String>> for Format {
         if let Some(value) = value {
             let value = value.to_lowercase();
             let value = value.as_str();
-            if value == "csv" {
-                return Self::CSV;
+            match value {
+                "csv" => return Self::CSV,
+                "parquet" => return Self::Parquet,
+                "arrow" => return Self::Arrow,
+                _ => {}
             }
         }
         Self::JSON
@@ -3,6 +3,8 @@
 pub use vecdb::{CheckedSub, Exit, PrintableIndex, Version};
 
 mod addr;
+mod addr_balance;
+mod addr_balance_history;
 mod addr_bytes;
 mod addr_chain_stats;
 mod addr_hash;
@@ -10,9 +12,12 @@ mod addr_hash_prefix_matches;
 mod addr_index_any;
 mod addr_index_outpoint;
 mod addr_index_tx_index;
+mod addr_label_list;
+mod addr_labels;
 mod addr_mempool_stats;
 mod addr_stats;
 mod addr_validation;
+mod addr_watch_event;
 mod age;
 mod basis_points_16;
 mod basis_points_32;
@@ -22,6 +27,8 @@ mod bitcoin;
 mod blk_metadata;
 mod blk_position;
 mod block;
+mod block_audit;
+mod block_coinbase;
 mod block_extras;
 mod block_fee_rates_entry;
 mod block_fees_entry;
@@ -50,6 +57,7 @@ mod cents_squared_sats;
 mod cohort;
 mod coinbase_tag;
 mod cpfp;
+mod custom_series;
 mod data_range_format;
 mod date;
 mod day1;
@@ -63,7 +71,9 @@ mod dollars;
 mod empty_addr_data;
 mod empty_addr_index;
 mod empty_output_index;
+mod entity_node;
 mod epoch;
+mod fee_estimate;
 mod feerate;
 mod feerate_percentiles;
 mod format;
@@ -89,12 +99,14 @@ mod mempool_block;
 mod mempool_entry_info;
 mod mempool_info;
 mod mempool_recent_tx;
+mod mempool_sample;
 mod merkle_proof;
 mod minute10;
 mod minute30;
 mod month1;
 mod month3;
 mod month6;
+mod network;
 mod next_block_hash;
 mod ohlc;
 mod op_return_index;
@@ -126,6 +138,7 @@ mod pool;
 mod pool_detail;
 mod pool_hashrate_entry;
 mod pool_info;
+mod pool_overrides;
 mod pool_slug;
 mod pool_stats;
 mod pools;
@@ -136,13 +149,24 @@ mod range_map;
 mod raw_locktime;
 mod rbf;
 mod recommended_fees;
+mod reorg;
 mod reward_stats;
+mod rich_list;
+mod rich_list_entry;
+mod rich_list_raw;
 mod sats;
 mod sats_fract;
 mod sats_signed;
+mod script_hash;
+mod script_hash_chain_stats;
+mod script_hash_prefix;
+mod script_hash_stats;
+mod script_hash_txout_index;
 mod search_query;
 mod series_count;
 mod series_data;
+mod series_expr;
+mod series_expr_selection;
 mod series_info;
 mod series_list;
 mod series_name;
@@ -170,7 +194,9 @@ mod time_period;
 mod timestamp;
 mod tree_node;
 mod tx;
+mod tx_analysis;
 mod tx_index;
+mod tx_simulation;
 mod tx_status;
 mod tx_version;
 mod tx_version_raw;
@@ -189,9 +215,16 @@ mod urpd_aggregation;
 mod urpd_bucket;
 mod urpd_raw;
 mod utxo;
+mod utxo_cohort_list;
 mod vin;
 mod vout;
 mod vsize;
+mod wallet_addr;
+mod wallet_chain_stats;
+mod wallet_descriptor;
+mod wallet_keychain;
+mod wallet_stats;
+mod wallet_utxo;
 mod week1;
 mod weight;
 mod with_range_format;
@@ -201,6 +234,8 @@ mod year1;
 mod year10;
 
 pub use addr::*;
+pub use addr_balance::*;
+pub use addr_balance_history::*;
 pub use addr_bytes::*;
 pub use addr_chain_stats::*;
 pub use addr_hash::*;
@@ -208,9 +243,12 @@ pub use addr_hash_prefix_matches::*;
 pub use addr_index_any::*;
 pub use addr_index_outpoint::*;
 pub use addr_index_tx_index::*;
+pub use addr_label_list::*;
+pub use addr_labels::*;
 pub use addr_mempool_stats::*;
 pub use addr_stats::*;
 pub use addr_validation::*;
+pub use addr_watch_event::*;
 pub use age::*;
 pub use basis_points_16::*;
 pub use basis_points_32::*;
@@ -220,6 +258,8 @@ pub use bitcoin::*;
 pub use blk_metadata::*;
 pub use blk_position::*;
 pub use block::*;
+pub use block_audit::*;
+pub use block_coinbase::*;
 pub use block_extras::*;
 pub use block_fee_rates_entry::*;
 pub use block_fees_entry::*;
@@ -248,6 +288,7 @@ pub use cents_squared_sats::*;
 pub use cohort::*;
 pub use coinbase_tag::*;
 pub use cpfp::*;
+pub use custom_series::*;
 pub use data_range_format::*;
 pub use date::*;
 pub use day1::*;
@@ -261,7 +302,9 @@ pub use dollars::*;
 pub use empty_addr_data::*;
 pub use empty_addr_index::*;
 pub use empty_output_index::*;
+pub use entity_node::*;
 pub use epoch::*;
+pub use fee_estimate::*;
 pub use feerate::*;
 pub use feerate_percentiles::*;
 pub use format::*;
@@ -287,12 +330,14 @@ pub use mempool_block::*;
 pub use mempool_entry_info::*;
 pub use mempool_info::*;
 pub use mempool_recent_tx::*;
+pub use mempool_sample::*;
 pub use merkle_proof::*;
 pub use minute10::*;
 pub use minute30::*;
 pub use month1::*;
 pub use month3::*;
 pub use month6::*;
+pub use network::*;
 pub use next_block_hash::*;
 pub use ohlc::*;
 pub use op_return_index::*;
@@ -324,6 +369,7 @@ pub use pool::*;
 pub use pool_detail::*;
 pub use pool_hashrate_entry::*;
 pub use pool_info::*;
+pub use pool_overrides::*;
 pub use pool_slug::*;
 pub use pool_stats::*;
 pub use pools::*;
@@ -334,13 +380,24 @@ pub use range_map::*;
 pub use raw_locktime::*;
 pub use rbf::*;
 pub use recommended_fees::*;
+pub use reorg::*;
 pub use reward_stats::*;
+pub use rich_list::*;
+pub use rich_list_entry::*;
+pub use rich_list_raw::*;
 pub use sats::*;
 pub use sats_fract::*;
 pub use sats_signed::*;
+pub use script_hash::*;
+pub use script_hash_chain_stats::*;
+pub use script_hash_prefix::*;
+pub use script_hash_stats::*;
+pub use script_hash_txout_index::*;
 pub use search_query::*;
 pub use series_count::*;
 pub use series_data::*;
+pub use series_expr::*;
+pub use series_expr_selection::*;
 pub use series_info::*;
 pub use series_list::*;
 pub use series_name::*;
@@ -368,7 +425,9 @@ pub use time_period::*;
 pub use timestamp::*;
 pub use tree_node::*;
 pub use tx::*;
+pub use tx_analysis::*;
 pub use tx_index::*;
+pub use tx_simulation::*;
 pub use tx_status::*;
 pub use tx_version::*;
 pub use tx_version_raw::*;
@@ -387,9 +446,16 @@ pub use urpd_aggregation::*;
 pub use urpd_bucket::*;
 pub use urpd_raw::*;
 pub use utxo::*;
+pub use utxo_cohort_list::*;
 pub use vin::*;
 pub use vout::*;
 pub use vsize::*;
+pub use wallet_addr::*;
+pub use wallet_chain_stats::*;
+pub use wallet_descriptor::*;
+pub use wallet_keychain::*;
+pub use wallet_stats::*;
+pub use wallet_utxo::*;
 pub use week1::*;
 pub use weight::*;
 pub use witness::*;
@@ -0,0 +1,64 @@
+use std::collections::BTreeMap;
+
+use crate::{BlockAudit, FeeRate, Height, Minute10, RecommendedFees, Sats, VSize};
+
+/// Lower bounds in sat/vB of the fee-rate bands a [`MempoolSample`]
+/// splits the pool's vsize into. The last band is open-ended.
+pub const MEMPOOL_FEE_BANDS: [u16; 10] = [0, 1, 2, 3, 5, 10, 20, 50, 100, 200];
+
+/// One reading of the live mempool, taken after a sync cycle.
+#[derive(Debug, Default, Clone)]
+pub struct MempoolSample {
+    pub count: u64,
+    pub vsize: VSize,
+    pub total_fee: Sats,
+    /// Vsize per fee-rate band, see [`MEMPOOL_FEE_BANDS`]
+    pub band_vsize: [VSize; MEMPOOL_FEE_BANDS.len()],
+    pub fees: RecommendedFees,
+    /// Total fees of the projected next block
+    pub next_block_fee: Sats,
+    /// Vsize-weighted median fee rate of the projected next block
+    pub next_block_median_fee_rate: FeeRate,
+}
+
+impl MempoolSample {
+    /// Index into [`MEMPOOL_FEE_BANDS`] of the band holding `rate`.
+    pub fn band_of(rate: FeeRate) -> usize {
+        let rate = f64::from(rate);
+        MEMPOOL_FEE_BANDS
+            .iter()
+            .rposition(|&lower| rate >= lower as f64)
+            .unwrap_or_default()
+    }
+}
+
+/// Samples recorded since the last time they were taken out. Each
+/// `minute10` bucket keeps its latest sample. `height` keeps, for every
+/// new tip, the last sample taken before the block was seen. `audit`
+/// keeps the audits of the blocks mined in the meantime.
+#[derive(Debug, Default, Clone)]
+pub struct MempoolHistory {
+    pub minute10: BTreeMap<Minute10, MempoolSample>,
+    pub height: BTreeMap<Height, MempoolSample>,
+    pub audit: BTreeMap<Height, BlockAudit>,
+}
+
+impl MempoolHistory {
+    pub fn is_empty(&self) -> bool {
+        self.minute10.is_empty() && self.height.is_empty() && self.audit.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn band_of_uses_lower_bounds() {
+        assert_eq!(MempoolSample::band_of(FeeRate::new(0.5)), 0);
+        assert_eq!(MempoolSample::band_of(FeeRate::new(1.0)), 1);
+        assert_eq!(MempoolSample::band_of(FeeRate::new(4.9)), 3);
+        assert_eq!(MempoolSample::band_of(FeeRate::new(5.0)), 4);
+        assert_eq!(MempoolSample::band_of(FeeRate::new(250.0)), 9);
+    }
+}
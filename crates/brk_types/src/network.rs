@@ -0,0 +1,67 @@
+use std::sync::OnceLock;
+
+pub use bitcoin::Network;
+
+use crate::BLOCKS_PER_HALVING;
+
+static NETWORK: OnceLock<Network> = OnceLock::new();
+
+/// Set once at startup, before any address is encoded or validated.
+/// Subsequent calls are ignored (first-wins), so library users that never
+/// call it keep the mainnet behaviour.
+pub fn init_network(network: Network) {
+    let _ = NETWORK.set(network);
+}
+
+/// Network the process is running against. Drives address encoding and
+/// validation, blk file magic bytes and the mainnet-only datasets (oracle
+/// prices, mining pool tags). Defaults to mainnet if [`init_network`] was
+/// never called.
+#[inline]
+pub fn network() -> Network {
+    NETWORK.get().copied().unwrap_or(Network::Bitcoin)
+}
+
+#[inline]
+pub fn is_mainnet() -> bool {
+    network() == Network::Bitcoin
+}
+
+/// Blocks between subsidy halvings on `network`, as in Bitcoin Core's
+/// chainparams. Only regtest differs from mainnet.
+#[inline]
+pub fn halving_interval(network: Network) -> u32 {
+    match network {
+        Network::Regtest => 150,
+        _ => BLOCKS_PER_HALVING,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn defaults_to_mainnet() {
+        assert_eq!(network(), Network::Bitcoin);
+        assert!(is_mainnet());
+    }
+
+    #[test]
+    fn halving_interval_per_network() {
+        assert_eq!(halving_interval(Network::Bitcoin), 210_000);
+        assert_eq!(halving_interval(Network::Testnet), 210_000);
+        assert_eq!(halving_interval(Network::Testnet4), 210_000);
+        assert_eq!(halving_interval(Network::Signet), 210_000);
+        assert_eq!(halving_interval(Network::Regtest), 150);
+    }
+
+    #[test]
+    fn magic_bytes_per_network() {
+        let magic = |network: Network| network.magic().to_bytes();
+        assert_eq!(magic(Network::Bitcoin), [0xf9, 0xbe, 0xb4, 0xd9]);
+        assert_eq!(magic(Network::Testnet4), [0x1c, 0x16, 0x3f, 0x28]);
+        assert_eq!(magic(Network::Signet), [0x0a, 0x03, 0xcf, 0x40]);
+        assert_eq!(magic(Network::Regtest), [0xfa, 0xbf, 0xb5, 0xda]);
+    }
+}
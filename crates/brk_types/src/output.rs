@@ -5,6 +5,8 @@ use crate::Format;
 pub enum Output {
     Json(Vec<u8>),
     CSV(String),
+    Parquet(Vec<u8>),
+    Arrow(Vec<u8>),
 }
 
 impl Output {
@@ -13,15 +15,19 @@ impl Output {
         match self {
             Output::CSV(s) => s,
             Output::Json(v) => unsafe { String::from_utf8_unchecked(v) },
+            Output::Parquet(v) | Output::Arrow(v) => String::from_utf8_lossy(&v).into_owned(),
         }
     }
 
-    pub fn default(format: Format) -> Self {
+    /// Output without data, `None` for Parquet and Arrow whose files carry
+    /// a schema and are built by the query.
+    pub fn empty(format: Format) -> Option<Self> {
         match format {
-            Format::CSV => Output::CSV(String::new()),
-            Format::JSON => {
-                Output::Json(br#"{"version":0,"total":0,"start":0,"end":0,"data":[]}"#.to_vec())
-            }
+            Format::CSV => Some(Output::CSV(String::new())),
+            Format::JSON => Some(Output::Json(
+                br#"{"version":0,"total":0,"start":0,"end":0,"data":[]}"#.to_vec(),
+            )),
+            Format::Parquet | Format::Arrow => None,
         }
     }
 }
@@ -31,6 +37,8 @@ impl Output {
 pub enum OutputLegacy {
     Json(LegacyValue),
     CSV(String),
+    Parquet(Vec<u8>),
+    Arrow(Vec<u8>),
 }
 
 impl OutputLegacy {
@@ -39,13 +47,19 @@ impl OutputLegacy {
         match self {
             OutputLegacy::CSV(s) => s,
             OutputLegacy::Json(v) => unsafe { String::from_utf8_unchecked(v.to_vec()) },
+            OutputLegacy::Parquet(v) | OutputLegacy::Arrow(v) => {
+                String::from_utf8_lossy(&v).into_owned()
+            }
         }
     }
 
-    pub fn default(format: Format) -> Self {
+    /// Output without data, `None` for Parquet and Arrow whose files carry
+    /// a schema and are built by the query.
+    pub fn empty(format: Format) -> Option<Self> {
         match format {
-            Format::CSV => OutputLegacy::CSV(String::new()),
-            Format::JSON => OutputLegacy::Json(LegacyValue::List(b"[]".to_vec())),
+            Format::CSV => Some(OutputLegacy::CSV(String::new())),
+            Format::JSON => Some(OutputLegacy::Json(LegacyValue::List(b"[]".to_vec()))),
+            Format::Parquet | Format::Arrow => None,
         }
     }
 }
@@ -71,6 +71,9 @@ impl OutputType {
         Self::P2A,
     ];
 
+    /// Spendable types without an address, only findable by script hash.
+    pub const SCRIPT_HASH_TYPES: [Self; 3] = [Self::P2MS, Self::Empty, Self::Unknown];
+
     fn is_valid(value: u8) -> bool {
         value <= Self::Unknown as u8
     }
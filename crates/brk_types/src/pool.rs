@@ -36,12 +36,12 @@ impl Pool {
     }
 
     /// Pool ID matching mempool.space's `unique_id` field (0-indexed, raw pools-v2.json value)
-    pub fn mempool_unique_id(&self) -> u8 {
+    pub fn mempool_unique_id(&self) -> u16 {
         self.slug.into()
     }
 
     /// Pool ID matching mempool.space's `id` field (1-indexed)
-    pub fn mempool_id(&self) -> u8 {
+    pub fn mempool_id(&self) -> u16 {
         self.mempool_unique_id() + 1
     }
 }
@@ -38,7 +38,7 @@ pub struct PoolDetail {
 pub struct PoolDetailInfo {
     /// Pool identifier
     #[schemars(example = 111)]
-    pub id: u8,
+    pub id: u16,
 
     /// Pool name
     #[schemars(example = &"Foundry USA")]
@@ -59,7 +59,7 @@ pub struct PoolDetailInfo {
 
     /// Unique pool identifier
     #[schemars(example = 44)]
-    pub unique_id: u8,
+    pub unique_id: u16,
 }
 
 impl From<&'static Pool> for PoolDetailInfo {
@@ -17,7 +17,7 @@ pub struct PoolInfo {
 
     /// Unique numeric pool identifier
     #[schemars(example = 44)]
-    pub unique_id: u8,
+    pub unique_id: u16,
 }
 
 impl From<&'static Pool> for PoolInfo {
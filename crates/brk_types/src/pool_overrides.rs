@@ -0,0 +1,79 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Height;
+
+/// Changes to the built-in mining pool list, declared in `pools.toml`
+/// and applied with [`crate::init_pools`].
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolOverrides {
+    /// New pools, or additions to built-in ones
+    #[serde(default)]
+    pub pools: Vec<PoolDefinition>,
+
+    /// Attribution rules, tried in order before the coinbase tags and
+    /// payout addresses of every pool
+    #[serde(default)]
+    pub rules: Vec<PoolRule>,
+}
+
+/// A pool to add, or a built-in pool (matched by `slug`) to rename or
+/// to give more payout addresses and coinbase tags.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolDefinition {
+    /// URL slug
+    pub slug: String,
+
+    /// Stable id of a new pool, 256 or above. It's what the computed
+    /// series store, so it must not change once blocks were attributed.
+    /// Leave it out for built-in pools.
+    #[serde(default)]
+    pub id: Option<u16>,
+
+    /// Display name, the slug by default for new pools
+    #[serde(default)]
+    pub name: Option<String>,
+
+    /// Website URL
+    #[serde(default)]
+    pub link: Option<String>,
+
+    /// Payout addresses, taking precedence over the built-in ones
+    #[serde(default)]
+    pub addresses: Vec<String>,
+
+    /// Case-insensitive regexes over the coinbase tag, taking
+    /// precedence over the built-in tags
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// Attributes to `pool` the blocks matching every criterion given.
+/// `mined_by` merges a pool into another, or splits it by height.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PoolRule {
+    /// Slug of the pool the blocks go to
+    pub pool: String,
+
+    /// First height the rule applies at
+    #[serde(default)]
+    pub from: Option<Height>,
+
+    /// Last height the rule applies at
+    #[serde(default)]
+    pub to: Option<Height>,
+
+    /// Case-insensitive regex over the coinbase tag
+    #[serde(default)]
+    pub coinbase_tag: Option<String>,
+
+    /// One of the coinbase's payout addresses
+    #[serde(default)]
+    pub address: Option<String>,
+
+    /// Slug of the pool the block would be attributed to otherwise
+    #[serde(default)]
+    pub mined_by: Option<String>,
+}
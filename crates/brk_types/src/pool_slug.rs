@@ -1,419 +1,132 @@
-use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
-use strum::Display;
+use std::fmt;
+
+use schemars::{JsonSchema, SchemaGenerator};
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
 use vecdb::{Bytes, Formattable};
 
-/// URL-friendly mining pool identifier
-#[allow(clippy::upper_case_acronyms)]
-#[derive(
-    Default,
-    Debug,
-    Copy,
-    Display,
-    Clone,
-    PartialEq,
-    Eq,
-    PartialOrd,
-    Ord,
-    Hash,
-    Serialize,
-    Deserialize,
-    JsonSchema,
-)]
-#[serde(rename_all = "lowercase")]
-#[strum(serialize_all = "lowercase")]
-#[repr(u8)]
-pub enum PoolSlug {
-    #[default]
-    Unknown,
-    BlockFills,
-    UltimusPool,
-    TerraPool,
-    Luxor,
-    #[serde(rename = "1thash")]
-    OneThash,
-    BtcCom,
-    Bitfarms,
-    HuobiPool,
-    WayiCn,
-    CanoePool,
-    BtcTop,
-    BitcoinCom,
-    #[serde(rename = "175btc")]
-    Pool175btc,
-    GbMiners,
-    AXbt,
-    AsicMiner,
-    BitMinter,
-    BitcoinRussia,
-    BtcServ,
-    SimplecoinUs,
-    BtcGuild,
-    Eligius,
-    OzCoin,
-    EclipseMc,
-    MaxBtc,
-    TripleMining,
-    CoinLab,
-    #[serde(rename = "50btc")]
-    Pool50btc,
-    GhashIo,
-    StMiningCorp,
-    Bitparking,
-    Mmpool,
-    Polmine,
-    KncMiner,
-    Bitalo,
-    F2Pool,
-    Hhtt,
-    MegaBigPower,
-    MtRed,
-    NmcBit,
-    YourbtcNet,
-    GiveMeCoins,
-    BraiinsPool,
-    AntPool,
-    MultiCoinCo,
-    BcpoolIo,
-    Cointerra,
-    KanoPool,
-    SoloCk,
-    CkPool,
-    NiceHash,
-    BitClub,
-    BitcoinAffiliateNetwork,
-    Btcc,
-    BwPool,
-    ExxBw,
-    Bitsolo,
-    BitFury,
-    #[serde(rename = "21inc")]
-    TwentyOneInc,
-    DigitalBtc,
-    #[serde(rename = "8baochi")]
-    EightBaochi,
-    MyBtcCoinPool,
-    TbDice,
-    HashPool,
-    Nexious,
-    BravoMining,
-    HotPool,
-    OkExPool,
-    BcMonster,
-    #[serde(rename = "1hash")]
-    OneHash,
-    Bixin,
-    TatmasPool,
-    ViaBtc,
-    ConnectBtc,
-    BatPool,
-    Waterhole,
-    DcExploration,
-    Dcex,
-    BtPool,
-    #[serde(rename = "58coin")]
-    FiftyEightCoin,
-    BitcoinIndia,
-    ShawnP0wers,
-    PHashIo,
-    RigPool,
-    HaoZhuZhu,
-    #[serde(rename = "7pool")]
-    SevenPool,
-    MiningKings,
-    HashBx,
-    DPool,
-    Rawpool,
-    Haominer,
-    Helix,
-    BitcoinUkraine,
-    Poolin,
-    SecretSuperstar,
-    TigerpoolNet,
-    SigmapoolCom,
-    OkpoolTop,
-    Hummerpool,
-    Tangpool,
-    BytePool,
-    SpiderPool,
-    NovaBlock,
-    MiningCity,
-    BinancePool,
-    Minerium,
-    LubianCom,
-    Okkong,
-    AaoPool,
-    EmcdPool,
-    FoundryUsa,
-    SbiCrypto,
-    ArkPool,
-    PureBtcCom,
-    MaraPool,
-    KuCoinPool,
-    EntrustCharityPool,
-    OkMiner,
-    Titan,
-    PegaPool,
-    BtcNuggets,
-    CloudHashing,
-    DigitalXMintsy,
-    Telco214,
-    BtcPoolParty,
-    Multipool,
-    TransactionCoinMining,
-    BtcDig,
-    TrickysBtcPool,
-    BtcMp,
-    Eobot,
-    Unomp,
-    Patels,
-    GoGreenLight,
-    BitcoinIndiaPool,
-    EkanemBtc,
-    Canoe,
-    Tiger,
-    #[serde(rename = "1m1x")]
-    OneM1x,
-    Zulupool,
-    SecPool,
-    Ocean,
-    WhitePool,
-    Wiz,
-    #[serde(skip)]
-    Dummy145,
-    #[serde(skip)]
-    Dummy146,
-    Wk057,
-    FutureBitApolloSolo,
-    #[serde(skip)]
-    Dummy149,
-    #[serde(skip)]
-    Dummy150,
-    CarbonNegative,
-    PortlandHodl,
-    Phoenix,
-    Neopool,
-    MaxiPool,
-    #[serde(skip)]
-    Dummy156,
-    BitFuFuPool,
-    GDPool,
-    MiningDutch,
-    PublicPool,
-    MiningSquared,
-    InnopolisTech,
-    #[serde(skip)]
-    Dummy163,
-    BtcLab,
-    Parasite,
-    RedRockPool,
-    Est3lar,
-    BraiinsSolo,
-    #[serde(rename = "solopoolcom")]
-    SoloPool,
-    Noderunners,
-    #[serde(skip)]
-    Dummy171,
-    #[serde(skip)]
-    Dummy172,
-    #[serde(skip)]
-    Dummy173,
-    #[serde(skip)]
-    Dummy174,
-    #[serde(skip)]
-    Dummy175,
-    #[serde(skip)]
-    Dummy176,
-    #[serde(skip)]
-    Dummy177,
-    #[serde(skip)]
-    Dummy178,
-    #[serde(skip)]
-    Dummy179,
-    #[serde(skip)]
-    Dummy180,
-    #[serde(skip)]
-    Dummy181,
-    #[serde(skip)]
-    Dummy182,
-    #[serde(skip)]
-    Dummy183,
-    #[serde(skip)]
-    Dummy184,
-    #[serde(skip)]
-    Dummy185,
-    #[serde(skip)]
-    Dummy186,
-    #[serde(skip)]
-    Dummy187,
-    #[serde(skip)]
-    Dummy188,
-    #[serde(skip)]
-    Dummy189,
-    #[serde(skip)]
-    Dummy190,
-    #[serde(skip)]
-    Dummy191,
-    #[serde(skip)]
-    Dummy192,
-    #[serde(skip)]
-    Dummy193,
-    #[serde(skip)]
-    Dummy194,
-    #[serde(skip)]
-    Dummy195,
-    #[serde(skip)]
-    Dummy196,
-    #[serde(skip)]
-    Dummy197,
-    #[serde(skip)]
-    Dummy198,
-    #[serde(skip)]
-    Dummy199,
-    #[serde(skip)]
-    Dummy200,
-    #[serde(skip)]
-    Dummy201,
-    #[serde(skip)]
-    Dummy202,
-    #[serde(skip)]
-    Dummy203,
-    #[serde(skip)]
-    Dummy204,
-    #[serde(skip)]
-    Dummy205,
-    #[serde(skip)]
-    Dummy206,
-    #[serde(skip)]
-    Dummy207,
-    #[serde(skip)]
-    Dummy208,
-    #[serde(skip)]
-    Dummy209,
-    #[serde(skip)]
-    Dummy210,
-    #[serde(skip)]
-    Dummy211,
-    #[serde(skip)]
-    Dummy212,
-    #[serde(skip)]
-    Dummy213,
-    #[serde(skip)]
-    Dummy214,
-    #[serde(skip)]
-    Dummy215,
-    #[serde(skip)]
-    Dummy216,
-    #[serde(skip)]
-    Dummy217,
-    #[serde(skip)]
-    Dummy218,
-    #[serde(skip)]
-    Dummy219,
-    #[serde(skip)]
-    Dummy220,
-    #[serde(skip)]
-    Dummy221,
-    #[serde(skip)]
-    Dummy222,
-    #[serde(skip)]
-    Dummy223,
-    #[serde(skip)]
-    Dummy224,
-    #[serde(skip)]
-    Dummy225,
-    #[serde(skip)]
-    Dummy226,
-    #[serde(skip)]
-    Dummy227,
-    #[serde(skip)]
-    Dummy228,
-    #[serde(skip)]
-    Dummy229,
-    #[serde(skip)]
-    Dummy230,
-    #[serde(skip)]
-    Dummy231,
-    #[serde(skip)]
-    Dummy232,
-    #[serde(skip)]
-    Dummy233,
-    #[serde(skip)]
-    Dummy234,
-    #[serde(skip)]
-    Dummy235,
-    #[serde(skip)]
-    Dummy236,
-    #[serde(skip)]
-    Dummy237,
-    #[serde(skip)]
-    Dummy238,
-    #[serde(skip)]
-    Dummy239,
-    #[serde(skip)]
-    Dummy240,
-    #[serde(skip)]
-    Dummy241,
-    #[serde(skip)]
-    Dummy242,
-    #[serde(skip)]
-    Dummy243,
-    #[serde(skip)]
-    Dummy244,
-    #[serde(skip)]
-    Dummy245,
-    #[serde(skip)]
-    Dummy246,
-    #[serde(skip)]
-    Dummy247,
-    #[serde(skip)]
-    Dummy248,
-    #[serde(skip)]
-    Dummy249,
-    #[serde(skip)]
-    Dummy250,
-    #[serde(skip)]
-    Dummy251,
-    #[serde(skip)]
-    Dummy252,
-    #[serde(skip)]
-    Dummy253,
-    #[serde(skip)]
-    Dummy254,
-    #[serde(skip)]
-    Dummy255,
-}
+use crate::pools;
+
+/// URL-friendly mining pool identifier.
+///
+/// Ids below [`PoolSlug::FIRST_CUSTOM`] are the built-in pools, numbered
+/// like mempool.space's `unique_id` in `pools-v2.json`. Pools declared in
+/// the override file (see [`crate::init_pools`]) take ids from there up.
+#[derive(Default, Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct PoolSlug(u16);
 
 impl PoolSlug {
+    pub const UNKNOWN: Self = Self(0);
+    pub const OCEAN: Self = Self(142);
+
+    pub const FIRST_CUSTOM: u16 = BUILTIN.len() as u16;
+
+    pub const fn new(id: u16) -> Self {
+        Self(id)
+    }
+
+    pub fn is_custom(&self) -> bool {
+        self.0 >= Self::FIRST_CUSTOM
+    }
+
+    /// URL slug, `""` for ids no pool uses.
+    pub fn as_str(&self) -> &'static str {
+        match BUILTIN.get(self.0 as usize) {
+            Some(slug) => slug,
+            None => pools().custom_slug(*self),
+        }
+    }
+
+    /// Built-in or custom pool with this URL slug.
+    pub fn from_slug(slug: &str) -> Option<Self> {
+        Self::from_builtin(slug)
+            .or_else(|| pools().iter().map(|p| p.slug).find(|p| p.as_str() == slug))
+    }
+
+    pub(crate) fn from_builtin(slug: &str) -> Option<Self> {
+        if slug.is_empty() {
+            return None;
+        }
+        BUILTIN
+            .iter()
+            .position(|s| *s == slug)
+            .map(|i| Self(i as u16))
+    }
+
     /// Pools with dominance above per-window thresholds get full series.
     /// Thresholds: all-time>=1.0%, 1y>=1.0%, 1m>=0.75%, 1w>=0.5%.
     /// Generated by `scripts/pool_major_threshold.py`.
     pub fn is_major(&self) -> bool {
-        matches!(
-            self,
-            Self::AntPool
-                | Self::BinancePool
-                | Self::BitFury
-                | Self::BraiinsPool
-                | Self::BtcCom
-                | Self::BtcGuild
-                | Self::BtcTop
-                | Self::Btcc
-                | Self::BwPool
-                | Self::Eligius
-                | Self::F2Pool
-                | Self::FoundryUsa
-                | Self::Luxor
-                | Self::MaraPool
-                | Self::Ocean
-                | Self::Poolin
-                | Self::SbiCrypto
-                | Self::SecPool
-                | Self::SpiderPool
-                | Self::Unknown
-                | Self::ViaBtc
-                | Self::WhitePool
-        )
+        !self.is_custom()
+            && matches!(
+                self.as_str(),
+                "antpool"
+                    | "binancepool"
+                    | "bitfury"
+                    | "braiinspool"
+                    | "btccom"
+                    | "btcguild"
+                    | "btctop"
+                    | "btcc"
+                    | "bwpool"
+                    | "eligius"
+                    | "f2pool"
+                    | "foundryusa"
+                    | "luxor"
+                    | "marapool"
+                    | "ocean"
+                    | "poolin"
+                    | "sbicrypto"
+                    | "secpool"
+                    | "spiderpool"
+                    | "unknown"
+                    | "viabtc"
+                    | "whitepool"
+            )
+    }
+}
+
+/// Series names and the `pool` vec's values use the lowercased variant
+/// names the built-in pools were first published under, which differ
+/// from the URL slug for pools whose slug starts with a digit.
+impl fmt::Display for PoolSlug {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let slug = self.as_str();
+        f.write_str(match slug {
+            "1thash" => "onethash",
+            "175btc" => "pool175btc",
+            "50btc" => "pool50btc",
+            "21inc" => "twentyoneinc",
+            "8baochi" => "eightbaochi",
+            "1hash" => "onehash",
+            "58coin" => "fiftyeightcoin",
+            "1m1x" => "onem1x",
+            "solopoolcom" => "solopool",
+            _ => slug,
+        })
+    }
+}
+
+impl Serialize for PoolSlug {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for PoolSlug {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let slug = String::deserialize(deserializer)?;
+        Self::from_slug(&slug).ok_or_else(|| de::Error::custom(format!("unknown pool: {slug}")))
+    }
+}
+
+impl JsonSchema for PoolSlug {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "PoolSlug".into()
+    }
+
+    fn json_schema(generator: &mut SchemaGenerator) -> schemars::Schema {
+        String::json_schema(generator)
     }
 }
 
@@ -437,35 +150,289 @@ impl Bytes for PoolSlug {
 
     #[inline]
     fn to_bytes(&self) -> Self::Array {
-        [*self as u8]
+        self.0.to_le_bytes()
     }
 
     #[inline]
     fn from_bytes(bytes: &[u8]) -> vecdb::Result<Self> {
-        if bytes.len() != size_of::<Self>() {
-            return Err(vecdb::Error::WrongLength {
-                expected: size_of::<Self>(),
-                received: bytes.len(),
-            });
-        };
-        // SAFETY: PoolId is repr(u8) and we're transmuting from u8
-        // All values 0-255 are valid (includes dummy variants)
-        let s: Self = unsafe { std::mem::transmute(bytes[0]) };
-        Ok(s)
+        let array: Self::Array = bytes.try_into().map_err(|_| vecdb::Error::WrongLength {
+            expected: size_of::<Self>(),
+            received: bytes.len(),
+        })?;
+        Ok(Self(u16::from_le_bytes(array)))
     }
 }
 
-impl From<u8> for PoolSlug {
+impl From<u16> for PoolSlug {
     #[inline]
-    fn from(val: u8) -> Self {
-        // SAFETY: PoolSlug is repr(u8) and all 256 values are valid (includes dummy variants)
-        unsafe { std::mem::transmute(val) }
+    fn from(val: u16) -> Self {
+        Self(val)
     }
 }
 
-impl From<PoolSlug> for u8 {
+impl From<PoolSlug> for u16 {
     #[inline]
-    fn from(val: PoolSlug) -> u8 {
-        val as u8
+    fn from(val: PoolSlug) -> u16 {
+        val.0
     }
 }
+
+/// URL slugs of the built-in pools by id, empty for unused ids.
+const BUILTIN: [&str; 256] = [
+    "unknown",
+    "blockfills",
+    "ultimuspool",
+    "terrapool",
+    "luxor",
+    "1thash",
+    "btccom",
+    "bitfarms",
+    "huobipool",
+    "wayicn",
+    "canoepool",
+    "btctop",
+    "bitcoincom",
+    "175btc",
+    "gbminers",
+    "axbt",
+    "asicminer",
+    "bitminter",
+    "bitcoinrussia",
+    "btcserv",
+    "simplecoinus",
+    "btcguild",
+    "eligius",
+    "ozcoin",
+    "eclipsemc",
+    "maxbtc",
+    "triplemining",
+    "coinlab",
+    "50btc",
+    "ghashio",
+    "stminingcorp",
+    "bitparking",
+    "mmpool",
+    "polmine",
+    "kncminer",
+    "bitalo",
+    "f2pool",
+    "hhtt",
+    "megabigpower",
+    "mtred",
+    "nmcbit",
+    "yourbtcnet",
+    "givemecoins",
+    "braiinspool",
+    "antpool",
+    "multicoinco",
+    "bcpoolio",
+    "cointerra",
+    "kanopool",
+    "solock",
+    "ckpool",
+    "nicehash",
+    "bitclub",
+    "bitcoinaffiliatenetwork",
+    "btcc",
+    "bwpool",
+    "exxbw",
+    "bitsolo",
+    "bitfury",
+    "21inc",
+    "digitalbtc",
+    "8baochi",
+    "mybtccoinpool",
+    "tbdice",
+    "hashpool",
+    "nexious",
+    "bravomining",
+    "hotpool",
+    "okexpool",
+    "bcmonster",
+    "1hash",
+    "bixin",
+    "tatmaspool",
+    "viabtc",
+    "connectbtc",
+    "batpool",
+    "waterhole",
+    "dcexploration",
+    "dcex",
+    "btpool",
+    "58coin",
+    "bitcoinindia",
+    "shawnp0wers",
+    "phashio",
+    "rigpool",
+    "haozhuzhu",
+    "7pool",
+    "miningkings",
+    "hashbx",
+    "dpool",
+    "rawpool",
+    "haominer",
+    "helix",
+    "bitcoinukraine",
+    "poolin",
+    "secretsuperstar",
+    "tigerpoolnet",
+    "sigmapoolcom",
+    "okpooltop",
+    "hummerpool",
+    "tangpool",
+    "bytepool",
+    "spiderpool",
+    "novablock",
+    "miningcity",
+    "binancepool",
+    "minerium",
+    "lubiancom",
+    "okkong",
+    "aaopool",
+    "emcdpool",
+    "foundryusa",
+    "sbicrypto",
+    "arkpool",
+    "purebtccom",
+    "marapool",
+    "kucoinpool",
+    "entrustcharitypool",
+    "okminer",
+    "titan",
+    "pegapool",
+    "btcnuggets",
+    "cloudhashing",
+    "digitalxmintsy",
+    "telco214",
+    "btcpoolparty",
+    "multipool",
+    "transactioncoinmining",
+    "btcdig",
+    "trickysbtcpool",
+    "btcmp",
+    "eobot",
+    "unomp",
+    "patels",
+    "gogreenlight",
+    "bitcoinindiapool",
+    "ekanembtc",
+    "canoe",
+    "tiger",
+    "1m1x",
+    "zulupool",
+    "secpool",
+    "ocean",
+    "whitepool",
+    "wiz",
+    "",
+    "",
+    "wk057",
+    "futurebitapollosolo",
+    "",
+    "",
+    "carbonnegative",
+    "portlandhodl",
+    "phoenix",
+    "neopool",
+    "maxipool",
+    "",
+    "bitfufupool",
+    "gdpool",
+    "miningdutch",
+    "publicpool",
+    "miningsquared",
+    "innopolistech",
+    "",
+    "btclab",
+    "parasite",
+    "redrockpool",
+    "est3lar",
+    "braiinssolo",
+    "solopoolcom",
+    "noderunners",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+    "",
+];
@@ -11,7 +11,7 @@ pub struct PoolStats {
     /// Unique pool identifier
     #[serde(rename = "poolId")]
     #[schemars(example = 111)]
-    pub pool_id: u8,
+    pub pool_id: u16,
 
     /// Pool name
     #[schemars(example = &"Foundry USA")]
@@ -45,7 +45,7 @@ pub struct PoolStats {
     /// Unique pool identifier
     #[serde(rename = "poolUniqueId")]
     #[schemars(example = 44)]
-    pub pool_unique_id: u8,
+    pub pool_unique_id: u16,
 }
 
 impl PoolStats {
@@ -1,8 +1,11 @@
 use std::sync::OnceLock;
 
+use brk_error::{Error, Result};
+use regex::Regex;
+use rustc_hash::FxHashMap;
 use serde::Deserialize;
 
-use crate::PoolSlug;
+use crate::{Addr, Height, PoolOverrides, PoolSlug, is_mainnet};
 
 use super::Pool;
 
@@ -10,6 +13,8 @@ const JSON_DATA: &str = include_str!("../pools-v2.json");
 const POOL_COUNT: usize = 171;
 const TESTNET_IDS: &[u16] = &[145, 146, 149, 150, 156, 163];
 
+static POOLS: OnceLock<Pools> = OnceLock::new();
+
 #[derive(Deserialize)]
 struct JsonPoolEntry {
     id: u16,
@@ -26,7 +31,7 @@ fn leak_str(s: String) -> &'static str {
 
 fn empty_pool(id: usize) -> Pool {
     Pool {
-        slug: PoolSlug::from(id as u8),
+        slug: PoolSlug::new(id as u16),
         name: "",
         addrs: Box::new([]),
         tags: Box::new([]),
@@ -35,45 +40,56 @@ fn empty_pool(id: usize) -> Pool {
     }
 }
 
-#[derive(Debug)]
-pub struct Pools(Vec<Pool>);
-
-impl Pools {
-    pub fn find_from_coinbase_tag(&self, coinbase_tag: &str) -> Option<&Pool> {
-        let coinbase_tag = coinbase_tag.to_lowercase();
-        self.iter().find(|pool| {
-            pool.tags_lowercase
-                .iter()
-                .any(|pool_tag| coinbase_tag.contains(pool_tag))
-        })
-    }
-
-    pub fn find_from_addr(&self, addr: &str) -> Option<&Pool> {
-        self.iter().find(|pool| pool.addrs.contains(&addr))
-    }
-
-    pub fn get_unknown(&self) -> &Pool {
-        &self.0[0]
-    }
+fn tag_regex(tag: &str) -> Result<Regex> {
+    Regex::new(&format!("(?i){tag}"))
+        .map_err(|e| Error::Parse(format!("invalid coinbase tag regex {tag:?}: {e}")))
+}
 
-    pub fn get(&self, slug: PoolSlug) -> &Pool {
-        let i: u8 = slug.into();
-        &self.0[i as usize]
-    }
+/// An override rule, see [`crate::PoolRule`].
+#[derive(Debug)]
+struct Rule {
+    pool: PoolSlug,
+    from: Height,
+    to: Height,
+    coinbase_tag: Option<Regex>,
+    addr: Option<String>,
+    mined_by: Option<PoolSlug>,
+}
 
-    pub fn iter(&self) -> impl Iterator<Item = &Pool> + '_ {
-        self.0.iter().filter(|p| !p.name.is_empty())
+impl Rule {
+    fn matches(
+        &self,
+        height: Height,
+        coinbase_tag: &str,
+        addrs: &[Addr],
+        mined_by: PoolSlug,
+    ) -> bool {
+        (self.from..=self.to).contains(&height)
+            && self
+                .coinbase_tag
+                .as_ref()
+                .is_none_or(|regex| regex.is_match(coinbase_tag))
+            && self
+                .addr
+                .as_ref()
+                .is_none_or(|addr| addrs.iter().any(|a| **a == *addr))
+            && self.mined_by.is_none_or(|slug| slug == mined_by)
     }
+}
 
-    #[allow(clippy::len_without_is_empty)]
-    pub fn len(&self) -> usize {
-        POOL_COUNT - TESTNET_IDS.len()
-    }
+#[derive(Debug)]
+pub struct Pools {
+    /// Indexed by id, built-in pools then the custom ones
+    pools: Vec<Pool>,
+    custom_slugs: Vec<&'static str>,
+    addrs: FxHashMap<String, PoolSlug>,
+    tags: Vec<(Regex, PoolSlug)>,
+    rules: Vec<Rule>,
+    fingerprint: u64,
 }
 
-pub fn pools() -> &'static Pools {
-    static POOLS: OnceLock<Pools> = OnceLock::new();
-    POOLS.get_or_init(|| {
+impl Pools {
+    fn build(overrides: &PoolOverrides) -> Result<Self> {
         let entries: Vec<JsonPoolEntry> =
             serde_json::from_str(JSON_DATA).expect("Failed to parse pools-v2.json");
 
@@ -81,7 +97,7 @@ pub fn pools() -> &'static Pools {
 
         // Position 0: Unknown pool
         pools[0] = Pool {
-            slug: PoolSlug::Unknown,
+            slug: PoolSlug::UNKNOWN,
             name: "Unknown",
             addrs: Box::new([]),
             tags: Box::new([]),
@@ -94,7 +110,7 @@ pub fn pools() -> &'static Pools {
                 continue;
             }
             let id = entry.id as usize;
-            let slug = PoolSlug::from(id as u8);
+            let slug = PoolSlug::new(entry.id);
             let tags_lowercase = entry
                 .tags
                 .iter()
@@ -121,6 +137,384 @@ pub fn pools() -> &'static Pools {
             };
         }
 
-        Pools(pools)
+        let mut custom_slugs: Vec<&'static str> = Vec::new();
+        let mut addrs = FxHashMap::default();
+        let mut tags = Vec::new();
+
+        for def in &overrides.pools {
+            let builtin = PoolSlug::from_builtin(&def.slug).filter(|slug| {
+                pools
+                    .get(u16::from(*slug) as usize)
+                    .is_some_and(|p| !p.name.is_empty())
+            });
+            let slug = match (builtin, def.id) {
+                (Some(slug), None) => slug,
+                (Some(_), Some(_)) => {
+                    return Err(Error::Parse(format!(
+                        "`{}` is a built-in pool, it can't take an id",
+                        def.slug
+                    )));
+                }
+                (None, None) => {
+                    return Err(Error::Parse(format!("new pool `{}` needs an id", def.slug)));
+                }
+                (None, Some(id)) => {
+                    if id < PoolSlug::FIRST_CUSTOM {
+                        return Err(Error::Parse(format!(
+                            "pool `{}`: ids below {} are reserved for built-in pools",
+                            def.slug,
+                            PoolSlug::FIRST_CUSTOM
+                        )));
+                    }
+                    let i = (id - PoolSlug::FIRST_CUSTOM) as usize;
+                    if custom_slugs.get(i).is_some_and(|s| !s.is_empty()) {
+                        return Err(Error::Parse(format!("pool id {id} is taken twice")));
+                    }
+                    if custom_slugs.contains(&def.slug.as_str()) {
+                        return Err(Error::Parse(format!(
+                            "pool `{}` is declared twice",
+                            def.slug
+                        )));
+                    }
+                    if def.slug.is_empty()
+                        || !def
+                            .slug
+                            .bytes()
+                            .all(|b| b.is_ascii_lowercase() || b.is_ascii_digit())
+                    {
+                        return Err(Error::Parse(format!(
+                            "pool slug {:?} must be lowercase letters and digits",
+                            def.slug
+                        )));
+                    }
+                    if custom_slugs.len() <= i {
+                        custom_slugs.resize(i + 1, "");
+                    }
+                    custom_slugs[i] = leak_str(def.slug.clone());
+                    let slug = PoolSlug::new(id);
+                    if pools.len() <= id as usize {
+                        pools.extend((pools.len()..=id as usize).map(empty_pool));
+                    }
+                    pools[id as usize].name = custom_slugs[i];
+                    slug
+                }
+            };
+
+            let pool = &mut pools[u16::from(slug) as usize];
+            if let Some(name) = &def.name {
+                pool.name = leak_str(name.clone());
+            }
+            if let Some(link) = &def.link {
+                pool.link = leak_str(link.clone());
+            }
+            for addr in &def.addresses {
+                addrs.insert(addr.clone(), slug);
+            }
+            for tag in &def.tags {
+                tags.push((tag_regex(tag)?, slug));
+            }
+        }
+
+        let resolve = |slug: &str| -> Result<PoolSlug> {
+            PoolSlug::from_builtin(slug)
+                .filter(|s| {
+                    pools
+                        .get(u16::from(*s) as usize)
+                        .is_some_and(|p| !p.name.is_empty())
+                })
+                .or_else(|| {
+                    custom_slugs
+                        .iter()
+                        .position(|s| *s == slug)
+                        .map(|i| PoolSlug::new(PoolSlug::FIRST_CUSTOM + i as u16))
+                })
+                .ok_or_else(|| Error::Parse(format!("unknown pool `{slug}`")))
+        };
+
+        let rules = overrides
+            .rules
+            .iter()
+            .map(|rule| {
+                Ok(Rule {
+                    pool: resolve(&rule.pool)?,
+                    from: rule.from.unwrap_or(Height::ZERO),
+                    to: rule.to.unwrap_or(Height::MAX),
+                    coinbase_tag: rule.coinbase_tag.as_deref().map(tag_regex).transpose()?,
+                    addr: rule.address.clone(),
+                    mined_by: rule.mined_by.as_deref().map(resolve).transpose()?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        let mut bytes = JSON_DATA.as_bytes().to_vec();
+        bytes.extend(serde_json::to_vec(overrides)?);
+
+        Ok(Self {
+            pools,
+            custom_slugs,
+            addrs,
+            tags,
+            rules,
+            fingerprint: rapidhash::v3::rapidhash_v3(&bytes),
+        })
+    }
+
+    /// Pool that mined the block at `height`: the first override rule
+    /// that matches, else the pool whose payout address or coinbase tag
+    /// the block carries, overrides first, else `Unknown`. The built-in
+    /// addresses and tags are mainnet ones, other networks only get the
+    /// overrides.
+    pub fn identify(&self, height: Height, coinbase_tag: &str, addrs: &[Addr]) -> &Pool {
+        self.identify_with(is_mainnet(), height, coinbase_tag, addrs)
+    }
+
+    fn identify_with(
+        &self,
+        builtin: bool,
+        height: Height,
+        coinbase_tag: &str,
+        addrs: &[Addr],
+    ) -> &Pool {
+        let mined_by = addrs
+            .iter()
+            .find_map(|addr| {
+                self.addrs
+                    .get(&**addr)
+                    .map(|slug| self.get(*slug))
+                    .or_else(|| builtin.then(|| self.find_from_addr(addr)).flatten())
+            })
+            .or_else(|| {
+                self.tags
+                    .iter()
+                    .find(|(regex, _)| regex.is_match(coinbase_tag))
+                    .map(|(_, slug)| self.get(*slug))
+            })
+            .or_else(|| {
+                builtin
+                    .then(|| self.find_from_coinbase_tag(coinbase_tag))
+                    .flatten()
+            })
+            .unwrap_or(self.get_unknown())
+            .slug;
+
+        self.rules
+            .iter()
+            .find(|rule| rule.matches(height, coinbase_tag, addrs, mined_by))
+            .map_or(self.get(mined_by), |rule| self.get(rule.pool))
+    }
+
+    /// Whether the overrides add addresses, tags or rules, the only
+    /// attributions off mainnet.
+    pub fn has_overrides(&self) -> bool {
+        !self.addrs.is_empty() || !self.tags.is_empty() || !self.rules.is_empty()
+    }
+
+    pub fn find_from_coinbase_tag(&self, coinbase_tag: &str) -> Option<&Pool> {
+        let coinbase_tag = coinbase_tag.to_lowercase();
+        self.iter().find(|pool| {
+            pool.tags_lowercase
+                .iter()
+                .any(|pool_tag| coinbase_tag.contains(pool_tag))
+        })
+    }
+
+    pub fn find_from_addr(&self, addr: &str) -> Option<&Pool> {
+        self.iter().find(|pool| pool.addrs.contains(&addr))
+    }
+
+    pub fn get_unknown(&self) -> &Pool {
+        &self.pools[0]
+    }
+
+    /// `Unknown` for ids no pool uses, like those of custom pools
+    /// since removed from the overrides.
+    pub fn get(&self, slug: PoolSlug) -> &Pool {
+        self.pools
+            .get(u16::from(slug) as usize)
+            .filter(|pool| !pool.name.is_empty())
+            .unwrap_or(self.get_unknown())
+    }
+
+    pub(crate) fn custom_slug(&self, slug: PoolSlug) -> &'static str {
+        let i = u16::from(slug).saturating_sub(PoolSlug::FIRST_CUSTOM) as usize;
+        self.custom_slugs.get(i).copied().unwrap_or("")
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = &Pool> + '_ {
+        self.pools.iter().filter(|p| !p.name.is_empty())
+    }
+
+    #[allow(clippy::len_without_is_empty)]
+    pub fn len(&self) -> usize {
+        self.iter().count()
+    }
+
+    /// Hash of the built-in list and the overrides. Attributions made
+    /// under another fingerprint may be stale.
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+}
+
+/// Applies `overrides` to the built-in pool list. Call once at startup,
+/// before [`pools`] is first used: a later call errors rather than leave
+/// the overrides silently unapplied. Library users that never call it get
+/// the built-in list.
+pub fn init_pools(overrides: &PoolOverrides) -> Result<()> {
+    POOLS
+        .set(Pools::build(overrides)?)
+        .map_err(|_| Error::Internal("pool list already initialized"))
+}
+
+pub fn pools() -> &'static Pools {
+    POOLS.get_or_init(|| {
+        Pools::build(&PoolOverrides::default()).expect("Failed to build the built-in pool list")
     })
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{PoolDefinition, PoolRule};
+
+    const ANTPOOL_ADDR: &str = "12dRugNcdxK39288NjcDV4GX7rMsKCGn6B";
+
+    fn def(slug: &str, id: Option<u16>) -> PoolDefinition {
+        PoolDefinition {
+            slug: slug.to_string(),
+            id,
+            name: None,
+            link: None,
+            addresses: vec![],
+            tags: vec![],
+        }
+    }
+
+    fn rule(pool: &str) -> PoolRule {
+        PoolRule {
+            pool: pool.to_string(),
+            from: None,
+            to: None,
+            coinbase_tag: None,
+            address: None,
+            mined_by: None,
+        }
+    }
+
+    fn build(pools: Vec<PoolDefinition>, rules: Vec<PoolRule>) -> Result<Pools> {
+        Pools::build(&PoolOverrides { pools, rules })
+    }
+
+    fn slug(pools: &Pools, height: u32, tag: &str, addrs: &[&str]) -> &'static str {
+        let addrs: Vec<Addr> = addrs.iter().map(|a| Addr::from(a.to_string())).collect();
+        let pool = pools.identify_with(true, Height::new(height), tag, &addrs);
+        if pool.slug.is_custom() {
+            pools.custom_slug(pool.slug)
+        } else {
+            pool.slug.as_str()
+        }
+    }
+
+    fn custom_id() -> u16 {
+        PoolSlug::FIRST_CUSTOM
+    }
+
+    #[test]
+    fn builtin_list_identifies_by_tag_and_addr() {
+        let pools = build(vec![], vec![]).unwrap();
+        assert_eq!(slug(&pools, 1, "/AntPool/", &[]), "antpool");
+        assert_eq!(slug(&pools, 1, "nothing", &[ANTPOOL_ADDR]), "antpool");
+        assert_eq!(slug(&pools, 1, "nothing", &[]), "unknown");
+        assert!(!pools.has_overrides());
+    }
+
+    #[test]
+    fn invalid_overrides_are_rejected() {
+        let id = custom_id();
+        assert!(build(vec![def("antpool", Some(id))], vec![]).is_err());
+        assert!(build(vec![def("newpool", None)], vec![]).is_err());
+        assert!(build(vec![def("newpool", Some(id - 1))], vec![]).is_err());
+        assert!(build(vec![def("a", Some(id)), def("b", Some(id))], vec![]).is_err());
+        assert!(build(vec![def("a", Some(id)), def("a", Some(id + 1))], vec![]).is_err());
+        assert!(build(vec![def("Bad-Slug", Some(id))], vec![]).is_err());
+        assert!(build(vec![], vec![rule("nosuchpool")]).is_err());
+
+        let mut bad_tag = def("newpool", Some(id));
+        bad_tag.tags.push("(".to_string());
+        assert!(build(vec![bad_tag], vec![]).is_err());
+    }
+
+    #[test]
+    fn custom_pools_get_their_id_and_default_name() {
+        let mut named = def("antpool", None);
+        named.name = Some("Ant".to_string());
+        let pools = build(vec![def("newpool", Some(custom_id() + 2)), named], vec![]).unwrap();
+
+        let custom = pools.get(PoolSlug::new(custom_id() + 2));
+        assert_eq!(custom.name, "newpool");
+        assert_eq!(pools.custom_slug(custom.slug), "newpool");
+        // Gaps below a custom id are no pool at all.
+        assert_eq!(pools.get(PoolSlug::new(custom_id())).slug, PoolSlug::UNKNOWN);
+        assert_eq!(pools.get(PoolSlug::new(44)).name, "Ant");
+    }
+
+    #[test]
+    fn override_tags_are_case_insensitive_regexes_taking_precedence() {
+        let mut newpool = def("newpool", Some(custom_id()));
+        newpool.tags.push(r"^/new\d+/".to_string());
+        newpool.tags.push("antpool".to_string());
+        let pools = build(vec![newpool], vec![]).unwrap();
+
+        assert_eq!(slug(&pools, 1, "/NEW42/rest", &[]), "newpool");
+        assert_eq!(slug(&pools, 1, "x/new42/", &[]), "unknown");
+        // Over the built-in `/AntPool/` tag.
+        assert_eq!(slug(&pools, 1, "/AntPool/", &[]), "newpool");
+        // Payout addresses still come before any tag.
+        assert_eq!(slug(&pools, 1, "/new1/", &[ANTPOOL_ADDR]), "antpool");
+    }
+
+    #[test]
+    fn override_addrs_take_precedence() {
+        let mut newpool = def("newpool", Some(custom_id()));
+        newpool.addresses.push(ANTPOOL_ADDR.to_string());
+        let pools = build(vec![newpool], vec![]).unwrap();
+        assert_eq!(slug(&pools, 1, "/AntPool/", &[ANTPOOL_ADDR]), "newpool");
+    }
+
+    #[test]
+    fn first_matching_rule_wins() {
+        let mut split = rule("newpool");
+        split.from = Some(Height::new(100));
+        split.to = Some(Height::new(200));
+        split.mined_by = Some("antpool".to_string());
+        let mut tagged = rule("foundryusa");
+        tagged.coinbase_tag = Some("special".to_string());
+        let pools = build(vec![def("newpool", Some(custom_id()))], vec![split, tagged]).unwrap();
+
+        assert_eq!(slug(&pools, 99, "/AntPool/", &[]), "antpool");
+        assert_eq!(slug(&pools, 100, "/AntPool/", &[]), "newpool");
+        assert_eq!(slug(&pools, 200, "/AntPool/ Special", &[]), "newpool");
+        assert_eq!(slug(&pools, 201, "/AntPool/ Special", &[]), "foundryusa");
+        assert_eq!(slug(&pools, 150, "special", &[]), "foundryusa");
+    }
+
+    #[test]
+    fn off_mainnet_only_overrides_attribute() {
+        let mut newpool = def("newpool", Some(custom_id()));
+        newpool.tags.push("/signet/".to_string());
+        let pools = build(vec![newpool], vec![]).unwrap();
+        assert!(pools.has_overrides());
+
+        let at = |tag: &str| pools.identify_with(false, Height::new(1), tag, &[]).slug;
+        assert_eq!(at("/AntPool/"), PoolSlug::UNKNOWN);
+        assert_eq!(at("/signet/"), PoolSlug::new(custom_id()));
+    }
+
+    #[test]
+    fn fingerprint_follows_the_overrides() {
+        let builtin = build(vec![], vec![]).unwrap().fingerprint();
+        assert_eq!(build(vec![], vec![]).unwrap().fingerprint(), builtin);
+        let custom = build(vec![def("newpool", Some(custom_id()))], vec![]).unwrap();
+        assert_ne!(custom.fingerprint(), builtin);
+    }
+}
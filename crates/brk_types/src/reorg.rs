@@ -0,0 +1,39 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{BlockHash, Height, Timestamp, Txid};
+
+/// A chain reorganization seen by the indexer. Returned by
+/// `GET /api/v1/reorgs`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Reorg {
+    /// Height of the last block both chains share
+    pub fork_height: Height,
+    pub fork_hash: BlockHash,
+
+    /// Number of orphaned blocks
+    pub depth: u32,
+
+    /// When the indexer noticed the reorg
+    pub detected_at: Timestamp,
+
+    /// Blocks that left the chain, in height order
+    pub orphaned: Vec<ReorgBlock>,
+
+    /// Blocks that took their heights, as far as the node had them when
+    /// the reorg was noticed. Can be shorter than `orphaned`.
+    pub replacement: Vec<ReorgBlock>,
+
+    /// Txs of the orphaned blocks that none of the replacement blocks
+    /// confirm (coinbases excluded)
+    pub dropped_txids: Vec<Txid>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ReorgBlock {
+    pub height: Height,
+    pub hash: BlockHash,
+    /// Header timestamp
+    pub timestamp: Timestamp,
+}
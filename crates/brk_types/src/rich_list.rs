@@ -0,0 +1,21 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Date, Height, OutputType, RichListEntry};
+
+/// Largest funded addresses at the end of a block, by balance
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RichList {
+    /// Block height the balances are taken at
+    #[schemars(example = Height::new(840000))]
+    pub height: Height,
+
+    /// Day of the snapshot, absent for the list at the tip
+    pub date: Option<Date>,
+
+    /// Address type the list is restricted to, every type if absent
+    pub addr_type: Option<OutputType>,
+
+    /// Largest first
+    pub entries: Vec<RichListEntry>,
+}
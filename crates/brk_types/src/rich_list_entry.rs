@@ -0,0 +1,20 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Addr, OutputType, Sats};
+
+/// A funded address and its place in the rich list
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct RichListEntry {
+    /// Position in the list, from 1
+    #[schemars(example = 1)]
+    pub rank: u32,
+
+    pub address: Addr,
+
+    pub addr_type: OutputType,
+
+    /// Amount held in satoshis
+    #[schemars(example = Sats::new(24800000000000))]
+    pub balance: Sats,
+}
@@ -0,0 +1,191 @@
+use brk_error::{Error, Result};
+use pco::{
+    ChunkConfig,
+    standalone::{simple_compress, simple_decompress},
+};
+use vecdb::Bytes;
+
+use crate::{Height, OutputType, Sats, TypeIndex};
+
+const HEADER_LEN: usize = 8;
+
+/// Raw on-disk rich list: the largest funded addresses of each type at the
+/// end of a block. Processed into [`crate::RichList`] for API responses.
+#[derive(Debug, Clone, Default)]
+pub struct RichListRaw {
+    pub height: Height,
+    /// One per address type, in [`OutputType::ADDR_TYPES`] order
+    pub types: Vec<RichListRawType>,
+}
+
+/// Largest funded addresses of a type.
+#[derive(Debug, Clone, Default)]
+pub struct RichListRawType {
+    /// Every address of the type holding at least this much was ranked,
+    /// before the list was cut to its depth
+    pub floor: Sats,
+    /// By balance, largest first
+    pub entries: Vec<(TypeIndex, Sats)>,
+}
+
+impl RichListRaw {
+    /// Ranked addresses of `output_type`, largest first.
+    pub fn of_type(&self, output_type: OutputType) -> &[(TypeIndex, Sats)] {
+        OutputType::ADDR_TYPES
+            .iter()
+            .position(|t| *t == output_type)
+            .and_then(|i| self.types.get(i))
+            .map_or(&[], |t| t.entries.as_slice())
+    }
+
+    /// Ranked addresses of every type, largest first.
+    pub fn merged(&self) -> Vec<(OutputType, TypeIndex, Sats)> {
+        let mut merged: Vec<_> = OutputType::ADDR_TYPES
+            .iter()
+            .zip(&self.types)
+            .flat_map(|(output_type, t)| {
+                t.entries
+                    .iter()
+                    .map(|&(type_index, balance)| (*output_type, type_index, balance))
+            })
+            .collect();
+        merged.sort_by_key(|e| std::cmp::Reverse(e.2));
+        merged
+    }
+
+    /// Deserialize from the pco-compressed format.
+    pub fn deserialize(data: &[u8]) -> Result<Self> {
+        let mut cursor = Cursor(data);
+        let height = Height::from(cursor.usize()?);
+
+        let mut types = Vec::with_capacity(OutputType::ADDR_TYPES.len());
+        for _ in OutputType::ADDR_TYPES {
+            let floor = Sats::from(cursor.usize()?);
+            let entry_count = cursor.usize()?;
+            let keys_len = cursor.usize()?;
+            let values_len = cursor.usize()?;
+
+            let keys: Vec<u32> = simple_decompress(cursor.take(keys_len)?)?;
+            let values: Vec<u64> = simple_decompress(cursor.take(values_len)?)?;
+            if keys.len() != entry_count || values.len() != entry_count {
+                return Err(Error::Deserialization(format!(
+                    "RichListRaw: expected {entry_count} entries, got {} and {}",
+                    keys.len(),
+                    values.len()
+                )));
+            }
+
+            let entries = keys
+                .into_iter()
+                .zip(values)
+                .map(|(k, v)| (TypeIndex::from(k), Sats::from(v)))
+                .collect();
+            types.push(RichListRawType { floor, entries });
+        }
+
+        Ok(Self { height, types })
+    }
+
+    /// Serialize to the pco-compressed format.
+    pub fn serialize(&self) -> Result<Vec<u8>> {
+        let config = ChunkConfig::default();
+
+        let mut buffer = Vec::new();
+        buffer.extend(usize::from(self.height).to_bytes());
+        for t in &self.types {
+            let keys: Vec<u32> = t.entries.iter().map(|(k, _)| u32::from(*k)).collect();
+            let values: Vec<u64> = t.entries.iter().map(|(_, v)| u64::from(*v)).collect();
+            let compressed_keys = simple_compress(&keys, &config)?;
+            let compressed_values = simple_compress(&values, &config)?;
+
+            buffer.extend(usize::from(t.floor).to_bytes());
+            buffer.extend(t.entries.len().to_bytes());
+            buffer.extend(compressed_keys.len().to_bytes());
+            buffer.extend(compressed_values.len().to_bytes());
+            buffer.extend(compressed_keys);
+            buffer.extend(compressed_values);
+        }
+
+        Ok(buffer)
+    }
+}
+
+struct Cursor<'a>(&'a [u8]);
+
+impl<'a> Cursor<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        if self.0.len() < len {
+            return Err(Error::Deserialization(format!(
+                "RichListRaw: data too short ({} bytes, need >= {len})",
+                self.0.len()
+            )));
+        }
+        let (head, rest) = self.0.split_at(len);
+        self.0 = rest;
+        Ok(head)
+    }
+
+    fn usize(&mut self) -> Result<usize> {
+        Ok(usize::from_bytes(self.take(HEADER_LEN)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn raw() -> RichListRaw {
+        RichListRaw {
+            height: Height::from(840_000usize),
+            types: OutputType::ADDR_TYPES
+                .iter()
+                .enumerate()
+                .map(|(i, _)| RichListRawType {
+                    floor: Sats::from(i as u64 * 1_000),
+                    entries: (0..i as u64)
+                        .map(|j| (TypeIndex::from(j as u32 * 7), Sats::from(1_000_000 - j)))
+                        .collect(),
+                })
+                .collect(),
+        }
+    }
+
+    #[test]
+    fn serialize_roundtrip() {
+        let raw = raw();
+        let back = RichListRaw::deserialize(&raw.serialize().unwrap()).unwrap();
+        assert_eq!(back.height, raw.height);
+        assert_eq!(back.types.len(), raw.types.len());
+        for (a, b) in back.types.iter().zip(&raw.types) {
+            assert_eq!(a.floor, b.floor);
+            assert_eq!(a.entries, b.entries);
+        }
+    }
+
+    #[test]
+    fn empty_types_roundtrip() {
+        let raw = RichListRaw {
+            height: Height::ZERO,
+            types: vec![RichListRawType::default(); OutputType::ADDR_TYPES.len()],
+        };
+        let back = RichListRaw::deserialize(&raw.serialize().unwrap()).unwrap();
+        assert!(back.types.iter().all(|t| t.entries.is_empty()));
+    }
+
+    #[test]
+    fn truncated_data_is_rejected() {
+        let bytes = raw().serialize().unwrap();
+        assert!(RichListRaw::deserialize(&bytes[..bytes.len() - 1]).is_err());
+        assert!(RichListRaw::deserialize(&bytes[..4]).is_err());
+    }
+
+    #[test]
+    fn merged_sorts_every_type_by_balance() {
+        let merged = raw().merged();
+        assert!(merged.windows(2).all(|w| w[0].2 >= w[1].2));
+        assert_eq!(
+            merged.len(),
+            raw().types.iter().map(|t| t.entries.len()).sum::<usize>()
+        );
+    }
+}
@@ -0,0 +1,145 @@
+use std::{fmt, str::FromStr};
+
+use bitcoin::{
+    ScriptBuf,
+    hashes::{Hash, sha256},
+    hex::{DisplayHex, FromHex},
+};
+use brk_error::Error;
+use byteview::ByteView;
+use schemars::JsonSchema;
+use serde::{Deserialize, Deserializer, Serialize, Serializer, de};
+use vecdb::{Bytes, Formattable};
+
+use crate::AddrBytes;
+
+/// SHA256 of an output's scriptPubKey, the Electrum protocol's key for
+/// an address. Displayed byte-reversed like txids, as Electrum clients
+/// expect.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, JsonSchema, Bytes)]
+#[schemars(
+    example = "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161",
+    transparent,
+    with = "String"
+)]
+#[repr(C)]
+pub struct ScriptHash([u8; 32]);
+
+impl ScriptHash {
+    pub fn as_slice(&self) -> &[u8] {
+        &self.0
+    }
+}
+
+impl From<&ScriptBuf> for ScriptHash {
+    #[inline]
+    fn from(script: &ScriptBuf) -> Self {
+        Self(sha256::Hash::hash(script.as_bytes()).to_byte_array())
+    }
+}
+
+impl From<&AddrBytes> for ScriptHash {
+    #[inline]
+    fn from(bytes: &AddrBytes) -> Self {
+        Self::from(&bytes.to_script_pubkey())
+    }
+}
+
+impl From<ByteView> for ScriptHash {
+    #[inline]
+    fn from(value: ByteView) -> Self {
+        Self((&*value).try_into().unwrap())
+    }
+}
+
+impl From<ScriptHash> for ByteView {
+    #[inline]
+    fn from(value: ScriptHash) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<&ScriptHash> for ByteView {
+    #[inline]
+    fn from(value: &ScriptHash) -> Self {
+        Self::from(value.0)
+    }
+}
+
+impl fmt::Display for ScriptHash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut reversed = self.0;
+        reversed.reverse();
+        f.write_str(&reversed.to_lower_hex_string())
+    }
+}
+
+impl FromStr for ScriptHash {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut bytes = <[u8; 32]>::from_hex(s)
+            .map_err(|_| Error::Parse(format!("'{s}' isn't a 64 character hex script hash")))?;
+        bytes.reverse();
+        Ok(Self(bytes))
+    }
+}
+
+impl Serialize for ScriptHash {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for ScriptHash {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let s = String::deserialize(deserializer)?;
+        Self::from_str(&s).map_err(de::Error::custom)
+    }
+}
+
+impl Formattable for ScriptHash {
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        use std::fmt::Write;
+        let mut s = String::new();
+        write!(s, "{}", self).unwrap();
+        buf.extend_from_slice(s.as_bytes());
+    }
+
+    fn fmt_json(&self, buf: &mut Vec<u8>) {
+        buf.push(b'"');
+        self.write_to(buf);
+        buf.push(b'"');
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Example from the Electrum protocol docs: the genesis coinbase
+    /// address `1A1zP1eP5QGefi2DMPTfTL5SLmv7DivfNa`.
+    #[test]
+    fn matches_electrum_docs() {
+        let script =
+            ScriptBuf::from_hex("76a91462e907b15cbf27d5425399ebf6f0fb50ebb88f1888ac").unwrap();
+        let hash = ScriptHash::from(&script);
+        assert_eq!(
+            hash.to_string(),
+            "8b01df4e368ea28f8dc0423bcf7a4923e3a12d307c875e47a0cfbf90b5c39161"
+        );
+        assert_eq!(ScriptHash::from_str(&hash.to_string()).unwrap(), hash);
+    }
+
+    #[test]
+    fn rejects_bad_hex() {
+        assert!(ScriptHash::from_str("8b01").is_err());
+        assert!(ScriptHash::from_str(&"zz".repeat(32)).is_err());
+    }
+}
@@ -0,0 +1,42 @@
+use crate::{AddrChainStats, Sats};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Script statistics on the blockchain (confirmed transactions only)
+///
+/// Based on Esplora's scripthash format.
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptHashChainStats {
+    /// Total number of transaction outputs that funded this script
+    #[schemars(example = 5)]
+    pub funded_txo_count: u32,
+
+    /// Total amount in satoshis received by this script across all funded outputs
+    #[schemars(example = Sats::new(15007599040))]
+    pub funded_txo_sum: Sats,
+
+    /// Total number of transaction outputs spent from this script
+    #[schemars(example = 5)]
+    pub spent_txo_count: u32,
+
+    /// Total amount in satoshis spent from this script
+    #[schemars(example = Sats::new(15007599040))]
+    pub spent_txo_sum: Sats,
+
+    /// Total number of confirmed transactions involving this script
+    #[schemars(example = 10)]
+    pub tx_count: u32,
+}
+
+impl From<AddrChainStats> for ScriptHashChainStats {
+    #[inline]
+    fn from(value: AddrChainStats) -> Self {
+        Self {
+            funded_txo_count: value.funded_txo_count,
+            funded_txo_sum: value.funded_txo_sum,
+            spent_txo_count: value.spent_txo_count,
+            spent_txo_sum: value.spent_txo_sum,
+            tx_count: value.tx_count,
+        }
+    }
+}
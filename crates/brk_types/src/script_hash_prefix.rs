@@ -0,0 +1,54 @@
+use byteview::ByteView;
+use derive_more::Deref;
+
+use super::ScriptHash;
+
+/// First-8-bytes prefix of a script hash, packed as a `u64`. Both
+/// `From<&ScriptHash>` (via `from_le_bytes`) and `From<ByteView>` (via
+/// `from_be_bytes`, inverse of the `to_be_bytes` writer) are
+/// host-independent so on-disk keys are portable across architectures.
+#[derive(Debug, Deref, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct ScriptHashPrefix(u64);
+
+impl ScriptHashPrefix {
+    pub const fn new(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<ScriptHash> for ScriptHashPrefix {
+    #[inline]
+    fn from(value: ScriptHash) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<&ScriptHash> for ScriptHashPrefix {
+    #[inline]
+    fn from(value: &ScriptHash) -> Self {
+        Self(u64::from_le_bytes(
+            value.as_slice()[0..8].try_into().unwrap(),
+        ))
+    }
+}
+
+impl From<ByteView> for ScriptHashPrefix {
+    #[inline]
+    fn from(value: ByteView) -> Self {
+        Self(u64::from_be_bytes((&*value).try_into().unwrap()))
+    }
+}
+
+impl From<ScriptHashPrefix> for ByteView {
+    #[inline]
+    fn from(value: ScriptHashPrefix) -> Self {
+        Self::from(&value)
+    }
+}
+
+impl From<&ScriptHashPrefix> for ByteView {
+    #[inline]
+    fn from(value: &ScriptHashPrefix) -> Self {
+        Self::from(value.to_be_bytes())
+    }
+}
@@ -0,0 +1,20 @@
+use crate::{AddrMempoolStats, OutputType, ScriptHash, ScriptHashChainStats};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Script information, keyed by the SHA256 of its scriptPubKey
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct ScriptHashStats {
+    /// Script hash, byte-reversed hex as in the Electrum protocol
+    pub scripthash: ScriptHash,
+
+    /// Script type (p2pkh, p2ms, unknown, empty, etc.)
+    pub script_type: OutputType,
+
+    /// Statistics for confirmed transactions on the blockchain
+    pub chain_stats: ScriptHashChainStats,
+
+    /// Statistics for unconfirmed transactions in the mempool. Only
+    /// tracked for address types, zero for the others.
+    pub mempool_stats: AddrMempoolStats,
+}
@@ -0,0 +1,105 @@
+use byteview::ByteView;
+use serde::Serialize;
+
+use super::{ScriptHashPrefix, TxOutIndex};
+
+/// Store key pairing a script hash prefix with an output paying to it.
+/// Big-endian, so a prefix scan on the script hash lists its outputs in
+/// chain order.
+#[derive(Debug, PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Serialize, Hash)]
+pub struct ScriptHashTxOutIndex {
+    script_hash_prefix: u64,
+    txout_index: u64,
+}
+
+impl ScriptHashTxOutIndex {
+    #[inline]
+    pub fn script_hash_prefix(&self) -> ScriptHashPrefix {
+        ScriptHashPrefix::new(self.script_hash_prefix)
+    }
+
+    #[inline]
+    pub fn txout_index(&self) -> TxOutIndex {
+        TxOutIndex::from(self.txout_index)
+    }
+}
+
+impl From<(ScriptHashPrefix, TxOutIndex)> for ScriptHashTxOutIndex {
+    #[inline]
+    fn from((script_hash_prefix, txout_index): (ScriptHashPrefix, TxOutIndex)) -> Self {
+        Self {
+            script_hash_prefix: *script_hash_prefix,
+            txout_index: u64::from(txout_index),
+        }
+    }
+}
+
+impl From<ByteView> for ScriptHashTxOutIndex {
+    #[inline]
+    fn from(value: ByteView) -> Self {
+        Self {
+            script_hash_prefix: u64::from_be_bytes(value[..8].try_into().unwrap()),
+            txout_index: u64::from_be_bytes(value[8..].try_into().unwrap()),
+        }
+    }
+}
+
+impl From<ScriptHashTxOutIndex> for ByteView {
+    #[inline]
+    fn from(value: ScriptHashTxOutIndex) -> Self {
+        ByteView::from(&value)
+    }
+}
+impl From<&ScriptHashTxOutIndex> for ByteView {
+    #[inline]
+    fn from(value: &ScriptHashTxOutIndex) -> Self {
+        ByteView::from(
+            [
+                value.script_hash_prefix.to_be_bytes(),
+                value.txout_index.to_be_bytes(),
+            ]
+            .concat(),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ScriptHash;
+
+    fn key(prefix: u64, txout_index: u64) -> ScriptHashTxOutIndex {
+        ScriptHashTxOutIndex::from((ScriptHashPrefix::new(prefix), TxOutIndex::from(txout_index)))
+    }
+
+    #[test]
+    fn byteview_roundtrip() {
+        let key = key(0x0102_0304_0506_0708, 840_000_123);
+        let bytes = ByteView::from(&key);
+        assert_eq!(bytes.len(), 16);
+        let back = ScriptHashTxOutIndex::from(bytes);
+        assert_eq!(back, key);
+        assert_eq!(*back.script_hash_prefix(), 0x0102_0304_0506_0708);
+        assert_eq!(back.txout_index(), TxOutIndex::from(840_000_123u64));
+    }
+
+    #[test]
+    fn keys_start_with_their_prefix_key() {
+        let prefix = ScriptHashPrefix::from(ScriptHash::from(&bitcoin::ScriptBuf::new()));
+        let key = ScriptHashTxOutIndex::from((prefix, TxOutIndex::from(7u64)));
+        assert!(ByteView::from(&key).starts_with(ByteView::from(prefix)));
+        assert_eq!(key.script_hash_prefix(), prefix);
+    }
+
+    #[test]
+    fn bytes_sort_by_prefix_then_chain_order() {
+        let keys = [key(1, 300), key(1, 2), key(2, 1), key(0, u64::MAX), key(1, 256)];
+        let mut by_bytes: Vec<_> = keys.iter().map(ByteView::from).collect();
+        by_bytes.sort();
+        let by_bytes: Vec<_> = by_bytes.into_iter().map(ScriptHashTxOutIndex::from).collect();
+        let mut by_value = keys.to_vec();
+        by_value.sort();
+        assert_eq!(by_bytes, by_value);
+        assert_eq!(by_value[1..4], [key(1, 2), key(1, 256), key(1, 300)]);
+    }
+}
@@ -44,14 +44,52 @@ impl SeriesData {
         let end = end.min(total);
         let start = start.min(end);
 
+        Self::write_header(
+            vec.version(),
+            index,
+            vec.value_type_to_string(),
+            start,
+            end,
+            buf,
+        );
+        vec.write_json(Some(start), Some(end), buf)?;
+        buf.push(b'}');
+        Ok(())
+    }
+
+    /// Same layout as `serialize` for values computed outside a vec, such as
+    /// an evaluated series expression. `data` must be a JSON array.
+    pub fn serialize_data(
+        version: Version,
+        index: Index,
+        value_type: &str,
+        start: usize,
+        end: usize,
+        data: &[u8],
+        buf: &mut Vec<u8>,
+    ) {
+        Self::write_header(version, index, value_type, start, end, buf);
+        buf.extend_from_slice(data);
+        buf.push(b'}');
+    }
+
+    /// Everything up to and including `"data":`.
+    fn write_header(
+        version: Version,
+        index: Index,
+        value_type: &str,
+        start: usize,
+        end: usize,
+        buf: &mut Vec<u8>,
+    ) {
         let mut itoa_buf = itoa::Buffer::new();
 
         buf.extend_from_slice(b"{\"version\":");
-        buf.extend_from_slice(itoa_buf.format(u32::from(vec.version())).as_bytes());
+        buf.extend_from_slice(itoa_buf.format(u32::from(version)).as_bytes());
         buf.extend_from_slice(b",\"index\":\"");
         buf.extend_from_slice(index.name().as_bytes());
         buf.extend_from_slice(b"\",\"type\":\"");
-        buf.extend_from_slice(vec.value_type_to_string().as_bytes());
+        buf.extend_from_slice(value_type.as_bytes());
         buf.extend_from_slice(b"\",\"start\":");
         buf.extend_from_slice(itoa_buf.format(start).as_bytes());
         buf.extend_from_slice(b",\"end\":");
@@ -59,10 +97,6 @@ impl SeriesData {
         buf.extend_from_slice(b",\"stamp\":\"");
         buf.extend_from_slice(Timestamp::now().to_iso8601().as_bytes());
         buf.extend_from_slice(b"\",\"data\":");
-
-        vec.write_json(Some(start), Some(end), buf)?;
-        buf.push(b'}');
-        Ok(())
     }
 }
 
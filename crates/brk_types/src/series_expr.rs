@@ -0,0 +1,460 @@
+use std::{fmt, str::FromStr};
+
+use brk_error::Error;
+
+use crate::{SeriesList, SeriesName};
+
+/// Longest accepted expression, in bytes.
+const MAX_LEN: usize = 1024;
+/// Deepest accepted nesting of calls, parentheses and operators.
+const MAX_DEPTH: usize = 32;
+/// Most distinct series one expression may reference, same as `SeriesList`.
+const MAX_SERIES: usize = 32;
+/// Largest window or lag, in index units.
+const MAX_WINDOW: usize = 1_000_000;
+
+/// Arithmetic over series, e.g. `sma(realized_cap_lth / market_cap, 30)`.
+///
+/// Series are referenced by name (use `_` rather than `-`, which is
+/// subtraction). Supports `+ - * /`, unary minus, numbers, parentheses and
+/// the functions in [`SeriesFn`].
+#[derive(Debug, Clone)]
+pub enum SeriesExpr {
+    Series(SeriesName),
+    Number(f64),
+    Neg(Box<SeriesExpr>),
+    Binary(BinaryOp, Box<SeriesExpr>, Box<SeriesExpr>),
+    Call(SeriesFn, Box<SeriesExpr>, usize),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BinaryOp {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// Functions over a window of `n` index units ending at each point.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SeriesFn {
+    /// `sma(x, n)`: simple moving average
+    Sma,
+    /// `ema(x, n)`: exponential moving average with `alpha = 2 / (n + 1)`,
+    /// seeded with the average of the first `n` points and computed over
+    /// the full history
+    Ema,
+    /// `stddev(x, n)`: population standard deviation
+    StdDev,
+    /// `zscore(x, n)`: `(x - sma(x, n)) / stddev(x, n)`
+    ZScore,
+    /// `lag(x, n)`: value `n` points earlier, `n` defaults to 1
+    Lag,
+    /// `pct_change(x, n)`: change in percent over `n` points, `n` defaults to 1
+    PctChange,
+}
+
+impl SeriesFn {
+    fn from_name(name: &str) -> Option<Self> {
+        Some(match name {
+            "sma" => Self::Sma,
+            "ema" => Self::Ema,
+            "stddev" => Self::StdDev,
+            "zscore" => Self::ZScore,
+            "lag" => Self::Lag,
+            "pct_change" => Self::PctChange,
+            _ => return None,
+        })
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::Sma => "sma",
+            Self::Ema => "ema",
+            Self::StdDev => "stddev",
+            Self::ZScore => "zscore",
+            Self::Lag => "lag",
+            Self::PctChange => "pct_change",
+        }
+    }
+
+    fn default_window(&self) -> Option<usize> {
+        match self {
+            Self::Lag | Self::PctChange => Some(1),
+            _ => None,
+        }
+    }
+}
+
+impl BinaryOp {
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Add | Self::Sub => 1,
+            Self::Mul | Self::Div => 2,
+        }
+    }
+
+    fn symbol(&self) -> char {
+        match self {
+            Self::Add => '+',
+            Self::Sub => '-',
+            Self::Mul => '*',
+            Self::Div => '/',
+        }
+    }
+}
+
+impl SeriesExpr {
+    /// Distinct series referenced, in order of first appearance.
+    pub fn series(&self) -> Vec<&SeriesName> {
+        let mut names: Vec<&SeriesName> = vec![];
+        self.visit_series(&mut |name| {
+            if !names.iter().any(|n| n.normalize() == name.normalize()) {
+                names.push(name);
+            }
+        });
+        names
+    }
+
+    pub fn series_list(&self) -> SeriesList {
+        SeriesList::from(self.series().into_iter().cloned().collect::<Vec<_>>())
+    }
+
+    /// Points before the first output a value depends on. `usize::MAX` if
+    /// it depends on the full history (`ema`).
+    pub fn lookback(&self) -> usize {
+        match self {
+            Self::Series(_) | Self::Number(_) => 0,
+            Self::Neg(x) => x.lookback(),
+            Self::Binary(_, l, r) => l.lookback().max(r.lookback()),
+            Self::Call(f, x, n) => match f {
+                SeriesFn::Ema => usize::MAX,
+                SeriesFn::Sma | SeriesFn::StdDev | SeriesFn::ZScore => {
+                    x.lookback().saturating_add(n - 1)
+                }
+                SeriesFn::Lag | SeriesFn::PctChange => x.lookback().saturating_add(*n),
+            },
+        }
+    }
+
+    fn visit_series<'a>(&'a self, f: &mut impl FnMut(&'a SeriesName)) {
+        match self {
+            Self::Series(name) => f(name),
+            Self::Number(_) => {}
+            Self::Neg(x) | Self::Call(_, x, _) => x.visit_series(f),
+            Self::Binary(_, l, r) => {
+                l.visit_series(f);
+                r.visit_series(f);
+            }
+        }
+    }
+
+    fn precedence(&self) -> u8 {
+        match self {
+            Self::Binary(op, ..) => op.precedence(),
+            _ => 3,
+        }
+    }
+}
+
+impl FromStr for SeriesExpr {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() > MAX_LEN {
+            return Err(Error::Parse(format!(
+                "Expression is longer than {MAX_LEN} bytes"
+            )));
+        }
+        let mut parser = Parser {
+            tokens: tokenize(s)?,
+            pos: 0,
+            depth: 0,
+        };
+        let expr = parser.expr()?;
+        if let Some(token) = parser.tokens.get(parser.pos) {
+            return Err(Error::Parse(format!("Unexpected {token} in expression")));
+        }
+        let count = expr.series().len();
+        if count == 0 {
+            return Err(Error::Parse("Expression references no series".into()));
+        }
+        if count > MAX_SERIES {
+            return Err(Error::Parse(format!(
+                "Expression references more than {MAX_SERIES} series"
+            )));
+        }
+        Ok(expr)
+    }
+}
+
+impl fmt::Display for SeriesExpr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Series(name) => write!(f, "{name}"),
+            Self::Number(n) => write!(f, "{n}"),
+            Self::Neg(x) if x.precedence() < 3 => write!(f, "-({x})"),
+            Self::Neg(x) => write!(f, "-{x}"),
+            Self::Binary(op, l, r) => {
+                let p = op.precedence();
+                if l.precedence() < p {
+                    write!(f, "({l})")?;
+                } else {
+                    write!(f, "{l}")?;
+                }
+                write!(f, " {} ", op.symbol())?;
+                if r.precedence() <= p {
+                    write!(f, "({r})")
+                } else {
+                    write!(f, "{r}")
+                }
+            }
+            Self::Call(func, x, n) => write!(f, "{}({x}, {n})", func.name()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Ident(String),
+    Number(f64),
+    Op(char),
+    Open,
+    Close,
+    Comma,
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Ident(s) => write!(f, "'{s}'"),
+            Self::Number(n) => write!(f, "'{n}'"),
+            Self::Op(c) => write!(f, "'{c}'"),
+            Self::Open => write!(f, "'('"),
+            Self::Close => write!(f, "')'"),
+            Self::Comma => write!(f, "','"),
+        }
+    }
+}
+
+/// Words are runs of `[A-Za-z0-9_.]`: numbers if they parse as one, else
+/// series or function names.
+fn tokenize(s: &str) -> Result<Vec<Token>, Error> {
+    let mut tokens = vec![];
+    let mut chars = s.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            c if c.is_whitespace() => {}
+            '+' | '-' | '*' | '/' => tokens.push(Token::Op(c)),
+            '(' => tokens.push(Token::Open),
+            ')' => tokens.push(Token::Close),
+            ',' => tokens.push(Token::Comma),
+            c if c.is_ascii_alphanumeric() || c == '_' || c == '.' => {
+                let mut end = i + 1;
+                while let Some(&(j, c)) = chars.peek() {
+                    if !(c.is_ascii_alphanumeric() || c == '_' || c == '.') {
+                        break;
+                    }
+                    end = j + 1;
+                    chars.next();
+                }
+                let word = &s[i..end];
+                tokens.push(match word.parse::<f64>() {
+                    Ok(n) if n.is_finite() => Token::Number(n),
+                    _ if word.contains('.') => {
+                        return Err(Error::Parse(format!("Invalid number '{word}'")));
+                    }
+                    _ => Token::Ident(word.to_lowercase()),
+                });
+            }
+            c => return Err(Error::Parse(format!("Unexpected '{c}' in expression"))),
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    pos: usize,
+    depth: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.pos)
+    }
+
+    fn next(&mut self) -> Result<Token, Error> {
+        let token = self
+            .tokens
+            .get(self.pos)
+            .cloned()
+            .ok_or_else(|| Error::Parse("Unexpected end of expression".into()))?;
+        self.pos += 1;
+        Ok(token)
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), Error> {
+        let token = self.next()?;
+        if token != expected {
+            return Err(Error::Parse(format!(
+                "Expected {expected}, found {token} in expression"
+            )));
+        }
+        Ok(())
+    }
+
+    fn descend(&mut self) -> Result<(), Error> {
+        self.depth += 1;
+        if self.depth > MAX_DEPTH {
+            return Err(Error::Parse(format!(
+                "Expression is nested deeper than {MAX_DEPTH} levels"
+            )));
+        }
+        Ok(())
+    }
+
+    fn expr(&mut self) -> Result<SeriesExpr, Error> {
+        self.descend()?;
+        let mut left = self.term()?;
+        while let Some(Token::Op(c @ ('+' | '-'))) = self.peek() {
+            let op = if *c == '+' {
+                BinaryOp::Add
+            } else {
+                BinaryOp::Sub
+            };
+            self.pos += 1;
+            left = SeriesExpr::Binary(op, Box::new(left), Box::new(self.term()?));
+        }
+        self.depth -= 1;
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<SeriesExpr, Error> {
+        let mut left = self.unary()?;
+        while let Some(Token::Op(c @ ('*' | '/'))) = self.peek() {
+            let op = if *c == '*' {
+                BinaryOp::Mul
+            } else {
+                BinaryOp::Div
+            };
+            self.pos += 1;
+            left = SeriesExpr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<SeriesExpr, Error> {
+        if let Some(Token::Op('-')) = self.peek() {
+            self.pos += 1;
+            self.descend()?;
+            let expr = match self.unary()? {
+                SeriesExpr::Number(n) => SeriesExpr::Number(-n),
+                x => SeriesExpr::Neg(Box::new(x)),
+            };
+            self.depth -= 1;
+            return Ok(expr);
+        }
+        self.primary()
+    }
+
+    fn primary(&mut self) -> Result<SeriesExpr, Error> {
+        match self.next()? {
+            Token::Number(n) => Ok(SeriesExpr::Number(n)),
+            Token::Open => {
+                let expr = self.expr()?;
+                self.expect(Token::Close)?;
+                Ok(expr)
+            }
+            Token::Ident(name) if self.peek() == Some(&Token::Open) => {
+                let func = SeriesFn::from_name(&name)
+                    .ok_or_else(|| Error::Parse(format!("Unknown function '{name}'")))?;
+                self.pos += 1;
+                let arg = self.expr()?;
+                let n = if self.peek() == Some(&Token::Comma) {
+                    self.pos += 1;
+                    Some(self.window(func)?)
+                } else {
+                    func.default_window()
+                };
+                self.expect(Token::Close)?;
+                let n = n.ok_or_else(|| {
+                    Error::Parse(format!("{}() needs a window, e.g. {0}(x, 30)", func.name()))
+                })?;
+                Ok(SeriesExpr::Call(func, Box::new(arg), n))
+            }
+            Token::Ident(name) => Ok(SeriesExpr::Series(SeriesName::from(name))),
+            token => Err(Error::Parse(format!("Unexpected {token} in expression"))),
+        }
+    }
+
+    fn window(&mut self, func: SeriesFn) -> Result<usize, Error> {
+        match self.next()? {
+            Token::Number(n) if n.fract() == 0.0 && n >= 1.0 && n <= MAX_WINDOW as f64 => {
+                Ok(n as usize)
+            }
+            token => Err(Error::Parse(format!(
+                "{}() window must be an integer between 1 and {MAX_WINDOW}, found {token}",
+                func.name()
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(s: &str) -> SeriesExpr {
+        s.parse().unwrap()
+    }
+
+    #[test]
+    fn precedence_and_display() {
+        assert_eq!(parse("a + b * c").to_string(), "a + b * c");
+        assert_eq!(parse("(a + b) * c").to_string(), "(a + b) * c");
+        assert_eq!(parse("a - (b - c)").to_string(), "a - (b - c)");
+        assert_eq!(parse("a-b-c").to_string(), "a - b - c");
+        assert_eq!(parse("-(a + b)").to_string(), "-(a + b)");
+        assert_eq!(
+            parse("sma(realized_cap_lth/market_cap,30)").to_string(),
+            "sma(realized_cap_lth / market_cap, 30)"
+        );
+        assert_eq!(
+            parse("pct_change(price)").to_string(),
+            "pct_change(price, 1)"
+        );
+    }
+
+    #[test]
+    fn series_and_lookback() {
+        let expr = parse("zscore(a / b, 30) + lag(a, 7) * 2");
+        let names: Vec<_> = expr.series().iter().map(|n| n.to_string()).collect();
+        assert_eq!(names, ["a", "b"]);
+        assert_eq!(expr.lookback(), 29);
+        assert_eq!(parse("sma(lag(a, 5), 10)").lookback(), 14);
+        assert_eq!(parse("ema(a, 10)").lookback(), usize::MAX);
+        assert_eq!(parse("1e3 * a").to_string(), "1000 * a");
+    }
+
+    #[test]
+    fn rejects() {
+        for s in [
+            "",
+            "1 + 2",
+            "a +",
+            "(a",
+            "a)",
+            "foo(a, 3)",
+            "sma(a)",
+            "sma(a, 0)",
+            "sma(a, 2.5)",
+            "a $ b",
+            "1.2.3 + a",
+        ] {
+            assert!(s.parse::<SeriesExpr>().is_err(), "{s}");
+        }
+        let deep = format!("{}a{}", "(".repeat(40), ")".repeat(40));
+        assert!(deep.parse::<SeriesExpr>().is_err());
+    }
+}
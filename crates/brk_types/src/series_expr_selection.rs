@@ -0,0 +1,35 @@
+use schemars::JsonSchema;
+use serde::Deserialize;
+
+use crate::{Index, SeriesList, SeriesSelection, with_range_format::with_range_format};
+
+with_range_format! {
+    /// Expression over series to evaluate
+    #[derive(Debug, Deserialize, JsonSchema)]
+    #[serde(deny_unknown_fields)]
+    pub struct SeriesExprSelection {
+        /// Expression, e.g. `sma(realized_cap_lth / market_cap, 30)`
+        #[serde(alias = "expr")]
+        #[schemars(example = &"sma(realized_cap_lth / market_cap, 30)")]
+        pub q: String,
+
+        /// Index to query
+        #[serde(alias = "i")]
+        pub index: Index,
+    }
+}
+
+impl SeriesExprSelection {
+    /// Selection of the series the expression references, with the same
+    /// range and format.
+    pub fn selection(&self, series: SeriesList) -> SeriesSelection {
+        SeriesSelection {
+            series,
+            index: self.index,
+            start: self.start,
+            end: self.end,
+            limit: self.limit,
+            format: self.format,
+        }
+    }
+}
@@ -27,6 +27,13 @@ impl From<SeriesName> for SeriesList {
     }
 }
 
+impl From<Vec<SeriesName>> for SeriesList {
+    #[inline]
+    fn from(value: Vec<SeriesName>) -> Self {
+        Self(value)
+    }
+}
+
 impl From<String> for SeriesList {
     #[inline]
     fn from(value: String) -> Self {
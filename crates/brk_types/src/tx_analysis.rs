@@ -0,0 +1,41 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{Addr, FeeRate, Sats, Transaction, Txid, VSize};
+
+/// A decoded tx with what's worth checking before signing or
+/// broadcasting it. Returned by `POST /api/tx/decode` and
+/// `POST /api/psbt/analyze`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TxAnalysis {
+    /// The tx, prevouts resolved from the mempool and the indexer.
+    /// Input and output script types are in `vin[].prevout` and `vout`.
+    pub tx: Transaction,
+
+    /// Fee in satoshis, `None` when a prevout is unknown
+    pub fee: Option<Sats>,
+
+    /// Virtual size in vbytes, unsigned inputs sized with an estimated
+    /// signature
+    pub vsize: VSize,
+
+    /// `fee / vsize` in sat/vB
+    pub fee_rate: Option<FeeRate>,
+
+    /// Whether every input is signed
+    pub complete: bool,
+
+    /// Whether an input signals BIP125 replaceability
+    pub rbf: bool,
+
+    /// Output addresses that already received coins, on chain or in the
+    /// mempool
+    pub reused_addrs: Vec<Addr>,
+
+    /// Live mempool txs spending the same outpoints
+    pub conflicts: Vec<Txid>,
+
+    /// Sighash and standardness concerns, one sentence each
+    pub warnings: Vec<String>,
+}
@@ -0,0 +1,59 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::{FeeRate, Sats, Txid, VSize};
+
+/// Body of `POST /api/v1/mempool/simulate`: either `hex`, or `feeRate`
+/// and `vsize` with optional `parents`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TxSimulationRequest {
+    /// Raw tx in hex, signed or unsigned. Inputs spending live txs make
+    /// them parents, inputs already spent in the pool replace their
+    /// spenders. Unsigned inputs are sized with an estimated witness.
+    pub hex: Option<String>,
+
+    /// Fee rate in sat/vB, with `vsize`
+    pub fee_rate: Option<FeeRate>,
+
+    /// Virtual size in vbytes, with `feeRate`
+    pub vsize: Option<VSize>,
+
+    /// Unconfirmed parents, with `feeRate`. Txids not in the pool are
+    /// taken as confirmed.
+    #[serde(default)]
+    pub parents: Vec<Txid>,
+}
+
+/// Where a tx would land if it entered the mempool now. Returned by
+/// `POST /api/v1/mempool/simulate`.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct TxSimulation {
+    /// Fee in satoshis
+    pub fee: Sats,
+
+    /// Virtual size in vbytes
+    pub vsize: VSize,
+
+    /// Rate of the chunk the tx lands in once linearized with its
+    /// cluster
+    pub chunk_rate: FeeRate,
+
+    /// Projected block, 0 being the next one. The last projected block
+    /// also holds everything below it.
+    pub block: u32,
+
+    /// Number of txs ahead of it in that block
+    pub position: u32,
+
+    /// Chunk rate the tx has to beat to make the next block
+    pub next_block_fee_rate: FeeRate,
+
+    /// Extra fee the tx needs to make the next block, 0 when it already
+    /// does
+    pub next_block_fee_delta: Sats,
+
+    /// Live txs it would replace, descendants included
+    pub replaces: Vec<Txid>,
+}
@@ -1,4 +1,4 @@
-use crate::{Addr, AddrBytes, OutputType, Sats};
+use crate::{Addr, AddrBytes, OutputType, Sats, addr_labels};
 use bitcoin::ScriptBuf;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize, Serializer, ser::SerializeStruct};
@@ -38,6 +38,13 @@ pub struct TxOut {
     #[schemars(with = "Option<Addr>", example = Some("bc1q8vryck26jhuh0uqr2ttwj96szfnu4nwxfmu39y".to_string()))]
     script_pubkey_addr: (),
 
+    /// Labels of the address, from the server's `labels.toml` (omitted
+    /// when none)
+    #[allow(dead_code)]
+    #[serde(skip, rename = "scriptpubkey_labels")]
+    #[schemars(with = "Option<Vec<String>>", example = Some(vec!["exchange".to_string()]))]
+    script_pubkey_labels: (),
+
     /// Value of the output in satoshis
     #[schemars(example = Sats::new(7782))]
     pub value: Sats,
@@ -70,6 +77,7 @@ impl From<bitcoin::TxOut> for TxOut {
             script_pubkey_asm: (),
             script_pubkey_addr: (),
             script_pubkey_type: (),
+            script_pubkey_labels: (),
         }
     }
 }
@@ -92,6 +100,7 @@ impl From<(ScriptBuf, Sats)> for TxOut {
             script_pubkey_addr: (),
             script_pubkey_asm: (),
             script_pubkey_type: (),
+            script_pubkey_labels: (),
             value,
         }
     }
@@ -104,7 +113,11 @@ impl Serialize for TxOut {
     {
         let output_type = self.type_();
         let addr = self.addr();
-        let field_count = if addr.is_some() { 5 } else { 4 };
+        let labels = Some(addr_labels())
+            .filter(|labels| !labels.is_empty())
+            .and_then(|labels| Some(labels.names_of(&self.addr_bytes()?)))
+            .unwrap_or_default();
+        let field_count = 4 + addr.is_some() as usize + !labels.is_empty() as usize;
         let mut state = serializer.serialize_struct("TxOut", field_count)?;
         state.serialize_field("scriptpubkey", &self.script_pubkey.to_hex_string())?;
         state.serialize_field("scriptpubkey_asm", &self.script_pubkey_asm())?;
@@ -112,6 +125,9 @@ impl Serialize for TxOut {
         if let Some(addr) = &addr {
             state.serialize_field("scriptpubkey_address", addr)?;
         }
+        if !labels.is_empty() {
+            state.serialize_field("scriptpubkey_labels", &labels)?;
+        }
         state.serialize_field("value", &self.value)?;
         state.end()
     }
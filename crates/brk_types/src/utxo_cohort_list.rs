@@ -0,0 +1,51 @@
+use serde::{Deserialize, Serialize};
+
+use crate::Sats;
+
+/// UTXO cohorts combining several filters, declared in `cohorts.toml`
+/// and applied with `brk_cohort::init_intersections`.
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UTXOCohortList {
+    #[serde(default)]
+    pub cohorts: Vec<UTXOCohortDefinition>,
+}
+
+/// The UTXOs matching every filter given, a filter left out doesn't
+/// restrict the cohort.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct UTXOCohortDefinition {
+    /// Lowercase letters, digits and underscores, it's part of the
+    /// cohort's series names
+    pub name: String,
+
+    /// `sth` or `lth`
+    #[serde(default)]
+    pub term: Option<String>,
+
+    /// Age the UTXOs join the cohort at, in hours
+    #[serde(default)]
+    pub min_age_hours: Option<usize>,
+
+    /// Age the UTXOs leave the cohort at, in hours
+    #[serde(default)]
+    pub max_age_hours: Option<usize>,
+
+    /// Smallest amount included, in sats
+    #[serde(default)]
+    pub min_sats: Option<Sats>,
+
+    /// Amount from which UTXOs are left out, in sats
+    #[serde(default)]
+    pub max_sats: Option<Sats>,
+
+    /// Output types, named like the type cohorts (`p2pkh`, `p2tr`,
+    /// `unknown_outputs`, ...), every spendable one if empty
+    #[serde(default)]
+    pub types: Vec<String>,
+
+    /// Metrics tier: `minimal` (default), `basic`, `core` or `extended`
+    #[serde(default)]
+    pub metrics: Option<String>,
+}
@@ -0,0 +1,25 @@
+use crate::{Addr, AddrChainStats, AddrMempoolStats};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// A used address of a wallet
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WalletAddr {
+    /// Bitcoin address string
+    #[serde(rename = "address")]
+    pub addr: Addr,
+
+    /// Keychain of the address, in descriptor order (0 = receive, 1 = change)
+    #[schemars(example = 0)]
+    pub keychain: u32,
+
+    /// Derivation index within the keychain
+    #[schemars(example = 3)]
+    pub index: u32,
+
+    /// Confirmed statistics, absent if the address was only seen in the mempool
+    pub chain_stats: Option<AddrChainStats>,
+
+    /// Unconfirmed statistics, absent without live mempool activity
+    pub mempool_stats: Option<AddrMempoolStats>,
+}
@@ -0,0 +1,28 @@
+use crate::Sats;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Wallet statistics on the blockchain (confirmed transactions only),
+/// summed over its used addresses
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WalletChainStats {
+    /// Total number of transaction outputs that funded the wallet
+    #[schemars(example = 5)]
+    pub funded_txo_count: u32,
+
+    /// Total amount in satoshis received by the wallet across all funded outputs
+    #[schemars(example = Sats::new(15007599040))]
+    pub funded_txo_sum: Sats,
+
+    /// Total number of transaction outputs spent from the wallet
+    #[schemars(example = 5)]
+    pub spent_txo_count: u32,
+
+    /// Total amount in satoshis spent from the wallet
+    #[schemars(example = Sats::new(15007599040))]
+    pub spent_txo_sum: Sats,
+
+    /// Number of distinct confirmed transactions involving the wallet
+    #[schemars(example = 10)]
+    pub tx_count: u32,
+}
@@ -0,0 +1,411 @@
+use std::str::FromStr;
+
+use bitcoin::{
+    NetworkKind, ScriptBuf, base58,
+    bip32::{ChildNumber, Xpub},
+    secp256k1::{Secp256k1, Verification},
+};
+use brk_error::Error;
+
+use crate::{OutputType, network};
+
+/// Characters allowed in a descriptor, in checksum order (BIP 380).
+const INPUT_CHARSET: &str = "0123456789()[],'/*abcdefgh@:$%{}IJKLMNOPQRSTUVWXYZ&+-.;<=>?!^_|~ijklmnopqrstuvwxyzABCDEFGH`#\"\\ ";
+const CHECKSUM_CHARSET: &[u8] = b"qpzry9x8gf2tvdw0s3jn54khce6mua7l";
+
+const XPUB_MAINNET: [u8; 4] = [0x04, 0x88, 0xb2, 0x1e];
+const XPUB_TESTNET: [u8; 4] = [0x04, 0x35, 0x87, 0xcf];
+
+/// Script paid to by each key of a wallet.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum ScriptTemplate {
+    Pkh,
+    ShWpkh,
+    Wpkh,
+    Tr,
+}
+
+impl ScriptTemplate {
+    fn output_type(self) -> OutputType {
+        match self {
+            Self::Pkh => OutputType::P2PKH,
+            Self::ShWpkh => OutputType::P2SH,
+            Self::Wpkh => OutputType::P2WPKH,
+            Self::Tr => OutputType::P2TR,
+        }
+    }
+
+    fn script_pubkey<C: Verification>(self, secp: &Secp256k1<C>, key: &Xpub) -> ScriptBuf {
+        match self {
+            Self::Pkh => ScriptBuf::new_p2pkh(&key.to_pub().pubkey_hash()),
+            Self::ShWpkh => {
+                let witness_program = ScriptBuf::new_p2wpkh(&key.to_pub().wpubkey_hash());
+                ScriptBuf::new_p2sh(&witness_program.script_hash())
+            }
+            Self::Wpkh => ScriptBuf::new_p2wpkh(&key.to_pub().wpubkey_hash()),
+            Self::Tr => ScriptBuf::new_p2tr(secp, key.to_x_only_pub(), None),
+        }
+    }
+}
+
+/// Watch-only wallet: a bare extended public key or a single-key output
+/// descriptor.
+///
+/// Bare keys follow their SLIP-132 prefix (`xpub`/`tpub` for P2PKH,
+/// `ypub`/`upub` for P2SH-P2WPKH, `zpub`/`vpub` for P2WPKH) and cover the
+/// receive (`/0/*`) and change (`/1/*`) keychains. Descriptors support
+/// `pkh`, `wpkh`, `sh(wpkh)` and `tr` without a script tree, with an
+/// optional key origin, `<a;b>` multipath step and `#` checksum. Every
+/// step after the extended key must be unhardened.
+#[derive(Debug, Clone)]
+pub struct WalletDescriptor {
+    template: ScriptTemplate,
+    /// Parent key of each keychain's addresses, receive first. The key
+    /// of the only address if not ranged.
+    keychains: Vec<Xpub>,
+    ranged: bool,
+}
+
+impl WalletDescriptor {
+    pub fn output_type(&self) -> OutputType {
+        self.template.output_type()
+    }
+
+    pub fn keychain_count(&self) -> usize {
+        self.keychains.len()
+    }
+
+    /// Scripts of `keychain`, from index 0 up. A single script if the
+    /// descriptor has no `*` wildcard.
+    pub fn script_pubkeys(&self, keychain: usize) -> impl Iterator<Item = ScriptBuf> + '_ {
+        let secp = Secp256k1::verification_only();
+        let parent = &self.keychains[keychain];
+        let count = if self.ranged { 1 << 31 } else { 1 };
+        (0..count).map_while(move |index| {
+            let key = if self.ranged {
+                parent
+                    .derive_pub(&secp, &[ChildNumber::Normal { index }])
+                    .ok()?
+            } else {
+                *parent
+            };
+            Some(self.template.script_pubkey(&secp, &key))
+        })
+    }
+
+    fn from_key_expr(template: ScriptTemplate, expr: &str) -> Result<Self, Error> {
+        let expr = match expr.strip_prefix('[') {
+            Some(origin) => {
+                origin
+                    .split_once(']')
+                    .ok_or_else(|| Error::Parse("Unclosed key origin".into()))?
+                    .1
+            }
+            None => expr,
+        };
+
+        let mut steps = expr.split('/');
+        let (xpub, _) = parse_extended_key(steps.next().unwrap_or_default())?;
+        let steps: Vec<&str> = steps.collect();
+        let ranged = steps.last() == Some(&"*");
+        let steps = if ranged {
+            &steps[..steps.len() - 1]
+        } else {
+            &steps[..]
+        };
+
+        let mut paths: Vec<Vec<ChildNumber>> = vec![vec![]];
+        for step in steps {
+            if let Some(multi) = step.strip_prefix('<').and_then(|s| s.strip_suffix('>')) {
+                if paths.len() > 1 {
+                    return Err(Error::Parse("Only one multipath step is allowed".into()));
+                }
+                paths = multi
+                    .split(';')
+                    .map(|step| {
+                        let mut path = paths[0].clone();
+                        path.push(parse_step(step)?);
+                        Ok(path)
+                    })
+                    .collect::<Result<_, Error>>()?;
+            } else {
+                let child = parse_step(step)?;
+                paths.iter_mut().for_each(|path| path.push(child));
+            }
+        }
+
+        let secp = Secp256k1::verification_only();
+        let keychains = paths
+            .iter()
+            .map(|path| {
+                xpub.derive_pub(&secp, path)
+                    .map_err(|e| Error::Parse(e.to_string()))
+            })
+            .collect::<Result<_, Error>>()?;
+
+        Ok(Self {
+            template,
+            keychains,
+            ranged,
+        })
+    }
+}
+
+impl FromStr for WalletDescriptor {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        let body = match s.split_once('#') {
+            Some((body, checksum)) => {
+                if descriptor_checksum(body).as_deref() != Some(checksum) {
+                    return Err(Error::Parse("Invalid descriptor checksum".into()));
+                }
+                body
+            }
+            None => s,
+        };
+
+        if let Some(inner) = unwrap_call(body, "sh") {
+            let inner = unwrap_call(inner, "wpkh").ok_or_else(|| {
+                Error::UnsupportedType("sh() descriptors other than sh(wpkh())".into())
+            })?;
+            return Self::from_key_expr(ScriptTemplate::ShWpkh, inner);
+        }
+        if let Some(inner) = unwrap_call(body, "wpkh") {
+            return Self::from_key_expr(ScriptTemplate::Wpkh, inner);
+        }
+        if let Some(inner) = unwrap_call(body, "pkh") {
+            return Self::from_key_expr(ScriptTemplate::Pkh, inner);
+        }
+        if let Some(inner) = unwrap_call(body, "tr") {
+            if inner.contains(',') {
+                return Err(Error::UnsupportedType("tr() with a script tree".into()));
+            }
+            return Self::from_key_expr(ScriptTemplate::Tr, inner);
+        }
+        if body.contains('(') {
+            return Err(Error::UnsupportedType(
+                "descriptor, expected pkh(), wpkh(), sh(wpkh()) or tr()".into(),
+            ));
+        }
+
+        let (xpub, template) = parse_extended_key(body)?;
+        let secp = Secp256k1::verification_only();
+        let keychains = [0, 1]
+            .into_iter()
+            .map(|index| {
+                xpub.derive_pub(&secp, &[ChildNumber::Normal { index }])
+                    .map_err(|e| Error::Parse(e.to_string()))
+            })
+            .collect::<Result<_, Error>>()?;
+        Ok(Self {
+            template,
+            keychains,
+            ranged: true,
+        })
+    }
+}
+
+/// `inner` of `function(inner)`.
+fn unwrap_call<'a>(s: &'a str, function: &str) -> Option<&'a str> {
+    s.strip_prefix(function)?
+        .strip_prefix('(')?
+        .strip_suffix(')')
+}
+
+/// Decodes an `xpub`, `ypub` or `zpub` (or their testnet variants) and
+/// the script its prefix stands for. Private keys are rejected without
+/// being echoed back.
+fn parse_extended_key(s: &str) -> Result<(Xpub, ScriptTemplate), Error> {
+    let mut data =
+        base58::decode_check(s).map_err(|_| Error::Parse("Invalid extended public key".into()))?;
+    if data.len() != 78 {
+        return Err(Error::Parse("Invalid extended public key".into()));
+    }
+
+    let (version, template) = match data[..4] {
+        [0x04, 0x88, 0xb2, 0x1e] => (XPUB_MAINNET, ScriptTemplate::Pkh),
+        [0x04, 0x9d, 0x7c, 0xb2] => (XPUB_MAINNET, ScriptTemplate::ShWpkh),
+        [0x04, 0xb2, 0x47, 0x46] => (XPUB_MAINNET, ScriptTemplate::Wpkh),
+        [0x04, 0x35, 0x87, 0xcf] => (XPUB_TESTNET, ScriptTemplate::Pkh),
+        [0x04, 0x4a, 0x52, 0x62] => (XPUB_TESTNET, ScriptTemplate::ShWpkh),
+        [0x04, 0x5f, 0x1c, 0xf6] => (XPUB_TESTNET, ScriptTemplate::Wpkh),
+        _ => {
+            return Err(Error::Parse(
+                "Unsupported extended key version, expected an xpub, ypub or zpub".into(),
+            ));
+        }
+    };
+    data[..4].copy_from_slice(&version);
+
+    let xpub = Xpub::decode(&data).map_err(|e| Error::Parse(e.to_string()))?;
+    if xpub.network != NetworkKind::from(network()) {
+        return Err(Error::InvalidNetwork);
+    }
+    Ok((xpub, template))
+}
+
+fn parse_step(step: &str) -> Result<ChildNumber, Error> {
+    if step.ends_with(['\'', 'h', 'H']) {
+        return Err(Error::Parse(
+            "Hardened derivation after the extended key needs the private key".into(),
+        ));
+    }
+    let index: u32 = step
+        .parse()
+        .map_err(|_| Error::Parse(format!("Invalid derivation step '{step}'")))?;
+    ChildNumber::from_normal_idx(index).map_err(|e| Error::Parse(e.to_string()))
+}
+
+/// BIP 380 descriptor checksum, `None` if `descriptor` has a character
+/// outside the descriptor charset.
+fn descriptor_checksum(descriptor: &str) -> Option<String> {
+    fn polymod(c: u64, val: u64) -> u64 {
+        const GENERATOR: [u64; 5] = [
+            0xf5dee51989,
+            0xa9fdca3312,
+            0x1bab10e32d,
+            0x3706b1677a,
+            0x644d626ffd,
+        ];
+        let c0 = c >> 35;
+        let mut c = ((c & 0x7ffffffff) << 5) ^ val;
+        for (i, g) in GENERATOR.iter().enumerate() {
+            if (c0 >> i) & 1 == 1 {
+                c ^= g;
+            }
+        }
+        c
+    }
+
+    let mut c = 1;
+    let mut class = 0;
+    let mut class_count = 0;
+    for ch in descriptor.chars() {
+        let pos = INPUT_CHARSET.find(ch)? as u64;
+        c = polymod(c, pos & 31);
+        class = class * 3 + (pos >> 5);
+        class_count += 1;
+        if class_count == 3 {
+            c = polymod(c, class);
+            class = 0;
+            class_count = 0;
+        }
+    }
+    if class_count > 0 {
+        c = polymod(c, class);
+    }
+    for _ in 0..8 {
+        c = polymod(c, 0);
+    }
+    c ^= 1;
+
+    Some(
+        (0..8)
+            .map(|j| CHECKSUM_CHARSET[((c >> (5 * (7 - j))) & 31) as usize] as char)
+            .collect(),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// BIP 32 test vector 1, master key.
+    const XPUB: &str = "xpub661MyMwAqRbcFtXgS5sYJABqqG9YLmC4Q1Rdap9gSE8NqtwybGhePY2gZ29ESFjqJoCu1Rupje8YtGqsefD265TMg7usUDFdp6W1EGMcet8";
+
+    #[test]
+    fn checksum_matches_bip380() {
+        assert_eq!(descriptor_checksum("raw(deadbeef)").unwrap(), "89f8spxm");
+        assert_eq!(
+            descriptor_checksum("addr(mkmZxiEcEd8ZqjQWVZuC6so5dFMKEFpN2j)").unwrap(),
+            "02wpgw69"
+        );
+    }
+
+    #[test]
+    fn keychains() {
+        let bare = WalletDescriptor::from_str(XPUB).unwrap();
+        assert_eq!(bare.output_type(), OutputType::P2PKH);
+        assert_eq!(bare.keychain_count(), 2);
+
+        let multipath = WalletDescriptor::from_str(&format!("wpkh({XPUB}/<0;1>/*)")).unwrap();
+        assert_eq!(multipath.output_type(), OutputType::P2WPKH);
+        assert_eq!(multipath.keychain_count(), 2);
+
+        let single =
+            WalletDescriptor::from_str(&format!("tr([d34db33f/86h/0h/0h]{XPUB}/0/5)")).unwrap();
+        assert_eq!(single.keychain_count(), 1);
+        assert_eq!(single.script_pubkeys(0).count(), 1);
+    }
+
+    /// Account keys of the test mnemonic ("abandon" × 11, "about") with
+    /// their first two receive addresses and first change address, as in
+    /// BIP 44, 49, 84 and 86.
+    #[test]
+    fn derives_bip_test_vectors() {
+        let vectors = [
+            (
+                "xpub6BosfCnifzxcFwrSzQiqu2DBVTshkCXacvNsWGYJVVhhawA7d4R5WSWGFNbi8Aw6ZRc1brxMyWMzG3DSSSSoekkudhUd9yLb6qx39T9nMdj",
+                [
+                    "1LqBGSKuX5yYUonjxT5qGfpUsXKYYWeabA",
+                    "1Ak8PffB2meyfYnbXZR9EGfLfFZVpzJvQP",
+                    "1J3J6EvPrv8q6AC3VCjWV45Uf3nssNMRtH",
+                ],
+            ),
+            (
+                "ypub6Ww3ibxVfGzLrAH1PNcjyAWenMTbbAosGNB6VvmSEgytSER9azLDWCxoJwW7Ke7icmizBMXrzBx9979FfaHxHcrArf3zbeJJJUZPf663zsP",
+                [
+                    "37VucYSaXLCAsxYyAPfbSi9eh4iEcbShgf",
+                    "3LtMnn87fqUeHBUG414p9CWwnoV6E2pNKS",
+                    "34K56kSjgUCUSD8GTtuF7c9Zzwokbs6uZ7",
+                ],
+            ),
+            (
+                "wpkh(zpub6rFR7y4Q2AijBEqTUquhVz398htDFrtymD9xYYfG1m4wAcvPhXNfE3EfH1r1ADqtfSdVCToUG868RvUUkgDKf31mGDtKsAYz2oz2AGutZYs/<0;1>/*)",
+                [
+                    "bc1qcr8te4kr609gcawutmrza0j4xv80jy8z306fyu",
+                    "bc1qnjg0jd8228aq7egyzacy8cys3knf9xvrerkf9g",
+                    "bc1q8c6fshw2dlwun7ekn9qwf37cu2rn755upcp6el",
+                ],
+            ),
+            (
+                "tr([73c5da0a/86h/0h/0h]xpub6BgBgsespWvERF3LHQu6CnqdvfEvtMcQjYrcRzx53QJjSxarj2afYWcLteoGVky7D3UKDP9QyrLprQ3VCECoY49yfdDEHGCtMMj92pReUsQ/<0;1>/*)",
+                [
+                    "bc1p5cyxnuxmeuwuvkwfem96lqzszd02n6xdcjrs20cac6yqjjwudpxqkedrcr",
+                    "bc1p4qhjn9zdvkux4e44uhx8tc55attvtyu358kutcqkudyccelu0was9fqzwh",
+                    "bc1p3qkhfews2uk44qtvauqyr2ttdsw7svhkl9nkm9s9c3x4ax5h60wqwruhk7",
+                ],
+            ),
+        ];
+
+        let script = |addr: &str| {
+            bitcoin::Address::from_str(addr)
+                .unwrap()
+                .assume_checked()
+                .script_pubkey()
+        };
+        for (descriptor, [receive_0, receive_1, change_0]) in vectors {
+            let wallet = WalletDescriptor::from_str(descriptor).unwrap();
+            assert_eq!(
+                wallet.script_pubkeys(0).take(2).collect::<Vec<_>>(),
+                [script(receive_0), script(receive_1)],
+                "{descriptor}"
+            );
+            assert_eq!(
+                wallet.script_pubkeys(1).next(),
+                Some(script(change_0)),
+                "{descriptor}"
+            );
+        }
+    }
+
+    #[test]
+    fn rejects_unsupported() {
+        assert!(WalletDescriptor::from_str(&format!("wpkh({XPUB}/0h/*)")).is_err());
+        assert!(WalletDescriptor::from_str(&format!("sh(pkh({XPUB}/0/*))")).is_err());
+        assert!(WalletDescriptor::from_str(&format!("wpkh({XPUB}/0/*)#00000000")).is_err());
+        assert!(WalletDescriptor::from_str("wpkh(02aabb)").is_err());
+    }
+}
@@ -0,0 +1,15 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Usage of one keychain of a wallet (receive, change, or a multipath branch)
+#[derive(Debug, Default, Serialize, Deserialize, JsonSchema)]
+pub struct WalletKeychain {
+    /// Number of addresses with on-chain or mempool activity
+    #[schemars(example = 12)]
+    pub used_count: u32,
+
+    /// Index right after the last used address, where the next fresh
+    /// address is
+    #[schemars(example = 14)]
+    pub next_index: u32,
+}
@@ -0,0 +1,22 @@
+use crate::{AddrMempoolStats, OutputType, WalletAddr, WalletChainStats, WalletKeychain};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Wallet information, over the addresses found by gap-limit scanning
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+pub struct WalletStats {
+    /// Address type derived by the descriptor
+    pub addr_type: OutputType,
+
+    /// Statistics for confirmed transactions on the blockchain
+    pub chain_stats: WalletChainStats,
+
+    /// Statistics for unconfirmed transactions in the mempool
+    pub mempool_stats: AddrMempoolStats,
+
+    /// Usage of each keychain, in descriptor order
+    pub keychains: Vec<WalletKeychain>,
+
+    /// Used addresses, by keychain then index
+    pub addrs: Vec<WalletAddr>,
+}
@@ -0,0 +1,20 @@
+use crate::{Addr, Utxo};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Unspent transaction output of a wallet, with the address holding it
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct WalletUtxo {
+    /// Bitcoin address string
+    #[serde(rename = "address")]
+    pub addr: Addr,
+
+    /// Keychain of the address, in descriptor order (0 = receive, 1 = change)
+    pub keychain: u32,
+
+    /// Derivation index within the keychain
+    pub index: u32,
+
+    #[serde(flatten)]
+    pub utxo: Utxo,
+}
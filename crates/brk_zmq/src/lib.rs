@@ -0,0 +1,242 @@
+#![doc = include_str!("../README.md")]
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicBool, Ordering},
+    },
+    thread,
+    time::Duration,
+};
+
+use brk_error::{Error, Result};
+
+mod signal;
+mod subscriber;
+mod wire;
+
+pub use signal::Signal;
+
+/// Interval of the plain polling loops when ZMQ isn't configured.
+pub const POLL_PERIOD: Duration = Duration::from_secs(1);
+
+/// How long a waiter trusts ZMQ before re-polling anyway. Covers
+/// notifications lost to a dropped connection or a full publisher queue
+/// (bitcoind's `zmqpubhwm`).
+pub const FALLBACK_PERIOD: Duration = Duration::from_secs(30);
+
+/// bitcoind notification topics, named after their `-zmqpub<topic>` options.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Topic {
+    HashBlock,
+    RawTx,
+    /// Block connect/disconnect and mempool add/remove, all in one stream.
+    Sequence,
+}
+
+impl Topic {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::HashBlock => "hashblock",
+            Self::RawTx => "rawtx",
+            Self::Sequence => "sequence",
+        }
+    }
+
+    fn from_bytes(bytes: &[u8]) -> Option<Self> {
+        match bytes {
+            b"hashblock" => Some(Self::HashBlock),
+            b"rawtx" => Some(Self::RawTx),
+            b"sequence" => Some(Self::Sequence),
+            _ => None,
+        }
+    }
+
+    fn join(topics: &[Self]) -> String {
+        topics
+            .iter()
+            .map(Self::as_str)
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Push notifications from bitcoind. Cheap to clone, clones share the
+/// same signals. Without any [`Notifications::subscribe`] call it stays
+/// disabled and waiters fall back to polling every [`POLL_PERIOD`].
+#[derive(Debug, Clone, Default)]
+pub struct Notifications(Arc<Inner>);
+
+#[derive(Debug, Default)]
+struct Inner {
+    enabled: AtomicBool,
+    /// Bumped on every connected or disconnected block.
+    block: Signal,
+    /// Bumped on every mempool change, blocks included since they evict
+    /// confirmed txs.
+    mempool: Signal,
+}
+
+impl Notifications {
+    /// Subscribes to `topics` on `endpoint` (`tcp://host:port`, as passed
+    /// to bitcoind's `-zmqpub*` options) on a background thread that
+    /// reconnects forever. Only fails on a malformed endpoint.
+    pub fn subscribe(&self, endpoint: &str, topics: &[Topic]) -> Result<()> {
+        let addr = endpoint
+            .strip_prefix("tcp://")
+            .filter(|addr| addr.rsplit_once(':').is_some())
+            .ok_or_else(|| {
+                Error::Parse(format!(
+                    "ZMQ endpoint '{endpoint}' must look like tcp://<host>:<port>"
+                ))
+            })?
+            .to_string();
+
+        self.0.enabled.store(true, Ordering::Release);
+
+        let notifications = self.clone();
+        let topics = topics.to_vec();
+        thread::Builder::new()
+            .name(format!("zmq {addr}"))
+            .spawn(move || subscriber::run(addr, topics, notifications))?;
+
+        Ok(())
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.0.enabled.load(Ordering::Acquire)
+    }
+
+    pub fn block(&self) -> &Signal {
+        &self.0.block
+    }
+
+    pub fn mempool(&self) -> &Signal {
+        &self.0.mempool
+    }
+
+    /// Waits for the next block notification past `seen`, or
+    /// [`FALLBACK_PERIOD`] when subscribed. Sleeps [`POLL_PERIOD`]
+    /// otherwise. Either way the caller re-checks the chain afterwards.
+    pub fn wait_for_block(&self, seen: &mut u64) {
+        if self.is_enabled() {
+            *seen = self.block().wait_past(*seen, FALLBACK_PERIOD);
+        } else {
+            thread::sleep(POLL_PERIOD);
+        }
+    }
+
+    fn notify_block(&self) {
+        self.0.block.notify();
+        self.0.mempool.notify();
+    }
+
+    fn notify_mempool(&self) {
+        self.0.mempool.notify();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        io::{BufReader, BufWriter},
+        net::{TcpListener, TcpStream},
+    };
+
+    use super::*;
+
+    const TIMEOUT: Duration = Duration::from_secs(10);
+
+    /// Minimal PUB peer standing in for bitcoind: handshakes, reads the
+    /// expected subscriptions, then hands the stream back for publishing.
+    fn publisher(topics: &[Topic]) -> (String, thread::JoinHandle<TcpStream>) {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let endpoint = format!("tcp://{}", listener.local_addr().unwrap());
+        let count = topics.len();
+        let handle = thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut writer = BufWriter::new(stream.try_clone().unwrap());
+            wire::read_greeting(&mut reader).unwrap();
+            wire::write_greeting(&mut writer).unwrap();
+            std::io::Write::flush(&mut writer).unwrap();
+            wire::read_ready(&mut reader).unwrap();
+            wire::write_ready(&mut writer, b"PUB").unwrap();
+            std::io::Write::flush(&mut writer).unwrap();
+            for _ in 0..count {
+                let parts = wire::read_message(&mut reader).unwrap();
+                assert_eq!(parts[0][0], 0x01);
+            }
+            stream
+        });
+        (endpoint, handle)
+    }
+
+    fn publish(stream: &mut TcpStream, topic: &[u8], body: &[u8]) {
+        wire::write_message(stream, &[topic, body, &0u32.to_le_bytes()]).unwrap();
+    }
+
+    fn subscribed(topics: &[Topic]) -> (Notifications, TcpStream) {
+        let (endpoint, handle) = publisher(topics);
+        let notifications = Notifications::default();
+        notifications.subscribe(&endpoint, topics).unwrap();
+        let stream = handle.join().unwrap();
+        // The subscriber notifies once on connect, wait it out.
+        notifications.block().wait_past(0, TIMEOUT);
+        (notifications, stream)
+    }
+
+    #[test]
+    fn rejects_non_tcp_endpoint() {
+        let notifications = Notifications::default();
+        assert!(
+            notifications
+                .subscribe("ipc:///tmp/bitcoind", &[Topic::HashBlock])
+                .is_err()
+        );
+        assert!(!notifications.is_enabled());
+    }
+
+    #[test]
+    fn hashblock_wakes_block_and_mempool() {
+        let (notifications, mut stream) = subscribed(&[Topic::HashBlock]);
+        let block = notifications.block().generation();
+        let mempool = notifications.mempool().generation();
+
+        publish(&mut stream, b"hashblock", &[0; 32]);
+
+        assert!(notifications.block().wait_past(block, TIMEOUT) > block);
+        assert!(notifications.mempool().wait_past(mempool, TIMEOUT) > mempool);
+    }
+
+    #[test]
+    fn rawtx_only_wakes_mempool() {
+        let (notifications, mut stream) = subscribed(&[Topic::RawTx]);
+        let block = notifications.block().generation();
+        let mempool = notifications.mempool().generation();
+
+        publish(&mut stream, b"rawtx", &[0; 300]);
+
+        assert!(notifications.mempool().wait_past(mempool, TIMEOUT) > mempool);
+        assert_eq!(notifications.block().generation(), block);
+    }
+
+    #[test]
+    fn sequence_dispatches_on_label() {
+        let (notifications, mut stream) = subscribed(&[Topic::Sequence]);
+        let block = notifications.block().generation();
+        let mempool = notifications.mempool().generation();
+
+        let mut added = vec![0; 32];
+        added.push(b'A');
+        added.extend_from_slice(&7u64.to_le_bytes());
+        publish(&mut stream, b"sequence", &added);
+        assert!(notifications.mempool().wait_past(mempool, TIMEOUT) > mempool);
+        assert_eq!(notifications.block().generation(), block);
+
+        let mut connected = vec![0; 32];
+        connected.push(b'C');
+        publish(&mut stream, b"sequence", &connected);
+        assert!(notifications.block().wait_past(block, TIMEOUT) > block);
+    }
+}
@@ -0,0 +1,66 @@
+use std::time::Duration;
+
+use parking_lot::{Condvar, Mutex};
+
+/// Generation counter that waiters can block on. Every [`Signal::notify`]
+/// bumps the generation, so a waiter that remembers the generation it last
+/// acted on never misses an event that fired while it was busy.
+#[derive(Debug, Default)]
+pub struct Signal {
+    generation: Mutex<u64>,
+    condvar: Condvar,
+}
+
+impl Signal {
+    pub fn notify(&self) {
+        *self.generation.lock() += 1;
+        self.condvar.notify_all();
+    }
+
+    pub fn generation(&self) -> u64 {
+        *self.generation.lock()
+    }
+
+    /// Blocks until the generation moves past `seen` or `timeout` elapses.
+    /// Returns the current generation, equal to `seen` on timeout.
+    pub fn wait_past(&self, seen: u64, timeout: Duration) -> u64 {
+        let mut generation = self.generation.lock();
+        self.condvar
+            .wait_while_for(&mut generation, |g| *g == seen, timeout);
+        *generation
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{sync::Arc, thread, time::Instant};
+
+    use super::*;
+
+    #[test]
+    fn wait_past_times_out_without_notify() {
+        let signal = Signal::default();
+        let started = Instant::now();
+        assert_eq!(signal.wait_past(0, Duration::from_millis(20)), 0);
+        assert!(started.elapsed() >= Duration::from_millis(20));
+    }
+
+    #[test]
+    fn wait_past_returns_at_once_when_already_notified() {
+        let signal = Signal::default();
+        signal.notify();
+        assert_eq!(signal.wait_past(0, Duration::from_secs(60)), 1);
+    }
+
+    #[test]
+    fn notify_wakes_waiter() {
+        let signal = Arc::new(Signal::default());
+        let notifier = signal.clone();
+        let handle = thread::spawn(move || {
+            thread::sleep(Duration::from_millis(10));
+            notifier.notify();
+        });
+        assert_eq!(signal.wait_past(0, Duration::from_secs(60)), 1);
+        handle.join().unwrap();
+    }
+}
@@ -0,0 +1,85 @@
+use std::{
+    io::{self, BufReader, BufWriter, Write},
+    net::TcpStream,
+    thread,
+    time::Duration,
+};
+
+use tracing::{debug, info, warn};
+
+use crate::{Notifications, Topic, wire};
+
+const MIN_BACKOFF: Duration = Duration::from_secs(1);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+/// `sequence` bodies are `<32-byte hash><label>[<8-byte mempool seq>]`.
+const SEQUENCE_LABEL_OFFSET: usize = 32;
+
+/// One SUB connection to `addr`, reconnecting with backoff forever.
+/// Every (re)connect notifies both signals since events may have been
+/// missed while disconnected.
+pub(crate) fn run(addr: String, topics: Vec<Topic>, notifications: Notifications) {
+    let mut backoff = MIN_BACKOFF;
+    loop {
+        match connect(&addr, &topics) {
+            Ok(reader) => {
+                info!("ZMQ subscribed to {} on {addr}", Topic::join(&topics));
+                backoff = MIN_BACKOFF;
+                notifications.notify_block();
+                let err = listen(reader, &notifications);
+                warn!("ZMQ connection to {addr} lost: {err}");
+            }
+            Err(e) => debug!("ZMQ connect to {addr} failed: {e}"),
+        }
+        thread::sleep(backoff);
+        backoff = (backoff * 2).min(MAX_BACKOFF);
+    }
+}
+
+/// Handshakes and subscribes, returning the reader the handshake went
+/// through so nothing it buffered past `READY` is lost.
+fn connect(addr: &str, topics: &[Topic]) -> io::Result<BufReader<TcpStream>> {
+    let stream = TcpStream::connect(addr)?;
+    stream.set_nodelay(true)?;
+
+    let mut writer = BufWriter::new(stream.try_clone()?);
+    let mut reader = BufReader::new(stream);
+
+    wire::write_greeting(&mut writer)?;
+    writer.flush()?;
+    wire::read_greeting(&mut reader)?;
+
+    wire::write_ready(&mut writer, b"SUB")?;
+    writer.flush()?;
+    wire::read_ready(&mut reader)?;
+
+    for topic in topics {
+        wire::write_subscribe(&mut writer, topic.as_str().as_bytes())?;
+    }
+    writer.flush()?;
+
+    Ok(reader)
+}
+
+/// Dispatches messages until the connection fails, returning why.
+fn listen(mut reader: BufReader<TcpStream>, notifications: &Notifications) -> io::Error {
+    loop {
+        let parts = match wire::read_message(&mut reader) {
+            Ok(parts) => parts,
+            Err(e) => return e,
+        };
+        let Some((topic, body)) = parts.first().zip(parts.get(1)) else {
+            continue;
+        };
+        match Topic::from_bytes(topic) {
+            Some(Topic::HashBlock) => notifications.notify_block(),
+            Some(Topic::RawTx) => notifications.notify_mempool(),
+            Some(Topic::Sequence) => match body.get(SEQUENCE_LABEL_OFFSET) {
+                Some(b'C' | b'D') => notifications.notify_block(),
+                Some(b'A' | b'R') => notifications.notify_mempool(),
+                _ => {}
+            },
+            None => {}
+        }
+    }
+}
@@ -0,0 +1,241 @@
+//! Just enough ZMTP 3.0 (RFC 23) to act as a SUB peer of bitcoind's PUB
+//! sockets: NULL security, no heartbeats, subscriptions sent as
+//! `0x01`-prefixed messages, which every ZMTP 3.x publisher accepts.
+
+use std::io::{self, Read, Write};
+
+const GREETING_LEN: usize = 64;
+const SIGNATURE_PADDING_END: usize = 9;
+const MECHANISM: &[u8] = b"NULL";
+const MECHANISM_OFFSET: usize = 12;
+const MECHANISM_LEN: usize = 20;
+
+const FLAG_MORE: u8 = 0x01;
+const FLAG_LONG: u8 = 0x02;
+const FLAG_COMMAND: u8 = 0x04;
+
+/// Largest frame accepted, twice a maximum-weight `rawblock`, so a corrupt
+/// or hostile length can't make us allocate gigabytes.
+const MAX_FRAME_LEN: u64 = 8_000_000;
+
+const READY: &[u8] = b"READY";
+const ERROR: &[u8] = b"ERROR";
+const SOCKET_TYPE: &[u8] = b"Socket-Type";
+const SUBSCRIBE: u8 = 0x01;
+
+pub(crate) enum Frame {
+    Command(Vec<u8>),
+    Message { body: Vec<u8>, more: bool },
+}
+
+pub(crate) fn write_greeting(w: &mut impl Write) -> io::Result<()> {
+    let mut greeting = [0u8; GREETING_LEN];
+    greeting[0] = 0xFF;
+    greeting[SIGNATURE_PADDING_END - 1] = 0x01;
+    greeting[SIGNATURE_PADDING_END] = 0x7F;
+    greeting[10] = 3;
+    greeting[11] = 0;
+    greeting[MECHANISM_OFFSET..MECHANISM_OFFSET + MECHANISM.len()].copy_from_slice(MECHANISM);
+    w.write_all(&greeting)
+}
+
+pub(crate) fn read_greeting(r: &mut impl Read) -> io::Result<()> {
+    let mut greeting = [0u8; GREETING_LEN];
+    r.read_exact(&mut greeting)?;
+    if greeting[0] != 0xFF || greeting[SIGNATURE_PADDING_END] & 0x01 == 0 {
+        return Err(invalid("not a ZMTP peer"));
+    }
+    if greeting[10] < 3 {
+        return Err(invalid("peer speaks ZMTP < 3.0"));
+    }
+    let mechanism = &greeting[MECHANISM_OFFSET..MECHANISM_OFFSET + MECHANISM_LEN];
+    if !mechanism.starts_with(MECHANISM) || mechanism[MECHANISM.len()..].iter().any(|&b| b != 0) {
+        return Err(invalid(
+            "peer requires a security mechanism other than NULL",
+        ));
+    }
+    Ok(())
+}
+
+/// NULL-mechanism `READY` command advertising `socket_type`.
+pub(crate) fn write_ready(w: &mut impl Write, socket_type: &[u8]) -> io::Result<()> {
+    let mut body =
+        Vec::with_capacity(1 + READY.len() + 1 + SOCKET_TYPE.len() + 4 + socket_type.len());
+    body.push(READY.len() as u8);
+    body.extend_from_slice(READY);
+    body.push(SOCKET_TYPE.len() as u8);
+    body.extend_from_slice(SOCKET_TYPE);
+    body.extend_from_slice(&(socket_type.len() as u32).to_be_bytes());
+    body.extend_from_slice(socket_type);
+    write_frame(w, FLAG_COMMAND, &body)
+}
+
+/// Reads the peer's handshake command. Properties are ignored, an `ERROR`
+/// command is surfaced with its reason.
+pub(crate) fn read_ready(r: &mut impl Read) -> io::Result<()> {
+    match read_frame(r)? {
+        Frame::Command(body) => {
+            let name_len = *body.first().ok_or_else(|| invalid("empty command"))? as usize;
+            let name = body
+                .get(1..1 + name_len)
+                .ok_or_else(|| invalid("truncated command"))?;
+            if name == READY {
+                Ok(())
+            } else if name == ERROR {
+                let reason = body.get(2 + name_len..).unwrap_or_default();
+                Err(invalid(&format!(
+                    "peer rejected handshake: {}",
+                    String::from_utf8_lossy(reason)
+                )))
+            } else {
+                Err(invalid("expected READY command"))
+            }
+        }
+        Frame::Message { .. } => Err(invalid("expected READY command, got a message")),
+    }
+}
+
+pub(crate) fn write_subscribe(w: &mut impl Write, topic: &[u8]) -> io::Result<()> {
+    let mut body = Vec::with_capacity(1 + topic.len());
+    body.push(SUBSCRIBE);
+    body.extend_from_slice(topic);
+    write_frame(w, 0, &body)
+}
+
+/// Publisher side, only needed by the tests' stand-in for bitcoind.
+#[cfg(test)]
+pub(crate) fn write_message(w: &mut impl Write, parts: &[&[u8]]) -> io::Result<()> {
+    for (i, part) in parts.iter().enumerate() {
+        let flags = if i + 1 < parts.len() { FLAG_MORE } else { 0 };
+        write_frame(w, flags, part)?;
+    }
+    w.flush()
+}
+
+/// Reads one multi-part message, skipping any interleaved commands.
+pub(crate) fn read_message(r: &mut impl Read) -> io::Result<Vec<Vec<u8>>> {
+    let mut parts = Vec::with_capacity(3);
+    loop {
+        match read_frame(r)? {
+            Frame::Command(_) => continue,
+            Frame::Message { body, more } => {
+                parts.push(body);
+                if !more {
+                    return Ok(parts);
+                }
+            }
+        }
+    }
+}
+
+fn write_frame(w: &mut impl Write, flags: u8, body: &[u8]) -> io::Result<()> {
+    if body.len() > u8::MAX as usize {
+        w.write_all(&[flags | FLAG_LONG])?;
+        w.write_all(&(body.len() as u64).to_be_bytes())?;
+    } else {
+        w.write_all(&[flags, body.len() as u8])?;
+    }
+    w.write_all(body)
+}
+
+pub(crate) fn read_frame(r: &mut impl Read) -> io::Result<Frame> {
+    let mut flags = [0u8; 1];
+    r.read_exact(&mut flags)?;
+    let flags = flags[0];
+
+    let len = if flags & FLAG_LONG != 0 {
+        let mut len = [0u8; 8];
+        r.read_exact(&mut len)?;
+        let len = u64::from_be_bytes(len);
+        if len > MAX_FRAME_LEN {
+            return Err(invalid(&format!("frame of {len} bytes is too large")));
+        }
+        len as usize
+    } else {
+        let mut len = [0u8; 1];
+        r.read_exact(&mut len)?;
+        len[0] as usize
+    };
+
+    let mut body = vec![0u8; len];
+    r.read_exact(&mut body)?;
+
+    Ok(if flags & FLAG_COMMAND != 0 {
+        Frame::Command(body)
+    } else {
+        Frame::Message {
+            body,
+            more: flags & FLAG_MORE != 0,
+        }
+    })
+}
+
+fn invalid(msg: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, msg)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Cursor;
+
+    use super::*;
+
+    #[test]
+    fn greeting_roundtrip() {
+        let mut buf = Vec::new();
+        write_greeting(&mut buf).unwrap();
+        assert_eq!(buf.len(), GREETING_LEN);
+        read_greeting(&mut Cursor::new(buf)).unwrap();
+    }
+
+    #[test]
+    fn greeting_rejects_curve() {
+        let mut buf = Vec::new();
+        write_greeting(&mut buf).unwrap();
+        buf[MECHANISM_OFFSET..MECHANISM_OFFSET + 5].copy_from_slice(b"CURVE");
+        assert!(read_greeting(&mut Cursor::new(buf)).is_err());
+    }
+
+    #[test]
+    fn ready_roundtrip() {
+        let mut buf = Vec::new();
+        write_ready(&mut buf, b"PUB").unwrap();
+        read_ready(&mut Cursor::new(buf)).unwrap();
+    }
+
+    #[test]
+    fn message_roundtrip_with_long_frame() {
+        let body = vec![7u8; 300];
+        let mut buf = Vec::new();
+        write_message(&mut buf, &[b"rawtx", &body, &1u32.to_le_bytes()]).unwrap();
+        let parts = read_message(&mut Cursor::new(buf)).unwrap();
+        assert_eq!(parts.len(), 3);
+        assert_eq!(parts[0], b"rawtx");
+        assert_eq!(parts[1], body);
+        assert_eq!(parts[2], 1u32.to_le_bytes());
+    }
+
+    #[test]
+    fn subscribe_is_prefixed_message() {
+        let mut buf = Vec::new();
+        write_subscribe(&mut buf, b"hashblock").unwrap();
+        match read_frame(&mut Cursor::new(buf)).unwrap() {
+            Frame::Message { body, more } => {
+                assert!(!more);
+                assert_eq!(body[0], SUBSCRIBE);
+                assert_eq!(&body[1..], b"hashblock");
+            }
+            Frame::Command(_) => panic!("subscription must be a message"),
+        }
+    }
+
+    #[test]
+    fn oversized_frame_is_rejected_before_allocating() {
+        let mut buf = vec![FLAG_LONG];
+        buf.extend_from_slice(&u64::MAX.to_be_bytes());
+        let Err(e) = read_frame(&mut Cursor::new(buf)) else {
+            panic!("oversized frame must be rejected");
+        };
+        assert_eq!(e.kind(), io::ErrorKind::InvalidData);
+    }
+}
@@ -1,6 +1,5 @@
 mod args;
 mod emitter;
-mod event;
 mod usage;
 
 use std::{
@@ -36,6 +35,7 @@ fn run() -> Result<()> {
     }
     let args = Args::parse(raw)?;
     let client = args.rpc()?;
+    brk_types::init_network(client.get_network()?);
     let mempool = Mempool::new(&client);
 
     let stdout = io::stdout();